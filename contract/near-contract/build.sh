@@ -23,6 +23,48 @@ cd p2p-transfer
 cargo near build
 cd ..
 
+# Build insurance pool contract
+echo "📦 Building insurance-pool..."
+cd insurance-pool
+cargo near build
+cd ..
+
+# Build factory contract
+echo "📦 Building factory..."
+cd factory
+cargo near build
+cd ..
+
+# Build deployment registry contract
+echo "📦 Building registry..."
+cd registry
+cargo near build
+cd ..
+
+# Build governance contract
+echo "📦 Building governance..."
+cd governance
+cargo near build
+cd ..
+
+# Build fee treasury contract
+echo "📦 Building treasury..."
+cd treasury
+cargo near build
+cd ..
+
+# Build swap/escrow orchestrator contract
+echo "📦 Building orchestrator..."
+cd orchestrator
+cargo near build
+cd ..
+
+# Build Poseidon verification oracle contract
+echo "📦 Building oracle..."
+cd oracle
+cargo near build
+cd ..
+
 # Create output directory
 mkdir -p out
 
@@ -32,11 +74,25 @@ echo "📋 Copying WASM files..."
 cp target/near/swap_contract/swap_contract.wasm out/
 cp target/near/escrow_contract/escrow_contract.wasm out/
 cp target/near/p2p_transfer_contract/p2p_transfer_contract.wasm out/    # ⬅️ ADD THIS
+cp target/near/insurance_pool/insurance_pool.wasm out/
+cp target/near/factory_contract/factory_contract.wasm out/
+cp target/near/registry_contract/registry_contract.wasm out/
+cp target/near/governance_contract/governance_contract.wasm out/
+cp target/near/treasury_contract/treasury_contract.wasm out/
+cp target/near/orchestrator_contract/orchestrator_contract.wasm out/
+cp target/near/oracle_contract/oracle_contract.wasm out/
 
 echo ""
 echo "✅ Build complete!"
 echo "   - swap_contract.wasm → ./out/"
 echo "   - escrow_contract.wasm → ./out/"
 echo "   - p2p_transfer_contract.wasm → ./out/"    # ⬅️ ADD THIS
+echo "   - insurance_pool.wasm → ./out/"
+echo "   - factory_contract.wasm → ./out/"
+echo "   - registry_contract.wasm → ./out/"
+echo "   - governance_contract.wasm → ./out/"
+echo "   - treasury_contract.wasm → ./out/"
+echo "   - orchestrator_contract.wasm → ./out/"
+echo "   - oracle_contract.wasm → ./out/"
 echo ""
 ls -lh out/
\ No newline at end of file
@@ -0,0 +1,49 @@
+// Off-chain companion to the on-chain hashing, so scripts and the backend can produce
+// hashlocks and commitments the contracts accept.
+//
+//   ciphra-poseidon hashlock <secret>       hashlock of a UTF-8 secret, as HashAlgorithm::PoseidonBn254
+//   ciphra-poseidon hash <field> [<field>]  circomlib poseidon of one or two field elements
+//   ciphra-poseidon commitment <secret> <blinding>
+//
+// Field elements are 64-character big-endian hex; output is lowercase hex without a prefix.
+use ciphra_poseidon::{commitment, hash1, hash2, hash_bytes, Fr};
+use std::process::exit;
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let digest = match args.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+        ["hashlock", secret] => hash_bytes(secret.as_bytes()),
+        ["hash", a] => hash1(parse_field(a)).to_be_bytes(),
+        ["hash", a, b] => hash2(parse_field(a), parse_field(b)).to_be_bytes(),
+        ["commitment", secret, blinding] => commitment(parse_field(secret), parse_field(blinding)).to_be_bytes(),
+        _ => {
+            eprintln!("Usage: ciphra-poseidon <hashlock <secret> | hash <field> [<field>] | commitment <secret> <blinding>>");
+            exit(2);
+        }
+    };
+    println!("{}", to_hex(&digest));
+}
+
+fn parse_field(value: &str) -> Fr {
+    let value = value.trim_start_matches("0x");
+    let mut bytes = [0u8; 32];
+    let valid = value.len() == 64
+        && value.is_ascii()
+        && bytes.iter_mut().enumerate().all(|(i, byte)| {
+            u8::from_str_radix(&value[i * 2..i * 2 + 2], 16)
+                .map(|parsed| *byte = parsed)
+                .is_ok()
+        });
+    if !valid {
+        eprintln!("Invalid field element: {}", value);
+        exit(2);
+    }
+    Fr::from_be_bytes(&bytes).unwrap_or_else(|| {
+        eprintln!("Field element exceeds the BN254 modulus: {}", value);
+        exit(2);
+    })
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
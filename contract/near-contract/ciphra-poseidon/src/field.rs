@@ -0,0 +1,149 @@
+use core::ops::{Add, Mul};
+
+// BN254 scalar field modulus, little-endian limbs
+const MODULUS: [u64; 4] = [0x43e1f593f0000001, 0x2833e84879b97091, 0xb85045b68181585d, 0x30644e72e131a029];
+// -MODULUS^-1 mod 2^64
+const INV: u64 = 0xc2e1f593efffffff;
+// 2^512 mod MODULUS, converts into Montgomery form
+const R2: [u64; 4] = [0x1bb8e645ae216da7, 0x53fe3ab1e35c59e3, 0x8c49833d53bb8085, 0x0216d0b17f4e44a5];
+
+// Element of the BN254 scalar field, kept in Montgomery form
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Fr([u64; 4]);
+
+impl Fr {
+    pub const ZERO: Fr = Fr([0; 4]);
+
+    pub(crate) const fn from_montgomery(limbs: [u64; 4]) -> Self {
+        Fr(limbs)
+    }
+
+    pub fn from_u64(value: u64) -> Self {
+        Fr(mont_mul(&[value, 0, 0, 0], &R2))
+    }
+
+    // Canonical big-endian encoding; None when the value is not below the modulus
+    pub fn from_be_bytes(bytes: &[u8; 32]) -> Option<Self> {
+        let limbs = limbs_from_be(bytes);
+        is_below_modulus(&limbs).then(|| Fr(mont_mul(&limbs, &R2)))
+    }
+
+    // Any 32 big-endian bytes, reduced modulo the field, e.g. for SHA-256 values from before
+    // hashing moved into the field
+    pub fn from_be_bytes_reduced(bytes: &[u8; 32]) -> Self {
+        let mut limbs = limbs_from_be(bytes);
+        while !is_below_modulus(&limbs) {
+            limbs = reduce_once(limbs);
+        }
+        Fr(mont_mul(&limbs, &R2))
+    }
+
+    // Big-endian bytes of up to 31 bytes, which always fit in the field
+    pub(crate) fn from_be_chunk(chunk: &[u8]) -> Self {
+        debug_assert!(chunk.len() < 32);
+        let mut bytes = [0u8; 32];
+        bytes[32 - chunk.len()..].copy_from_slice(chunk);
+        Fr(mont_mul(&limbs_from_be(&bytes), &R2))
+    }
+
+    pub fn to_be_bytes(&self) -> [u8; 32] {
+        let limbs = mont_mul(&self.0, &[1, 0, 0, 0]);
+        let mut bytes = [0u8; 32];
+        for (i, limb) in limbs.iter().enumerate() {
+            bytes[24 - i * 8..32 - i * 8].copy_from_slice(&limb.to_be_bytes());
+        }
+        bytes
+    }
+
+    // x^5, the Poseidon S-box
+    pub(crate) fn pow5(self) -> Self {
+        let square = self * self;
+        square * square * self
+    }
+}
+
+impl Add for Fr {
+    type Output = Fr;
+
+    fn add(self, other: Fr) -> Fr {
+        let mut sum = [0u64; 4];
+        let mut carry = 0u64;
+        for ((limb, a), b) in sum.iter_mut().zip(self.0).zip(other.0) {
+            let wide = a as u128 + b as u128 + carry as u128;
+            *limb = wide as u64;
+            carry = (wide >> 64) as u64;
+        }
+        // Both operands are below the modulus (< 2^254), so the sum never overflows 256 bits
+        Fr(reduce_once(sum))
+    }
+}
+
+impl Mul for Fr {
+    type Output = Fr;
+
+    fn mul(self, other: Fr) -> Fr {
+        Fr(mont_mul(&self.0, &other.0))
+    }
+}
+
+fn limbs_from_be(bytes: &[u8; 32]) -> [u64; 4] {
+    let mut limbs = [0u64; 4];
+    for (i, limb) in limbs.iter_mut().enumerate() {
+        let mut word = [0u8; 8];
+        word.copy_from_slice(&bytes[24 - i * 8..32 - i * 8]);
+        *limb = u64::from_be_bytes(word);
+    }
+    limbs
+}
+
+fn is_below_modulus(limbs: &[u64; 4]) -> bool {
+    for i in (0..4).rev() {
+        if limbs[i] != MODULUS[i] {
+            return limbs[i] < MODULUS[i];
+        }
+    }
+    false
+}
+
+fn reduce_once(limbs: [u64; 4]) -> [u64; 4] {
+    if is_below_modulus(&limbs) {
+        return limbs;
+    }
+    let mut difference = [0u64; 4];
+    let mut borrow = false;
+    for ((limb, value), modulus) in difference.iter_mut().zip(limbs).zip(MODULUS) {
+        let (value, underflow) = value.overflowing_sub(modulus);
+        let (value, underflow_borrow) = value.overflowing_sub(borrow as u64);
+        *limb = value;
+        borrow = underflow || underflow_borrow;
+    }
+    difference
+}
+
+// Montgomery product a * b * 2^-256 mod MODULUS (CIOS)
+fn mont_mul(a: &[u64; 4], b: &[u64; 4]) -> [u64; 4] {
+    let mut t = [0u64; 5];
+    for &b_limb in b {
+        let mut carry = 0u64;
+        for j in 0..4 {
+            let wide = t[j] as u128 + a[j] as u128 * b_limb as u128 + carry as u128;
+            t[j] = wide as u64;
+            carry = (wide >> 64) as u64;
+        }
+        t[4] += carry;
+        
+        let m = t[0].wrapping_mul(INV);
+        let wide = t[0] as u128 + m as u128 * MODULUS[0] as u128;
+        let mut carry = (wide >> 64) as u64;
+        for j in 1..4 {
+            let wide = t[j] as u128 + m as u128 * MODULUS[j] as u128 + carry as u128;
+            t[j - 1] = wide as u64;
+            carry = (wide >> 64) as u64;
+        }
+        // The modulus is below 2^254, so t stays below 2 * MODULUS and t[4] never carries out
+        let wide = t[4] as u128 + carry as u128;
+        t[3] = wide as u64;
+        t[4] = (wide >> 64) as u64;
+    }
+    reduce_once([t[0], t[1], t[2], t[3]])
+}
@@ -0,0 +1,118 @@
+// Poseidon over the BN254 scalar field with the circomlib parameters (x^5 S-box, 8 full
+// rounds). The contracts verify hashlocks with it and off-chain tools derive commitments
+// with it, so both sides always agree on the hash. No dependencies and no_std so it builds
+// for wasm32 and for native tooling alike.
+#![no_std]
+
+mod constants;
+mod field;
+
+pub use field::Fr;
+
+use constants::{MDS_2, MDS_3, ROUND_CONSTANTS_2, ROUND_CONSTANTS_3};
+
+const FULL_ROUNDS: usize = 8;
+const PARTIAL_ROUNDS_2: usize = 56;
+const PARTIAL_ROUNDS_3: usize = 57;
+
+// Preimage bytes packed per field element, so every chunk is below the modulus
+const CHUNK_BYTES: usize = 31;
+
+// circomlib `poseidon([a])`
+pub fn hash1(a: Fr) -> Fr {
+    permute([Fr::ZERO, a], &ROUND_CONSTANTS_2, &MDS_2, PARTIAL_ROUNDS_2)[0]
+}
+
+// circomlib `poseidon([a, b])`
+pub fn hash2(a: Fr, b: Fr) -> Fr {
+    permute([Fr::ZERO, a, b], &ROUND_CONSTANTS_3, &MDS_3, PARTIAL_ROUNDS_3)[0]
+}
+
+// Hash of arbitrary bytes, as used for Poseidon hashlocks. Starts from hash1(length) so
+// preimages differing only in leading zero bytes don't collide, then folds in the preimage
+// as 31-byte big-endian chunks with hash2. Returned big-endian.
+pub fn hash_bytes(preimage: &[u8]) -> [u8; 32] {
+    let mut state = hash1(Fr::from_u64(preimage.len() as u64));
+    for chunk in preimage.chunks(CHUNK_BYTES) {
+        state = hash2(state, Fr::from_be_chunk(chunk));
+    }
+    state.to_be_bytes()
+}
+
+// Commitment to a secret under a blinding value, e.g. for shielded notes
+pub fn commitment(secret: Fr, blinding: Fr) -> Fr {
+    hash2(secret, blinding)
+}
+
+fn permute<const T: usize>(
+    mut state: [Fr; T],
+    round_constants: &[[u64; 4]],
+    mds: &[[[u64; 4]; T]; T],
+    partial_rounds: usize,
+) -> [Fr; T] {
+    let half_full = FULL_ROUNDS / 2;
+    for round in 0..FULL_ROUNDS + partial_rounds {
+        for (i, element) in state.iter_mut().enumerate() {
+            *element = *element + Fr::from_montgomery(round_constants[round * T + i]);
+        }
+        
+        if round < half_full || round >= half_full + partial_rounds {
+            for element in state.iter_mut() {
+                *element = element.pow5();
+            }
+        } else {
+            state[0] = state[0].pow5();
+        }
+        
+        let mut mixed = [Fr::ZERO; T];
+        for (i, row) in mds.iter().enumerate() {
+            for (j, entry) in row.iter().enumerate() {
+                mixed[i] = mixed[i] + Fr::from_montgomery(*entry) * state[j];
+            }
+        }
+        state = mixed;
+    }
+    state
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn from_hex(hex: &str) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).unwrap();
+        }
+        bytes
+    }
+
+    // Known answers from circomlibjs `poseidon([1])` and `poseidon([1, 2])`
+    #[test]
+    fn hash1_matches_circomlib() {
+        let expected = from_hex("29176100eaa962bdc1fe6c654d6a3c130e96a4d1168b33848b897dc502820133");
+        assert_eq!(hash1(Fr::from_u64(1)).to_be_bytes(), expected);
+    }
+
+    #[test]
+    fn hash2_matches_circomlib() {
+        let expected = from_hex("115cc0f5e7d690413df64c6b9662e9cf2a3617f2743245519e19607a4417189a");
+        assert_eq!(hash2(Fr::from_u64(1), Fr::from_u64(2)).to_be_bytes(), expected);
+    }
+
+    #[test]
+    fn reduced_bytes_wrap_around_the_modulus() {
+        let modulus_plus_one = from_hex("30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000002");
+        assert!(Fr::from_be_bytes(&modulus_plus_one).is_none());
+        assert_eq!(Fr::from_be_bytes_reduced(&modulus_plus_one), Fr::from_u64(1));
+        assert_eq!(Fr::from_be_bytes_reduced(&[0xff; 32]).to_be_bytes(), {
+            // 2^256 - 1 mod r
+            from_hex("0e0a77c19a07df2f666ea36f7879462e36fc76959f60cd29ac96341c4ffffffa")
+        });
+    }
+
+    #[test]
+    fn hash_bytes_separates_leading_zeros() {
+        assert_ne!(hash_bytes(&[1]), hash_bytes(&[0, 1]));
+    }
+}
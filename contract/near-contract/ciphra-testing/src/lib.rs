@@ -0,0 +1,683 @@
+// In-process NEAR host for the contracts' unit tests. Natively, near-sdk's `env` calls the
+// near-sys imports as plain C symbols; this crate defines them over thread-local state, so
+// contract methods run as ordinary Rust calls and every #[test] (one thread each) starts from
+// an empty chain. Outgoing promises are recorded, not executed: tests inspect them with
+// `receipts`/`transfers` and drive callbacks by setting `promise_results` first. There's no
+// curve arithmetic: alt_bn128 calls are recorded, point operations return the zero point and
+// pairing checks return what `set_pairing_result` last set (failing by default).
+use sha2::{Digest, Sha256};
+use sha3::{Keccak256, Keccak512};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+
+pub const NEAR: u128 = 1_000_000_000_000_000_000_000_000;
+
+// Account the contract under test is deployed on
+pub const CONTRACT: &str = "contract.near";
+
+const DEFAULT_CALLER: &str = "alice.near";
+const DEFAULT_BALANCE: u128 = 1_000 * NEAR;
+const DEFAULT_PREPAID_GAS: u64 = 300_000_000_000_000;
+const START_TIMESTAMP: u64 = 1_700_000_000_000_000_000;
+
+// Per-record overhead the protocol charges on top of key and value bytes
+const STORAGE_RECORD_OVERHEAD: u64 = 40;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Action {
+    Transfer(u128),
+    FunctionCall { method: String, args: Vec<u8>, deposit: u128 },
+    Other(&'static str),
+}
+
+// One outgoing promise; `after` holds the promises it was chained behind with `then`
+#[derive(Clone, Debug)]
+pub struct Receipt {
+    pub receiver: String,
+    pub actions: Vec<Action>,
+    pub after: Vec<u64>,
+}
+
+struct Host {
+    registers: HashMap<u64, Vec<u8>>,
+    storage: BTreeMap<Vec<u8>, Vec<u8>>,
+    current: String,
+    predecessor: String,
+    signer: String,
+    deposit: u128,
+    balance: u128,
+    timestamp: u64,
+    height: u64,
+    logs: Vec<String>,
+    receipts: Vec<Receipt>,
+    promise_results: Vec<Option<Vec<u8>>>,
+    pairing_result: bool,
+    alt_bn128_calls: Vec<(&'static str, Vec<u8>)>,
+}
+
+impl Default for Host {
+    fn default() -> Self {
+        Self {
+            registers: HashMap::new(),
+            storage: BTreeMap::new(),
+            current: CONTRACT.to_string(),
+            predecessor: DEFAULT_CALLER.to_string(),
+            signer: DEFAULT_CALLER.to_string(),
+            deposit: 0,
+            balance: DEFAULT_BALANCE,
+            timestamp: START_TIMESTAMP,
+            height: 100,
+            logs: Vec::new(),
+            receipts: Vec::new(),
+            promise_results: Vec::new(),
+            pairing_result: false,
+            alt_bn128_calls: Vec::new(),
+        }
+    }
+}
+
+thread_local! {
+    static HOST: RefCell<Host> = RefCell::new(Host::default());
+}
+
+fn with_host<R>(f: impl FnOnce(&mut Host) -> R) -> R {
+    HOST.with(|host| f(&mut host.borrow_mut()))
+}
+
+// Calls come from `account`, signed by it, with no deposit attached
+pub fn set_caller(account: &str) {
+    with_host(|host| {
+        host.predecessor = account.to_string();
+        host.signer = account.to_string();
+        host.deposit = 0;
+    });
+}
+
+// Calls arrive from `predecessor` on behalf of `signer`, e.g. through a relayer or another contract
+pub fn set_relayed_caller(predecessor: &str, signer: &str) {
+    set_caller(signer);
+    with_host(|host| host.predecessor = predecessor.to_string());
+}
+
+// Next calls come from the contract itself, as its callbacks do
+pub fn set_self_caller() {
+    set_caller(CONTRACT);
+}
+
+pub fn set_deposit(amount: u128) {
+    with_host(|host| host.deposit = amount);
+}
+
+pub fn set_account_balance(amount: u128) {
+    with_host(|host| host.balance = amount);
+}
+
+pub fn block_timestamp() -> u64 {
+    with_host(|host| host.timestamp)
+}
+
+pub fn advance_seconds(seconds: u64) {
+    with_host(|host| {
+        host.timestamp += seconds * 1_000_000_000;
+        host.height += seconds;
+    });
+}
+
+// Outcomes the next callback sees: `Some(return bytes)` for success, `None` for failure
+pub fn set_promise_results(results: Vec<Option<Vec<u8>>>) {
+    with_host(|host| host.promise_results = results);
+}
+
+// Outcome of the next alt_bn128 pairing checks
+pub fn set_pairing_result(result: bool) {
+    with_host(|host| host.pairing_result = result);
+}
+
+// Every alt_bn128 call made so far, as (host function, input)
+pub fn alt_bn128_calls() -> Vec<(&'static str, Vec<u8>)> {
+    with_host(|host| host.alt_bn128_calls.clone())
+}
+
+pub fn logs() -> Vec<String> {
+    with_host(|host| host.logs.clone())
+}
+
+pub fn receipts() -> Vec<Receipt> {
+    with_host(|host| host.receipts.clone())
+}
+
+// Every NEAR transfer scheduled so far, as (receiver, amount)
+pub fn transfers() -> Vec<(String, u128)> {
+    with_host(|host| {
+        host.receipts
+            .iter()
+            .flat_map(|receipt| {
+                receipt.actions.iter().filter_map(|action| match action {
+                    Action::Transfer(amount) => Some((receipt.receiver.clone(), *amount)),
+                    _ => None,
+                })
+            })
+            .collect()
+    })
+}
+
+// Every function call scheduled so far, as (receiver, method)
+pub fn function_calls() -> Vec<(String, String)> {
+    with_host(|host| {
+        host.receipts
+            .iter()
+            .flat_map(|receipt| {
+                receipt.actions.iter().filter_map(|action| match action {
+                    Action::FunctionCall { method, .. } => Some((receipt.receiver.clone(), method.clone())),
+                    _ => None,
+                })
+            })
+            .collect()
+    })
+}
+
+pub fn clear_receipts() {
+    with_host(|host| {
+        host.receipts.clear();
+        host.logs.clear();
+    });
+}
+
+unsafe fn bytes<'a>(len: u64, ptr: u64) -> &'a [u8] {
+    std::slice::from_raw_parts(ptr as *const u8, len as usize)
+}
+
+unsafe fn string(len: u64, ptr: u64) -> String {
+    String::from_utf8_lossy(bytes(len, ptr)).into_owned()
+}
+
+unsafe fn read_u128(ptr: u64) -> u128 {
+    u128::from_le_bytes(bytes(16, ptr).try_into().expect("16 bytes"))
+}
+
+unsafe fn write_u128(ptr: u64, value: u128) {
+    std::ptr::copy_nonoverlapping(value.to_le_bytes().as_ptr(), ptr as *mut u8, 16);
+}
+
+fn record_alt_bn128(function: &'static str, input: &[u8]) {
+    with_host(|host| host.alt_bn128_calls.push((function, input.to_vec())));
+}
+
+fn set_register(register_id: u64, data: Vec<u8>) {
+    with_host(|host| host.registers.insert(register_id, data));
+}
+
+fn push_receipt(receiver: String, after: Vec<u64>) -> u64 {
+    with_host(|host| {
+        host.receipts.push(Receipt { receiver, actions: Vec::new(), after });
+        host.receipts.len() as u64 - 1
+    })
+}
+
+fn push_action(promise_index: u64, action: Action) {
+    with_host(|host| host.receipts[promise_index as usize].actions.push(action));
+}
+
+fn unsupported(name: &str) -> ! {
+    panic!("{} is not supported by the test host", name)
+}
+
+// The near-sys imports, exported under their C names
+mod imports {
+    use super::*;
+
+    // Registers
+
+    #[no_mangle]
+    unsafe extern "C-unwind" fn read_register(register_id: u64, ptr: u64) {
+        let data = with_host(|host| host.registers.get(&register_id).cloned()).expect("register is empty");
+        std::ptr::copy_nonoverlapping(data.as_ptr(), ptr as *mut u8, data.len());
+    }
+
+    #[no_mangle]
+    extern "C-unwind" fn register_len(register_id: u64) -> u64 {
+        with_host(|host| host.registers.get(&register_id).map_or(u64::MAX, |data| data.len() as u64))
+    }
+
+    #[no_mangle]
+    unsafe extern "C-unwind" fn write_register(register_id: u64, data_len: u64, data_ptr: u64) {
+        set_register(register_id, bytes(data_len, data_ptr).to_vec());
+    }
+
+    // Context
+
+    #[no_mangle]
+    extern "C-unwind" fn current_account_id(register_id: u64) {
+        set_register(register_id, with_host(|host| host.current.clone().into_bytes()));
+    }
+
+    #[no_mangle]
+    extern "C-unwind" fn signer_account_id(register_id: u64) {
+        set_register(register_id, with_host(|host| host.signer.clone().into_bytes()));
+    }
+
+    #[no_mangle]
+    extern "C-unwind" fn signer_account_pk(register_id: u64) {
+        // ed25519 curve tag followed by a zero key
+        let mut key = vec![0u8];
+        key.extend([0u8; 32]);
+        set_register(register_id, key);
+    }
+
+    #[no_mangle]
+    extern "C-unwind" fn predecessor_account_id(register_id: u64) {
+        set_register(register_id, with_host(|host| host.predecessor.clone().into_bytes()));
+    }
+
+    #[no_mangle]
+    extern "C-unwind" fn input(register_id: u64) {
+        set_register(register_id, Vec::new());
+    }
+
+    #[no_mangle]
+    extern "C-unwind" fn block_index() -> u64 {
+        with_host(|host| host.height)
+    }
+
+    #[no_mangle]
+    extern "C-unwind" fn block_timestamp() -> u64 {
+        with_host(|host| host.timestamp)
+    }
+
+    #[no_mangle]
+    extern "C-unwind" fn epoch_height() -> u64 {
+        with_host(|host| host.height / 43_200)
+    }
+
+    #[no_mangle]
+    extern "C-unwind" fn storage_usage() -> u64 {
+        with_host(|host| {
+            host.storage
+                .iter()
+                .map(|(key, value)| key.len() as u64 + value.len() as u64 + STORAGE_RECORD_OVERHEAD)
+                .sum()
+        })
+    }
+
+    // Economics
+
+    #[no_mangle]
+    unsafe extern "C-unwind" fn account_balance(balance_ptr: u64) {
+        write_u128(balance_ptr, with_host(|host| host.balance));
+    }
+
+    #[no_mangle]
+    unsafe extern "C-unwind" fn account_locked_balance(balance_ptr: u64) {
+        write_u128(balance_ptr, 0);
+    }
+
+    #[no_mangle]
+    unsafe extern "C-unwind" fn attached_deposit(balance_ptr: u64) {
+        write_u128(balance_ptr, with_host(|host| host.deposit));
+    }
+
+    #[no_mangle]
+    extern "C-unwind" fn prepaid_gas() -> u64 {
+        DEFAULT_PREPAID_GAS
+    }
+
+    #[no_mangle]
+    extern "C-unwind" fn used_gas() -> u64 {
+        0
+    }
+
+    #[no_mangle]
+    unsafe extern "C-unwind" fn validator_stake(_account_id_len: u64, _account_id_ptr: u64, stake_ptr: u64) {
+        write_u128(stake_ptr, 0);
+    }
+
+    #[no_mangle]
+    unsafe extern "C-unwind" fn validator_total_stake(stake_ptr: u64) {
+        write_u128(stake_ptr, 0);
+    }
+
+    // Math
+
+    #[no_mangle]
+    extern "C-unwind" fn random_seed(register_id: u64) {
+        set_register(register_id, vec![0; 32]);
+    }
+
+    #[no_mangle]
+    unsafe extern "C-unwind" fn sha256(value_len: u64, value_ptr: u64, register_id: u64) {
+        set_register(register_id, Sha256::digest(bytes(value_len, value_ptr)).to_vec());
+    }
+
+    #[no_mangle]
+    unsafe extern "C-unwind" fn keccak256(value_len: u64, value_ptr: u64, register_id: u64) {
+        set_register(register_id, Keccak256::digest(bytes(value_len, value_ptr)).to_vec());
+    }
+
+    #[no_mangle]
+    unsafe extern "C-unwind" fn keccak512(value_len: u64, value_ptr: u64, register_id: u64) {
+        set_register(register_id, Keccak512::digest(bytes(value_len, value_ptr)).to_vec());
+    }
+
+    #[no_mangle]
+    extern "C-unwind" fn ripemd160(_value_len: u64, _value_ptr: u64, _register_id: u64) {
+        unsupported("ripemd160")
+    }
+
+    #[no_mangle]
+    extern "C-unwind" fn ecrecover(
+        _hash_len: u64,
+        _hash_ptr: u64,
+        _sig_len: u64,
+        _sig_ptr: u64,
+        _v: u64,
+        _malleability_flag: u64,
+        _register_id: u64,
+    ) -> u64 {
+        unsupported("ecrecover")
+    }
+
+    #[no_mangle]
+    extern "C-unwind" fn ed25519_verify(
+        _sig_len: u64,
+        _sig_ptr: u64,
+        _msg_len: u64,
+        _msg_ptr: u64,
+        _pub_key_len: u64,
+        _pub_key_ptr: u64,
+    ) -> u64 {
+        unsupported("ed25519_verify")
+    }
+
+    #[no_mangle]
+    unsafe extern "C-unwind" fn alt_bn128_g1_multiexp(value_len: u64, value_ptr: u64, register_id: u64) {
+        record_alt_bn128("alt_bn128_g1_multiexp", bytes(value_len, value_ptr));
+        set_register(register_id, vec![0; 64]);
+    }
+
+    #[no_mangle]
+    unsafe extern "C-unwind" fn alt_bn128_g1_sum(value_len: u64, value_ptr: u64, register_id: u64) {
+        record_alt_bn128("alt_bn128_g1_sum", bytes(value_len, value_ptr));
+        set_register(register_id, vec![0; 64]);
+    }
+
+    #[no_mangle]
+    unsafe extern "C-unwind" fn alt_bn128_pairing_check(value_len: u64, value_ptr: u64) -> u64 {
+        record_alt_bn128("alt_bn128_pairing_check", bytes(value_len, value_ptr));
+        with_host(|host| host.pairing_result) as u64
+    }
+
+    // Miscellaneous
+
+    #[no_mangle]
+    extern "C-unwind" fn value_return(_value_len: u64, _value_ptr: u64) {}
+
+    #[no_mangle]
+    extern "C-unwind" fn panic() -> ! {
+        panic!("explicit guest panic")
+    }
+
+    #[no_mangle]
+    unsafe extern "C-unwind" fn panic_utf8(len: u64, ptr: u64) -> ! {
+        let message = string(len, ptr);
+        panic!("{}", message)
+    }
+
+    #[no_mangle]
+    unsafe extern "C-unwind" fn log_utf8(len: u64, ptr: u64) {
+        let message = string(len, ptr);
+        with_host(|host| host.logs.push(message));
+    }
+
+    // Promises
+
+    #[no_mangle]
+    unsafe extern "C-unwind" fn promise_create(
+        account_id_len: u64,
+        account_id_ptr: u64,
+        function_name_len: u64,
+        function_name_ptr: u64,
+        arguments_len: u64,
+        arguments_ptr: u64,
+        amount_ptr: u64,
+        gas: u64,
+    ) -> u64 {
+        let index = push_receipt(string(account_id_len, account_id_ptr), Vec::new());
+        promise_batch_action_function_call(
+            index,
+            function_name_len,
+            function_name_ptr,
+            arguments_len,
+            arguments_ptr,
+            amount_ptr,
+            gas,
+        );
+        index
+    }
+
+    #[no_mangle]
+    unsafe extern "C-unwind" fn promise_then(
+        promise_index: u64,
+        account_id_len: u64,
+        account_id_ptr: u64,
+        function_name_len: u64,
+        function_name_ptr: u64,
+        arguments_len: u64,
+        arguments_ptr: u64,
+        amount_ptr: u64,
+        gas: u64,
+    ) -> u64 {
+        let index = push_receipt(string(account_id_len, account_id_ptr), vec![promise_index]);
+        promise_batch_action_function_call(
+            index,
+            function_name_len,
+            function_name_ptr,
+            arguments_len,
+            arguments_ptr,
+            amount_ptr,
+            gas,
+        );
+        index
+    }
+
+    #[no_mangle]
+    unsafe extern "C-unwind" fn promise_and(promise_idx_ptr: u64, promise_idx_count: u64) -> u64 {
+        let joined = bytes(promise_idx_count * 8, promise_idx_ptr)
+            .chunks(8)
+            .map(|chunk| u64::from_le_bytes(chunk.try_into().expect("8 bytes")))
+            .collect();
+        push_receipt(String::new(), joined)
+    }
+
+    #[no_mangle]
+    unsafe extern "C-unwind" fn promise_batch_create(account_id_len: u64, account_id_ptr: u64) -> u64 {
+        push_receipt(string(account_id_len, account_id_ptr), Vec::new())
+    }
+
+    #[no_mangle]
+    unsafe extern "C-unwind" fn promise_batch_then(
+        promise_index: u64,
+        account_id_len: u64,
+        account_id_ptr: u64,
+    ) -> u64 {
+        push_receipt(string(account_id_len, account_id_ptr), vec![promise_index])
+    }
+
+    #[no_mangle]
+    extern "C-unwind" fn promise_batch_action_create_account(promise_index: u64) {
+        push_action(promise_index, Action::Other("create_account"));
+    }
+
+    #[no_mangle]
+    extern "C-unwind" fn promise_batch_action_deploy_contract(promise_index: u64, _code_len: u64, _code_ptr: u64) {
+        push_action(promise_index, Action::Other("deploy_contract"));
+    }
+
+    #[no_mangle]
+    unsafe extern "C-unwind" fn promise_batch_action_function_call(
+        promise_index: u64,
+        function_name_len: u64,
+        function_name_ptr: u64,
+        arguments_len: u64,
+        arguments_ptr: u64,
+        amount_ptr: u64,
+        _gas: u64,
+    ) {
+        push_action(
+            promise_index,
+            Action::FunctionCall {
+                method: string(function_name_len, function_name_ptr),
+                args: bytes(arguments_len, arguments_ptr).to_vec(),
+                deposit: read_u128(amount_ptr),
+            },
+        );
+    }
+
+    #[no_mangle]
+    unsafe extern "C-unwind" fn promise_batch_action_function_call_weight(
+        promise_index: u64,
+        function_name_len: u64,
+        function_name_ptr: u64,
+        arguments_len: u64,
+        arguments_ptr: u64,
+        amount_ptr: u64,
+        gas: u64,
+        _weight: u64,
+    ) {
+        promise_batch_action_function_call(
+            promise_index,
+            function_name_len,
+            function_name_ptr,
+            arguments_len,
+            arguments_ptr,
+            amount_ptr,
+            gas,
+        );
+    }
+
+    #[no_mangle]
+    unsafe extern "C-unwind" fn promise_batch_action_transfer(promise_index: u64, amount_ptr: u64) {
+        push_action(promise_index, Action::Transfer(read_u128(amount_ptr)));
+    }
+
+    #[no_mangle]
+    extern "C-unwind" fn promise_batch_action_stake(
+        promise_index: u64,
+        _amount_ptr: u64,
+        _public_key_len: u64,
+        _public_key_ptr: u64,
+    ) {
+        push_action(promise_index, Action::Other("stake"));
+    }
+
+    #[no_mangle]
+    extern "C-unwind" fn promise_batch_action_add_key_with_full_access(
+        promise_index: u64,
+        _public_key_len: u64,
+        _public_key_ptr: u64,
+        _nonce: u64,
+    ) {
+        push_action(promise_index, Action::Other("add_key"));
+    }
+
+    #[no_mangle]
+    extern "C-unwind" fn promise_batch_action_add_key_with_function_call(
+        promise_index: u64,
+        _public_key_len: u64,
+        _public_key_ptr: u64,
+        _nonce: u64,
+        _allowance_ptr: u64,
+        _receiver_id_len: u64,
+        _receiver_id_ptr: u64,
+        _function_names_len: u64,
+        _function_names_ptr: u64,
+    ) {
+        push_action(promise_index, Action::Other("add_key"));
+    }
+
+    #[no_mangle]
+    extern "C-unwind" fn promise_batch_action_delete_key(
+        promise_index: u64,
+        _public_key_len: u64,
+        _public_key_ptr: u64,
+    ) {
+        push_action(promise_index, Action::Other("delete_key"));
+    }
+
+    #[no_mangle]
+    extern "C-unwind" fn promise_batch_action_delete_account(
+        promise_index: u64,
+        _beneficiary_id_len: u64,
+        _beneficiary_id_ptr: u64,
+    ) {
+        push_action(promise_index, Action::Other("delete_account"));
+    }
+
+    #[no_mangle]
+    extern "C-unwind" fn promise_results_count() -> u64 {
+        with_host(|host| host.promise_results.len() as u64)
+    }
+
+    // 1 = successful (data in `register_id`), 2 = failed
+    #[no_mangle]
+    extern "C-unwind" fn promise_result(result_idx: u64, register_id: u64) -> u64 {
+        let result = with_host(|host| host.promise_results.get(result_idx as usize).cloned());
+        match result.expect("no such promise result") {
+            Some(data) => {
+                set_register(register_id, data);
+                1
+            }
+            None => 2,
+        }
+    }
+
+    #[no_mangle]
+    extern "C-unwind" fn promise_return(_promise_id: u64) {}
+
+    // Storage
+
+    #[no_mangle]
+    unsafe extern "C-unwind" fn storage_write(
+        key_len: u64,
+        key_ptr: u64,
+        value_len: u64,
+        value_ptr: u64,
+        register_id: u64,
+    ) -> u64 {
+        let key = bytes(key_len, key_ptr).to_vec();
+        let value = bytes(value_len, value_ptr).to_vec();
+        match with_host(|host| host.storage.insert(key, value)) {
+            Some(evicted) => {
+                set_register(register_id, evicted);
+                1
+            }
+            None => 0,
+        }
+    }
+
+    #[no_mangle]
+    unsafe extern "C-unwind" fn storage_read(key_len: u64, key_ptr: u64, register_id: u64) -> u64 {
+        match with_host(|host| host.storage.get(bytes(key_len, key_ptr)).cloned()) {
+            Some(value) => {
+                set_register(register_id, value);
+                1
+            }
+            None => 0,
+        }
+    }
+
+    #[no_mangle]
+    unsafe extern "C-unwind" fn storage_remove(key_len: u64, key_ptr: u64, register_id: u64) -> u64 {
+        match with_host(|host| host.storage.remove(bytes(key_len, key_ptr))) {
+            Some(evicted) => {
+                set_register(register_id, evicted);
+                1
+            }
+            None => 0,
+        }
+    }
+
+    #[no_mangle]
+    unsafe extern "C-unwind" fn storage_has_key(key_len: u64, key_ptr: u64) -> u64 {
+        with_host(|host| host.storage.contains_key(bytes(key_len, key_ptr))) as u64
+    }
+}
@@ -0,0 +1,127 @@
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{env, AccountId};
+use schemars::JsonSchema;
+use std::collections::BTreeMap;
+
+use crate::ensure;
+
+// Upper bound on accounts holding any one role
+pub const MAX_ROLE_MEMBERS: usize = 10;
+
+// Delegated admin duties; everything not covered by a role stays owner-only
+#[derive(
+    BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug,
+    JsonSchema,
+)]
+#[serde(crate = "near_sdk::serde")]
+pub enum Role {
+    FeeManager, // Fee rates and exemptions; fee destinations stay with the owner
+    Operator,   // Operational bounds such as time locks and retention periods
+}
+
+// Panics unless the caller is `owner`; the one owner check all contracts use
+pub fn assert_owner(owner: &AccountId) {
+    ensure!(&env::predecessor_account_id() == owner, Unauthorized, "Only owner");
+}
+
+// Admin state shared by the payment contracts: the pending owner of a two-step transfer and
+// the role grants. The owner itself stays a contract field that is passed in, as with
+// PauseGuard, so the proposed owner has to accept before it takes over.
+#[derive(BorshDeserialize, BorshSerialize, Default)]
+pub struct AccessControl {
+    pub pending_owner: Option<AccountId>,
+    pub roles: BTreeMap<Role, Vec<AccountId>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AccessStatus {
+    pub owner: String,
+    pub pending_owner: Option<String>,
+    pub roles: Vec<(Role, Vec<String>)>,
+}
+
+impl AccessControl {
+    // Re-proposing replaces the pending owner
+    pub fn propose_owner(&mut self, owner: &AccountId, new_owner: AccountId) {
+        assert_owner(owner);
+        ensure!(&new_owner != owner, InvalidArgument, "Account is already the owner");
+        self.pending_owner = Some(new_owner);
+    }
+
+    // Returns the new owner for the contract to store
+    pub fn accept_owner(&mut self) -> AccountId {
+        let caller = env::predecessor_account_id();
+        ensure!(
+            self.pending_owner.as_ref() == Some(&caller),
+            Unauthorized,
+            "Only the proposed owner can accept"
+        );
+        self.pending_owner = None;
+        caller
+    }
+
+    pub fn cancel_owner_transfer(&mut self, owner: &AccountId) {
+        assert_owner(owner);
+        ensure!(self.pending_owner.is_some(), InvalidState, "No pending owner transfer");
+        self.pending_owner = None;
+    }
+
+    pub fn grant_role(&mut self, owner: &AccountId, role: Role, account_id: AccountId) {
+        assert_owner(owner);
+        let members = self.roles.entry(role).or_default();
+        if !members.contains(&account_id) {
+            ensure!(members.len() < MAX_ROLE_MEMBERS, LimitExceeded, "Role member limit reached");
+            members.push(account_id);
+        }
+    }
+
+    pub fn revoke_role(&mut self, owner: &AccountId, role: Role, account_id: &AccountId) {
+        assert_owner(owner);
+        self.remove_member(role, account_id);
+    }
+
+    // Lets a compromised or retiring key drop its own role without the owner
+    pub fn renounce_role(&mut self, role: Role) {
+        let caller = env::predecessor_account_id();
+        ensure!(self.has_role(role, &caller), InvalidState, "Role not held");
+        self.remove_member(role, &caller);
+    }
+
+    pub fn has_role(&self, role: Role, account_id: &AccountId) -> bool {
+        self.roles.get(&role).is_some_and(|members| members.contains(account_id))
+    }
+
+    // The owner implicitly holds every role
+    pub fn assert_owner_or_role(&self, owner: &AccountId, role: Role) {
+        let caller = env::predecessor_account_id();
+        ensure!(
+            &caller == owner || self.has_role(role, &caller),
+            Unauthorized,
+            "Only owner or {:?}",
+            role
+        );
+    }
+
+    pub fn status(&self, owner: &AccountId) -> AccessStatus {
+        AccessStatus {
+            owner: owner.to_string(),
+            pending_owner: self.pending_owner.as_ref().map(|a| a.to_string()),
+            roles: self
+                .roles
+                .iter()
+                .map(|(role, members)| (*role, members.iter().map(|a| a.to_string()).collect()))
+                .collect(),
+        }
+    }
+
+    fn remove_member(&mut self, role: Role, account_id: &AccountId) {
+        if let Some(members) = self.roles.get_mut(&role) {
+            members.retain(|member| member != account_id);
+            if members.is_empty() {
+                self.roles.remove(&role);
+            }
+        }
+    }
+}
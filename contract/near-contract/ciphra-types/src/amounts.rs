@@ -0,0 +1,132 @@
+use crate::errors::{fail, ErrorCode};
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::U128;
+use std::io;
+
+// Amounts cross the API as U128 (yoctoNEAR or token base units, JSON-encoded as decimal
+// strings) and shares as basis points
+pub const BPS_DENOMINATOR: u128 = 10_000;
+
+pub const YOCTO_PER_NEAR: u128 = 1_000_000_000_000_000_000_000_000;
+pub const NEAR_DECIMALS: u8 = 24;
+
+// `bps` basis points of `amount_yocto`, rounded down
+pub fn bps_of(amount_yocto: u128, bps: u16) -> u128 {
+    (amount_yocto * bps as u128) / BPS_DENOMINATOR
+}
+
+// `a * b / divisor` rounded down, with the product kept at full width. Share pricing multiplies
+// two yoctoNEAR-scale numbers, which overflows u128 long before the quotient does.
+pub fn mul_div(a: u128, b: u128, divisor: u128) -> u128 {
+    if divisor == 0 {
+        fail(ErrorCode::InvalidArgument, "Division by zero");
+    }
+    const LOW: u128 = u64::MAX as u128;
+    let (a_high, a_low, b_high, b_low) = (a >> 64, a & LOW, b >> 64, b & LOW);
+    let (low_low, high_low, low_high) = (a_low * b_low, a_high * b_low, a_low * b_high);
+    let middle = (low_low >> 64) + (high_low & LOW) + (low_high & LOW);
+    let low = (low_low & LOW) | (middle << 64);
+    let high = a_high * b_high + (high_low >> 64) + (low_high >> 64) + (middle >> 64);
+    if high == 0 {
+        return low / divisor;
+    }
+    if high >= divisor {
+        fail(ErrorCode::InvalidArgument, "Amount overflow");
+    }
+    
+    // Long division of the 256-bit product; a set top bit means the shifted remainder exceeds divisor
+    let (mut remainder, mut quotient) = (high, 0u128);
+    for bit in (0..128).rev() {
+        let carry = remainder >> 127;
+        remainder = (remainder << 1) | ((low >> bit) & 1);
+        quotient <<= 1;
+        if carry == 1 || remainder >= divisor {
+            remainder = remainder.wrapping_sub(divisor);
+            quotient |= 1;
+        }
+    }
+    quotient
+}
+
+// `amount` in whole units with trailing zeros trimmed, e.g. 1_500_000 at 6 decimals -> "1.5"
+pub fn format_amount(amount: U128, decimals: u8) -> String {
+    // Past 38 decimals the scale overflows u128 and every amount is below one whole unit
+    let (whole, fraction) = match 10u128.checked_pow(decimals as u32) {
+        Some(scale) => (amount.0 / scale, amount.0 % scale),
+        None => (0, amount.0),
+    };
+    if fraction == 0 {
+        return whole.to_string();
+    }
+    let fraction = format!("{:0width$}", fraction, width = decimals as usize);
+    format!("{}.{}", whole, fraction.trim_end_matches('0'))
+}
+
+// e.g. "2.5 NEAR"
+pub fn format_near(amount: U128) -> String {
+    format!("{} NEAR", format_amount(amount, NEAR_DECIMALS))
+}
+
+// Stored records predate U128 amounts and hold them as decimal strings. Amount fields keep
+// that encoding through these codecs, so existing state decodes unchanged:
+// #[borsh(serialize_with = "decimal_borsh::serialize", deserialize_with = "decimal_borsh::deserialize")]
+pub mod decimal_borsh {
+    use super::*;
+
+    pub fn serialize<W: io::Write>(amount: &U128, writer: &mut W) -> io::Result<()> {
+        amount.0.to_string().serialize(writer)
+    }
+
+    pub fn deserialize<R: io::Read>(reader: &mut R) -> io::Result<U128> {
+        let amount = String::deserialize_reader(reader)?;
+        amount
+            .parse()
+            .map(U128)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "Invalid amount"))
+    }
+}
+
+// Same encoding for Option<U128> fields
+pub mod decimal_borsh_option {
+    use super::*;
+
+    pub fn serialize<W: io::Write>(amount: &Option<U128>, writer: &mut W) -> io::Result<()> {
+        amount.map(|amount| amount.0.to_string()).serialize(writer)
+    }
+
+    pub fn deserialize<R: io::Read>(reader: &mut R) -> io::Result<Option<U128>> {
+        Option::<String>::deserialize_reader(reader)?
+            .map(|amount| {
+                amount
+                    .parse()
+                    .map(U128)
+                    .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "Invalid amount"))
+            })
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mul_div_matches_narrow_math() {
+        assert_eq!(mul_div(7, 9, 4), 15);
+        assert_eq!(mul_div(u64::MAX as u128, u64::MAX as u128, 1), (u64::MAX as u128) * (u64::MAX as u128));
+    }
+
+    #[test]
+    fn test_mul_div_wide_product() {
+        let ten_near = 10 * YOCTO_PER_NEAR;
+        assert_eq!(mul_div(ten_near, ten_near, 11 * YOCTO_PER_NEAR), ten_near * 10 / 11);
+        assert_eq!(mul_div(u128::MAX, u128::MAX, u128::MAX), u128::MAX);
+        assert_eq!(mul_div(u128::MAX, 3, 4), u128::MAX / 4 * 3 + 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "Amount overflow")]
+    fn test_mul_div_quotient_overflow() {
+        mul_div(u128::MAX, 2, 1);
+    }
+}
@@ -0,0 +1,288 @@
+use near_sdk::env;
+use near_sdk::AccountId;
+
+use crate::HashAlgorithm;
+
+// Starknet field prime 2^251 + 17 * 2^192 + 1; felts are strictly below it
+const STARK_PRIME_HEX: &str = "0800000000000011000000000000000000000000000000000000000000000001";
+
+const BASE58_ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+const BECH32_CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const BECH32_CONST: u32 = 1;
+const BECH32M_CONST: u32 = 0x2bc8_30a3;
+const BECH32_MAX_LENGTH: usize = 90;
+
+// EVM networks accepted by name; anything else EVM goes through its CAIP-2 id, e.g. "eip155:10"
+const EVM_CHAINS: [&str; 8] = ["ethereum", "sepolia", "polygon", "arbitrum", "optimism", "base", "bsc", "avalanche"];
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum BitcoinNetwork {
+    Mainnet,
+    Testnet, // Testnet and signet share address prefixes
+    Regtest,
+}
+
+impl BitcoinNetwork {
+    fn bech32_hrp(&self) -> &'static str {
+        match self {
+            BitcoinNetwork::Mainnet => "bc",
+            BitcoinNetwork::Testnet => "tb",
+            BitcoinNetwork::Regtest => "bcrt",
+        }
+    }
+
+    // Base58check version bytes for P2PKH and P2SH
+    fn base58_versions(&self) -> [u8; 2] {
+        match self {
+            BitcoinNetwork::Mainnet => [0x00, 0x05],
+            BitcoinNetwork::Testnet | BitcoinNetwork::Regtest => [0x6f, 0xc4],
+        }
+    }
+}
+
+// Address, hashlock and transaction hash formats of the chains Ciphra swaps and proves against.
+// Chains without an adapter are passed through unchecked, so new chains work before they get one.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ChainAdapter {
+    Near,
+    Evm,
+    Starknet,
+    Bitcoin(BitcoinNetwork),
+}
+
+impl ChainAdapter {
+    // Adapter for a `target_chain` / `chain_id` name, matched case-insensitively
+    pub fn for_chain(chain: &str) -> Option<Self> {
+        let chain = chain.to_ascii_lowercase();
+        match chain.as_str() {
+            "near" | "near-testnet" => Some(ChainAdapter::Near),
+            "starknet" | "starknet-sepolia" => Some(ChainAdapter::Starknet),
+            "bitcoin" => Some(ChainAdapter::Bitcoin(BitcoinNetwork::Mainnet)),
+            "bitcoin-testnet" | "bitcoin-signet" => Some(ChainAdapter::Bitcoin(BitcoinNetwork::Testnet)),
+            "bitcoin-regtest" => Some(ChainAdapter::Bitcoin(BitcoinNetwork::Regtest)),
+            name if EVM_CHAINS.contains(&name) => Some(ChainAdapter::Evm),
+            name => name
+                .strip_prefix("eip155:")
+                .filter(|id| !id.is_empty() && id.bytes().all(|b| b.is_ascii_digit()))
+                .map(|_| ChainAdapter::Evm),
+        }
+    }
+
+    pub fn validate_address(&self, address: &str) -> Result<(), &'static str> {
+        match self {
+            ChainAdapter::Near => address.parse::<AccountId>().map(|_| ()).map_err(|_| "Invalid NEAR account id"),
+            ChainAdapter::Evm => validate_evm_address(address),
+            ChainAdapter::Starknet => parse_felt(address).map(|_| ()).ok_or("Address must be a Starknet felt"),
+            ChainAdapter::Bitcoin(network) => validate_bitcoin_address(address, *network),
+        }
+    }
+
+    // `hash` is already 64 lowercase hex characters; checks the algorithm can be unlocked on this chain
+    pub fn validate_hashlock(&self, hash: &str, algorithm: &HashAlgorithm) -> Result<(), &'static str> {
+        match (self, algorithm) {
+            (ChainAdapter::Near, _) => Ok(()),
+            (ChainAdapter::Evm, HashAlgorithm::Poseidon) => {
+                Err("EVM hashlocks use SHA256, Keccak256 or PoseidonBn254")
+            }
+            (ChainAdapter::Evm, _) => Ok(()),
+            // A Starknet Poseidon hash is a felt itself
+            (ChainAdapter::Starknet, HashAlgorithm::Poseidon) => {
+                parse_felt(hash).map(|_| ()).ok_or("Poseidon hashlock must be a Starknet felt")
+            }
+            (ChainAdapter::Starknet, HashAlgorithm::PoseidonBn254) => {
+                Err("Starknet hashlocks use Poseidon, SHA256 or Keccak256")
+            }
+            (ChainAdapter::Starknet, _) => Ok(()),
+            // Bitcoin scripts can only check OP_SHA256 preimages
+            (ChainAdapter::Bitcoin(_), HashAlgorithm::SHA256) => Ok(()),
+            (ChainAdapter::Bitcoin(_), _) => Err("Bitcoin hashlocks must be SHA256"),
+        }
+    }
+
+    // Canonical form of a transaction hash, so one transaction always maps to one proof key
+    pub fn normalize_tx_hash(&self, tx_hash: &str) -> Result<String, &'static str> {
+        match self {
+            ChainAdapter::Near => match base58_decode(tx_hash) {
+                Some(bytes) if bytes.len() == 32 => Ok(tx_hash.to_string()),
+                _ => Err("NEAR transaction hash must be 32 bytes of base58"),
+            },
+            ChainAdapter::Evm => parse_hex32(tx_hash.strip_prefix("0x").unwrap_or(tx_hash))
+                .map(|hash| format!("0x{}", hash))
+                .ok_or("EVM transaction hash must be 32 bytes of hex"),
+            ChainAdapter::Starknet => {
+                parse_felt(tx_hash).map(|felt| format!("0x{}", felt)).ok_or("Starknet transaction hash must be a felt")
+            }
+            ChainAdapter::Bitcoin(_) => parse_hex32(tx_hash).ok_or("Bitcoin txid must be 32 bytes of hex"),
+        }
+    }
+}
+
+// Checks `address` against the adapter for `chain`, if there is one
+pub fn validate_chain_address(chain: &str, address: &str) -> Result<(), &'static str> {
+    match ChainAdapter::for_chain(chain) {
+        Some(adapter) => adapter.validate_address(address),
+        None => Ok(()),
+    }
+}
+
+// Lowercase 64-character hex hashlock (an optional 0x prefix is dropped), checked against the
+// adapter for `chain`, if there is one
+pub fn normalize_hashlock(chain: &str, hash_lock: &str, algorithm: &HashAlgorithm) -> Result<String, &'static str> {
+    let hash = parse_hex32(hash_lock.strip_prefix("0x").unwrap_or(hash_lock))
+        .ok_or("Hash lock must be 64 characters (32 bytes hex)")?;
+    if let Some(adapter) = ChainAdapter::for_chain(chain) {
+        adapter.validate_hashlock(&hash, algorithm)?;
+    }
+    Ok(hash)
+}
+
+// Canonical transaction hash for `chain`; returned unchanged when the chain has no adapter
+pub fn normalize_tx_hash(chain: &str, tx_hash: &str) -> Result<String, &'static str> {
+    match ChainAdapter::for_chain(chain) {
+        Some(adapter) => adapter.normalize_tx_hash(tx_hash),
+        None => Ok(tx_hash.to_string()),
+    }
+}
+
+// Exactly 64 hex characters, lowercased
+fn parse_hex32(value: &str) -> Option<String> {
+    (value.len() == 64 && value.bytes().all(|b| b.is_ascii_hexdigit())).then(|| value.to_ascii_lowercase())
+}
+
+// 0x-prefixed felt of up to 64 hex digits below the Stark prime; returned zero-padded to 64 digits
+fn parse_felt(value: &str) -> Option<String> {
+    let digits = value.strip_prefix("0x").unwrap_or(value);
+    if digits.is_empty() || digits.len() > 64 || !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    let padded = format!("{:0>64}", digits.to_ascii_lowercase());
+    // Equal-length lowercase hex compares like the numbers it encodes
+    (padded.as_str() < STARK_PRIME_HEX).then_some(padded)
+}
+
+// 0x + 40 hex digits; mixed case must carry a valid EIP-55 checksum
+fn validate_evm_address(address: &str) -> Result<(), &'static str> {
+    let digits = address.strip_prefix("0x").ok_or("EVM address must start with 0x")?;
+    if digits.len() != 40 || !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err("EVM address must be 20 bytes of hex");
+    }
+    let has_lower = digits.bytes().any(|b| b.is_ascii_lowercase());
+    let has_upper = digits.bytes().any(|b| b.is_ascii_uppercase());
+    if !(has_lower && has_upper) {
+        return Ok(());
+    }
+
+    let hash = env::keccak256(digits.to_ascii_lowercase().as_bytes());
+    let checksummed = digits.bytes().enumerate().all(|(index, byte)| {
+        let nibble = (hash[index / 2] >> if index % 2 == 0 { 4 } else { 0 }) & 0x0f;
+        !byte.is_ascii_alphabetic() || byte.is_ascii_uppercase() == (nibble >= 8)
+    });
+    if checksummed {
+        Ok(())
+    } else {
+        Err("Invalid EIP-55 address checksum")
+    }
+}
+
+// Legacy base58check (P2PKH / P2SH) or bech32 / bech32m segwit address for `network`
+fn validate_bitcoin_address(address: &str, network: BitcoinNetwork) -> Result<(), &'static str> {
+    let hrp = network.bech32_hrp();
+    let segwit_prefix = format!("{}1", hrp);
+    if address.get(..segwit_prefix.len()).is_some_and(|prefix| prefix.eq_ignore_ascii_case(&segwit_prefix)) {
+        return validate_segwit_address(address, hrp);
+    }
+
+    let bytes = base58_decode(address).ok_or("Invalid Bitcoin address")?;
+    if bytes.len() != 25 || !network.base58_versions().contains(&bytes[0]) {
+        return Err("Invalid Bitcoin address");
+    }
+    let checksum = env::sha256(&env::sha256(&bytes[..21]));
+    if checksum[..4] != bytes[21..] {
+        return Err("Invalid Bitcoin address checksum");
+    }
+    Ok(())
+}
+
+// BIP-173 / BIP-350: witness v0 uses bech32 with a 20 or 32 byte program, v1+ uses bech32m
+fn validate_segwit_address(address: &str, hrp: &str) -> Result<(), &'static str> {
+    if address.len() > BECH32_MAX_LENGTH
+        || (address.bytes().any(|b| b.is_ascii_lowercase()) && address.bytes().any(|b| b.is_ascii_uppercase()))
+    {
+        return Err("Invalid Bitcoin address");
+    }
+    let address = address.to_ascii_lowercase();
+    let data = address[hrp.len() + 1..]
+        .bytes()
+        .map(|b| BECH32_CHARSET.iter().position(|c| *c == b).map(|value| value as u8))
+        .collect::<Option<Vec<u8>>>()
+        .ok_or("Invalid Bitcoin address")?;
+    if data.len() < 7 {
+        return Err("Invalid Bitcoin address");
+    }
+
+    let mut values: Vec<u8> = hrp.bytes().map(|b| b >> 5).collect();
+    values.push(0);
+    values.extend(hrp.bytes().map(|b| b & 0x1f));
+    values.extend(&data);
+    let checksum = bech32_polymod(&values);
+
+    let version = data[0];
+    let program = convert_5_to_8(&data[1..data.len() - 6]).ok_or("Invalid Bitcoin address")?;
+    let valid = match version {
+        0 => checksum == BECH32_CONST && (program.len() == 20 || program.len() == 32),
+        1..=16 => checksum == BECH32M_CONST && (2..=40).contains(&program.len()),
+        _ => false,
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err("Invalid Bitcoin address checksum")
+    }
+}
+
+fn bech32_polymod(values: &[u8]) -> u32 {
+    const GENERATORS: [u32; 5] = [0x3b6a_57b2, 0x2650_8e6d, 0x1ea1_19fa, 0x3d42_33dd, 0x2a14_62b3];
+    values.iter().fold(1u32, |checksum, value| {
+        let top = checksum >> 25;
+        let checksum = ((checksum & 0x01ff_ffff) << 5) ^ *value as u32;
+        (0..5)
+            .filter(|bit| (top >> bit) & 1 == 1)
+            .fold(checksum, |checksum, bit| checksum ^ GENERATORS[bit])
+    })
+}
+
+// Regroups 5-bit words into bytes; leftover bits must be zero padding
+fn convert_5_to_8(data: &[u8]) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(data.len() * 5 / 8);
+    let (mut acc, mut bits) = (0u32, 0u32);
+    for value in data {
+        acc = ((acc << 5) | *value as u32) & 0x0fff;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((acc >> bits) as u8);
+        }
+    }
+    (bits < 5 && (acc << (8 - bits)) & 0xff == 0).then_some(bytes)
+}
+
+fn base58_decode(value: &str) -> Option<Vec<u8>> {
+    // Little-endian while accumulating
+    let mut bytes: Vec<u8> = Vec::new();
+    for c in value.bytes() {
+        let mut carry = BASE58_ALPHABET.iter().position(|a| *a == c)? as u32;
+        for byte in bytes.iter_mut() {
+            carry += *byte as u32 * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+    // Each leading '1' stands for a leading zero byte
+    bytes.extend(value.bytes().take_while(|c| *c == b'1').map(|_| 0));
+    bytes.reverse();
+    (!value.is_empty()).then_some(bytes)
+}
@@ -0,0 +1,74 @@
+use near_sdk::env;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::serde_json::json;
+use schemars::JsonSchema;
+
+// Machine-readable failure classes. Contracts panic with
+// ERROR_JSON:{"code","message"} so clients can branch on `code` and show `message`.
+#[derive(Serialize, Deserialize, Clone, Copy, JsonSchema, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum ErrorCode {
+    Unauthorized,      // Caller lacks the role or party position the method requires
+    NotFound,          // Referenced record doesn't exist
+    AlreadyExists,     // Id or entry is already taken
+    InvalidArgument,   // Malformed or out-of-range input
+    InvalidState,      // Record isn't in a state that allows the call
+    InvalidProof,      // Secret, proof or signature didn't verify
+    InsufficientFunds, // Attached deposit or available balance too small
+    LimitExceeded,     // A size, count or rate bound would be exceeded
+    TooEarly,          // A time lock, window or period hasn't been reached
+    Expired,           // A deadline or window has passed
+    Paused,            // Contract paused by its owner or a guardian
+    Internal,          // Stored data failed to parse; indicates a contract bug
+}
+
+#[derive(Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CiphraError {
+    pub code: ErrorCode,
+    pub message: String,
+}
+
+impl CiphraError {
+    pub fn new(code: ErrorCode, message: &str) -> Self {
+        Self { code, message: message.to_string() }
+    }
+
+    pub fn panic(&self) -> ! {
+        env::panic_str(&format!(
+            "ERROR_JSON:{}",
+            json!({ "code": self.code, "message": self.message })
+        ))
+    }
+}
+
+pub fn fail(code: ErrorCode, message: &str) -> ! {
+    CiphraError::new(code, message).panic()
+}
+
+// `expect` with a typed error: `.or_fail(ErrorCode::NotFound, "Escrow not found")`
+pub trait OrFail<T> {
+    fn or_fail(self, code: ErrorCode, message: &str) -> T;
+}
+
+impl<T> OrFail<T> for Option<T> {
+    fn or_fail(self, code: ErrorCode, message: &str) -> T {
+        self.unwrap_or_else(|| fail(code, message))
+    }
+}
+
+impl<T, E> OrFail<T> for Result<T, E> {
+    fn or_fail(self, code: ErrorCode, message: &str) -> T {
+        self.unwrap_or_else(|_| fail(code, message))
+    }
+}
+
+// `assert!` with a typed error: `ensure!(cond, Unauthorized, "Only owner")`
+#[macro_export]
+macro_rules! ensure {
+    ($cond:expr, $code:ident, $($message:tt)+) => {
+        if !($cond) {
+            $crate::fail($crate::ErrorCode::$code, &format!($($message)+))
+        }
+    };
+}
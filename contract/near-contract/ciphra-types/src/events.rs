@@ -0,0 +1,63 @@
+use near_sdk::env;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::serde_json::{self, json, Value};
+
+pub const EVENT_LOG_PREFIX: &str = "EVENT_JSON:";
+
+// Every contract's standard is "ciphra_<contract>", so indexers pick out all Ciphra events
+// with one prefix and dispatch on the contract part
+pub const EVENT_NAMESPACE: &str = "ciphra";
+
+// One contract's NEP-297 event stream. Bump `version` whenever an event's data fields change
+// so indexers can tell the schemas apart.
+pub struct EventStandard {
+    pub contract: &'static str,
+    pub version: &'static str,
+}
+
+impl EventStandard {
+    pub const fn new(contract: &'static str, version: &'static str) -> Self {
+        Self { contract, version }
+    }
+
+    pub fn standard(&self) -> String {
+        format!("{}_{}", EVENT_NAMESPACE, self.contract)
+    }
+
+    // Logs EVENT_JSON:{"standard","version","event","data":[...]}
+    pub fn emit(&self, event: &str, data: Value) {
+        env::log_str(&format!(
+            "{}{}",
+            EVENT_LOG_PREFIX,
+            json!({
+                "standard": self.standard(),
+                "version": self.version,
+                "event": event,
+                "data": [data],
+            })
+        ));
+    }
+}
+
+// A Ciphra event as read back from a receipt log, for indexers and tests
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct EventLog {
+    pub standard: String,
+    pub version: String,
+    pub event: String,
+    pub data: Vec<Value>,
+}
+
+impl EventLog {
+    // None for plain logs, malformed events and events of other standards
+    pub fn parse(log: &str) -> Option<Self> {
+        let event: EventLog = serde_json::from_str(log.strip_prefix(EVENT_LOG_PREFIX)?).ok()?;
+        event.contract().is_some().then_some(event)
+    }
+
+    // The emitting contract, e.g. "escrow" for "ciphra_escrow"
+    pub fn contract(&self) -> Option<&str> {
+        self.standard.strip_prefix(EVENT_NAMESPACE)?.strip_prefix('_')
+    }
+}
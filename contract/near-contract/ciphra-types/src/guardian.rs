@@ -0,0 +1,84 @@
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{env, AccountId};
+use schemars::JsonSchema;
+
+use crate::{assert_owner, ensure};
+
+// Upper bound on guardians per contract
+pub const MAX_GUARDIANS: usize = 10;
+
+// Incident-response switch shared by the payment contracts. Guardians are accounts other
+// than the owner that can halt new deposits and creations; settlement and refund paths
+// never check it. Only the owner resumes.
+#[derive(BorshDeserialize, BorshSerialize, Default)]
+pub struct PauseGuard {
+    pub guardians: Vec<AccountId>,
+    pub paused: bool,
+    pub paused_by: Option<AccountId>,
+    pub paused_at: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PauseStatus {
+    pub paused: bool,
+    pub paused_by: Option<String>,
+    pub paused_at: Option<u64>,
+    pub guardians: Vec<String>,
+}
+
+impl PauseGuard {
+    // Returns the caller that paused
+    pub fn pause(&mut self, owner: &AccountId) -> AccountId {
+        let caller = env::predecessor_account_id();
+        ensure!(
+            &caller == owner || self.guardians.contains(&caller),
+            Unauthorized,
+            "Only owner or guardian"
+        );
+        ensure!(!self.paused, InvalidState, "Contract already paused");
+        
+        self.paused = true;
+        self.paused_by = Some(caller.clone());
+        self.paused_at = Some(env::block_timestamp());
+        caller
+    }
+
+    pub fn unpause(&mut self, owner: &AccountId) {
+        assert_owner(owner);
+        ensure!(self.paused, InvalidState, "Contract not paused");
+        
+        self.paused = false;
+        self.paused_by = None;
+        self.paused_at = None;
+    }
+
+    // The guardian key is meant to live apart from the owner key, so the owner can't be one
+    pub fn add_guardian(&mut self, owner: &AccountId, account_id: AccountId) {
+        assert_owner(owner);
+        ensure!(&account_id != owner, InvalidArgument, "Owner cannot be a guardian");
+        if !self.guardians.contains(&account_id) {
+            ensure!(self.guardians.len() < MAX_GUARDIANS, LimitExceeded, "Guardian limit reached");
+            self.guardians.push(account_id);
+        }
+    }
+
+    pub fn remove_guardian(&mut self, owner: &AccountId, account_id: &AccountId) {
+        assert_owner(owner);
+        self.guardians.retain(|g| g != account_id);
+    }
+
+    pub fn assert_not_paused(&self) {
+        ensure!(!self.paused, Paused, "Contract is paused");
+    }
+
+    pub fn status(&self) -> PauseStatus {
+        PauseStatus {
+            paused: self.paused,
+            paused_by: self.paused_by.as_ref().map(|a| a.to_string()),
+            paused_at: self.paused_at,
+            guardians: self.guardians.iter().map(|g| g.to_string()).collect(),
+        }
+    }
+}
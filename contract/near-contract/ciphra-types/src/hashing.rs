@@ -0,0 +1,26 @@
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::env;
+use near_sdk::serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+
+// Hashlock algorithms across the contracts; not every contract accepts every variant
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum HashAlgorithm {
+    SHA256,
+    Poseidon,      // Checked off-chain by the swap oracle
+    Keccak256,     // Matches hashlocks created on EVM chains
+    PoseidonBn254, // ciphra_poseidon::hash_bytes, shared with the off-chain tooling
+}
+
+impl HashAlgorithm {
+    // Hex hash of `preimage`, or None when the algorithm can't be computed on-chain
+    pub fn hash_hex(&self, preimage: &[u8]) -> Option<String> {
+        match self {
+            HashAlgorithm::SHA256 => Some(hex::encode(env::sha256(preimage))),
+            HashAlgorithm::Keccak256 => Some(hex::encode(env::keccak256(preimage))),
+            HashAlgorithm::PoseidonBn254 => Some(hex::encode(ciphra_poseidon::hash_bytes(preimage))),
+            HashAlgorithm::Poseidon => None,
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::AccountId;
+use schemars::JsonSchema;
+use std::collections::BTreeMap;
+
+use crate::{PauseGuard, RateLimitConfig};
+
+// One-call introspection view every payment contract returns from `get_contract_info`. The
+// shape is the same everywhere; the map keys name the contract's own parameters, with
+// durations in seconds as their setters take them, so dashboards can render any contract
+// generically and SDKs can still look a value up by name.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ContractInfo {
+    pub contract: String,      // "swap", "escrow" or "p2p", as in the event standard
+    pub version: String,       // Package version of the deployed code
+    pub state_version: String, // Stored layout version, e.g. "V4"
+    pub owner: String,
+    pub pending_owner: Option<String>,
+    pub paused: bool,
+    pub fees_bps: BTreeMap<String, u16>,
+    pub bounds: BTreeMap<String, u64>,
+    pub counters: BTreeMap<String, u64>,
+    pub rate_limit: Option<RateLimitConfig>,
+}
+
+impl ContractInfo {
+    pub fn new(
+        contract: &str,
+        version: &str,
+        state_version: String,
+        owner: &AccountId,
+        pending_owner: Option<&AccountId>,
+        pause_guard: &PauseGuard,
+    ) -> Self {
+        Self {
+            contract: contract.to_string(),
+            version: version.to_string(),
+            state_version,
+            owner: owner.to_string(),
+            pending_owner: pending_owner.map(|a| a.to_string()),
+            paused: pause_guard.paused,
+            fees_bps: BTreeMap::new(),
+            bounds: BTreeMap::new(),
+            counters: BTreeMap::new(),
+            rate_limit: None,
+        }
+    }
+
+    pub fn fee(mut self, name: &str, bps: u16) -> Self {
+        self.fees_bps.insert(name.to_string(), bps);
+        self
+    }
+
+    pub fn bound(mut self, name: &str, value: u64) -> Self {
+        self.bounds.insert(name.to_string(), value);
+        self
+    }
+
+    pub fn counter(mut self, name: &str, value: u64) -> Self {
+        self.counters.insert(name.to_string(), value);
+        self
+    }
+
+    pub fn rate_limit(mut self, config: Option<RateLimitConfig>) -> Self {
+        self.rate_limit = config;
+        self
+    }
+}
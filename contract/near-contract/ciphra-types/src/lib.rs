@@ -0,0 +1,43 @@
+// Models shared by the swap, escrow and p2p contracts and by off-chain tools. Borsh layouts
+// here are stored on-chain, so variants and fields are only ever appended.
+mod access;
+mod amounts;
+mod chain_adapters;
+mod errors;
+mod events;
+mod guardian;
+mod hashing;
+mod info;
+mod metadata;
+mod paging;
+mod proofs;
+mod rate_limit;
+mod settlement;
+mod status;
+mod storage;
+mod versioning;
+
+pub use access::{assert_owner, AccessControl, AccessStatus, Role, MAX_ROLE_MEMBERS};
+pub use amounts::{
+    bps_of, decimal_borsh, decimal_borsh_option, format_amount, format_near, mul_div, BPS_DENOMINATOR,
+    NEAR_DECIMALS, YOCTO_PER_NEAR,
+};
+pub use chain_adapters::{
+    normalize_hashlock, normalize_tx_hash, validate_chain_address, BitcoinNetwork, ChainAdapter,
+};
+pub use errors::{fail, CiphraError, ErrorCode, OrFail};
+pub use events::{EventLog, EventStandard, EVENT_LOG_PREFIX, EVENT_NAMESPACE};
+pub use guardian::{PauseGuard, PauseStatus, MAX_GUARDIANS};
+pub use hashing::HashAlgorithm;
+pub use info::ContractInfo;
+pub use metadata::EscrowMetadata;
+pub use paging::{page_bounds, Paged, MAX_PAGE_SIZE};
+pub use proofs::{CrossChainProof, ProofData};
+pub use rate_limit::{RateLimitConfig, RateLimiter, WindowCounter, DEFAULT_RATE_LIMIT, MAX_RATE_LIMIT_EXEMPTIONS};
+pub use settlement::{Settlement, SettlementLedger};
+pub use status::{EscrowStatus, SwapStatus, TransferStatus};
+pub use storage::{StorageAccount, StorageAccounts, StorageBalance, StorageBalanceBounds};
+pub use versioning::{
+    drain_legacy, insert_stored_field, migrate_state, state_read_appending, state_read_rewriting, stored_state_version,
+    take_stored_field, write_state_version, VersionedState, STATE_VERSION_KEY,
+};
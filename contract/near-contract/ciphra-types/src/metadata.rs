@@ -0,0 +1,45 @@
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+
+use crate::ensure;
+
+// Length caps on metadata fields, in bytes
+const MAX_TITLE_LEN: usize = 128;
+const MAX_CATEGORY_LEN: usize = 32;
+const MAX_CONTENT_HASH_LEN: usize = 128;
+const MAX_EXTERNAL_URI_LEN: usize = 256;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct EscrowMetadata {
+    pub title: String,
+    pub category: String,             // Free-form tag used by get_escrows_by_category
+    pub content_hash: Option<String>, // Hash of the off-chain agreement
+    pub external_uri: Option<String>,
+}
+
+impl EscrowMetadata {
+    pub fn validate(&self) {
+        ensure!(
+            !self.title.is_empty() && self.title.len() <= MAX_TITLE_LEN,
+            InvalidArgument,
+            "Title must be 1-128 bytes"
+        );
+        ensure!(
+            !self.category.is_empty() && self.category.len() <= MAX_CATEGORY_LEN,
+            InvalidArgument,
+            "Category must be 1-32 bytes"
+        );
+        ensure!(
+            self.content_hash.as_ref().is_none_or(|hash| hash.len() <= MAX_CONTENT_HASH_LEN),
+            LimitExceeded,
+            "Content hash too long"
+        );
+        ensure!(
+            self.external_uri.as_ref().is_none_or(|uri| uri.len() <= MAX_EXTERNAL_URI_LEN),
+            LimitExceeded,
+            "External URI too long"
+        );
+    }
+}
@@ -0,0 +1,82 @@
+use near_sdk::serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+
+use crate::ensure;
+
+// Upper bound on items returned by one page; also the default limit
+pub const MAX_PAGE_SIZE: u64 = 100;
+
+// One page of an unbounded list view. Every such view takes `from_index: Option<u64>` and
+// `limit: Option<u64>`, defaulting to the start and MAX_PAGE_SIZE; callers page on until
+// `from_index + items.len()` reaches `total`. Short config lists (guardians, denominations)
+// and per-record lists capped by their own limits are returned whole.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Paged<T> {
+    pub items: Vec<T>,
+    pub total: u64,
+    pub from_index: u64,
+}
+
+impl<T> Paged<T> {
+    // Pages through an id index, loading each id; ids whose record is gone are skipped, so a
+    // page can come back short while `total` still counts them
+    pub fn from_ids<I>(
+        ids: &[I],
+        from_index: Option<u64>,
+        limit: Option<u64>,
+        load: impl FnMut(&I) -> Option<T>,
+    ) -> Self {
+        let (from_index, limit) = page_bounds(from_index, limit);
+        Self {
+            items: ids.iter().skip(from_index as usize).take(limit as usize).filter_map(load).collect(),
+            total: ids.len() as u64,
+            from_index,
+        }
+    }
+
+    // Pages through positions 0..total of an indexed collection such as a Vector
+    pub fn from_range(
+        total: u64,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+        load: impl FnMut(u64) -> Option<T>,
+    ) -> Self {
+        let (from_index, limit) = page_bounds(from_index, limit);
+        let end = from_index.saturating_add(limit).min(total);
+        Self {
+            items: (from_index.min(end)..end).filter_map(load).collect(),
+            total,
+            from_index,
+        }
+    }
+
+    // Pages through a filtered iterator; `total` counts every match, so the whole iterator is consumed
+    pub fn from_iter(items: impl Iterator<Item = T>, from_index: Option<u64>, limit: Option<u64>) -> Self {
+        let (from_index, limit) = page_bounds(from_index, limit);
+        let mut page = Vec::new();
+        let mut total = 0;
+        for item in items {
+            if total >= from_index && (page.len() as u64) < limit {
+                page.push(item);
+            }
+            total += 1;
+        }
+        Self { items: page, total, from_index }
+    }
+
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Paged<U> {
+        Paged {
+            items: self.items.into_iter().map(f).collect(),
+            total: self.total,
+            from_index: self.from_index,
+        }
+    }
+}
+
+// Resolves the optional page arguments, panicking on a limit above MAX_PAGE_SIZE
+pub fn page_bounds(from_index: Option<u64>, limit: Option<u64>) -> (u64, u64) {
+    let limit = limit.unwrap_or(MAX_PAGE_SIZE);
+    ensure!(limit <= MAX_PAGE_SIZE, LimitExceeded, "Limit cannot exceed {}", MAX_PAGE_SIZE);
+    (from_index.unwrap_or(0), limit)
+}
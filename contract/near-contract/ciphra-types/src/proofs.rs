@@ -0,0 +1,47 @@
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+
+// Receipt inclusion proof; all byte fields are hex-encoded
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ProofData {
+    pub block_hash: String,    // 32 bytes
+    pub block_header: String,  // RLP header hashing to `block_hash`
+    pub receipts_root: String,
+    pub receipt_index: u64,
+    pub receipt_data: String,
+    pub proof_nodes: Vec<String>, // Merkle-Patricia path from `receipts_root` to the receipt
+    pub log_index: u32,           // Log within the receipt the escrow relies on
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CrossChainProof {
+    pub proof_id: String, // Label unique within the escrow, e.g. "payment" or "delivery"
+    pub chain_id: String,
+    pub tx_hash: String, // Reference only; the proof is identified by its receipt location
+    pub block_number: u64,
+    pub proof_data: ProofData,
+    pub verified: bool,
+    pub verified_at: Option<u64>,
+    pub approvals: Vec<String>, // Trusted verifiers that approved; verified once the quorum is met
+}
+
+impl CrossChainProof {
+    // Replay key naming the log the proof evidences, so the same payment can't be replayed under
+    // another transaction hash. Legacy proofs carry no receipt location and keep their tx hash key.
+    pub fn receipt_key(&self) -> String {
+        let block_hash = self.proof_data.block_hash.trim_start_matches("0x");
+        if block_hash.is_empty() {
+            return format!("{}:{}", self.chain_id, self.tx_hash);
+        }
+        format!(
+            "{}:{}:{}:{}",
+            self.chain_id,
+            block_hash.to_ascii_lowercase(),
+            self.proof_data.receipt_index,
+            self.proof_data.log_index
+        )
+    }
+}
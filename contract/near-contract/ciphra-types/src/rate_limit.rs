@@ -0,0 +1,120 @@
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::collections::LookupMap;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{env, AccountId, IntoStorageKey};
+use schemars::JsonSchema;
+
+use crate::ensure;
+
+// Upper bound on accounts exempt from limiting
+pub const MAX_RATE_LIMIT_EXEMPTIONS: usize = 10;
+
+// Default bound on records one account can create per window
+pub const DEFAULT_RATE_LIMIT: RateLimitConfig = RateLimitConfig {
+    max_actions: 100,
+    window_seconds: 3_600,
+};
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, PartialEq, Debug, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RateLimitConfig {
+    pub max_actions: u16,
+    pub window_seconds: u32,
+}
+
+// Sliding-window counter: counts for the current and previous fixed window, 8 bytes per account
+#[derive(BorshDeserialize, BorshSerialize, Clone, Copy, Default)]
+pub struct WindowCounter {
+    pub window: u32,
+    pub current: u16,
+    pub previous: u16,
+}
+
+// Per-account limit on record-creating calls, against storage spam from accounts that
+// prepay once and then create records in a loop. The previous window's count is weighted by
+// how much of it still overlaps the sliding window, so bursts at a window edge can't double
+// the limit.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct RateLimiter {
+    pub config: Option<RateLimitConfig>, // None disables limiting
+    pub counters: LookupMap<AccountId, WindowCounter>,
+    pub exempt: Vec<AccountId>, // Contracts acting for many users, which limit those users themselves
+}
+
+impl RateLimiter {
+    pub fn new<S: IntoStorageKey>(prefix: S, config: Option<RateLimitConfig>) -> Self {
+        Self { config, counters: LookupMap::new(prefix), exempt: Vec::new() }
+    }
+
+    // Counts `actions` records created by `account_id`, panicking once over the limit
+    pub fn record(&mut self, account_id: &AccountId, actions: u16) {
+        let Some(config) = self.config else {
+            return;
+        };
+        if self.exempt.contains(account_id) {
+            return;
+        }
+        let (mut counter, elapsed) = self.rolled_counter(account_id, &config);
+        let used = Self::weighted_count(&counter, elapsed, &config);
+        ensure!(
+            used + actions as u64 <= config.max_actions as u64,
+            LimitExceeded,
+            "Rate limit exceeded: {} per {}s",
+            config.max_actions,
+            config.window_seconds
+        );
+
+        counter.current = counter.current.saturating_add(actions);
+        self.counters.insert(account_id, &counter);
+    }
+
+    // Records `account_id` can still create in the current window
+    pub fn remaining(&self, account_id: &AccountId) -> Option<u16> {
+        let config = self.config.filter(|_| !self.exempt.contains(account_id))?;
+        let (counter, elapsed) = self.rolled_counter(account_id, &config);
+        let used = Self::weighted_count(&counter, elapsed, &config);
+        Some((config.max_actions as u64).saturating_sub(used) as u16)
+    }
+
+    pub fn set_config(&mut self, config: Option<RateLimitConfig>) {
+        if let Some(config) = config {
+            ensure!(
+                config.max_actions > 0 && config.window_seconds > 0,
+                InvalidArgument,
+                "Rate limit must allow at least one action per non-empty window"
+            );
+        }
+        self.config = config;
+    }
+
+    pub fn set_exempt(&mut self, account_id: AccountId, exempt: bool) {
+        if !exempt {
+            self.exempt.retain(|a| a != &account_id);
+        } else if !self.exempt.contains(&account_id) {
+            ensure!(self.exempt.len() < MAX_RATE_LIMIT_EXEMPTIONS, LimitExceeded, "Exemption limit reached");
+            self.exempt.push(account_id);
+        }
+    }
+
+    // The account's counter moved to the current window, with the seconds elapsed in it
+    fn rolled_counter(&self, account_id: &AccountId, config: &RateLimitConfig) -> (WindowCounter, u64) {
+        let now_seconds = env::block_timestamp() / 1_000_000_000;
+        let window_seconds = config.window_seconds as u64;
+        let window = (now_seconds / window_seconds) as u32;
+        let elapsed = now_seconds % window_seconds;
+
+        let stored = self.counters.get(account_id).unwrap_or_default();
+        let counter = match window.checked_sub(stored.window) {
+            Some(0) => stored,
+            Some(1) => WindowCounter { window, current: 0, previous: stored.current },
+            _ => WindowCounter { window, current: 0, previous: 0 },
+        };
+        (counter, elapsed)
+    }
+
+    fn weighted_count(counter: &WindowCounter, elapsed: u64, config: &RateLimitConfig) -> u64 {
+        let window_seconds = config.window_seconds as u64;
+        let overlap = window_seconds - elapsed;
+        counter.previous as u64 * overlap / window_seconds + counter.current as u64
+    }
+}
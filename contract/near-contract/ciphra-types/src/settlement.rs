@@ -0,0 +1,209 @@
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::collections::LookupMap;
+use near_sdk::json_types::U128;
+use near_sdk::{env, AccountId, IntoStorageKey, NearToken, Promise};
+
+use crate::{decimal_borsh, ensure};
+
+// NEAR transfer recorded before its Promise goes out and kept until the callback resolves it
+#[derive(BorshDeserialize, BorshSerialize, Clone)]
+pub struct Settlement {
+    pub settlement_id: u64,
+    pub receiver: AccountId,
+    #[borsh(serialize_with = "decimal_borsh::serialize", deserialize_with = "decimal_borsh::deserialize")]
+    pub amount: U128,
+    pub created_at: u64,
+}
+
+// Failure-safe money movement shared by the payment contracts: record the intent, send the
+// transfer, then confirm it or roll it back from the contract's private callback. Rolled
+// back amounts either go to a fallback payee or become claimable by the receiver, so a
+// failed transfer never strands funds in the contract.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct SettlementLedger {
+    pub pending: LookupMap<u64, Settlement>,
+    pub claimable: LookupMap<AccountId, u128>,
+    pub next_settlement_id: u64,
+    pub total_pending: u128,
+    pub total_claimable: u128,
+}
+
+impl SettlementLedger {
+    pub fn new<P: IntoStorageKey, C: IntoStorageKey>(pending_prefix: P, claimable_prefix: C) -> Self {
+        Self {
+            pending: LookupMap::new(pending_prefix),
+            claimable: LookupMap::new(claimable_prefix),
+            next_settlement_id: 0,
+            total_pending: 0,
+            total_claimable: 0,
+        }
+    }
+
+    // Records the intent and sends the transfer; `callback` builds the contract call that
+    // passes the settlement id back to `resolve`
+    pub fn send(
+        &mut self,
+        receiver: AccountId,
+        amount_yocto: u128,
+        callback: impl FnOnce(u64) -> Promise,
+    ) -> Promise {
+        let transfer = Promise::new(receiver.clone()).transfer(NearToken::from_yoctonear(amount_yocto));
+        self.send_call(receiver, amount_yocto, transfer, callback)
+    }
+
+    // Like `send`, but delivers through `call` - a payable contract call (e.g. a fee
+    // contract's `deposit_fees`) that must attach exactly `amount_yocto`
+    pub fn send_call(
+        &mut self,
+        receiver: AccountId,
+        amount_yocto: u128,
+        call: Promise,
+        callback: impl FnOnce(u64) -> Promise,
+    ) -> Promise {
+        let settlement_id = self.next_settlement_id;
+        self.next_settlement_id += 1;
+        self.pending.insert(
+            &settlement_id,
+            &Settlement {
+                settlement_id,
+                receiver,
+                amount: U128(amount_yocto),
+                created_at: env::block_timestamp(),
+            },
+        );
+        self.total_pending += amount_yocto;
+        
+        call.then(callback(settlement_id))
+    }
+
+    // Confirms a delivered settlement, or returns the failed one for the caller to roll back
+    // with `credit` or a fresh `send`. Unknown ids were already resolved.
+    pub fn resolve(&mut self, settlement_id: u64, delivered: bool) -> Option<Settlement> {
+        let settlement = self.pending.remove(&settlement_id)?;
+        self.total_pending -= settlement.amount.0;
+        (!delivered).then_some(settlement)
+    }
+
+    pub fn credit(&mut self, account_id: &AccountId, amount_yocto: u128) {
+        let balance = self.claimable.get(account_id).unwrap_or(0);
+        self.claimable.insert(account_id, &(balance + amount_yocto));
+        self.total_claimable += amount_yocto;
+    }
+
+    // Takes the whole claimable balance for the caller to `send` again
+    pub fn claim(&mut self, account_id: &AccountId) -> u128 {
+        let amount_yocto = self.claimable.remove(account_id).unwrap_or(0);
+        ensure!(amount_yocto > 0, InvalidState, "Nothing to claim");
+        self.total_claimable -= amount_yocto;
+        amount_yocto
+    }
+
+    pub fn claimable_of(&self, account_id: &AccountId) -> u128 {
+        self.claimable.get(account_id).unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ciphra_testing as host;
+    use near_sdk::Gas;
+
+    fn ledger() -> SettlementLedger {
+        SettlementLedger::new(b"p".to_vec(), b"c".to_vec())
+    }
+
+    fn account(name: &str) -> AccountId {
+        name.parse().unwrap()
+    }
+
+    fn send(ledger: &mut SettlementLedger, receiver: &str, amount: u128) -> u64 {
+        let settlement_id = ledger.next_settlement_id;
+        ledger.send(account(receiver), amount, |id| {
+            Promise::new(account(host::CONTRACT)).function_call(
+                "on_settlement".to_string(),
+                format!("{{\"settlement_id\":{}}}", id).into_bytes(),
+                NearToken::from_yoctonear(0),
+                Gas::from_tgas(5),
+            )
+        });
+        settlement_id
+    }
+
+    #[test]
+    fn send_transfers_and_tracks_pending() {
+        let mut ledger = ledger();
+        let id = send(&mut ledger, "bob.near", 700);
+        
+        assert_eq!(host::transfers(), vec![("bob.near".to_string(), 700)]);
+        assert_eq!(host::function_calls(), vec![(host::CONTRACT.to_string(), "on_settlement".to_string())]);
+        assert_eq!(ledger.total_pending, 700);
+        assert_eq!(ledger.pending.get(&id).unwrap().receiver, account("bob.near"));
+    }
+
+    #[test]
+    fn send_call_tracks_the_call_as_pending() {
+        let mut ledger = ledger();
+        let deposit = Promise::new(account("treasury.near")).function_call(
+            "deposit_fees".to_string(),
+            Vec::new(),
+            NearToken::from_yoctonear(700),
+            Gas::from_tgas(5),
+        );
+        let id = ledger.next_settlement_id;
+        ledger.send_call(account("treasury.near"), 700, deposit, |_| {
+            Promise::new(account(host::CONTRACT)).function_call(
+                "on_settlement".to_string(),
+                Vec::new(),
+                NearToken::from_yoctonear(0),
+                Gas::from_tgas(5),
+            )
+        });
+        
+        assert!(host::transfers().is_empty());
+        assert_eq!(host::function_calls()[0], ("treasury.near".to_string(), "deposit_fees".to_string()));
+        assert_eq!(ledger.total_pending, 700);
+        assert_eq!(ledger.resolve(id, false).unwrap().receiver, account("treasury.near"));
+    }
+
+    #[test]
+    fn delivered_settlement_is_cleared() {
+        let mut ledger = ledger();
+        let id = send(&mut ledger, "bob.near", 700);
+        
+        assert!(ledger.resolve(id, true).is_none());
+        assert_eq!(ledger.total_pending, 0);
+        // A second resolve of the same id is a no-op
+        assert!(ledger.resolve(id, false).is_none());
+    }
+
+    #[test]
+    fn failed_settlement_can_be_credited_and_claimed_once() {
+        let mut ledger = ledger();
+        let id = send(&mut ledger, "bob.near", 700);
+        
+        let failed = ledger.resolve(id, false).unwrap();
+        ledger.credit(&failed.receiver, failed.amount.0);
+        assert_eq!(ledger.total_pending, 0);
+        assert_eq!(ledger.claimable_of(&account("bob.near")), 700);
+        
+        assert_eq!(ledger.claim(&account("bob.near")), 700);
+        assert_eq!(ledger.total_claimable, 0);
+        assert_eq!(ledger.claimable_of(&account("bob.near")), 0);
+    }
+
+    #[test]
+    fn credits_accumulate() {
+        let mut ledger = ledger();
+        ledger.credit(&account("bob.near"), 300);
+        ledger.credit(&account("bob.near"), 400);
+        assert_eq!(ledger.claimable_of(&account("bob.near")), 700);
+        assert_eq!(ledger.total_claimable, 700);
+    }
+
+    #[test]
+    #[should_panic(expected = "Nothing to claim")]
+    fn claim_without_balance_fails() {
+        ledger().claim(&account("bob.near"));
+    }
+}
@@ -0,0 +1,255 @@
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::collections::LookupMap;
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{env, AccountId, IntoStorageKey, NearToken, Promise};
+use schemars::JsonSchema;
+
+use crate::{ensure, ErrorCode, OrFail};
+
+// NEP-145 storage account shared by the payment contracts. Accounts prepay storage; every
+// record they create locks a fixed deposit that is unlocked again when the record is removed.
+#[derive(BorshDeserialize, BorshSerialize, Default)]
+pub struct StorageAccount {
+    pub total: u128,
+    pub locked: u128,
+}
+
+// NEP-145 balance
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StorageBalance {
+    #[schemars(with = "String")]
+    pub total: U128,
+    #[schemars(with = "String")]
+    pub available: U128,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StorageBalanceBounds {
+    #[schemars(with = "String")]
+    pub min: U128,
+    #[schemars(with = "Option<String>")]
+    pub max: Option<U128>,
+}
+
+impl StorageAccount {
+    pub fn available(&self) -> u128 {
+        self.total - self.locked
+    }
+
+    pub fn lock(&mut self, amount_yocto: u128) {
+        ensure!(
+            self.available() >= amount_yocto,
+            InsufficientFunds,
+            "Insufficient storage deposit, call storage_deposit first"
+        );
+        self.locked += amount_yocto;
+    }
+
+    // Returns the amount actually unlocked
+    pub fn unlock(&mut self, amount_yocto: u128) -> u128 {
+        let unlocked_yocto = self.locked.min(amount_yocto);
+        self.locked -= unlocked_yocto;
+        unlocked_yocto
+    }
+
+    pub fn to_balance(&self) -> StorageBalance {
+        StorageBalance {
+            total: U128(self.total),
+            available: U128(self.available()),
+        }
+    }
+}
+
+// The NEP-145 accounts of one contract. Each contract passes its own per-record deposit, which
+// is also the minimum balance; the borsh layout is that of the wrapped map, so contracts that
+// stored the map directly read it back unchanged.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct StorageAccounts {
+    pub accounts: LookupMap<AccountId, StorageAccount>,
+}
+
+impl StorageAccounts {
+    pub fn new<S: IntoStorageKey>(prefix: S) -> Self {
+        Self { accounts: LookupMap::new(prefix) }
+    }
+
+    pub fn get(&self, account_id: &AccountId) -> Option<StorageAccount> {
+        self.accounts.get(account_id)
+    }
+
+    // Credits the attached deposit and returns the amount kept. Registration only keeps enough
+    // for a single record and refunds the rest.
+    pub fn deposit(
+        &mut self,
+        account_id: &AccountId,
+        registration_only: bool,
+        record_deposit_yocto: u128,
+    ) -> (StorageBalance, u128) {
+        let mut amount_yocto = env::attached_deposit().as_yoctonear();
+        let mut storage = self.accounts.get(account_id).unwrap_or_default();
+
+        if registration_only {
+            let keep_yocto = record_deposit_yocto.saturating_sub(storage.total).min(amount_yocto);
+            let refund_yocto = amount_yocto - keep_yocto;
+            if refund_yocto > 0 {
+                Promise::new(env::predecessor_account_id()).transfer(NearToken::from_yoctonear(refund_yocto));
+            }
+            amount_yocto = keep_yocto;
+        } else {
+            ensure!(amount_yocto > 0, InsufficientFunds, "Must attach NEAR tokens");
+        }
+
+        storage.total += amount_yocto;
+        self.accounts.insert(account_id, &storage);
+
+        env::log_str(&format!(
+            "Storage deposit: {} | Amount: {} | Total: {}",
+            account_id, amount_yocto, storage.total
+        ));
+
+        (storage.to_balance(), amount_yocto)
+    }
+
+    // Sends the caller up to their unlocked balance, all of it when no amount is given, and
+    // returns the amount withdrawn
+    pub fn withdraw(&mut self, amount: Option<U128>) -> (StorageBalance, u128) {
+        near_sdk::assert_one_yocto();
+        let account_id = env::predecessor_account_id();
+        let mut storage = self.accounts.get(&account_id).or_fail(ErrorCode::NotFound, "Account not registered");
+
+        let available_yocto = storage.available();
+        let amount_yocto = amount.map(|amount| amount.0).unwrap_or(available_yocto);
+        ensure!(amount_yocto <= available_yocto, InsufficientFunds, "Exceeds available storage balance");
+
+        if amount_yocto > 0 {
+            storage.total -= amount_yocto;
+            self.accounts.insert(&account_id, &storage);
+            Promise::new(account_id.clone()).transfer(NearToken::from_yoctonear(amount_yocto));
+        }
+
+        env::log_str(&format!("Storage withdrawal: {} | Amount: {}", account_id, amount_yocto));
+
+        (storage.to_balance(), amount_yocto)
+    }
+
+    // Removes the caller's account and refunds its balance. Records can't be removed on the
+    // caller's behalf, so accounts with locked storage stay registered and `force` is refused.
+    // Returns the amount refunded, None when the caller wasn't registered.
+    pub fn unregister(&mut self, force: bool) -> Option<u128> {
+        near_sdk::assert_one_yocto();
+        let account_id = env::predecessor_account_id();
+        let storage = self.accounts.get(&account_id)?;
+        ensure!(!force, InvalidArgument, "Forced unregistration is not supported");
+        ensure!(storage.locked == 0, InvalidState, "Account still has records holding storage");
+
+        self.accounts.remove(&account_id);
+        if storage.total > 0 {
+            Promise::new(account_id.clone()).transfer(NearToken::from_yoctonear(storage.total));
+        }
+
+        env::log_str(&format!("Storage unregistered: {} | Refund: {}", account_id, storage.total));
+
+        Some(storage.total)
+    }
+
+    pub fn balance_of(&self, account_id: &AccountId) -> Option<StorageBalance> {
+        self.accounts.get(account_id).map(|storage| storage.to_balance())
+    }
+
+    pub fn bounds(record_deposit_yocto: u128) -> StorageBalanceBounds {
+        StorageBalanceBounds { min: U128(record_deposit_yocto), max: None }
+    }
+
+    pub fn lock(&mut self, account_id: &AccountId, amount_yocto: u128) {
+        let mut storage = self.accounts.get(account_id).unwrap_or_default();
+        storage.lock(amount_yocto);
+        self.accounts.insert(account_id, &storage);
+    }
+
+    // Returns the amount actually unlocked, nothing for unregistered accounts
+    pub fn unlock(&mut self, account_id: &AccountId, amount_yocto: u128) -> u128 {
+        let Some(mut storage) = self.accounts.get(account_id) else {
+            return 0;
+        };
+        let unlocked_yocto = storage.unlock(amount_yocto);
+        self.accounts.insert(account_id, &storage);
+        unlocked_yocto
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ciphra_testing as host;
+
+    const RECORD: u128 = 10 * host::NEAR / 1_000;
+
+    fn account(name: &str) -> AccountId {
+        name.parse().unwrap()
+    }
+
+    fn funded(total: u128) -> StorageAccounts {
+        let mut accounts = StorageAccounts::new(b"s".to_vec());
+        host::set_caller("alice.near");
+        host::set_deposit(total);
+        accounts.deposit(&account("alice.near"), false, RECORD);
+        host::clear_receipts();
+        accounts
+    }
+
+    #[test]
+    fn registration_only_refunds_the_excess() {
+        let mut accounts = StorageAccounts::new(b"s".to_vec());
+        host::set_caller("alice.near");
+        host::set_deposit(3 * RECORD);
+        let (balance, kept) = accounts.deposit(&account("alice.near"), true, RECORD);
+
+        assert_eq!(kept, RECORD);
+        assert_eq!(balance.total.0, RECORD);
+        assert_eq!(host::transfers(), vec![("alice.near".to_string(), 2 * RECORD)]);
+    }
+
+    #[test]
+    fn withdraw_leaves_locked_storage() {
+        let mut accounts = funded(3 * RECORD);
+        accounts.lock(&account("alice.near"), RECORD);
+        host::set_deposit(1);
+        let (balance, withdrawn) = accounts.withdraw(None);
+
+        assert_eq!(withdrawn, 2 * RECORD);
+        assert_eq!(balance.total.0, RECORD);
+        assert_eq!(balance.available.0, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Insufficient storage deposit")]
+    fn lock_above_balance() {
+        let mut accounts = funded(RECORD);
+        accounts.lock(&account("alice.near"), RECORD);
+        accounts.lock(&account("alice.near"), RECORD);
+    }
+
+    #[test]
+    fn unregister_refunds_the_balance() {
+        let mut accounts = funded(2 * RECORD);
+        accounts.lock(&account("alice.near"), RECORD);
+        assert_eq!(accounts.unlock(&account("alice.near"), RECORD), RECORD);
+        host::set_deposit(1);
+
+        assert_eq!(accounts.unregister(false), Some(2 * RECORD));
+        assert!(accounts.balance_of(&account("alice.near")).is_none());
+        assert_eq!(host::transfers(), vec![("alice.near".to_string(), 2 * RECORD)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Account still has records holding storage")]
+    fn unregister_with_locked_storage() {
+        let mut accounts = funded(2 * RECORD);
+        accounts.lock(&account("alice.near"), RECORD);
+        host::set_deposit(1);
+        accounts.unregister(false);
+    }
+}
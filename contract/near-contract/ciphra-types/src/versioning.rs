@@ -0,0 +1,121 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::UnorderedMap;
+use near_sdk::env;
+use std::fmt::Debug;
+
+use crate::{ErrorCode, OrFail};
+
+// Raw storage key holding the layout version, kept outside the contract struct so any layout can read it
+pub const STATE_VERSION_KEY: &[u8] = b"STATE_VERSION";
+
+// Raw storage key near-sdk keeps the contract struct under
+const CONTRACT_STATE_KEY: &[u8] = b"STATE";
+
+// Upgrade path shared by the contracts. Each contract keeps a `StateVersion` enum with one
+// variant per layout, appended with every layout change, and implements `migrate_from` for
+// the layouts it can still upgrade from. Its `#[init(ignore_state)] migrate()` then only
+// calls `migrate_state`, and `new()` calls `write_state_version`.
+pub trait VersionedState: BorshDeserialize + BorshSerialize + Sized {
+    type Version: BorshDeserialize + BorshSerialize + PartialEq + Debug;
+
+    // Layout written by this build
+    const CURRENT_VERSION: Self::Version;
+    // Layout assumed for deployments that predate versioning and never wrote the key
+    const UNVERSIONED: Self::Version;
+
+    // Rebuilds the current state from an older layout still in storage
+    fn migrate_from(version: &Self::Version) -> Self;
+
+    // Runs before any state is read, e.g. to refuse layouts that need a staged upgrade
+    fn pre_migrate(_from: &Self::Version) {}
+
+    // Runs on the migrated state, e.g. to backfill indexes or defaults for new fields
+    fn post_migrate(&mut self, _from: &Self::Version) {}
+}
+
+pub fn stored_state_version<T: VersionedState>() -> T::Version {
+    env::storage_read(STATE_VERSION_KEY)
+        .map(|bytes| T::Version::try_from_slice(&bytes).or_fail(ErrorCode::Internal, "Invalid state version"))
+        .unwrap_or(T::UNVERSIONED)
+}
+
+pub fn write_state_version<T: VersionedState>() {
+    env::storage_write(
+        STATE_VERSION_KEY,
+        &borsh::to_vec(&T::CURRENT_VERSION).or_fail(ErrorCode::Internal, "Failed to serialize version"),
+    );
+}
+
+// Reads state from whichever version is stored, upgrades it and stamps the current version.
+// Re-running it on current state is a no-op apart from the hooks.
+pub fn migrate_state<T: VersionedState>() -> T {
+    let from = stored_state_version::<T>();
+    T::pre_migrate(&from);
+
+    let mut state = if from == T::CURRENT_VERSION {
+        env::state_read().or_fail(ErrorCode::InvalidState, "Contract not initialized")
+    } else {
+        T::migrate_from(&from)
+    };
+    state.post_migrate(&from);
+    write_state_version::<T>();
+
+    env::log_str(&format!("State migrated: {:?} | To: {:?}", from, T::CURRENT_VERSION));
+
+    state
+}
+
+// Takes every entry out of a collection stored under an old value layout. Old and new layouts
+// usually share a storage prefix, so all entries are read before any is rewritten.
+pub fn drain_legacy<K, V>(map: &mut UnorderedMap<K, V>) -> Vec<(K, V)>
+where
+    K: BorshSerialize + BorshDeserialize,
+    V: BorshSerialize + BorshDeserialize,
+{
+    let entries = map.to_vec();
+    map.clear();
+    entries
+}
+
+// Reads state written before fields were appended to the end of the contract struct, taking
+// their values from `appended`. Only valid when the new layout is the stored one plus
+// trailing fields.
+pub fn state_read_appending<T: BorshDeserialize>(appended: &impl BorshSerialize) -> T {
+    state_read_rewriting(|_| (), appended).0
+}
+
+// Like `state_read_appending`, after `rewrite` has edited the stored bytes, e.g. with
+// `take_stored_field` for fields the new layout dropped. Returns what `rewrite` returned.
+pub fn state_read_rewriting<T: BorshDeserialize, R>(
+    rewrite: impl FnOnce(&mut Vec<u8>) -> R,
+    appended: &impl BorshSerialize,
+) -> (T, R) {
+    let mut bytes = env::storage_read(CONTRACT_STATE_KEY).or_fail(ErrorCode::InvalidState, "Contract not initialized");
+    let rewritten = rewrite(&mut bytes);
+    bytes.extend(borsh::to_vec(appended).or_fail(ErrorCode::Internal, "Failed to serialize appended fields"));
+    let state =
+        T::try_from_slice(&bytes).or_fail(ErrorCode::Internal, "Stored state does not match the previous layout");
+    (state, rewritten)
+}
+
+// Cuts a dropped field out of serialized state and returns its value. `Head` is a tuple of the
+// types stored ahead of it, only decoded to find where the field starts.
+pub fn take_stored_field<Head: BorshDeserialize, Field: BorshDeserialize>(bytes: &mut Vec<u8>) -> Field {
+    let mut rest = &bytes[..];
+    Head::deserialize(&mut rest).or_fail(ErrorCode::Internal, "Stored state does not match the previous layout");
+    let start = bytes.len() - rest.len();
+    let field = Field::deserialize(&mut rest).or_fail(ErrorCode::Internal, "Stored field does not match its layout");
+    let end = bytes.len() - rest.len();
+    bytes.drain(start..end);
+    field
+}
+
+// Inserts a field into serialized state right after the fields in `Head`, e.g. one appended
+// to a struct nested mid-layout. The counterpart of `take_stored_field`.
+pub fn insert_stored_field<Head: BorshDeserialize>(bytes: &mut Vec<u8>, field: &impl BorshSerialize) {
+    let mut rest = &bytes[..];
+    Head::deserialize(&mut rest).or_fail(ErrorCode::Internal, "Stored state does not match the previous layout");
+    let start = bytes.len() - rest.len();
+    let field = borsh::to_vec(field).or_fail(ErrorCode::Internal, "Failed to serialize inserted field");
+    bytes.splice(start..start, field);
+}
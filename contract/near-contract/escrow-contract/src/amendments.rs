@@ -0,0 +1,120 @@
+use crate::*;
+
+// Upper bound on accepted amendments kept per escrow
+const MAX_AMENDMENTS: usize = 10;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Amendment {
+    pub proposed_by: String,
+    pub release_time: Option<u64>,   // Extended release time
+    pub beneficiary: Option<String>, // Replacement beneficiary
+    pub arbiter: Option<String>,     // Arbiter to add or replace
+    pub proposed_at: u64,
+    pub accepted_at: Option<u64>,
+}
+
+#[near_bindgen]
+impl EscrowContract {
+    // Either party proposes changes to an active escrow; a new proposal replaces a pending one
+    pub fn propose_amendment(
+        &mut self,
+        escrow_id: String,
+        release_time: Option<u64>,
+        beneficiary: Option<AccountId>,
+        arbiter: Option<AccountId>,
+    ) -> Amendment {
+        let mut escrow = self.escrows.get(&escrow_id).or_fail(ErrorCode::NotFound, "Escrow not found");
+        
+        let caller = env::predecessor_account_id();
+        ensure!(
+            caller.as_str() == escrow.depositor || caller.as_str() == escrow.beneficiary,
+            Unauthorized,
+            "Only parties can propose amendments"
+        );
+        ensure!(
+            matches!(escrow.status, EscrowStatus::Active),
+            InvalidState,
+            "Escrow not active"
+        );
+        ensure!(
+            release_time.is_some() || beneficiary.is_some() || arbiter.is_some(),
+            InvalidState,
+            "Amendment has no changes"
+        );
+        if let Some(release_time) = release_time {
+            ensure!(release_time > escrow.release_time, InvalidArgument, "Release time can only be extended");
+        }
+        ensure!(
+            beneficiary.is_none() || escrow.payees.is_empty(),
+            InvalidState,
+            "Split escrows cannot change beneficiary"
+        );
+        ensure!(escrow.amendments.len() < MAX_AMENDMENTS, LimitExceeded, "Amendment limit reached");
+        
+        let amendment = Amendment {
+            proposed_by: caller.to_string(),
+            release_time,
+            beneficiary: beneficiary.map(|b| b.to_string()),
+            arbiter: arbiter.map(|a| a.to_string()),
+            proposed_at: env::block_timestamp(),
+            accepted_at: None,
+        };
+        escrow.pending_amendment = Some(amendment.clone());
+        self.escrows.insert(&escrow_id, &escrow);
+        
+        env::log_str(&format!("Amendment proposed: {} | By: {}", escrow_id, caller));
+        
+        amendment
+    }
+
+    // The other party accepts, applying the changes and recording them in the history
+    pub fn accept_amendment(&mut self, escrow_id: String) -> Escrow {
+        let mut escrow = self.escrows.get(&escrow_id).or_fail(ErrorCode::NotFound, "Escrow not found");
+        let mut amendment = escrow.pending_amendment.take().or_fail(ErrorCode::InvalidState, "No pending amendment");
+        
+        let caller = env::predecessor_account_id();
+        ensure!(
+            caller.as_str() == escrow.depositor || caller.as_str() == escrow.beneficiary,
+            Unauthorized,
+            "Only parties can accept amendments"
+        );
+        ensure!(caller.as_str() != amendment.proposed_by, Unauthorized, "Proposer cannot accept");
+        ensure!(
+            matches!(escrow.status, EscrowStatus::Active),
+            InvalidState,
+            "Escrow not active"
+        );
+        
+        if let Some(release_time) = amendment.release_time {
+            escrow.release_time = release_time;
+        }
+        if let Some(beneficiary) = amendment.beneficiary.as_ref() {
+            let previous = std::mem::replace(&mut escrow.beneficiary, beneficiary.clone());
+            self.reindex_beneficiary(&escrow_id, &previous, beneficiary);
+            // The old beneficiary's payout address doesn't carry over
+            escrow.payout_address = None;
+        }
+        if let Some(arbiter) = amendment.arbiter.as_ref() {
+            escrow.arbiter = Some(arbiter.clone());
+        }
+        
+        amendment.accepted_at = Some(env::block_timestamp());
+        escrow.amendments.push(amendment);
+        self.escrows.insert(&escrow_id, &escrow);
+        
+        env::log_str(&format!("Amendment accepted: {} | By: {}", escrow_id, caller));
+        emit_event("amended", json!({
+            "escrow_id": escrow_id,
+            "release_time": escrow.release_time,
+            "beneficiary": escrow.beneficiary,
+            "arbiter": escrow.arbiter,
+        }));
+        
+        escrow
+    }
+
+    pub fn get_amendments(&self, escrow_id: String) -> Vec<Amendment> {
+        self.escrows.get(&escrow_id).map(|escrow| escrow.amendments).unwrap_or_default()
+    }
+}
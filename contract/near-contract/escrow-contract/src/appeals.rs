@@ -0,0 +1,143 @@
+use crate::*;
+
+// Upper bound on members of the appeal committee
+const MAX_APPEAL_COMMITTEE_SIZE: usize = 20;
+
+// A single arbiter's decision, held until the appeal window closes
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Ruling {
+    pub arbiter: String,
+    pub depositor_bps: u16, // Beneficiary receives the remainder
+    pub ruled_at: u64,
+    pub appeal_deadline: u64,
+}
+
+#[near_bindgen]
+impl EscrowContract {
+    // Either party escalates a pending ruling to the appeal committee, or to the owner if none is set
+    pub fn appeal_ruling(&mut self, escrow_id: String) {
+        let mut escrow = self.escrows.get(&escrow_id).or_fail(ErrorCode::NotFound, "Escrow not found");
+        
+        let caller = env::predecessor_account_id();
+        let depositor: AccountId = escrow.depositor.parse().or_fail(ErrorCode::Internal, "Invalid depositor");
+        ensure!(
+            caller == depositor || escrow.is_payee(&caller),
+            Unauthorized,
+            "Only parties can appeal"
+        );
+        let ruling = escrow.ruling.take().or_fail(ErrorCode::InvalidState, "No ruling to appeal");
+        ensure!(env::block_timestamp() < ruling.appeal_deadline, Expired, "Appeal window has closed");
+        
+        escrow.appealed = true;
+        escrow.committee = self.appeal_committee.iter().map(|a| a.to_string()).collect();
+        escrow.threshold = self.appeal_threshold;
+        escrow.votes = Vec::new();
+        // The appeal gets a full dispute period before the default outcome can apply
+        escrow.dispute_deadline = Some(env::block_timestamp() + self.dispute_period);
+        self.escrows.insert(&escrow_id, &escrow);
+        
+        env::log_str(&format!(
+            "Ruling appealed: {} | By: {} | Arbiter: {}",
+            escrow_id, caller, ruling.arbiter
+        ));
+        emit_event("ruling_appealed", json!({
+            "escrow_id": escrow_id,
+            "appealed_by": caller,
+            "arbiter": ruling.arbiter,
+            "depositor_bps": ruling.depositor_bps,
+            "committee": escrow.committee,
+            "dispute_deadline": escrow.dispute_deadline,
+        }));
+    }
+
+    // Pay out an unappealed ruling once its window closes - callable by anyone
+    pub fn finalize_ruling(&mut self, escrow_id: String) -> Promise {
+        let escrow = self.escrows.get(&escrow_id).or_fail(ErrorCode::NotFound, "Escrow not found");
+        
+        let ruling = escrow.ruling.clone().or_fail(ErrorCode::InvalidState, "No pending ruling");
+        ensure!(env::block_timestamp() >= ruling.appeal_deadline, TooEarly, "Appeal window still open");
+        
+        env::log_str(&format!(
+            "Ruling finalized: {} | Depositor bps: {}",
+            escrow_id, ruling.depositor_bps
+        ));
+        
+        self.settle_split(escrow, ruling.depositor_bps, vec![ruling.arbiter.parse().or_fail(ErrorCode::Internal, "Invalid arbiter")])
+    }
+
+    // Owner settles an appealed dispute directly; final, and no arbiter fee is paid
+    pub fn resolve_appeal(&mut self, escrow_id: String, outcome: DisputeOutcome) -> Promise {
+        assert_owner(&self.owner);
+        let escrow = self.escrows.get(&escrow_id).or_fail(ErrorCode::NotFound, "Escrow not found");
+        
+        ensure!(escrow.appealed, InvalidState, "Escrow has no appeal");
+        ensure!(
+            matches!(escrow.status, EscrowStatus::Disputed),
+            InvalidState,
+            "Escrow is not disputed"
+        );
+        let depositor_bps = outcome.depositor_bps();
+        ensure!(depositor_bps <= 10000, LimitExceeded, "Share cannot exceed 10000 bps");
+        
+        self.settle_split(escrow, depositor_bps, Vec::new())
+    }
+
+    pub fn get_ruling(&self, escrow_id: String) -> Option<Ruling> {
+        self.escrows.get(&escrow_id).and_then(|escrow| escrow.ruling)
+    }
+
+    // An empty committee leaves appeals to the owner
+    pub fn set_appeal_config(&mut self, appeal_period_seconds: u64, committee: Vec<AccountId>, threshold: u8) {
+        assert_owner(&self.owner);
+        ensure!(appeal_period_seconds > 0, InvalidArgument, "Appeal period must be positive");
+        ensure!(committee.len() <= MAX_APPEAL_COMMITTEE_SIZE, LimitExceeded, "Appeal committee cannot exceed 20 members");
+        ensure!(
+            committee.is_empty() || (threshold > 0 && threshold as usize <= committee.len()),
+            InvalidArgument,
+            "Threshold must be between 1 and the committee size"
+        );
+        let mut members = committee;
+        members.sort();
+        members.dedup();
+        
+        self.appeal_period = appeal_period_seconds * 1_000_000_000;
+        self.appeal_committee = members;
+        self.appeal_threshold = threshold;
+    }
+
+    pub fn get_appeal_committee(&self) -> Vec<AccountId> {
+        self.appeal_committee.clone()
+    }
+
+    pub fn get_appeal_period(&self) -> u64 {
+        self.appeal_period
+    }
+
+    // Record a single arbiter's decision; funds move through finalize_ruling or the appeal
+    pub(crate) fn propose_ruling(&mut self, mut escrow: Escrow, depositor_bps: u16, arbiter: AccountId) {
+        ensure!(!escrow.appealed, InvalidState, "Dispute is under appeal");
+        ensure!(escrow.ruling.is_none(), InvalidState, "Ruling already pending");
+        
+        let now = env::block_timestamp();
+        let ruling = Ruling {
+            arbiter: arbiter.to_string(),
+            depositor_bps,
+            ruled_at: now,
+            appeal_deadline: now + self.appeal_period,
+        };
+        escrow.ruling = Some(ruling.clone());
+        self.escrows.insert(&escrow.escrow_id, &escrow);
+        
+        env::log_str(&format!(
+            "Ruling proposed: {} | Arbiter: {} | Depositor bps: {}",
+            escrow.escrow_id, arbiter, depositor_bps
+        ));
+        emit_event("ruling_proposed", json!({
+            "escrow_id": escrow.escrow_id,
+            "arbiter": arbiter,
+            "depositor_bps": depositor_bps,
+            "appeal_deadline": ruling.appeal_deadline,
+        }));
+    }
+}
@@ -0,0 +1,97 @@
+use crate::*;
+
+// Upper bound on attachments per escrow
+const MAX_ATTACHMENTS: usize = 10;
+
+// Longest accepted CID and MIME type
+const MAX_CID_LENGTH: usize = 128;
+const MAX_MIME_LENGTH: usize = 128;
+
+const BASE58_ALPHABET: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+// Off-chain document backing the escrow (contract, invoice), addressed by its IPFS CID
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ContentRef {
+    pub cid: String,
+    pub mime: String,
+    pub size: u64, // Bytes
+}
+
+impl ContentRef {
+    pub(crate) fn validate(&self) {
+        ensure!(is_valid_cid(&self.cid), InvalidArgument, "Invalid CID");
+        let mut parts = self.mime.split('/');
+        let well_formed = matches!(
+            (parts.next(), parts.next(), parts.next()),
+            (Some(kind), Some(subtype), None) if !kind.is_empty() && !subtype.is_empty()
+        );
+        ensure!(
+            well_formed && self.mime.len() <= MAX_MIME_LENGTH && self.mime.is_ascii(),
+            InvalidArgument,
+            "Invalid MIME type"
+        );
+        ensure!(self.size > 0, InvalidArgument, "Attachment size must be positive");
+    }
+}
+
+// CIDv0 is a base58btc sha256 multihash ("Qm..."); CIDv1 is accepted in its default base32 form ("b...")
+fn is_valid_cid(cid: &str) -> bool {
+    if let Some(rest) = cid.strip_prefix("Qm") {
+        return cid.len() == 46 && rest.chars().all(|c| BASE58_ALPHABET.contains(c));
+    }
+    match cid.strip_prefix('b') {
+        Some(rest) => {
+            cid.len() <= MAX_CID_LENGTH
+                && rest.len() >= 8
+                && rest.chars().all(|c| c.is_ascii_lowercase() || ('2'..='7').contains(&c))
+        }
+        None => false,
+    }
+}
+
+#[near_bindgen]
+impl EscrowContract {
+    // Either party references a document backing the escrow while it is open
+    pub fn add_attachment(&mut self, escrow_id: String, attachment: ContentRef) {
+        let mut escrow = self.escrows.get(&escrow_id).or_fail(ErrorCode::NotFound, "Escrow not found");
+        
+        let caller = env::predecessor_account_id();
+        ensure!(
+            caller.as_str() == escrow.depositor || escrow.is_payee(&caller),
+            Unauthorized,
+            "Only parties can add attachments"
+        );
+        ensure!(
+            matches!(escrow.status, EscrowStatus::Active | EscrowStatus::Disputed),
+            InvalidState,
+            "Escrow already settled"
+        );
+        attachment.validate();
+        ensure!(escrow.attachments.len() < MAX_ATTACHMENTS, LimitExceeded, "Attachment limit reached");
+        ensure!(
+            escrow.attachments.iter().all(|a| a.cid != attachment.cid),
+            InvalidState,
+            "Attachment already added"
+        );
+        
+        escrow.attachments.push(attachment.clone());
+        self.escrows.insert(&escrow_id, &escrow);
+        
+        env::log_str(&format!(
+            "Attachment added: {} | By: {} | CID: {}",
+            escrow_id, caller, attachment.cid
+        ));
+        emit_event("attachment_added", json!({
+            "escrow_id": escrow_id,
+            "added_by": caller,
+            "cid": attachment.cid,
+            "mime": attachment.mime,
+            "size": attachment.size,
+        }));
+    }
+
+    pub fn get_attachments(&self, escrow_id: String) -> Vec<ContentRef> {
+        self.escrows.get(&escrow_id).map(|escrow| escrow.attachments).unwrap_or_default()
+    }
+}
@@ -0,0 +1,64 @@
+use crate::*;
+
+// Upper bound on escrows opened by one batch call
+const MAX_BATCH_SIZE: usize = 20;
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct EscrowParams {
+    pub escrow_id: String,
+    pub beneficiary: AccountId,
+    pub amount: U128,
+    pub release_time: u64,
+    pub arbiter: Option<AccountId>,
+    pub metadata: EscrowMetadata,
+    pub arbiter_fee: Option<ArbiterFee>,
+    pub required_proofs: Option<Vec<String>>,
+}
+
+#[near_bindgen]
+impl EscrowContract {
+    // Open several escrows from one deposit - the attached amount must equal the sum of their amounts
+    #[payable]
+    pub fn create_escrows_batch(&mut self, escrows: Vec<EscrowParams>) -> Vec<Escrow> {
+        ensure!(
+            !escrows.is_empty() && escrows.len() <= MAX_BATCH_SIZE,
+            InvalidArgument,
+            "Batch must have 1-20 escrows"
+        );
+        ensure!(escrows.iter().all(|params| params.amount.0 > 0), InvalidArgument, "Escrow amounts must be positive");
+        let total_yocto: u128 = escrows.iter().map(|params| params.amount.0).sum();
+        ensure!(
+            total_yocto == env::attached_deposit().as_yoctonear(),
+            InvalidArgument,
+            "Deposit must equal the sum of escrow amounts"
+        );
+        
+        let depositor = env::predecessor_account_id();
+        let count = escrows.len();
+        let created: Vec<Escrow> = escrows
+            .into_iter()
+            .map(|params| {
+                self.new_escrow(
+                    params.escrow_id,
+                    depositor.clone(),
+                    params.amount.0,
+                    None,
+                    params.beneficiary,
+                    params.release_time,
+                    params.arbiter,
+                    params.metadata,
+                    params.arbiter_fee,
+                    params.required_proofs.unwrap_or_default(),
+                )
+            })
+            .collect();
+        
+        env::log_str(&format!(
+            "Escrow batch created: {} escrows | Total: {}",
+            count, total_yocto
+        ));
+        
+        created
+    }
+}
@@ -0,0 +1,219 @@
+use crate::*;
+use near_sdk::{Gas, PromiseError};
+use proofs::check_receipt_proof;
+
+const GAS_FOR_CHALLENGE_CALLBACK: Gas = Gas::from_tgas(20);
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct VerifierBond {
+    #[borsh(serialize_with = "decimal_borsh::serialize", deserialize_with = "decimal_borsh::deserialize")]
+    #[schemars(with = "String")]
+    pub amount: U128,
+    pub last_approval: u64, // Bond stays locked for a challenge window after this
+}
+
+#[near_bindgen]
+impl EscrowContract {
+    // Verifiers bond NEAR before the owner can add them; the bond backs their approvals
+    #[payable]
+    pub fn bond_verifier(&mut self) -> VerifierBond {
+        let verifier = env::predecessor_account_id();
+        let deposit = env::attached_deposit().as_yoctonear();
+        ensure!(deposit > 0, InsufficientFunds, "Must attach NEAR tokens");
+        
+        let mut bond = self.verifier_bonds.get(&verifier).unwrap_or(VerifierBond {
+            amount: U128(0),
+            last_approval: 0,
+        });
+        let amount_yocto: u128 = bond.amount.0;
+        bond.amount = U128(amount_yocto + deposit);
+        self.verifier_bonds.insert(&verifier, &bond);
+        
+        env::log_str(&format!("Verifier bonded: {} | Bond: {}", verifier, bond.amount.0));
+        
+        bond
+    }
+
+    // Withdraw once the challenge window on the last approval has closed; active verifiers keep the minimum
+    pub fn withdraw_verifier_bond(&mut self, amount: U128) -> Promise {
+        let verifier = env::predecessor_account_id();
+        let mut bond = self.verifier_bonds.get(&verifier).or_fail(ErrorCode::InvalidState, "No bond");
+        
+        ensure!(
+            env::block_timestamp() >= bond.last_approval + CHALLENGE_WINDOW,
+            TooEarly,
+            "Bond locked during challenge window"
+        );
+        let amount_yocto: u128 = bond.amount.0;
+        ensure!(amount.0 > 0 && amount.0 <= amount_yocto, InvalidArgument, "Invalid withdrawal amount");
+        let remaining = amount_yocto - amount.0;
+        ensure!(
+            !self.trusted_verifiers.contains(&verifier) || remaining >= self.min_verifier_bond,
+            InvalidState,
+            "Trusted verifiers must keep the minimum bond"
+        );
+        
+        bond.amount = U128(remaining);
+        self.verifier_bonds.insert(&verifier, &bond);
+        
+        env::log_str(&format!("Verifier bond withdrawn: {} | Amount: {}", verifier, amount.0));
+        
+        self.settle(verifier, amount.0)
+    }
+
+    pub fn get_verifier_bond(&self, verifier: AccountId) -> Option<VerifierBond> {
+        self.verifier_bonds.get(&verifier)
+    }
+
+    pub fn set_min_verifier_bond(&mut self, amount: U128) {
+        assert_owner(&self.owner);
+        self.min_verifier_bond = amount.0;
+    }
+
+    pub fn get_min_verifier_bond(&self) -> U128 {
+        U128(self.min_verifier_bond)
+    }
+
+    // Anyone can challenge a verifier-approved proof within the window by checking it against a light client
+    pub fn challenge_verification(&mut self, escrow_id: String, proof_id: String) -> PromiseOrValue<bool> {
+        let escrow = self.escrows.get(&escrow_id).or_fail(ErrorCode::NotFound, "Escrow not found");
+        let proof = escrow.proofs.iter().find(|p| p.proof_id == proof_id).or_fail(ErrorCode::NotFound, "Proof not found");
+        
+        ensure!(proof.verified, InvalidState, "Proof not verified");
+        ensure!(!proof.approvals.is_empty(), InvalidState, "Proof was not verified by trusted verifiers");
+        ensure!(
+            env::block_timestamp() < proof.verified_at.unwrap_or(0) + CHALLENGE_WINDOW,
+            Expired,
+            "Challenge window has closed"
+        );
+        let light_client = self
+            .light_clients
+            .get(&proof.chain_id)
+            .or_fail(ErrorCode::NotFound, "No light client to challenge against");
+        
+        match check_receipt_proof(proof, escrow.expected_payment(&proof_id)) {
+            Err(reason) => {
+                self.slash_verification(&escrow_id, &proof_id, reason);
+                PromiseOrValue::Value(true)
+            }
+            Ok((number, block_hash)) => PromiseOrValue::Promise(
+                Self::query_block_hash(light_client, number).then(
+                    Self::ext(env::current_account_id())
+                        .with_static_gas(GAS_FOR_CHALLENGE_CALLBACK)
+                        .on_challenge_block_hash(escrow_id, proof_id, block_hash),
+                ),
+            ),
+        }
+    }
+
+    // Callback - a different finalized hash at that height contradicts the verification.
+    // An unknown block is inconclusive and leaves the verification standing.
+    #[private]
+    pub fn on_challenge_block_hash(
+        &mut self,
+        escrow_id: String,
+        proof_id: String,
+        block_hash: String,
+        #[callback_result] known_hash: Result<Option<String>, PromiseError>,
+    ) -> bool {
+        let contradicted = known_hash
+            .ok()
+            .flatten()
+            .is_some_and(|hash| !hash.trim_start_matches("0x").eq_ignore_ascii_case(&block_hash));
+        if !contradicted {
+            env::log_str(&format!("Challenge rejected: {} | Proof: {}", escrow_id, proof_id));
+            return false;
+        }
+        
+        self.slash_verification(&escrow_id, &proof_id, "Light client contradicts block hash");
+        true
+    }
+
+    // Harmed depositors collect their compensation from the insurance pool
+    pub fn claim_insurance(&mut self) -> Promise {
+        let account = env::predecessor_account_id();
+        let amount_yocto = self.insurance_claims.get(&account).or_fail(ErrorCode::InvalidState, "No insurance claim");
+        self.insurance_claims.remove(&account);
+        
+        env::log_str(&format!("Insurance claimed: {} | Amount: {}", account, amount_yocto));
+        
+        self.settle(account, amount_yocto)
+    }
+
+    pub fn get_insurance_claim(&self, account_id: AccountId) -> U128 {
+        U128(self.insurance_claims.get(&account_id).unwrap_or(0))
+    }
+
+    pub fn get_insurance_pool(&self) -> U128 {
+        U128(self.insurance_pool)
+    }
+
+    pub(crate) fn record_approval(&mut self, verifier: &AccountId) {
+        if let Some(mut bond) = self.verifier_bonds.get(verifier) {
+            bond.last_approval = env::block_timestamp();
+            self.verifier_bonds.insert(verifier, &bond);
+        }
+    }
+
+    pub(crate) fn has_minimum_bond(&self, verifier: &AccountId) -> bool {
+        self.verifier_bonds.get(verifier).is_some_and(|bond| {
+            let amount_yocto: u128 = bond.amount.0;
+            amount_yocto >= self.min_verifier_bond
+        })
+    }
+
+    // Un-verify the proof and move every approving verifier's bond into the insurance pool.
+    // If funds already left a NEAR escrow on the bad proof, the depositor is compensated first.
+    fn slash_verification(&mut self, escrow_id: &str, proof_id: &str, reason: &str) {
+        let mut escrow = self.escrows.get(&escrow_id.to_string()).or_fail(ErrorCode::NotFound, "Escrow not found");
+        let Some(proof) = escrow.proofs.iter_mut().find(|p| p.proof_id == proof_id && p.verified) else {
+            return;
+        };
+        
+        let mut slashed_yocto = 0;
+        for approver in proof.approvals.drain(..) {
+            let approver: AccountId = approver.parse().or_fail(ErrorCode::Internal, "Invalid verifier");
+            if let Some(mut bond) = self.verifier_bonds.get(&approver) {
+                let amount_yocto: u128 = bond.amount.0;
+                slashed_yocto += amount_yocto;
+                bond.amount = U128(0);
+                self.verifier_bonds.insert(&approver, &bond);
+            }
+        }
+        proof.verified = false;
+        proof.verified_at = None;
+        
+        self.proof_verifications.remove(&format!("{}:{}", proof.chain_id, proof.tx_hash));
+        let proof_key = proof.receipt_key();
+        if self.consumed_proofs.get(&proof_key).as_deref() == Some(escrow_id) {
+            self.consumed_proofs.remove(&proof_key);
+        }
+        self.escrows.insert(&escrow_id.to_string(), &escrow);
+        
+        let exposed_yocto: u128 = match escrow.status {
+            _ if escrow.token.is_some() => 0,
+            EscrowStatus::Completed => escrow.amount.0,
+            _ => escrow.released.0,
+        };
+        let compensation_yocto = slashed_yocto.min(exposed_yocto);
+        if compensation_yocto > 0 {
+            let depositor: AccountId = escrow.depositor.parse().or_fail(ErrorCode::Internal, "Invalid depositor");
+            let claim = self.insurance_claims.get(&depositor).unwrap_or(0);
+            self.insurance_claims.insert(&depositor, &(claim + compensation_yocto));
+        }
+        self.insurance_pool += slashed_yocto - compensation_yocto;
+        
+        env::log_str(&format!(
+            "Verification slashed: {} | Proof: {} | Slashed: {} | Reason: {}",
+            escrow_id, proof_id, slashed_yocto, reason
+        ));
+        emit_event("verification_slashed", json!({
+            "escrow_id": escrow_id,
+            "proof_id": proof_id,
+            "slashed": slashed_yocto.to_string(),
+            "compensation": compensation_yocto.to_string(),
+            "reason": reason,
+        }));
+    }
+}
@@ -0,0 +1,127 @@
+use crate::*;
+
+// Time after release_time before anyone may refund an abandoned escrow (30 days)
+const PUBLIC_REFUND_GRACE: u64 = 30 * 86_400 * 1_000_000_000;
+
+// Bounty paid to the caller of public_refund (0.1% of the refund)
+const PUBLIC_REFUND_BOUNTY_BPS: u128 = 10;
+
+#[near_bindgen]
+impl EscrowContract {
+    // Either party proposes unwinding the escrow before release time
+    pub fn propose_cancel(&mut self, escrow_id: String) {
+        let mut escrow = self.escrows.get(&escrow_id).or_fail(ErrorCode::NotFound, "Escrow not found");
+        
+        let caller = env::predecessor_account_id();
+        ensure!(
+            caller.as_str() == escrow.depositor || caller.as_str() == escrow.beneficiary,
+            Unauthorized,
+            "Only parties can propose cancellation"
+        );
+        Self::assert_cancellable(&escrow);
+        
+        escrow.cancel_proposed_by = Some(caller.to_string());
+        self.escrows.insert(&escrow_id, &escrow);
+        
+        env::log_str(&format!("Cancellation proposed: {} | By: {}", escrow_id, caller));
+    }
+
+    // The other party agrees; unreleased funds go back to the depositor
+    pub fn accept_cancel(&mut self, escrow_id: String) -> Promise {
+        let mut escrow = self.escrows.get(&escrow_id).or_fail(ErrorCode::NotFound, "Escrow not found");
+        let proposed_by = escrow.cancel_proposed_by.take().or_fail(ErrorCode::InvalidState, "No pending cancellation");
+        
+        let caller = env::predecessor_account_id();
+        ensure!(
+            caller.as_str() == escrow.depositor || caller.as_str() == escrow.beneficiary,
+            Unauthorized,
+            "Only parties can accept cancellation"
+        );
+        ensure!(caller.as_str() != proposed_by, Unauthorized, "Proposer cannot accept");
+        Self::assert_cancellable(&escrow);
+        
+        self.settle_escrow(&mut escrow, EscrowStatus::Refunded);
+        self.escrows.insert(&escrow_id, &escrow);
+        
+        let amount_yocto = Self::unreleased_amount(&escrow);
+        
+        env::log_str(&format!("Escrow cancelled: {} | Amount: {}", escrow_id, amount_yocto));
+        emit_event("cancelled", json!({
+            "escrow_id": escrow_id,
+            "depositor": escrow.depositor,
+            "amount": amount_yocto.to_string(),
+        }));
+        
+        let depositor: AccountId = escrow.depositor.parse().or_fail(ErrorCode::Internal, "Invalid depositor");
+        self.payout(&escrow, depositor, amount_yocto)
+    }
+
+    // Anyone can return funds from an escrow long past release_time with no verified proof
+    // or dispute, keeping a small bounty - protects depositors who lose access to their keys
+    pub fn public_refund(&mut self, escrow_id: String) -> Promise {
+        let mut escrow = self.escrows.get(&escrow_id).or_fail(ErrorCode::NotFound, "Escrow not found");
+        
+        ensure!(
+            matches!(escrow.status, EscrowStatus::Active),
+            InvalidState,
+            "Escrow not active"
+        );
+        ensure!(
+            env::block_timestamp() >= escrow.release_time + PUBLIC_REFUND_GRACE,
+            TooEarly,
+            "Public refund grace period has not passed"
+        );
+        ensure!(!escrow.any_proof_verified(), InvalidState, "Proof already verified");
+        // Vested tranches and periods belong to the beneficiary
+        ensure!(
+            escrow.schedule.is_none() && escrow.vesting.is_none(),
+            InvalidState,
+            "Escrow releases on a schedule"
+        );
+        
+        self.settle_escrow(&mut escrow, EscrowStatus::Refunded);
+        self.escrows.insert(&escrow_id, &escrow);
+        
+        let amount_yocto = Self::unreleased_amount(&escrow);
+        let bounty_yocto = (amount_yocto * PUBLIC_REFUND_BOUNTY_BPS) / 10000;
+        let refund_yocto = amount_yocto - bounty_yocto;
+        let caller = env::predecessor_account_id();
+        
+        env::log_str(&format!(
+            "Escrow publicly refunded: {} | Amount: {} | Bounty: {}",
+            escrow_id, refund_yocto, bounty_yocto
+        ));
+        emit_event("refunded", json!({
+            "escrow_id": escrow_id,
+            "depositor": escrow.depositor,
+            "amount": refund_yocto.to_string(),
+            "bounty": bounty_yocto.to_string(),
+            "refunded_by": caller,
+        }));
+        
+        let depositor: AccountId = escrow.depositor.parse().or_fail(ErrorCode::Internal, "Invalid depositor");
+        let refund = self.payout(&escrow, depositor, refund_yocto);
+        if bounty_yocto == 0 {
+            return refund;
+        }
+        refund.and(self.payout(&escrow, caller, bounty_yocto))
+    }
+
+    fn assert_cancellable(escrow: &Escrow) {
+        ensure!(
+            matches!(escrow.status, EscrowStatus::Active),
+            InvalidState,
+            "Escrow not active"
+        );
+        ensure!(
+            env::block_timestamp() < escrow.release_time,
+            Expired,
+            "Release time has passed"
+        );
+        ensure!(
+            !escrow.any_proof_verified(),
+            InvalidState,
+            "Proof already verified"
+        );
+    }
+}
@@ -0,0 +1,52 @@
+use crate::*;
+
+// Upper bound on escrows removed in one call
+const MAX_CLEANUP_BATCH: usize = 50;
+
+#[near_bindgen]
+impl EscrowContract {
+    // Remove settled escrows past the retention window - callable by anyone. Each removed
+    // record unlocks its depositor's storage deposit; ids that don't qualify are skipped.
+    pub fn cleanup_escrows(&mut self, escrow_ids: Vec<String>) -> u32 {
+        ensure!(escrow_ids.len() <= MAX_CLEANUP_BATCH, LimitExceeded, "Cannot clean up more than 50 escrows");
+        
+        let now = env::block_timestamp();
+        let mut removed = 0;
+        for escrow_id in escrow_ids {
+            let Some(escrow) = self.escrows.get(&escrow_id) else {
+                continue;
+            };
+            let settled = matches!(
+                escrow.status,
+                EscrowStatus::Completed | EscrowStatus::Refunded | EscrowStatus::Resolved
+            );
+            if !settled || escrow.settled_at.is_none_or(|at| now < at + self.retention_period) {
+                continue;
+            }
+            
+            self.escrows.remove(&escrow_id);
+            for proof in escrow.proofs.iter() {
+                self.proof_verifications.remove(&format!("{}:{}", proof.chain_id, proof.tx_hash));
+            }
+            self.unindex_escrow(&escrow);
+            let unlocked_yocto = self.release_escrow_storage(&escrow_id);
+            removed += 1;
+            
+            env::log_str(&format!(
+                "Escrow cleaned up: {} | Storage unlocked: {}",
+                escrow_id, unlocked_yocto
+            ));
+        }
+        
+        removed
+    }
+
+    pub fn get_retention_period(&self) -> u64 {
+        self.retention_period
+    }
+
+    pub fn set_retention_period(&mut self, retention_seconds: u64) {
+        self.access.assert_owner_or_role(&self.owner, Role::Operator);
+        self.retention_period = retention_seconds * 1_000_000_000;
+    }
+}
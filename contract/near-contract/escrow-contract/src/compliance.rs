@@ -0,0 +1,176 @@
+use crate::*;
+use near_sdk::{ext_contract, Gas, PromiseResult};
+
+const GAS_FOR_REGISTRY_VIEW: Gas = Gas::from_tgas(5);
+const GAS_FOR_SCREENING_CALLBACK: Gas = Gas::from_tgas(20);
+const GAS_FOR_RELEASE_CALLBACK: Gas = Gas::from_tgas(100);
+
+// Allowlist registry the escrow's parties are screened against
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Compliance {
+    pub registry: String,
+    pub screened_at: Option<u64>, // Set once both parties pass the creation check
+}
+
+// Only the generated ext_allowlist module is used
+#[allow(dead_code)]
+#[ext_contract(ext_allowlist)]
+trait AllowlistRegistry {
+    fn is_allowed(&self, account_id: AccountId) -> bool;
+}
+
+#[near_bindgen]
+impl EscrowContract {
+    // Compliance escrow - funds lock immediately, but nothing releases until the depositor and
+    // beneficiary pass `registry`; if either is rejected the deposit goes straight back
+    #[payable]
+    pub fn create_compliant_escrow(
+        &mut self,
+        escrow_id: String,
+        beneficiary: AccountId,
+        release_time: u64,
+        arbiter: Option<AccountId>,
+        metadata: EscrowMetadata,
+        arbiter_fee: Option<ArbiterFee>,
+        registry: AccountId,
+    ) -> Escrow {
+        ensure!(self.compliance_registries.contains(&registry), InvalidState, "Registry not approved");
+        
+        let mut escrow = self.create_escrow(
+            escrow_id.clone(),
+            beneficiary.clone(),
+            release_time,
+            arbiter,
+            metadata,
+            arbiter_fee,
+            None,
+        );
+        escrow.compliance = Some(Compliance {
+            registry: registry.to_string(),
+            screened_at: None,
+        });
+        self.escrows.insert(&escrow_id, &escrow);
+        
+        env::log_str(&format!("Compliance escrow created: {} | Registry: {}", escrow_id, registry));
+        
+        Self::screen_accounts(registry, vec![env::predecessor_account_id(), beneficiary]).then(
+            Self::ext(env::current_account_id())
+                .with_static_gas(GAS_FOR_SCREENING_CALLBACK)
+                .on_creation_screened(escrow_id),
+        );
+        
+        escrow
+    }
+
+    // Callback - approve the escrow, or refund it when a party is not on the allowlist
+    #[private]
+    pub fn on_creation_screened(&mut self, escrow_id: String) -> PromiseOrValue<bool> {
+        let mut escrow = self.escrows.get(&escrow_id).or_fail(ErrorCode::NotFound, "Escrow not found");
+        
+        if Self::all_allowed() {
+            if let Some(compliance) = escrow.compliance.as_mut() {
+                compliance.screened_at = Some(env::block_timestamp());
+            }
+            self.escrows.insert(&escrow_id, &escrow);
+            emit_event("compliance_screened", json!({ "escrow_id": escrow_id }));
+            return PromiseOrValue::Value(true);
+        }
+        // The depositor may already have refunded an expired escrow meanwhile
+        if !matches!(escrow.status, EscrowStatus::Active) {
+            return PromiseOrValue::Value(false);
+        }
+        
+        self.settle_escrow(&mut escrow, EscrowStatus::Refunded);
+        self.escrows.insert(&escrow_id, &escrow);
+        
+        let amount_yocto = Self::unreleased_amount(&escrow);
+        
+        env::log_str(&format!("Compliance check failed: {} | Refund: {}", escrow_id, amount_yocto));
+        emit_event("refunded", json!({
+            "escrow_id": escrow_id,
+            "depositor": escrow.depositor,
+            "amount": amount_yocto.to_string(),
+            "reason": "compliance",
+        }));
+        
+        let depositor: AccountId = escrow.depositor.parse().or_fail(ErrorCode::Internal, "Invalid depositor");
+        PromiseOrValue::Promise(self.payout(&escrow, depositor, amount_yocto))
+    }
+
+    // Release path for compliance escrows: the parties and payout receivers are screened again first
+    pub fn release_compliant(&mut self, escrow_id: String) -> Promise {
+        let escrow = self.escrows.get(&escrow_id).or_fail(ErrorCode::NotFound, "Escrow not found");
+        let compliance = escrow.compliance.as_ref().or_fail(ErrorCode::InvalidState, "Not a compliance escrow");
+        
+        ensure!(compliance.screened_at.is_some(), InvalidState, "Parties not screened yet");
+        ensure!(
+            matches!(escrow.status, EscrowStatus::Active),
+            InvalidState,
+            "Escrow not active"
+        );
+        
+        let mut accounts: Vec<AccountId> = escrow.payee_amounts(0).into_iter().map(|(account, _)| account).collect();
+        accounts.push(escrow.depositor.parse().or_fail(ErrorCode::Internal, "Invalid depositor"));
+        accounts.push(escrow.beneficiary.parse().or_fail(ErrorCode::Internal, "Invalid beneficiary"));
+        accounts.sort();
+        accounts.dedup();
+        
+        Self::screen_accounts(compliance.registry.parse().or_fail(ErrorCode::Internal, "Invalid registry"), accounts).then(
+            Self::ext(env::current_account_id())
+                .with_static_gas(GAS_FOR_RELEASE_CALLBACK)
+                .on_release_screened(escrow_id, env::predecessor_account_id()),
+        )
+    }
+
+    // Callback - release on the original caller's authority if everyone is still allowed
+    #[private]
+    pub fn on_release_screened(&mut self, escrow_id: String, caller: AccountId) -> PromiseOrValue<bool> {
+        if !Self::all_allowed() {
+            env::log_str(&format!("Compliance check failed: {} | Release blocked", escrow_id));
+            emit_event("compliance_blocked", json!({ "escrow_id": escrow_id }));
+            return PromiseOrValue::Value(false);
+        }
+        
+        let escrow = self.escrows.get(&escrow_id).or_fail(ErrorCode::NotFound, "Escrow not found");
+        PromiseOrValue::Promise(self.execute_release(escrow, caller))
+    }
+
+    pub fn add_compliance_registry(&mut self, registry: AccountId) {
+        assert_owner(&self.owner);
+        if !self.compliance_registries.contains(&registry) {
+            self.compliance_registries.push(registry);
+        }
+    }
+
+    // Existing escrows keep screening against the registry they were created with
+    pub fn remove_compliance_registry(&mut self, registry: AccountId) {
+        assert_owner(&self.owner);
+        self.compliance_registries.retain(|r| r != &registry);
+    }
+
+    pub fn get_compliance_registries(&self) -> Vec<AccountId> {
+        self.compliance_registries.clone()
+    }
+
+    // One allowlist view per account, joined so the callback sees every answer
+    fn screen_accounts(registry: AccountId, accounts: Vec<AccountId>) -> Promise {
+        accounts
+            .into_iter()
+            .map(|account_id| {
+                ext_allowlist::ext(registry.clone())
+                    .with_static_gas(GAS_FOR_REGISTRY_VIEW)
+                    .is_allowed(account_id)
+            })
+            .reduce(|all, check| all.and(check))
+            .or_fail(ErrorCode::InvalidArgument, "No accounts to screen")
+    }
+
+    // A failed or malformed registry answer counts as not allowed
+    fn all_allowed() -> bool {
+        (0..env::promise_results_count()).all(|i| match env::promise_result(i) {
+            PromiseResult::Successful(value) => near_sdk::serde_json::from_slice::<bool>(&value).unwrap_or(false),
+            _ => false,
+        })
+    }
+}
@@ -0,0 +1,127 @@
+use crate::*;
+
+#[near_bindgen]
+impl EscrowContract {
+    #[payable]
+    pub fn create_escrow(
+        &mut self,
+        escrow_id: String,
+        beneficiary: AccountId,
+        release_time: u64,
+        arbiter: Option<AccountId>,
+        metadata: EscrowMetadata,
+        arbiter_fee: Option<ArbiterFee>,
+        required_proofs: Option<Vec<String>>,
+    ) -> Escrow {
+        let depositor = env::predecessor_account_id();
+        let amount = env::attached_deposit();
+        
+        ensure!(amount.as_yoctonear() > 0, InsufficientFunds, "Must attach NEAR tokens");
+        
+        self.new_escrow(
+            escrow_id,
+            depositor,
+            amount.as_yoctonear(),
+            None,
+            beneficiary,
+            release_time,
+            arbiter,
+            metadata,
+            arbiter_fee,
+            required_proofs.unwrap_or_default(),
+        )
+    }
+
+    // Validate and store a new escrow funded with `amount_yocto` of NEAR or `token`
+    pub(crate) fn new_escrow(
+        &mut self,
+        escrow_id: String,
+        depositor: AccountId,
+        amount_yocto: u128,
+        token: Option<AccountId>,
+        beneficiary: AccountId,
+        release_time: u64,
+        arbiter: Option<AccountId>,
+        metadata: EscrowMetadata,
+        arbiter_fee: Option<ArbiterFee>,
+        required_proofs: Vec<String>,
+    ) -> Escrow {
+        self.assert_not_paused();
+        self.rate_limiter.record(&depositor, 1);
+        ensure!(self.escrows.get(&escrow_id).is_none(), AlreadyExists, "Escrow ID already exists");
+        ensure!(required_proofs.len() <= MAX_PROOFS, LimitExceeded, "Too many required proofs");
+        metadata.validate();
+        ensure!(release_time > env::block_timestamp(), InvalidArgument, "Release time must be in future");
+        if let Some(fee) = arbiter_fee.as_ref() {
+            fee.validate();
+        }
+        
+        let escrow = Escrow {
+            escrow_id: escrow_id.clone(),
+            depositor: depositor.to_string(),
+            beneficiary: beneficiary.to_string(),
+            amount: U128(amount_yocto),
+            release_time,
+            status: EscrowStatus::Active,
+            proofs: Vec::new(),
+            required_proofs,
+            allow_shared_proofs: false,
+            arbiter: arbiter.map(|a| a.to_string()),
+            created_at: env::block_timestamp(),
+            metadata,
+            settled_at: None,
+            milestones: Vec::new(),
+            released: U128(0),
+            committee: Vec::new(),
+            threshold: 0,
+            votes: Vec::new(),
+            arbiter_fee,
+            evidence: Vec::new(),
+            dispute_deadline: None,
+            default_outcome: None,
+            token: token.map(|t| t.to_string()),
+            pending_amendment: None,
+            amendments: Vec::new(),
+            cancel_proposed_by: None,
+            schedule: None,
+            vesting: None,
+            payees: Vec::new(),
+            price_condition: None,
+            hashlock: None,
+            staking: None,
+            disputed_at: None,
+            ruling: None,
+            appealed: false,
+            swap_link: None,
+            payout_address: None,
+            compliance: None,
+            deposits: vec![Deposit {
+                amount: U128(amount_yocto),
+                deposited_at: env::block_timestamp(),
+            }],
+            attachments: Vec::new(),
+            expected_payments: Vec::new(),
+            dispute_payout: None,
+        };
+        
+        self.escrows.insert(&escrow_id, &escrow);
+        self.index_escrow(&escrow);
+        self.track_new_escrow(&escrow);
+        self.lock_escrow_storage(&depositor, &escrow_id);
+        
+        env::log_str(&format!(
+            "Escrow created: {} | Amount: {} | Beneficiary: {}",
+            escrow_id, amount_yocto, beneficiary
+        ));
+        emit_event("created", json!({
+            "escrow_id": escrow_id,
+            "depositor": escrow.depositor,
+            "beneficiary": escrow.beneficiary,
+            "amount": escrow.amount,
+            "token": escrow.token,
+            "release_time": release_time,
+        }));
+        
+        escrow
+    }
+}
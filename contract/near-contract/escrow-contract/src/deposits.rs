@@ -0,0 +1,69 @@
+use crate::*;
+
+// Upper bound on deposits recorded per escrow
+const MAX_DEPOSITS: usize = 50;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Deposit {
+    #[borsh(serialize_with = "decimal_borsh::serialize", deserialize_with = "decimal_borsh::deserialize")]
+    #[schemars(with = "String")]
+    pub amount: U128,
+    pub deposited_at: u64,
+}
+
+#[near_bindgen]
+impl EscrowContract {
+    // Depositor adds funds to an active escrow, e.g. to extend a long-running service agreement
+    #[payable]
+    pub fn top_up(&mut self, escrow_id: String) -> Escrow {
+        self.assert_not_paused();
+        let mut escrow = self.escrows.get(&escrow_id).or_fail(ErrorCode::NotFound, "Escrow not found");
+        
+        let amount_yocto = env::attached_deposit().as_yoctonear();
+        ensure!(amount_yocto > 0, InsufficientFunds, "Must attach NEAR tokens");
+        let depositor: AccountId = escrow.depositor.parse().or_fail(ErrorCode::Internal, "Invalid depositor");
+        ensure!(env::predecessor_account_id() == depositor, Unauthorized, "Only depositor can top up");
+        ensure!(
+            matches!(escrow.status, EscrowStatus::Active),
+            InvalidState,
+            "Escrow not active"
+        );
+        ensure!(escrow.token.is_none(), InvalidState, "Only NEAR escrows can be topped up");
+        // Scheduled amounts and staked principal are fixed at creation
+        ensure!(
+            escrow.milestones.is_empty()
+                && escrow.schedule.is_none()
+                && escrow.vesting.is_none()
+                && escrow.staking.is_none(),
+            InvalidState,
+            "Escrow amount is fixed by its schedule"
+        );
+        ensure!(escrow.deposits.len() < MAX_DEPOSITS, LimitExceeded, "Deposit limit reached");
+        
+        let held_yocto: u128 = escrow.amount.0;
+        escrow.amount = U128(held_yocto + amount_yocto);
+        escrow.deposits.push(Deposit {
+            amount: U128(amount_yocto),
+            deposited_at: env::block_timestamp(),
+        });
+        self.escrows.insert(&escrow_id, &escrow);
+        self.track_inflow(&escrow, amount_yocto);
+        
+        env::log_str(&format!(
+            "Escrow topped up: {} | Amount: {} | Total: {}",
+            escrow_id, amount_yocto, escrow.amount.0
+        ));
+        emit_event("topped_up", json!({
+            "escrow_id": escrow_id,
+            "amount": amount_yocto.to_string(),
+            "total": escrow.amount,
+        }));
+        
+        escrow
+    }
+
+    pub fn get_deposits(&self, escrow_id: String) -> Vec<Deposit> {
+        self.escrows.get(&escrow_id).map(|escrow| escrow.deposits).unwrap_or_default()
+    }
+}
@@ -0,0 +1,444 @@
+use crate::*;
+
+// Upper bound on arbiters in a committee
+const MAX_COMMITTEE_SIZE: usize = 10;
+
+// Upper bound for a percentage arbiter fee (10%)
+const MAX_ARBITER_FEE_BPS: u16 = 1000;
+
+// Evidence entries kept per escrow, and the longest accepted evidence URI
+const MAX_EVIDENCE_ENTRIES: usize = 20;
+const MAX_EVIDENCE_URI_LENGTH: usize = 256;
+
+// Paid to the resolving arbiter(s) out of the escrowed amount when a dispute is settled
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub enum ArbiterFee {
+    Bps(u16),
+    Flat(
+        #[borsh(serialize_with = "decimal_borsh::serialize", deserialize_with = "decimal_borsh::deserialize")]
+        #[schemars(with = "String")]
+        U128,
+    ), // yoctoNEAR
+}
+
+impl ArbiterFee {
+    pub(crate) fn validate(&self) {
+        match self {
+            ArbiterFee::Bps(bps) => ensure!(*bps <= MAX_ARBITER_FEE_BPS, LimitExceeded, "Arbiter fee cannot exceed 10%"),
+            ArbiterFee::Flat(_) => {}
+        }
+    }
+
+    // Fee owed on `amount_yocto`, never more than the amount itself
+    fn amount_for(&self, amount_yocto: u128) -> u128 {
+        match self {
+            ArbiterFee::Bps(bps) => (amount_yocto * *bps as u128) / 10000,
+            ArbiterFee::Flat(amount) => amount.0.min(amount_yocto),
+        }
+    }
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ArbiterVote {
+    pub arbiter: String,
+    pub depositor_bps: u16, // Beneficiary receives the remainder
+    pub voted_at: u64,
+}
+
+// Applied by anyone once a dispute passes its deadline unresolved
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub enum DisputeOutcome {
+    RefundDepositor,
+    ReleaseToBeneficiary,
+    Split(u16), // Depositor share in bps, remainder to the beneficiary
+}
+
+impl DisputeOutcome {
+    pub(crate) fn depositor_bps(&self) -> u16 {
+        match self {
+            DisputeOutcome::RefundDepositor => 10000,
+            DisputeOutcome::ReleaseToBeneficiary => 0,
+            DisputeOutcome::Split(depositor_bps) => *depositor_bps,
+        }
+    }
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Evidence {
+    pub submitted_by: String,
+    pub content_hash: String, // Hex sha256 of the off-chain content
+    pub uri: String,
+    pub submitted_at: u64,
+}
+
+// Split paid out by a settled dispute, kept so depositors that forward their share (e.g.
+// the p2p contract's chargebacks) can read what came back
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DisputePayout {
+    #[borsh(serialize_with = "decimal_borsh::serialize", deserialize_with = "decimal_borsh::deserialize")]
+    #[schemars(with = "String")]
+    pub depositor: U128,
+    #[borsh(serialize_with = "decimal_borsh::serialize", deserialize_with = "decimal_borsh::deserialize")]
+    #[schemars(with = "String")]
+    pub beneficiary: U128,
+    #[borsh(serialize_with = "decimal_borsh::serialize", deserialize_with = "decimal_borsh::deserialize")]
+    #[schemars(with = "String")]
+    pub arbiter_fee: U128,
+}
+
+#[near_bindgen]
+impl EscrowContract {
+    // Committee escrow - disputes are settled by `threshold` matching votes instead of one arbiter
+    #[payable]
+    pub fn create_committee_escrow(
+        &mut self,
+        escrow_id: String,
+        beneficiary: AccountId,
+        release_time: u64,
+        committee: Vec<AccountId>,
+        threshold: u8,
+        metadata: EscrowMetadata,
+        arbiter_fee: Option<ArbiterFee>,
+    ) -> Escrow {
+        ensure!(
+            !committee.is_empty() && committee.len() <= MAX_COMMITTEE_SIZE,
+            InvalidArgument,
+            "Committee must have 1-10 arbiters"
+        );
+        ensure!(
+            threshold > 0 && threshold as usize <= committee.len(),
+            InvalidArgument,
+            "Threshold must be between 1 and the committee size"
+        );
+        let mut members: Vec<String> = committee.iter().map(|a| a.to_string()).collect();
+        members.sort();
+        members.dedup();
+        ensure!(members.len() == committee.len(), InvalidArgument, "Duplicate committee member");
+        
+        let mut escrow = self.create_escrow(
+            escrow_id.clone(),
+            beneficiary,
+            release_time,
+            None,
+            metadata,
+            arbiter_fee,
+            None,
+        );
+        escrow.committee = members;
+        escrow.threshold = threshold;
+        self.escrows.insert(&escrow_id, &escrow);
+        
+        env::log_str(&format!(
+            "Committee escrow created: {} | Threshold: {}/{}",
+            escrow_id, threshold, escrow.committee.len()
+        ));
+        
+        escrow
+    }
+
+    // Committee member votes on a split; funds move once `threshold` members agree on
+    // the same split. Members may change their vote until then.
+    pub fn vote_dispute(&mut self, escrow_id: String, depositor_bps: u16) -> PromiseOrValue<bool> {
+        let mut escrow = self.escrows.get(&escrow_id).or_fail(ErrorCode::NotFound, "Escrow not found");
+        
+        let caller = env::predecessor_account_id().to_string();
+        ensure!(escrow.committee.contains(&caller), Unauthorized, "Only committee members can vote");
+        ensure!(
+            matches!(escrow.status, EscrowStatus::Disputed),
+            InvalidState,
+            "Escrow is not disputed"
+        );
+        ensure!(depositor_bps <= 10000, LimitExceeded, "Share cannot exceed 10000 bps");
+        
+        escrow.votes.retain(|vote| vote.arbiter != caller);
+        escrow.votes.push(ArbiterVote {
+            arbiter: caller.clone(),
+            depositor_bps,
+            voted_at: env::block_timestamp(),
+        });
+        
+        let matching = escrow.votes
+            .iter()
+            .filter(|vote| vote.depositor_bps == depositor_bps)
+            .count();
+        
+        env::log_str(&format!(
+            "Dispute vote: {} | Arbiter: {} | Depositor bps: {} | Votes: {}/{}",
+            escrow_id, caller, depositor_bps, matching, escrow.threshold
+        ));
+        
+        if matching < escrow.threshold as usize {
+            self.escrows.insert(&escrow_id, &escrow);
+            return PromiseOrValue::Value(false);
+        }
+        
+        // The fee is shared by the members who voted for the winning split
+        let arbiters = escrow.votes
+            .iter()
+            .filter(|vote| vote.depositor_bps == depositor_bps)
+            .map(|vote| vote.arbiter.parse().or_fail(ErrorCode::Internal, "Invalid arbiter"))
+            .collect();
+        PromiseOrValue::Promise(self.settle_split(escrow, depositor_bps, arbiters))
+    }
+
+    // Arbiter rules on a dispute: Disputed moves to Refunded, Completed or Resolved (split)
+    // once the appeal window closes. Committee votes and the default outcome settle directly.
+    pub fn arbitrate(&mut self, escrow_id: String, outcome: DisputeOutcome) {
+        let escrow = self.escrows.get(&escrow_id).or_fail(ErrorCode::NotFound, "Escrow not found");
+        
+        let caller = env::predecessor_account_id();
+        let is_arbiter = escrow.arbiter.as_ref().is_some_and(|a| {
+            let arbiter: AccountId = a.parse().or_fail(ErrorCode::Internal, "Invalid arbiter");
+            arbiter == caller
+        });
+        ensure!(is_arbiter, Unauthorized, "Only arbiter can resolve disputes");
+        ensure!(
+            matches!(escrow.status, EscrowStatus::Disputed),
+            InvalidState,
+            "Escrow is not disputed"
+        );
+        let depositor_bps = outcome.depositor_bps();
+        ensure!(depositor_bps <= 10000, LimitExceeded, "Share cannot exceed 10000 bps");
+        
+        self.propose_ruling(escrow, depositor_bps, caller)
+    }
+
+    // Arbiter rules a split of the unreleased amount between the parties, subject to appeal
+    pub fn resolve_dispute(&mut self, escrow_id: String, depositor_bps: u16, beneficiary_bps: u16) {
+        let escrow = self.escrows.get(&escrow_id).or_fail(ErrorCode::NotFound, "Escrow not found");
+        
+        let caller = env::predecessor_account_id();
+        let is_arbiter = escrow.arbiter.as_ref().is_some_and(|a| {
+            let arbiter: AccountId = a.parse().or_fail(ErrorCode::Internal, "Invalid arbiter");
+            arbiter == caller
+        });
+        ensure!(is_arbiter, Unauthorized, "Only arbiter can resolve disputes");
+        ensure!(
+            matches!(escrow.status, EscrowStatus::Disputed),
+            InvalidState,
+            "Escrow is not disputed"
+        );
+        ensure!(
+            depositor_bps as u32 + beneficiary_bps as u32 == 10000,
+            InvalidArgument,
+            "Shares must sum to 10000 bps"
+        );
+        
+        self.propose_ruling(escrow, depositor_bps, caller)
+    }
+
+    // Either party references off-chain evidence for the arbiters while the dispute is open
+    pub fn submit_evidence(&mut self, escrow_id: String, content_hash: String, uri: String) {
+        let mut escrow = self.escrows.get(&escrow_id).or_fail(ErrorCode::NotFound, "Escrow not found");
+        
+        let caller = env::predecessor_account_id();
+        let depositor: AccountId = escrow.depositor.parse().or_fail(ErrorCode::Internal, "Invalid depositor");
+        let beneficiary: AccountId = escrow.beneficiary.parse().or_fail(ErrorCode::Internal, "Invalid beneficiary");
+        ensure!(
+            caller == depositor || caller == beneficiary,
+            Unauthorized,
+            "Only parties can submit evidence"
+        );
+        ensure!(
+            matches!(escrow.status, EscrowStatus::Disputed),
+            InvalidState,
+            "Escrow is not disputed"
+        );
+        ensure!(content_hash.len() == 64, InvalidArgument, "Content hash must be 64 characters");
+        ensure!(
+            !uri.is_empty() && uri.len() <= MAX_EVIDENCE_URI_LENGTH,
+            InvalidArgument,
+            "URI must be 1-256 characters"
+        );
+        ensure!(escrow.evidence.len() < MAX_EVIDENCE_ENTRIES, LimitExceeded, "Evidence limit reached");
+        
+        escrow.evidence.push(Evidence {
+            submitted_by: caller.to_string(),
+            content_hash: content_hash.clone(),
+            uri,
+            submitted_at: env::block_timestamp(),
+        });
+        self.escrows.insert(&escrow_id, &escrow);
+        
+        env::log_str(&format!(
+            "Evidence submitted: {} | By: {} | Hash: {}",
+            escrow_id, caller, content_hash
+        ));
+    }
+
+    pub fn get_evidence(&self, escrow_id: String) -> Vec<Evidence> {
+        self.escrows.get(&escrow_id).map(|escrow| escrow.evidence).unwrap_or_default()
+    }
+
+    // Settle an overdue dispute with the default outcome - callable by anyone. No arbiter
+    // resolved it, so no arbiter fee is paid.
+    pub fn apply_default_outcome(&mut self, escrow_id: String) -> Promise {
+        let escrow = self.escrows.get(&escrow_id).or_fail(ErrorCode::NotFound, "Escrow not found");
+        
+        ensure!(
+            matches!(escrow.status, EscrowStatus::Disputed),
+            InvalidState,
+            "Escrow is not disputed"
+        );
+        ensure!(escrow.ruling.is_none(), InvalidState, "Dispute has a pending ruling");
+        let deadline = escrow.dispute_deadline.or_fail(ErrorCode::InvalidState, "No dispute deadline");
+        ensure!(env::block_timestamp() >= deadline, TooEarly, "Dispute deadline has not passed");
+        
+        let depositor_bps = escrow.default_outcome.as_ref().or_fail(ErrorCode::InvalidState, "No default outcome").depositor_bps();
+        
+        env::log_str(&format!(
+            "Default dispute outcome applied: {} | Depositor bps: {}",
+            escrow_id, depositor_bps
+        ));
+        
+        self.settle_split(escrow, depositor_bps, Vec::new())
+    }
+
+    pub fn set_dispute_config(&mut self, dispute_period_seconds: u64, default_outcome: DisputeOutcome) {
+        assert_owner(&self.owner);
+        ensure!(dispute_period_seconds > 0, InvalidArgument, "Dispute period must be positive");
+        if let DisputeOutcome::Split(depositor_bps) = default_outcome {
+            ensure!(depositor_bps <= 10000, LimitExceeded, "Share cannot exceed 10000 bps");
+        }
+        
+        self.dispute_period = dispute_period_seconds * 1_000_000_000;
+        self.default_dispute_outcome = default_outcome;
+    }
+
+    pub fn get_dispute_votes(&self, escrow_id: String) -> Vec<ArbiterVote> {
+        self.escrows.get(&escrow_id).map(|escrow| escrow.votes).unwrap_or_default()
+    }
+
+    // Pay the arbiter fee, then split the rest of the unreleased amount - `depositor_bps`
+    // to the depositor and the remainder to the beneficiary
+    pub(crate) fn settle_split(&mut self, mut escrow: Escrow, depositor_bps: u16, arbiters: Vec<AccountId>) -> Promise {
+        let unreleased_yocto = Self::unreleased_amount(&escrow);
+        let fee_yocto = match escrow.arbiter_fee.as_ref() {
+            Some(fee) if !arbiters.is_empty() => fee.amount_for(unreleased_yocto),
+            _ => 0,
+        };
+        let amount_yocto = unreleased_yocto - fee_yocto;
+        let depositor_yocto = (amount_yocto * depositor_bps as u128) / 10000;
+        let beneficiary_yocto = amount_yocto - depositor_yocto;
+        
+        // A one-sided outcome ends in the same state as an undisputed release or refund
+        self.settle_escrow(&mut escrow, match depositor_bps {
+            0 => EscrowStatus::Completed,
+            10000 => EscrowStatus::Refunded,
+            _ => EscrowStatus::Resolved,
+        });
+        escrow.released = escrow.amount;
+        escrow.dispute_payout = Some(DisputePayout {
+            depositor: U128(depositor_yocto),
+            beneficiary: U128(beneficiary_yocto),
+            arbiter_fee: U128(fee_yocto),
+        });
+        self.escrows.insert(&escrow.escrow_id, &escrow);
+        
+        env::log_str(&format!(
+            "Dispute resolved: {} | Depositor: {} | Beneficiary: {} | Arbiter fee: {}",
+            escrow.escrow_id, depositor_yocto, beneficiary_yocto, fee_yocto
+        ));
+        emit_event("resolved", json!({
+            "escrow_id": escrow.escrow_id,
+            "depositor_amount": depositor_yocto.to_string(),
+            "beneficiary_amount": beneficiary_yocto.to_string(),
+            "arbiter_fee": fee_yocto.to_string(),
+            "arbiters": arbiters,
+        }));
+        
+        // Rounding dust from the fee share goes to the first arbiter
+        let fee_share_yocto = fee_yocto / arbiters.len().max(1) as u128;
+        let fee_dust_yocto = fee_yocto - fee_share_yocto * arbiters.len() as u128;
+        let mut payouts: Vec<(AccountId, u128)> = arbiters
+            .into_iter()
+            .enumerate()
+            .map(|(i, arbiter)| (arbiter, if i == 0 { fee_share_yocto + fee_dust_yocto } else { fee_share_yocto }))
+            .collect();
+        payouts.push((escrow.depositor.parse().or_fail(ErrorCode::Internal, "Invalid depositor"), depositor_yocto));
+        payouts.extend(escrow.payee_amounts(beneficiary_yocto));
+        self.track_release(&escrow, beneficiary_yocto);
+        
+        payouts
+            .into_iter()
+            .filter(|(_, amount_yocto)| *amount_yocto > 0)
+            .map(|(account_id, amount_yocto)| {
+                self.payout(&escrow, account_id, amount_yocto)
+            })
+            .reduce(|all, payout| all.and(payout))
+            .or_fail(ErrorCode::InvalidState, "Nothing to settle")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use ciphra_testing as host;
+
+    const ARBITER: &str = "arbiter.near";
+
+    fn disputed_escrow(contract: &mut EscrowContract) {
+        host::set_caller(DEPOSITOR);
+        host::set_deposit(host::NEAR);
+        contract.storage_deposit(None, None);
+        host::set_deposit(10 * host::NEAR);
+        contract.create_escrow(
+            "e1".to_string(),
+            account(BENEFICIARY),
+            host::block_timestamp() + 86_400 * 1_000_000_000,
+            Some(account(ARBITER)),
+            EscrowMetadata {
+                title: "Disputed escrow".to_string(),
+                category: "test".to_string(),
+                content_hash: None,
+                external_uri: None,
+            },
+            None,
+            None,
+        );
+        host::set_deposit(0);
+        contract.raise_dispute("e1".to_string());
+    }
+
+    #[test]
+    fn test_split_ruling_records_the_payout() {
+        let mut contract = contract();
+        disputed_escrow(&mut contract);
+        host::set_caller(ARBITER);
+        contract.resolve_dispute("e1".to_string(), 4000, 6000);
+        host::advance_seconds(30 * 86_400);
+        let _ = contract.finalize_ruling("e1".to_string());
+        
+        let escrow = contract.get_escrow("e1".to_string()).unwrap();
+        assert!(matches!(escrow.status, EscrowStatus::Resolved));
+        let payout = escrow.dispute_payout.unwrap();
+        assert_eq!((payout.depositor, payout.beneficiary), (U128(4 * host::NEAR), U128(6 * host::NEAR)));
+    }
+
+    #[test]
+    fn test_default_refund_records_the_payout() {
+        let mut contract = contract();
+        disputed_escrow(&mut contract);
+        host::advance_seconds(30 * 86_400);
+        let _ = contract.apply_default_outcome("e1".to_string());
+        
+        let escrow = contract.get_escrow("e1".to_string()).unwrap();
+        assert!(matches!(escrow.status, EscrowStatus::Refunded));
+        assert_eq!(escrow.dispute_payout.unwrap().depositor, U128(10 * host::NEAR));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only parties can raise dispute")]
+    fn test_outsider_cannot_dispute() {
+        let mut contract = contract();
+        create_escrow(&mut contract, "e1", 10 * host::NEAR);
+        host::set_caller(ARBITER);
+        contract.raise_dispute("e1".to_string());
+    }
+}
@@ -0,0 +1,278 @@
+use crate::*;
+use ciphra_types::bps_of;
+use near_sdk::{ext_contract, is_promise_success, Gas};
+
+const GAS_FOR_INSURANCE_DEPOSIT: Gas = Gas::from_tgas(10);
+const GAS_FOR_INSURANCE_CALLBACK: Gas = Gas::from_tgas(5);
+const GAS_FOR_TREASURY_DEPOSIT: Gas = Gas::from_tgas(10);
+const GAS_FOR_TREASURY_CALLBACK: Gas = Gas::from_tgas(5);
+
+// Upper bound for the settlement fee (5%)
+pub(crate) const MAX_SETTLEMENT_FEE_BPS: u16 = 500;
+
+// Upper bound for the insurance share of each NEAR settlement fee (50%)
+pub(crate) const MAX_INSURANCE_FEE_BPS: u16 = 5000;
+
+// Treasury key for native NEAR fees; token fees are keyed by token contract
+const NEAR_ASSET: &str = "near";
+
+// Only the generated ext_insurance_pool module is used
+#[allow(dead_code)]
+#[ext_contract(ext_insurance_pool)]
+trait InsurancePool {
+    fn deposit_fees(&mut self);
+}
+
+// Only the generated ext_fee_treasury module is used
+#[allow(dead_code)]
+#[ext_contract(ext_fee_treasury)]
+trait FeeTreasury {
+    fn deposit_fees(&mut self);
+}
+
+#[near_bindgen]
+impl EscrowContract {
+    pub fn set_settlement_fee(&mut self, fee_bps: u16) {
+        self.access.assert_owner_or_role(&self.owner, Role::FeeManager);
+        ensure!(fee_bps <= MAX_SETTLEMENT_FEE_BPS, LimitExceeded, "Fee cannot exceed 5%");
+        self.settlement_fee_bps = fee_bps;
+    }
+
+    pub fn get_settlement_fee(&self) -> u16 {
+        self.settlement_fee_bps
+    }
+
+    pub fn set_treasury_account(&mut self, treasury_account: AccountId) {
+        assert_owner(&self.owner);
+        self.treasury_account = treasury_account;
+    }
+
+    pub fn get_treasury_account(&self) -> AccountId {
+        self.treasury_account.clone()
+    }
+
+    // Accrued fees in NEAR, or in `token` for token escrows
+    pub fn get_treasury_balance(&self, token: Option<AccountId>) -> U128 {
+        U128(self.treasury.get(&Self::treasury_key(token.as_ref())).unwrap_or(0))
+    }
+
+    // Treasury account withdraws the fees accrued in one asset
+    pub fn claim_treasury(&mut self, token: Option<AccountId>) -> Promise {
+        ensure!(
+            env::predecessor_account_id() == self.treasury_account,
+            Unauthorized,
+            "Only treasury account"
+        );
+        let key = Self::treasury_key(token.as_ref());
+        let amount_yocto = self.treasury.get(&key).unwrap_or(0);
+        ensure!(amount_yocto > 0, InvalidState, "Nothing to claim");
+        self.treasury.remove(&key);
+        
+        env::log_str(&format!("Treasury claimed: {} | Amount: {}", key, amount_yocto));
+        
+        let receiver = self.treasury_account.clone();
+        match token {
+            Some(token) => self.token_transfer(token, receiver, amount_yocto),
+            None => self.settle(receiver, amount_yocto),
+        }
+    }
+
+    // Fee treasury contract that accrued NEAR fees can be forwarded to
+    pub fn set_fee_treasury(&mut self, fee_treasury: Option<AccountId>) {
+        assert_owner(&self.owner);
+        self.fee_treasury = fee_treasury;
+    }
+
+    pub fn get_fee_treasury(&self) -> Option<AccountId> {
+        self.fee_treasury.clone()
+    }
+
+    // Forward the accrued NEAR fees to the fee treasury - callable by anyone.
+    // Token fees stay here for claim_treasury.
+    pub fn forward_treasury_fees(&mut self) -> Promise {
+        let fee_treasury = self.fee_treasury.clone().or_fail(ErrorCode::NotFound, "No fee treasury configured");
+        let amount_yocto = self.treasury.get(&NEAR_ASSET.to_string()).unwrap_or(0);
+        ensure!(amount_yocto > 0, InvalidState, "Nothing to forward");
+        self.treasury.remove(&NEAR_ASSET.to_string());
+        
+        env::log_str(&format!("Treasury fees forwarded: {} | Amount: {}", fee_treasury, amount_yocto));
+        
+        ext_fee_treasury::ext(fee_treasury)
+            .with_attached_deposit(NearToken::from_yoctonear(amount_yocto))
+            .with_static_gas(GAS_FOR_TREASURY_DEPOSIT)
+            .deposit_fees()
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_TREASURY_CALLBACK)
+                    .on_treasury_forwarded(U128(amount_yocto)),
+            )
+    }
+
+    // Callback - a rejected deposit comes back, so it accrues again
+    #[private]
+    pub fn on_treasury_forwarded(&mut self, amount: U128) -> bool {
+        if is_promise_success() {
+            return true;
+        }
+        let key = NEAR_ASSET.to_string();
+        let balance = self.treasury.get(&key).unwrap_or(0);
+        self.treasury.insert(&key, &(balance + amount.0));
+        env::log_str(&format!("Treasury forward failed | Amount: {}", amount.0));
+        false
+    }
+
+    // Route `insurance_fee_bps` of NEAR settlement fees to the insurance pool contract
+    pub fn set_insurance_fund(&mut self, insurance_fund: Option<AccountId>, insurance_fee_bps: u16) {
+        assert_owner(&self.owner);
+        ensure!(insurance_fee_bps <= MAX_INSURANCE_FEE_BPS, LimitExceeded, "Insurance share cannot exceed 50%");
+        self.insurance_fund = insurance_fund;
+        self.insurance_fee_bps = insurance_fee_bps;
+    }
+
+    pub fn get_insurance_fund(&self) -> Option<AccountId> {
+        self.insurance_fund.clone()
+    }
+
+    pub fn get_insurance_fee_accrued(&self) -> U128 {
+        U128(self.insurance_fee_accrued)
+    }
+
+    // Forward the accrued insurance slice to the pool - callable by anyone
+    pub fn forward_insurance_fees(&mut self) -> Promise {
+        let insurance_fund = self.insurance_fund.clone().or_fail(ErrorCode::NotFound, "No insurance fund configured");
+        let amount_yocto = self.insurance_fee_accrued;
+        ensure!(amount_yocto > 0, InvalidState, "Nothing to forward");
+        self.insurance_fee_accrued = 0;
+        
+        env::log_str(&format!("Insurance fees forwarded: {} | Amount: {}", insurance_fund, amount_yocto));
+        
+        ext_insurance_pool::ext(insurance_fund)
+            .with_attached_deposit(NearToken::from_yoctonear(amount_yocto))
+            .with_static_gas(GAS_FOR_INSURANCE_DEPOSIT)
+            .deposit_fees()
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_INSURANCE_CALLBACK)
+                    .on_insurance_forwarded(U128(amount_yocto)),
+            )
+    }
+
+    // Callback - a rejected deposit comes back, so it accrues again for the next forward
+    #[private]
+    pub fn on_insurance_forwarded(&mut self, amount: U128) -> bool {
+        if is_promise_success() {
+            return true;
+        }
+        self.insurance_fee_accrued += amount.0;
+        env::log_str(&format!("Insurance forward failed | Amount: {}", amount.0));
+        false
+    }
+
+    // Pay a release to the payees less the settlement fee, which accrues to the treasury
+    pub(crate) fn release_payout(&mut self, escrow: &Escrow, amount_yocto: u128) -> Promise {
+        let fee_yocto = bps_of(amount_yocto, self.settlement_fee_bps);
+        self.track_release(escrow, amount_yocto);
+        if fee_yocto > 0 {
+            self.track_outflow(escrow, fee_yocto);
+            // Only NEAR fees feed the insurance pool; token fees stay in the treasury
+            let insurance_yocto = match (&self.insurance_fund, &escrow.token) {
+                (Some(_), None) => bps_of(fee_yocto, self.insurance_fee_bps),
+                _ => 0,
+            };
+            self.insurance_fee_accrued += insurance_yocto;
+            let key = escrow.token.clone().unwrap_or_else(|| NEAR_ASSET.to_string());
+            let balance = self.treasury.get(&key).unwrap_or(0);
+            self.treasury.insert(&key, &(balance + fee_yocto - insurance_yocto));
+            
+            env::log_str(&format!(
+                "Settlement fee: {} | Fee: {}",
+                escrow.escrow_id, fee_yocto
+            ));
+        }
+        
+        escrow
+            .payee_amounts(amount_yocto - fee_yocto)
+            .into_iter()
+            .filter(|(_, share_yocto)| *share_yocto > 0)
+            .map(|(payee, share_yocto)| self.payout(escrow, payee, share_yocto))
+            .reduce(|all, payout| all.and(payout))
+            .or_fail(ErrorCode::InvalidState, "Nothing to release")
+    }
+
+    fn treasury_key(token: Option<&AccountId>) -> String {
+        token.map_or_else(|| NEAR_ASSET.to_string(), |t| t.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use ciphra_testing as host;
+
+    const TREASURY: &str = "treasury.near";
+
+    // Released escrow of 100 NEAR at a 1% fee, leaving 1 NEAR in the treasury
+    fn contract_with_fees() -> EscrowContract {
+        let mut contract = contract();
+        contract.set_settlement_fee(100);
+        contract.set_treasury_account(account(TREASURY));
+        create_escrow(&mut contract, "e1", 100 * host::NEAR);
+        host::advance_seconds(86_400);
+        host::set_caller(BENEFICIARY);
+        let _ = contract.release_funds("e1".to_string());
+        host::clear_receipts();
+        contract
+    }
+
+    #[test]
+    fn test_release_accrues_fee_and_pays_the_rest() {
+        let mut contract = contract();
+        contract.set_settlement_fee(100);
+        create_escrow(&mut contract, "e1", 100 * host::NEAR);
+        host::advance_seconds(86_400);
+        host::set_caller(BENEFICIARY);
+        let _ = contract.release_funds("e1".to_string());
+        
+        assert_eq!(host::transfers(), vec![(BENEFICIARY.to_string(), 99 * host::NEAR)]);
+        assert_eq!(contract.get_treasury_balance(None), U128(host::NEAR));
+    }
+
+    #[test]
+    fn test_claim_treasury_settles_through_ledger() {
+        let mut contract = contract_with_fees();
+        let pending_before = contract.settlements.total_pending;
+        host::set_caller(TREASURY);
+        let _ = contract.claim_treasury(None);
+        
+        assert_eq!(host::transfers(), vec![(TREASURY.to_string(), host::NEAR)]);
+        assert_eq!(host::function_calls(), vec![(host::CONTRACT.to_string(), "on_settlement".to_string())]);
+        assert_eq!(contract.get_treasury_balance(None), U128(0));
+        assert_eq!(contract.settlements.total_pending, pending_before + host::NEAR);
+    }
+
+    #[test]
+    fn test_failed_treasury_claim_stays_claimable() {
+        let mut contract = contract_with_fees();
+        let pending_before = contract.settlements.total_pending;
+        host::set_caller(TREASURY);
+        let _ = contract.claim_treasury(None);
+        let settlement_id = contract.settlements.next_settlement_id - 1;
+        
+        host::set_self_caller();
+        host::set_promise_results(vec![None]);
+        assert!(!contract.on_settlement(settlement_id));
+        host::set_promise_results(vec![]);
+        
+        assert_eq!(contract.get_claimable_settlement(account(TREASURY)), U128(host::NEAR));
+        assert_eq!(contract.settlements.total_pending, pending_before);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only treasury account")]
+    fn test_claim_treasury_only_by_treasury() {
+        let mut contract = contract_with_fees();
+        host::set_caller(DEPOSITOR);
+        let _ = contract.claim_treasury(None);
+    }
+}
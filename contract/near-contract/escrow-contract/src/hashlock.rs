@@ -0,0 +1,90 @@
+use crate::*;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Hashlock {
+    pub hash: String, // Hex hash of the secret
+    pub algorithm: HashAlgorithm,
+    pub secret: Option<String>, // Published on claim so the counterparty can unlock the other leg
+}
+
+#[near_bindgen]
+impl EscrowContract {
+    // Hashlock escrow - revealing the secret before release_time pays the beneficiary;
+    // after release_time the depositor can refund as usual
+    #[payable]
+    pub fn create_hashlock_escrow(
+        &mut self,
+        escrow_id: String,
+        beneficiary: AccountId,
+        hash: String,
+        algorithm: HashAlgorithm,
+        release_time: u64,
+        arbiter: Option<AccountId>,
+        metadata: EscrowMetadata,
+        arbiter_fee: Option<ArbiterFee>,
+    ) -> Escrow {
+        ensure!(
+            hash.len() == 64 && hex::decode(&hash).is_ok(),
+            InvalidArgument,
+            "Hash must be 64 hex characters"
+        );
+        // The secret is checked on-chain, so the algorithm has to be computable here
+        ensure!(algorithm != HashAlgorithm::Poseidon, InvalidArgument, "Poseidon hashlocks are not supported");
+        
+        let mut escrow = self.create_escrow(
+            escrow_id.clone(),
+            beneficiary,
+            release_time,
+            arbiter,
+            metadata,
+            arbiter_fee,
+            None,
+        );
+        escrow.hashlock = Some(Hashlock {
+            hash: hash.to_lowercase(),
+            algorithm,
+            secret: None,
+        });
+        self.escrows.insert(&escrow_id, &escrow);
+        
+        env::log_str(&format!("Hashlock escrow created: {} | Hash: {}", escrow_id, hash));
+        
+        escrow
+    }
+
+    // Anyone holding the secret can unlock the escrow for the beneficiary
+    pub fn claim_with_secret(&mut self, escrow_id: String, secret: String) -> Promise {
+        let mut escrow = self.escrows.get(&escrow_id).or_fail(ErrorCode::NotFound, "Escrow not found");
+        
+        ensure!(
+            matches!(escrow.status, EscrowStatus::Active),
+            InvalidState,
+            "Escrow not active"
+        );
+        ensure!(env::block_timestamp() < escrow.release_time, Expired, "Hashlock has expired");
+        
+        let hashlock = escrow.hashlock.as_mut().or_fail(ErrorCode::InvalidState, "Not a hashlock escrow");
+        let hash = hashlock.algorithm.hash_hex(secret.as_bytes()).or_fail(ErrorCode::InvalidArgument, "Unsupported hash algorithm");
+        ensure!(hash == hashlock.hash, InvalidProof, "Invalid secret");
+        hashlock.secret = Some(secret.clone());
+        
+        self.settle_escrow(&mut escrow, EscrowStatus::Completed);
+        self.escrows.insert(&escrow_id, &escrow);
+        
+        let amount_yocto = Self::unreleased_amount(&escrow);
+        
+        env::log_str(&format!(
+            "Hashlock claimed: {} | Secret: {} | Amount: {}",
+            escrow_id, secret, amount_yocto
+        ));
+        emit_event("released", json!({
+            "escrow_id": escrow_id,
+            "beneficiary": escrow.beneficiary,
+            "amount": amount_yocto.to_string(),
+            "secret": secret,
+        }));
+        
+        self.release_payout(&escrow, amount_yocto)
+    }
+}
@@ -0,0 +1,98 @@
+use crate::*;
+
+#[near_bindgen]
+impl EscrowContract {
+    pub fn get_escrows_by_depositor(
+        &self,
+        account_id: AccountId,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Paged<Escrow> {
+        self.paginate(self.escrows_by_depositor.get(&account_id).unwrap_or_default(), from_index, limit)
+    }
+
+    pub fn get_escrows_by_beneficiary(
+        &self,
+        account_id: AccountId,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Paged<Escrow> {
+        self.paginate(self.escrows_by_beneficiary.get(&account_id).unwrap_or_default(), from_index, limit)
+    }
+
+    pub fn get_escrows_by_category(
+        &self,
+        category: String,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Paged<Escrow> {
+        self.paginate(self.escrows_by_category.get(&category).unwrap_or_default(), from_index, limit)
+    }
+
+    pub fn get_escrow_count_by_depositor(&self, account_id: AccountId) -> u64 {
+        self.escrows_by_depositor.get(&account_id).map(|ids| ids.len() as u64).unwrap_or(0)
+    }
+
+    pub fn get_escrow_count_by_beneficiary(&self, account_id: AccountId) -> u64 {
+        self.escrows_by_beneficiary.get(&account_id).map(|ids| ids.len() as u64).unwrap_or(0)
+    }
+
+    pub(crate) fn index_escrow(&mut self, escrow: &Escrow) {
+        let depositor: AccountId = escrow.depositor.parse().or_fail(ErrorCode::Internal, "Invalid depositor");
+        let beneficiary: AccountId = escrow.beneficiary.parse().or_fail(ErrorCode::Internal, "Invalid beneficiary");
+        
+        let mut ids = self.escrows_by_depositor.get(&depositor).unwrap_or_default();
+        ids.push(escrow.escrow_id.clone());
+        self.escrows_by_depositor.insert(&depositor, &ids);
+        
+        let mut ids = self.escrows_by_beneficiary.get(&beneficiary).unwrap_or_default();
+        ids.push(escrow.escrow_id.clone());
+        self.escrows_by_beneficiary.insert(&beneficiary, &ids);
+        
+        let mut ids = self.escrows_by_category.get(&escrow.metadata.category).unwrap_or_default();
+        ids.push(escrow.escrow_id.clone());
+        self.escrows_by_category.insert(&escrow.metadata.category, &ids);
+    }
+
+    pub(crate) fn index_beneficiary(&mut self, escrow_id: &str, beneficiary: &AccountId) {
+        let mut ids = self.escrows_by_beneficiary.get(beneficiary).unwrap_or_default();
+        ids.push(escrow_id.to_string());
+        self.escrows_by_beneficiary.insert(beneficiary, &ids);
+    }
+
+    pub(crate) fn reindex_beneficiary(&mut self, escrow_id: &str, previous: &str, beneficiary: &str) {
+        let previous: AccountId = previous.parse().or_fail(ErrorCode::Internal, "Invalid beneficiary");
+        if let Some(mut ids) = self.escrows_by_beneficiary.get(&previous) {
+            ids.retain(|id| id != escrow_id);
+            self.escrows_by_beneficiary.insert(&previous, &ids);
+        }
+        
+        self.index_beneficiary(escrow_id, &beneficiary.parse().or_fail(ErrorCode::Internal, "Invalid beneficiary"));
+    }
+
+    pub(crate) fn unindex_escrow(&mut self, escrow: &Escrow) {
+        let depositor: AccountId = escrow.depositor.parse().or_fail(ErrorCode::Internal, "Invalid depositor");
+        if let Some(mut ids) = self.escrows_by_depositor.get(&depositor) {
+            ids.retain(|id| *id != escrow.escrow_id);
+            self.escrows_by_depositor.insert(&depositor, &ids);
+        }
+        
+        let beneficiaries = std::iter::once(&escrow.beneficiary).chain(escrow.payees.iter().map(|p| &p.account_id));
+        for beneficiary in beneficiaries {
+            let beneficiary: AccountId = beneficiary.parse().or_fail(ErrorCode::Internal, "Invalid beneficiary");
+            if let Some(mut ids) = self.escrows_by_beneficiary.get(&beneficiary) {
+                ids.retain(|id| *id != escrow.escrow_id);
+                self.escrows_by_beneficiary.insert(&beneficiary, &ids);
+            }
+        }
+        
+        if let Some(mut ids) = self.escrows_by_category.get(&escrow.metadata.category) {
+            ids.retain(|id| *id != escrow.escrow_id);
+            self.escrows_by_category.insert(&escrow.metadata.category, &ids);
+        }
+    }
+
+    fn paginate(&self, ids: Vec<String>, from_index: Option<u64>, limit: Option<u64>) -> Paged<Escrow> {
+        Paged::from_ids(&ids, from_index, limit, |id| self.escrows.get(id))
+    }
+}
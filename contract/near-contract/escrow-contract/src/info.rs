@@ -0,0 +1,35 @@
+use crate::*;
+use crate::events::EVENTS;
+use crate::fees::{MAX_INSURANCE_FEE_BPS, MAX_SETTLEMENT_FEE_BPS};
+
+#[near_bindgen]
+impl EscrowContract {
+    // Configuration and counters in the shape every Ciphra contract shares
+    pub fn get_contract_info(&self) -> ContractInfo {
+        ContractInfo::new(
+            EVENTS.contract,
+            env!("CARGO_PKG_VERSION"),
+            format!("{:?}", self.get_state_version()),
+            &self.owner,
+            self.access.pending_owner.as_ref(),
+            &self.pause_guard,
+        )
+        .fee("settlement_fee_bps", self.settlement_fee_bps)
+        .fee("insurance_fee_bps", self.insurance_fee_bps)
+        .bound("max_settlement_fee_bps", MAX_SETTLEMENT_FEE_BPS as u64)
+        .bound("max_insurance_fee_bps", MAX_INSURANCE_FEE_BPS as u64)
+        .bound("verifier_threshold", self.verifier_threshold as u64)
+        .bound("dispute_period", self.dispute_period / 1_000_000_000)
+        .bound("appeal_period", self.appeal_period / 1_000_000_000)
+        .bound("retention_period", self.retention_period / 1_000_000_000)
+        .counter("escrows", self.escrows.len())
+        .counter("active", self.stats.active_count)
+        .counter("completed", self.stats.completed_count)
+        .counter("disputed", self.stats.disputed_count)
+        .counter("refunded", self.stats.refunded_count)
+        .counter("resolved", self.stats.resolved_count)
+        .counter("trusted_verifiers", self.trusted_verifiers.len() as u64)
+        .counter("settlements", self.settlements.next_settlement_id)
+        .rate_limit(self.rate_limiter.config)
+    }
+}
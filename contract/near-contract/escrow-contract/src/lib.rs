@@ -1,36 +1,133 @@
 use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
-use near_sdk::collections::UnorderedMap;
+use near_sdk::collections::{LookupMap, UnorderedMap};
 use near_sdk::json_types::U128;
-use near_sdk::{env, near_bindgen, AccountId, BorshStorageKey, PanicOnDefault, Promise, NearToken};
+use near_sdk::{env, near_bindgen, AccountId, BorshStorageKey, PanicOnDefault, Promise, PromiseOrValue, NearToken};
 use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::serde_json::json;
 use schemars::JsonSchema;
 
+use ciphra_types::{
+    assert_owner, decimal_borsh, ensure, fail, mul_div, normalize_tx_hash, validate_chain_address, ChainAdapter,
+    ErrorCode, OrFail,
+};
+pub use ciphra_types::{
+    AccessControl, AccessStatus, ContractInfo, CrossChainProof, EscrowMetadata, EscrowStatus, HashAlgorithm, Paged,
+    PauseGuard, PauseStatus, ProofData, RateLimitConfig, RateLimiter, Role, SettlementLedger, StorageAccounts,
+    StorageBalance, StorageBalanceBounds,
+};
+
+mod access;
+mod amendments;
+mod appeals;
+mod attachments;
+mod batch;
+mod bonds;
+mod cancellation;
+mod cleanup;
+mod deposits;
+mod events;
+mod fees;
+mod indexes;
+mod info;
+mod migration;
+mod oracle;
+mod pause;
+mod payees;
+mod proofs;
+mod rate_limit;
+mod settlement;
+mod staking;
+mod stats;
+mod storage;
+mod swap_links;
+#[cfg(test)]
+mod test_utils;
+mod tokens;
+
+// Modules with escrow constructors: create_escrow and the create_*_escrow variants that add
+// their own terms to its arguments. near_bindgen doesn't carry a method's #[allow] onto the
+// ext wrapper it generates beside the impl, so the lint is allowed per module.
+#[allow(clippy::too_many_arguments)]
+mod compliance;
+#[allow(clippy::too_many_arguments)]
+mod creation;
+#[allow(clippy::too_many_arguments)]
+mod disputes;
+#[allow(clippy::too_many_arguments)]
+mod hashlock;
+#[allow(clippy::too_many_arguments)]
+mod milestones;
+#[allow(clippy::too_many_arguments)]
+mod recurring;
+#[allow(clippy::too_many_arguments)]
+mod vesting;
+
+use events::emit_event;
+
+pub use amendments::Amendment;
+pub use appeals::Ruling;
+pub use attachments::ContentRef;
+pub use batch::EscrowParams;
+pub use bonds::VerifierBond;
+pub use compliance::Compliance;
+pub use deposits::Deposit;
+pub use disputes::{ArbiterFee, ArbiterVote, DisputeOutcome, DisputePayout, Evidence};
+pub use hashlock::Hashlock;
+pub use migration::StateVersion;
+pub use milestones::{Milestone, MilestoneInput, ReleaseCondition};
+pub use oracle::{PriceComparison, PriceCondition};
+pub use payees::{Payee, PayeeInput};
+pub use proofs::ExpectedPayment;
+pub use recurring::RecurringSchedule;
+pub use staking::{EscrowStaking, StakingStatus};
+pub use stats::{EscrowStats, EscrowStatsView};
+pub use swap_links::SwapLink;
+pub use tokens::TokenEscrowMsg;
+pub use vesting::VestingSchedule;
 
 #[derive(BorshSerialize, BorshStorageKey)]
 pub enum StorageKey {
     Escrows,
     ProofVerifications,
+    FailedTokenPayouts,
+    EscrowsByDepositor,
+    EscrowsByBeneficiary,
+    LightClients,
+    ConsumedProofs,
+    StorageAccounts,
+    RecordStoragePayers,
+    VerifierBonds,
+    InsuranceClaims,
+    Treasury,
+    EscrowsByCategory,
+    FailedPayouts, // Dropped in V7; kept so later keys keep their prefixes
+    PoolShares,
+    PendingSettlements,
+    ClaimableSettlements,
+    RateLimits,
+    PoolOperations,
 }
 
-#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
-#[serde(crate = "near_sdk::serde")]
-pub enum EscrowStatus {
-    Active,
-    Completed,
-    Disputed,
-    Refunded,
-}
+// Default time arbiters have to settle a dispute before the default outcome applies (14 days)
+const DEFAULT_DISPUTE_PERIOD: u64 = 14 * 86_400 * 1_000_000_000;
 
-#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
-#[serde(crate = "near_sdk::serde")]
-pub struct CrossChainProof {
-    pub chain_id: String,
-    pub tx_hash: String,
-    pub block_number: u64,
-    pub proof_data: String,
-    pub verified: bool,
-    pub verified_at: Option<u64>,
-}
+// Default time either party has to appeal a single arbiter's ruling (3 days)
+const DEFAULT_APPEAL_PERIOD: u64 = 3 * 86_400 * 1_000_000_000;
+
+// Upper bound on cross-chain proofs carried by one escrow
+const MAX_PROOFS: usize = 10;
+
+// Default bond a trusted verifier must hold (10 NEAR)
+const DEFAULT_MIN_VERIFIER_BOND: u128 = 10_000_000_000_000_000_000_000_000;
+
+// Time after a verification during which it can be challenged (1 day)
+const CHALLENGE_WINDOW: u64 = 86_400 * 1_000_000_000;
+
+// Default fee taken from releases to the beneficiary (0.1%)
+const DEFAULT_SETTLEMENT_FEE_BPS: u16 = 10;
+
+// Default time settled escrows are kept before cleanup_escrows may remove them (30 days)
+const DEFAULT_RETENTION_PERIOD: u64 = 30 * 86_400 * 1_000_000_000;
 
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, JsonSchema)]
 #[serde(crate = "near_sdk::serde")]
@@ -38,228 +135,464 @@ pub struct Escrow {
     pub escrow_id: String,
     pub depositor: String,
     pub beneficiary: String,
-    pub amount: String,
+    #[borsh(serialize_with = "decimal_borsh::serialize", deserialize_with = "decimal_borsh::deserialize")]
+    #[schemars(with = "String")]
+    pub amount: U128,
     pub release_time: u64,
     pub status: EscrowStatus,
-    pub cross_chain_proof: Option<CrossChainProof>,
+    pub proofs: Vec<CrossChainProof>,
+    pub required_proofs: Vec<String>, // Proof ids that must all verify; empty means any verified proof
+    pub allow_shared_proofs: bool,    // Accept proofs another escrow already released on
     pub arbiter: Option<String>,
     pub created_at: u64,
-    pub metadata: String,
+    pub metadata: EscrowMetadata,
+    pub settled_at: Option<u64>,
+    pub milestones: Vec<Milestone>, // Empty for single-release escrows
+    #[borsh(serialize_with = "decimal_borsh::serialize", deserialize_with = "decimal_borsh::deserialize")]
+    #[schemars(with = "String")]
+    pub released: U128,           // Amount already paid out through milestones
+    pub committee: Vec<String>,     // Arbiter committee; disputes need `threshold` matching votes
+    pub threshold: u8,
+    pub votes: Vec<ArbiterVote>,
+    pub arbiter_fee: Option<ArbiterFee>,
+    pub evidence: Vec<Evidence>,
+    pub dispute_deadline: Option<u64>,
+    pub default_outcome: Option<DisputeOutcome>,
+    pub token: Option<String>, // NEP-141 contract for token escrows; None for NEAR
+    pub pending_amendment: Option<Amendment>,
+    pub amendments: Vec<Amendment>, // Accepted amendments, oldest first
+    pub cancel_proposed_by: Option<String>,
+    pub schedule: Option<RecurringSchedule>, // Set for recurring escrows
+    pub vesting: Option<VestingSchedule>,    // Set for vesting escrows
+    pub payees: Vec<Payee>,                  // Empty for single-beneficiary escrows
+    pub price_condition: Option<PriceCondition>, // Set for price escrows
+    pub hashlock: Option<Hashlock>,              // Set for hashlock escrows
+    pub staking: Option<EscrowStaking>,          // Set once the depositor opts into staking
+    pub disputed_at: Option<u64>,
+    pub ruling: Option<Ruling>, // Single-arbiter decision awaiting its appeal window
+    pub appealed: bool,
+    pub swap_link: Option<SwapLink>,
+    pub payout_address: Option<String>, // Beneficiary-designated receiver of its releases
+    pub compliance: Option<Compliance>,
+    pub deposits: Vec<Deposit>, // Initial deposit followed by any top-ups
+    pub attachments: Vec<ContentRef>,
+    pub expected_payments: Vec<ExpectedPayment>, // Terms light client proofs are checked against
+    pub dispute_payout: Option<DisputePayout>,    // What each side received when a dispute settled
 }
 
-#[near_bindgen]
+// Version and link come from the package; the escrow also emits NEP-297 events
+#[near_bindgen(contract_metadata(
+    standard(standard = "nep330", version = "1.1.0"),
+    standard(standard = "nep297", version = "1.0.0"),
+))]
 #[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
 pub struct EscrowContract {
     pub escrows: UnorderedMap<String, Escrow>,
     pub proof_verifications: UnorderedMap<String, bool>,
     pub owner: AccountId,
     pub trusted_verifiers: Vec<AccountId>,
+    pub verifier_threshold: u8, // Trusted verifier approvals needed per proof
+    pub verifier_bonds: LookupMap<AccountId, VerifierBond>,
+    pub min_verifier_bond: u128,
+    pub insurance_pool: u128,                       // Slashed bonds not yet paid out as compensation
+    pub insurance_claims: LookupMap<AccountId, u128>, // Compensation owed to harmed depositors
+    pub settlement_fee_bps: u16,
+    pub treasury_account: AccountId,
+    pub treasury: LookupMap<String, u128>, // Accrued fees per asset ("near" or token contract)
+    pub retention_period: u64,
+    pub insurance_fund: Option<AccountId>, // External insurance pool contract fed from settlement fees
+    pub insurance_fee_bps: u16,
+    pub insurance_fee_accrued: u128,
+    pub price_oracle: Option<AccountId>,
+    pub swap_contract: Option<AccountId>, // Attests completed swaps for linked escrows
+    pub compliance_registries: Vec<AccountId>, // Allowlist registries escrows may opt into
+    pub staking_pools: Vec<AccountId>,          // Whitelisted pools escrows may stake with
+    pub pool_shares: LookupMap<AccountId, u128>, // Total escrow shares outstanding per pool
+    pub stats: EscrowStats,
+    pub pause_guard: PauseGuard, // Guardians may pause but not unpause
+    pub dispute_period: u64,
+    pub default_dispute_outcome: DisputeOutcome,
+    pub appeal_period: u64,
+    pub appeal_committee: Vec<AccountId>, // Takes over appealed disputes; empty leaves them to the owner
+    pub appeal_threshold: u8,
+    pub failed_token_payouts: LookupMap<String, u128>,
+    pub escrows_by_depositor: UnorderedMap<AccountId, Vec<String>>,
+    pub escrows_by_beneficiary: UnorderedMap<AccountId, Vec<String>>,
+    pub escrows_by_category: UnorderedMap<String, Vec<String>>,
+    pub light_clients: LookupMap<String, AccountId>, // chain_id -> light client; other chains use trusted verifiers
+    pub consumed_proofs: LookupMap<String, String>,  // Proof receipt key -> escrow released on it
+    pub fee_treasury: Option<AccountId>, // Fee treasury contract the NEAR treasury balance is forwarded to
+    pub storage_accounts: StorageAccounts, // NEP-145 prepaid storage
+    pub record_storage_payers: LookupMap<String, AccountId>,     // escrow_id -> account whose deposit it locks
+    pub access: AccessControl, // Pending owner transfer and delegated roles
+    pub settlements: SettlementLedger, // NEAR payouts awaiting their transfer callback
+    pub rate_limiter: RateLimiter,     // Bounds escrows created per depositor
+    pub pool_operations: LookupMap<AccountId, String>, // Pool -> escrow whose stake or unstake is in flight
 }
 
 #[near_bindgen]
 impl EscrowContract {
     #[init]
     pub fn new(owner: AccountId) -> Self {
+        Self::write_state_version();
         Self {
             escrows: UnorderedMap::new(StorageKey::Escrows),
             proof_verifications: UnorderedMap::new(StorageKey::ProofVerifications),
             owner: owner.clone(),
-            trusted_verifiers: vec![owner],
+            trusted_verifiers: vec![owner.clone()],
+            verifier_threshold: 1,
+            verifier_bonds: LookupMap::new(StorageKey::VerifierBonds),
+            min_verifier_bond: DEFAULT_MIN_VERIFIER_BOND,
+            insurance_pool: 0,
+            insurance_claims: LookupMap::new(StorageKey::InsuranceClaims),
+            settlement_fee_bps: DEFAULT_SETTLEMENT_FEE_BPS,
+            treasury_account: owner,
+            treasury: LookupMap::new(StorageKey::Treasury),
+            retention_period: DEFAULT_RETENTION_PERIOD,
+            insurance_fund: None,
+            insurance_fee_bps: 0,
+            insurance_fee_accrued: 0,
+            price_oracle: None,
+            swap_contract: None,
+            compliance_registries: Vec::new(),
+            staking_pools: Vec::new(),
+            pool_shares: LookupMap::new(StorageKey::PoolShares),
+            stats: EscrowStats::default(),
+            pause_guard: PauseGuard::default(),
+            dispute_period: DEFAULT_DISPUTE_PERIOD,
+            default_dispute_outcome: DisputeOutcome::RefundDepositor,
+            appeal_period: DEFAULT_APPEAL_PERIOD,
+            appeal_committee: Vec::new(),
+            appeal_threshold: 0,
+            failed_token_payouts: LookupMap::new(StorageKey::FailedTokenPayouts),
+            escrows_by_depositor: UnorderedMap::new(StorageKey::EscrowsByDepositor),
+            escrows_by_beneficiary: UnorderedMap::new(StorageKey::EscrowsByBeneficiary),
+            escrows_by_category: UnorderedMap::new(StorageKey::EscrowsByCategory),
+            light_clients: LookupMap::new(StorageKey::LightClients),
+            consumed_proofs: LookupMap::new(StorageKey::ConsumedProofs),
+            fee_treasury: None,
+            storage_accounts: StorageAccounts::new(StorageKey::StorageAccounts),
+            record_storage_payers: LookupMap::new(StorageKey::RecordStoragePayers),
+            access: AccessControl::default(),
+            settlements: Self::new_settlement_ledger(),
+            rate_limiter: Self::new_rate_limiter(),
+            pool_operations: Self::new_pool_operations(),
         }
     }
 
-    #[payable]
-    pub fn create_escrow(
-        &mut self,
-        escrow_id: String,
-        beneficiary: AccountId,
-        release_time: u64,
-        arbiter: Option<AccountId>,
-        metadata: String,
-    ) -> Escrow {
-        let depositor = env::predecessor_account_id();
-        let amount = env::attached_deposit();
-        
-        assert!(amount.as_yoctonear() > 0, "Must attach NEAR tokens");
-        assert!(self.escrows.get(&escrow_id).is_none(), "Escrow ID already exists");
-        assert!(release_time > env::block_timestamp(), "Release time must be in future");
-        
-        let escrow = Escrow {
-            escrow_id: escrow_id.clone(),
-            depositor: depositor.to_string(),
-            beneficiary: beneficiary.to_string(),
-            amount: amount.as_yoctonear().to_string(),
-            release_time,
-            status: EscrowStatus::Active,
-            cross_chain_proof: None,
-            arbiter: arbiter.map(|a| a.to_string()),
-            created_at: env::block_timestamp(),
-            metadata,
-        };
-        
-        self.escrows.insert(&escrow_id, &escrow);
-        
-        env::log_str(&format!(
-            "Escrow created: {} | Amount: {} | Beneficiary: {}",
-            escrow_id, amount, beneficiary
-        ));
-        
-        escrow
-    }
-
+    // Parties add or replace an unapproved proof; each proof id is verified independently
     pub fn submit_cross_chain_proof(
         &mut self,
         escrow_id: String,
+        proof_id: String,
         chain_id: String,
         tx_hash: String,
         block_number: u64,
-        proof_data: String,
+        proof_data: ProofData,
     ) {
-        let mut escrow = self.escrows.get(&escrow_id).expect("Escrow not found");
+        self.assert_not_paused();
+        let mut escrow = self.escrows.get(&escrow_id).or_fail(ErrorCode::NotFound, "Escrow not found");
         
-        assert!(
+        let caller = env::predecessor_account_id();
+        ensure!(
+            caller.as_str() == escrow.depositor || escrow.is_payee(&caller),
+            Unauthorized,
+            "Only parties can submit proofs"
+        );
+        ensure!(
             matches!(escrow.status, EscrowStatus::Active),
+            InvalidState,
             "Escrow must be active"
         );
         
+        // Canonical form, so the same transaction can't be replayed under another spelling
+        let tx_hash = normalize_tx_hash(&chain_id, &tx_hash)
+            .unwrap_or_else(|err| fail(ErrorCode::InvalidArgument, err));
+        Self::assert_valid_block_hash(&proof_data.block_hash);
+        let existing = escrow.proofs.iter().position(|p| p.proof_id == proof_id);
+        if let Some(index) = existing {
+            ensure!(!escrow.proofs[index].verified, InvalidState, "Proof already verified");
+            // Replacing the proof would discard the quorum's approvals
+            ensure!(escrow.proofs[index].approvals.is_empty(), InvalidState, "Proof already has approvals");
+        } else {
+            ensure!(escrow.proofs.len() < MAX_PROOFS, LimitExceeded, "Too many proofs");
+        }
+        
         let proof = CrossChainProof {
-            chain_id,
+            proof_id: proof_id.clone(),
+            chain_id: chain_id.clone(),
             tx_hash: tx_hash.clone(),
             block_number,
             proof_data,
             verified: false,
             verified_at: None,
+            approvals: Vec::new(),
         };
+        self.assert_proof_unconsumed(&escrow, &proof.receipt_key());
         
-        escrow.cross_chain_proof = Some(proof);
+        match existing {
+            Some(index) => escrow.proofs[index] = proof,
+            None => escrow.proofs.push(proof),
+        }
         self.escrows.insert(&escrow_id, &escrow);
         
         env::log_str(&format!(
-            "Cross-chain proof submitted for escrow: {} | TX: {}",
-            escrow_id, tx_hash
+            "Cross-chain proof submitted for escrow: {} | Proof: {} | TX: {}",
+            escrow_id, proof_id, tx_hash
         ));
+        let tx_key = format!("{}:{}", chain_id, tx_hash);
+        emit_event("proof_submitted", json!({
+            "escrow_id": escrow_id,
+            "proof_id": proof_id,
+            "submitted_by": caller,
+            "chain_id": chain_id,
+            "tx_hash": tx_hash,
+            "block_number": block_number,
+            "replaced": existing.is_some(),
+            // The same transaction already verified elsewhere is worth an alert
+            "previously_verified": self.proof_verifications.get(&tx_key).unwrap_or(false),
+        }));
     }
 
-    pub fn verify_proof(&mut self, escrow_id: String) {
+    // Proofs from chains with a light client are checked on-chain by anyone; other chains need a trusted verifier
+    pub fn verify_proof(&mut self, escrow_id: String, proof_id: String) -> PromiseOrValue<bool> {
+        self.assert_not_paused();
+        let mut escrow = self.escrows.get(&escrow_id).or_fail(ErrorCode::NotFound, "Escrow not found");
+        let index = escrow.proofs.iter().position(|p| p.proof_id == proof_id).or_fail(ErrorCode::NotFound, "Proof not found");
+        ensure!(!escrow.proofs[index].verified, InvalidState, "Proof already verified");
+        
+        if let Some(light_client) = self.light_clients.get(&escrow.proofs[index].chain_id) {
+            let expected = escrow
+                .expected_payment(&proof_id)
+                .or_fail(ErrorCode::InvalidState, "No expected payment for proof");
+            return self.verify_with_light_client(escrow_id, light_client, &escrow.proofs[index], expected);
+        }
+        
         let verifier = env::predecessor_account_id();
         
-        assert!(
-            self.trusted_verifiers.contains(&verifier) || verifier == self.owner,
+        ensure!(
+            self.is_trusted_verifier(&verifier),
+            Unauthorized,
             "Not authorized to verify proofs"
         );
         
-        let mut escrow = self.escrows.get(&escrow_id).expect("Escrow not found");
+        // Each trusted verifier approves once; approvals from since-removed verifiers don't count
+        let proof = &mut escrow.proofs[index];
+        ensure!(!proof.approvals.contains(&verifier.to_string()), InvalidState, "Already approved");
+        proof.approvals.push(verifier.to_string());
+        self.record_approval(&verifier);
+        let approvals = proof
+            .approvals
+            .iter()
+            .filter(|a| a.parse().is_ok_and(|a: AccountId| self.is_trusted_verifier(&a)))
+            .count();
+        self.escrows.insert(&escrow_id, &escrow);
         
-        assert!(escrow.cross_chain_proof.is_some(), "No proof submitted");
+        let proof = &escrow.proofs[index];
         
-        if let Some(mut proof) = escrow.cross_chain_proof {
-            proof.verified = true;
-            proof.verified_at = Some(env::block_timestamp());
-            escrow.cross_chain_proof = Some(proof.clone());
-            self.escrows.insert(&escrow_id, &escrow);
-            
-            let proof_key = format!("{}:{}", proof.chain_id, proof.tx_hash);
-            self.proof_verifications.insert(&proof_key, &true);
-            
-            env::log_str(&format!("Proof verified for escrow: {}", escrow_id));
+        env::log_str(&format!(
+            "Proof approved: {} | Proof: {} | Approvals: {}/{}",
+            escrow_id, proof_id, approvals, self.verifier_threshold
+        ));
+        emit_event("proof_approved", json!({
+            "escrow_id": escrow_id,
+            "proof_id": proof_id,
+            "verifier": verifier,
+            "chain_id": proof.chain_id,
+            "tx_hash": proof.tx_hash,
+            "block_number": proof.block_number,
+            "approvals": approvals,
+            "threshold": self.verifier_threshold,
+        }));
+        if approvals < self.verifier_threshold as usize {
+            return PromiseOrValue::Value(false);
         }
+        
+        self.mark_proof_verified(&escrow_id, &proof_id, verifier);
+        PromiseOrValue::Value(true)
     }
 
     pub fn release_funds(&mut self, escrow_id: String) -> Promise {
-        let mut escrow = self.escrows.get(&escrow_id).expect("Escrow not found");
-        
-        let caller = env::predecessor_account_id();
+        let escrow = self.escrows.get(&escrow_id).or_fail(ErrorCode::NotFound, "Escrow not found");
+        ensure!(
+            escrow.compliance.is_none(),
+            InvalidState,
+            "Compliance escrows release through release_compliant"
+        );
         
-        let beneficiary: AccountId = escrow.beneficiary.parse().expect("Invalid beneficiary");
-        let is_beneficiary = caller == beneficiary;
-        let is_arbiter = escrow.arbiter.as_ref().map_or(false, |a| {
-            let arbiter: AccountId = a.parse().expect("Invalid arbiter");
+        self.execute_release(escrow, env::predecessor_account_id())
+    }
+
+    // Shared by release_funds and the compliance screening callback
+    fn execute_release(&mut self, mut escrow: Escrow, caller: AccountId) -> Promise {
+        let escrow_id = escrow.escrow_id.clone();
+        let is_beneficiary = escrow.is_payee(&caller);
+        let is_arbiter = escrow.arbiter.as_ref().is_some_and(|a| {
+            let arbiter: AccountId = a.parse().or_fail(ErrorCode::Internal, "Invalid arbiter");
             arbiter == caller
         });
         let time_passed = env::block_timestamp() >= escrow.release_time;
-        let proof_verified = escrow
-            .cross_chain_proof
-            .as_ref()
-            .map_or(false, |p| p.verified);
+        let proof_verified = escrow.proofs_satisfied();
         
-        assert!(
+        ensure!(
             (is_beneficiary && (time_passed || proof_verified)) || is_arbiter,
+            TooEarly,
             "Cannot release funds yet"
         );
-        assert!(
+        // Milestone tranches are only released by approval
+        ensure!(
+            escrow.milestones.is_empty() || is_arbiter,
+            InvalidState,
+            "Milestone escrows release through approve_milestone"
+        );
+        ensure!(
+            escrow.schedule.is_none() || is_arbiter,
+            InvalidState,
+            "Recurring escrows release through claim_periods"
+        );
+        ensure!(
+            escrow.vesting.is_none() || is_arbiter,
+            InvalidState,
+            "Vesting escrows release through claim_vested"
+        );
+        ensure!(
+            escrow.price_condition.is_none() || is_arbiter,
+            InvalidState,
+            "Price escrows release through release_on_price"
+        );
+        ensure!(
+            escrow.hashlock.is_none() || is_arbiter,
+            InvalidState,
+            "Hashlock escrows release through claim_with_secret"
+        );
+        // Before release time the beneficiary is releasing on the strength of its proofs
+        if !is_arbiter && !time_passed {
+            self.consume_proofs(&escrow);
+        }
+        ensure!(
+            !matches!(escrow.status, EscrowStatus::Disputed),
+            InvalidState,
+            "Disputed escrows settle through arbitration"
+        );
+        ensure!(
             matches!(escrow.status, EscrowStatus::Active),
+            InvalidState,
             "Escrow not active"
         );
         
-        escrow.status = EscrowStatus::Completed;
+        self.settle_escrow(&mut escrow, EscrowStatus::Completed);
         self.escrows.insert(&escrow_id, &escrow);
         
-        let amount_yocto: u128 = escrow.amount.parse().expect("Invalid amount");
+        let amount_yocto = Self::unreleased_amount(&escrow);
         
         env::log_str(&format!(
             "Funds released from escrow: {} | Amount: {}",
             escrow_id, amount_yocto
         ));
+        emit_event("released", json!({
+            "escrow_id": escrow_id,
+            "beneficiary": escrow.beneficiary,
+            "payout_address": escrow.payout_address,
+            "amount": amount_yocto.to_string(),
+        }));
         
-        let release_amount = NearToken::from_yoctonear(amount_yocto);
-        let beneficiary: AccountId = escrow.beneficiary.parse().expect("Invalid beneficiary");
-        Promise::new(beneficiary).transfer(release_amount)
+        self.release_payout(&escrow, amount_yocto)
     }
 
     pub fn refund_escrow(&mut self, escrow_id: String) -> Promise {
-        let mut escrow = self.escrows.get(&escrow_id).expect("Escrow not found");
+        let mut escrow = self.escrows.get(&escrow_id).or_fail(ErrorCode::NotFound, "Escrow not found");
         
         let caller = env::predecessor_account_id();
         
-        let depositor: AccountId = escrow.depositor.parse().expect("Invalid depositor");
+        let depositor: AccountId = escrow.depositor.parse().or_fail(ErrorCode::Internal, "Invalid depositor");
         let is_depositor = caller == depositor;
-        let is_arbiter = escrow.arbiter.as_ref().map_or(false, |a| {
-            let arbiter: AccountId = a.parse().expect("Invalid arbiter");
+        let is_arbiter = escrow.arbiter.as_ref().is_some_and(|a| {
+            let arbiter: AccountId = a.parse().or_fail(ErrorCode::Internal, "Invalid arbiter");
             arbiter == caller
         });
         
-        assert!(
+        ensure!(
             is_depositor || is_arbiter,
+            Unauthorized,
             "Only depositor or arbiter can refund"
         );
         
         let time_passed = env::block_timestamp() >= escrow.release_time;
-        let no_verified_proof = !escrow
-            .cross_chain_proof
-            .as_ref()
-            .map_or(false, |p| p.verified);
+        let no_verified_proof = !escrow.any_proof_verified();
         
-        assert!(
+        ensure!(
             time_passed && no_verified_proof,
+            InvalidState,
             "Cannot refund: time not passed or proof verified"
         );
+        // Vested periods belong to the beneficiary
+        ensure!(
+            escrow.schedule.is_none() || is_arbiter,
+            InvalidState,
+            "Recurring escrows refund through cancel_future_periods"
+        );
+        ensure!(
+            escrow.vesting.is_none() || is_arbiter,
+            InvalidState,
+            "Vesting escrows refund through revoke_vesting"
+        );
+        ensure!(
+            !matches!(escrow.status, EscrowStatus::Disputed),
+            InvalidState,
+            "Disputed escrows settle through arbitration"
+        );
+        ensure!(
+            matches!(escrow.status, EscrowStatus::Active),
+            InvalidState,
+            "Escrow not active"
+        );
         
-        escrow.status = EscrowStatus::Refunded;
+        self.settle_escrow(&mut escrow, EscrowStatus::Refunded);
         self.escrows.insert(&escrow_id, &escrow);
         
         env::log_str(&format!("Escrow refunded: {}", escrow_id));
         
-        let amount_yocto: u128 = escrow.amount.parse().expect("Invalid amount");
-        let refund_amount = NearToken::from_yoctonear(amount_yocto);
-        let depositor: AccountId = escrow.depositor.parse().expect("Invalid depositor");
-        Promise::new(depositor).transfer(refund_amount)
+        let amount_yocto = Self::unreleased_amount(&escrow);
+        emit_event("refunded", json!({
+            "escrow_id": escrow_id,
+            "depositor": escrow.depositor,
+            "amount": amount_yocto.to_string(),
+        }));
+        let depositor: AccountId = escrow.depositor.parse().or_fail(ErrorCode::Internal, "Invalid depositor");
+        self.payout(&escrow, depositor, amount_yocto)
     }
 
     pub fn raise_dispute(&mut self, escrow_id: String) {
-        let mut escrow = self.escrows.get(&escrow_id).expect("Escrow not found");
+        let mut escrow = self.escrows.get(&escrow_id).or_fail(ErrorCode::NotFound, "Escrow not found");
         
         let caller = env::predecessor_account_id();
-        let depositor: AccountId = escrow.depositor.parse().expect("Invalid depositor");
-        let beneficiary: AccountId = escrow.beneficiary.parse().expect("Invalid beneficiary");
-        assert!(
+        let depositor: AccountId = escrow.depositor.parse().or_fail(ErrorCode::Internal, "Invalid depositor");
+        let beneficiary: AccountId = escrow.beneficiary.parse().or_fail(ErrorCode::Internal, "Invalid beneficiary");
+        ensure!(
             caller == depositor || caller == beneficiary,
+            Unauthorized,
             "Only parties can raise dispute"
         );
+        ensure!(
+            matches!(escrow.status, EscrowStatus::Active),
+            InvalidState,
+            "Escrow not active"
+        );
         
+        // Unresolved disputes fall back to the outcome configured when they were raised
         escrow.status = EscrowStatus::Disputed;
+        escrow.disputed_at = Some(env::block_timestamp());
+        escrow.dispute_deadline = Some(env::block_timestamp() + self.dispute_period);
+        escrow.default_outcome = Some(self.default_dispute_outcome.clone());
         self.escrows.insert(&escrow_id, &escrow);
+        self.track_dispute(&escrow);
         
         env::log_str(&format!("Dispute raised for escrow: {}", escrow_id));
+        emit_event("disputed", json!({
+            "escrow_id": escrow_id,
+            "raised_by": caller,
+            "dispute_deadline": escrow.dispute_deadline,
+        }));
     }
 
     pub fn get_escrow(&self, escrow_id: String) -> Option<Escrow> {
@@ -267,19 +600,81 @@ impl EscrowContract {
     }
     
     pub fn is_proof_verified(&self, chain_id: String, tx_hash: String) -> bool {
+        let tx_hash = normalize_tx_hash(&chain_id, &tx_hash).unwrap_or(tx_hash);
         let proof_key = format!("{}:{}", chain_id, tx_hash);
         self.proof_verifications.get(&proof_key).unwrap_or(false)
     }
 
     pub fn add_trusted_verifier(&mut self, verifier: AccountId) {
-        assert_eq!(env::predecessor_account_id(), self.owner, "Only owner");
+        assert_owner(&self.owner);
+        ensure!(self.has_minimum_bond(&verifier), InsufficientFunds, "Verifier has not bonded the minimum");
         if !self.trusted_verifiers.contains(&verifier) {
             self.trusted_verifiers.push(verifier);
         }
     }
     
     pub fn remove_trusted_verifier(&mut self, verifier: AccountId) {
-        assert_eq!(env::predecessor_account_id(), self.owner, "Only owner");
+        assert_owner(&self.owner);
         self.trusted_verifiers.retain(|v| v != &verifier);
+        ensure!(
+            self.trusted_verifiers.len() >= self.verifier_threshold as usize,
+            LimitExceeded,
+            "Threshold exceeds remaining verifiers"
+        );
+    }
+
+    // K-of-N quorum of trusted verifiers required to verify a proof
+    pub fn set_verifier_threshold(&mut self, threshold: u8) {
+        assert_owner(&self.owner);
+        ensure!(
+            threshold > 0 && threshold as usize <= self.trusted_verifiers.len(),
+            InvalidArgument,
+            "Threshold must be between 1 and the number of verifiers"
+        );
+        self.verifier_threshold = threshold;
+    }
+
+    pub fn get_verifier_threshold(&self) -> u8 {
+        self.verifier_threshold
+    }
+
+    pub fn get_owner(&self) -> AccountId {
+        self.owner.clone()
+    }
+
+    // The owner verifies unbonded; everyone else needs a listed, sufficiently bonded account
+    fn is_trusted_verifier(&self, account: &AccountId) -> bool {
+        *account == self.owner || (self.trusted_verifiers.contains(account) && self.has_minimum_bond(account))
+    }
+
+    fn mark_proof_verified(&mut self, escrow_id: &str, proof_id: &str, verifier: AccountId) {
+        let mut escrow = self.escrows.get(&escrow_id.to_string()).or_fail(ErrorCode::NotFound, "Escrow not found");
+        
+        if let Some(proof) = escrow.proofs.iter_mut().find(|p| p.proof_id == proof_id) {
+            proof.verified = true;
+            proof.verified_at = Some(env::block_timestamp());
+            let proof = proof.clone();
+            self.escrows.insert(&escrow_id.to_string(), &escrow);
+            
+            let proof_key = format!("{}:{}", proof.chain_id, proof.tx_hash);
+            let previously_verified = self.proof_verifications.insert(&proof_key, &true).unwrap_or(false);
+            
+            env::log_str(&format!("Proof verified for escrow: {} | Proof: {}", escrow_id, proof_id));
+            emit_event("proof_verified", json!({
+                "escrow_id": escrow_id,
+                "proof_id": proof_id,
+                "verifier": verifier,
+                "chain_id": proof.chain_id,
+                "tx_hash": proof.tx_hash,
+                "block_number": proof.block_number,
+                "previously_verified": previously_verified,
+            }));
+        }
+    }
+
+    fn unreleased_amount(escrow: &Escrow) -> u128 {
+        let amount_yocto: u128 = escrow.amount.0;
+        let released_yocto: u128 = escrow.released.0;
+        amount_yocto - released_yocto
     }
 }
\ No newline at end of file
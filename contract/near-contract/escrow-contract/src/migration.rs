@@ -0,0 +1,337 @@
+use crate::*;
+use ciphra_types::{
+    drain_legacy, migrate_state, state_read_rewriting, stored_state_version, take_stored_field,
+    write_state_version, VersionedState,
+};
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, PartialEq, Debug, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub enum StateVersion {
+    V1, // Original deployment: single proof, string metadata, no fees or indexes
+    V2,
+    V3, // Appended `access`
+    V4, // Appended `settlements`
+    V5, // Appended `rate_limiter`
+    V6, // Appended `pool_operations`
+    V7, // Dropped `failed_payouts`; its balances moved into `settlements`
+}
+
+// Contract layout of the original deployment
+#[derive(BorshDeserialize, BorshSerialize)]
+struct EscrowContractV1 {
+    escrows: UnorderedMap<String, EscrowV1>,
+    proof_verifications: UnorderedMap<String, bool>,
+    owner: AccountId,
+    trusted_verifiers: Vec<AccountId>,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+struct EscrowV1 {
+    escrow_id: String,
+    depositor: String,
+    beneficiary: String,
+    amount: String,
+    release_time: u64,
+    status: EscrowStatus, // Later variants were appended, so V1 values decode unchanged
+    cross_chain_proof: Option<CrossChainProofV1>,
+    arbiter: Option<String>,
+    created_at: u64,
+    metadata: String,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+struct CrossChainProofV1 {
+    chain_id: String,
+    tx_hash: String,
+    block_number: u64,
+    proof_data: String,
+    verified: bool,
+    verified_at: Option<u64>,
+}
+
+// Fields stored ahead of `failed_payouts` in layouts V2 to V6
+type FieldsBeforeFailedPayouts = (
+    (
+        UnorderedMap<String, Escrow>,
+        UnorderedMap<String, bool>,
+        AccountId,
+        Vec<AccountId>,
+        u8,
+        LookupMap<AccountId, VerifierBond>,
+        u128,
+        u128,
+        LookupMap<AccountId, u128>,
+        u16,
+        AccountId,
+        LookupMap<String, u128>,
+    ),
+    (
+        u64,
+        Option<AccountId>,
+        u16,
+        u128,
+        Option<AccountId>,
+        Option<AccountId>,
+        Vec<AccountId>,
+        Vec<AccountId>,
+        LookupMap<AccountId, u128>,
+        EscrowStats,
+        PauseGuard,
+        u64,
+    ),
+    (DisputeOutcome, u64, Vec<AccountId>, u8, LookupMap<String, u128>),
+);
+
+// Add a new variant and a `migrate_from` arm with every layout change
+impl VersionedState for EscrowContract {
+    type Version = StateVersion;
+
+    const CURRENT_VERSION: StateVersion = StateVersion::V7;
+    const UNVERSIONED: StateVersion = StateVersion::V1;
+
+    fn migrate_from(version: &StateVersion) -> Self {
+        match version {
+            StateVersion::V1 => Self::migrate_from_v1(),
+            StateVersion::V2 => Self::migrate_dropping_failed_payouts(&(
+                AccessControl::default(),
+                Self::new_settlement_ledger(),
+                Self::new_rate_limiter(),
+                Self::new_pool_operations(),
+            )),
+            StateVersion::V3 => Self::migrate_dropping_failed_payouts(&(
+                Self::new_settlement_ledger(),
+                Self::new_rate_limiter(),
+                Self::new_pool_operations(),
+            )),
+            StateVersion::V4 => {
+                Self::migrate_dropping_failed_payouts(&(Self::new_rate_limiter(), Self::new_pool_operations()))
+            }
+            StateVersion::V5 => Self::migrate_dropping_failed_payouts(&Self::new_pool_operations()),
+            StateVersion::V6 => Self::migrate_dropping_failed_payouts(&()),
+            StateVersion::V7 => fail(ErrorCode::Internal, "Already at the current version"),
+        }
+    }
+}
+
+#[near_bindgen]
+impl EscrowContract {
+    // Called with the upgrade deploy; rewrites state from whichever version is stored
+    #[private]
+    #[init(ignore_state)]
+    pub fn migrate() -> Self {
+        migrate_state()
+    }
+
+    pub fn get_state_version(&self) -> StateVersion {
+        stored_state_version::<Self>()
+    }
+
+    pub(crate) fn write_state_version() {
+        write_state_version::<Self>();
+    }
+
+    fn migrate_from_v1() -> Self {
+        let mut old: EscrowContractV1 = env::state_read().or_fail(ErrorCode::InvalidState, "Contract not initialized");
+        let mut contract = Self::new(old.owner.clone());
+        contract.trusted_verifiers = old.trusted_verifiers;
+        contract.proof_verifications = old.proof_verifications;
+        
+        // Same storage prefix, new value layout - drain the old entries before re-inserting
+        for (escrow_id, escrow) in drain_legacy(&mut old.escrows) {
+            let escrow = contract.upgrade_escrow(escrow);
+            contract.escrows.insert(&escrow_id, &escrow);
+            contract.index_escrow(&escrow);
+            contract.track_migrated_escrow(&escrow);
+        }
+        
+        contract
+    }
+
+    // Failed payouts predating the settlement ledger become claimable settlements. The map can't
+    // be iterated, so it is looked up for every account an escrow could have paid: parties,
+    // arbiters and payout addresses. Public refund bounties went to the caller, which just
+    // signed, so they could not fail.
+    fn migrate_dropping_failed_payouts(appended: &impl BorshSerialize) -> Self {
+        let (mut contract, mut failed_payouts): (Self, LookupMap<AccountId, u128>) = state_read_rewriting(
+            take_stored_field::<FieldsBeforeFailedPayouts, LookupMap<AccountId, u128>>,
+            appended,
+        );
+        
+        let mut accounts: Vec<String> = contract.escrows.values().flat_map(|escrow| escrow.paid_accounts()).collect();
+        accounts.extend(contract.escrows_by_depositor.keys().map(|account_id| account_id.to_string()));
+        accounts.extend(contract.escrows_by_beneficiary.keys().map(|account_id| account_id.to_string()));
+        accounts.extend(contract.appeal_committee.iter().map(|account_id| account_id.to_string()));
+        accounts.push(contract.owner.to_string());
+        
+        let mut moved_yocto = 0;
+        for account_id in accounts.iter().filter_map(|account| account.parse::<AccountId>().ok()) {
+            if let Some(amount_yocto) = failed_payouts.remove(&account_id) {
+                contract.settlements.credit(&account_id, amount_yocto);
+                moved_yocto += amount_yocto;
+            }
+        }
+        
+        env::log_str(&format!("Failed payouts moved to settlements | Amount: {}", moved_yocto));
+        
+        contract
+    }
+
+    fn upgrade_escrow(&self, old: EscrowV1) -> Escrow {
+        let now = env::block_timestamp();
+        let disputed = matches!(old.status, EscrowStatus::Disputed);
+        let settled = !matches!(old.status, EscrowStatus::Active | EscrowStatus::Disputed);
+        
+        // Legacy proofs carried an opaque string, so only their verification status survives
+        let proofs = old.cross_chain_proof.map(|proof| CrossChainProof {
+            proof_id: "legacy".to_string(),
+            chain_id: proof.chain_id,
+            tx_hash: proof.tx_hash,
+            block_number: proof.block_number,
+            proof_data: ProofData {
+                block_hash: String::new(),
+                block_header: String::new(),
+                receipts_root: String::new(),
+                receipt_index: 0,
+                receipt_data: proof.proof_data,
+                proof_nodes: Vec::new(),
+                log_index: 0,
+            },
+            verified: proof.verified,
+            verified_at: proof.verified_at,
+            approvals: Vec::new(),
+        });
+        
+        let amount = U128(old.amount.parse().or_fail(ErrorCode::Internal, "Invalid amount"));
+        let mut title = old.metadata;
+        while title.len() > 128 {
+            title.pop();
+        }
+        
+        Escrow {
+            escrow_id: old.escrow_id,
+            depositor: old.depositor,
+            beneficiary: old.beneficiary,
+            amount,
+            release_time: old.release_time,
+            status: old.status,
+            proofs: proofs.into_iter().collect(),
+            required_proofs: Vec::new(),
+            allow_shared_proofs: false,
+            arbiter: old.arbiter,
+            created_at: old.created_at,
+            metadata: EscrowMetadata {
+                title,
+                category: "legacy".to_string(),
+                content_hash: None,
+                external_uri: None,
+            },
+            // Settled escrows start their retention period at the upgrade
+            settled_at: settled.then_some(now),
+            milestones: Vec::new(),
+            released: U128(0),
+            committee: Vec::new(),
+            threshold: 0,
+            votes: Vec::new(),
+            arbiter_fee: None,
+            evidence: Vec::new(),
+            // Open disputes get a fresh deadline under the current dispute config
+            dispute_deadline: disputed.then(|| now + self.dispute_period),
+            default_outcome: disputed.then(|| self.default_dispute_outcome.clone()),
+            token: None,
+            pending_amendment: None,
+            amendments: Vec::new(),
+            cancel_proposed_by: None,
+            schedule: None,
+            vesting: None,
+            payees: Vec::new(),
+            price_condition: None,
+            hashlock: None,
+            staking: None,
+            disputed_at: None,
+            ruling: None,
+            appealed: false,
+            swap_link: None,
+            payout_address: None,
+            compliance: None,
+            deposits: vec![Deposit {
+                amount,
+                deposited_at: old.created_at,
+            }],
+            attachments: Vec::new(),
+            expected_payments: Vec::new(),
+            dispute_payout: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use ciphra_testing as host;
+    use ciphra_types::STATE_VERSION_KEY;
+    use near_sdk::borsh;
+
+    // `contract` as a V6 deployment stored it, with `failed_payouts` after `failed_token_payouts`
+    fn store_v6_state(contract: &EscrowContract, failed_payouts: &LookupMap<AccountId, u128>) {
+        let c = contract;
+        let head = borsh::to_vec(&(
+            (
+                &c.escrows, &c.proof_verifications, &c.owner, &c.trusted_verifiers, &c.verifier_threshold,
+                &c.verifier_bonds, &c.min_verifier_bond, &c.insurance_pool, &c.insurance_claims,
+                &c.settlement_fee_bps, &c.treasury_account, &c.treasury,
+            ),
+            (
+                &c.retention_period, &c.insurance_fund, &c.insurance_fee_bps, &c.insurance_fee_accrued,
+                &c.price_oracle, &c.swap_contract, &c.compliance_registries, &c.staking_pools, &c.pool_shares,
+                &c.stats, &c.pause_guard, &c.dispute_period,
+            ),
+            (&c.default_dispute_outcome, &c.appeal_period, &c.appeal_committee, &c.appeal_threshold),
+            &c.failed_token_payouts,
+        ))
+        .unwrap();
+        let current = borsh::to_vec(contract).unwrap();
+        assert!(current.starts_with(&head));
+        
+        let mut stored = head.clone();
+        stored.extend(borsh::to_vec(failed_payouts).unwrap());
+        stored.extend(&current[head.len()..]);
+        env::storage_write(b"STATE", &stored);
+        env::storage_write(STATE_VERSION_KEY, &borsh::to_vec(&StateVersion::V6).unwrap());
+    }
+
+    #[test]
+    fn test_v6_failed_payouts_become_claimable_settlements() {
+        let mut contract = contract();
+        create_escrow(&mut contract, "e1", 10 * host::NEAR);
+        let mut failed_payouts = LookupMap::new(StorageKey::FailedPayouts);
+        failed_payouts.insert(&account(BENEFICIARY), &(2 * host::NEAR));
+        store_v6_state(&contract, &failed_payouts);
+        
+        host::set_self_caller();
+        let migrated = EscrowContract::migrate();
+        
+        assert_eq!(migrated.get_state_version(), StateVersion::V7);
+        assert_eq!(migrated.get_claimable_settlement(account(BENEFICIARY)), U128(2 * host::NEAR));
+        assert_eq!(migrated.settlements.total_claimable, 2 * host::NEAR);
+        assert!(migrated.get_escrow("e1".to_string()).is_some());
+        assert!(failed_payouts.get(&account(BENEFICIARY)).is_none());
+    }
+
+    #[test]
+    fn test_migrated_balance_claims_through_ledger() {
+        let mut contract = contract();
+        create_escrow(&mut contract, "e1", 10 * host::NEAR);
+        let mut failed_payouts = LookupMap::new(StorageKey::FailedPayouts);
+        failed_payouts.insert(&account(DEPOSITOR), &host::NEAR);
+        store_v6_state(&contract, &failed_payouts);
+        host::set_self_caller();
+        let mut migrated = EscrowContract::migrate();
+        
+        host::set_caller(DEPOSITOR);
+        let _ = migrated.claim_settlement();
+        
+        assert_eq!(host::transfers(), vec![(DEPOSITOR.to_string(), host::NEAR)]);
+        assert_eq!(migrated.get_claimable_settlement(account(DEPOSITOR)), U128(0));
+    }
+}
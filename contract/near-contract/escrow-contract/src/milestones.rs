@@ -0,0 +1,158 @@
+use crate::*;
+
+// Upper bound on tranches per escrow
+const MAX_MILESTONES: usize = 20;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub enum ReleaseCondition {
+    Approval,       // Depositor or arbiter sign-off only
+    AfterTime(u64), // Approval allowed once this timestamp has passed
+    ProofVerified,  // Approval allowed once the escrow's cross-chain proof is verified
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Milestone {
+    #[borsh(serialize_with = "decimal_borsh::serialize", deserialize_with = "decimal_borsh::deserialize")]
+    #[schemars(with = "String")]
+    pub amount: U128,
+    pub description: String,
+    pub condition: ReleaseCondition,
+    pub released_at: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MilestoneInput {
+    pub amount: U128,
+    pub description: String,
+    pub condition: ReleaseCondition,
+}
+
+#[near_bindgen]
+impl EscrowContract {
+    // Milestone escrow - the deposit is split into ordered tranches released one at a time
+    #[payable]
+    pub fn create_milestone_escrow(
+        &mut self,
+        escrow_id: String,
+        beneficiary: AccountId,
+        release_time: u64,
+        arbiter: Option<AccountId>,
+        metadata: EscrowMetadata,
+        milestones: Vec<MilestoneInput>,
+        arbiter_fee: Option<ArbiterFee>,
+    ) -> Escrow {
+        ensure!(
+            !milestones.is_empty() && milestones.len() <= MAX_MILESTONES,
+            InvalidArgument,
+            "Escrow must have 1-20 milestones"
+        );
+        ensure!(
+            milestones.iter().all(|milestone| milestone.amount.0 > 0),
+            InvalidArgument,
+            "Milestone amounts must be positive"
+        );
+        let total_yocto: u128 = milestones.iter().map(|milestone| milestone.amount.0).sum();
+        ensure!(
+            total_yocto == env::attached_deposit().as_yoctonear(),
+            InvalidArgument,
+            "Deposit must equal the sum of milestone amounts"
+        );
+        
+        let mut escrow = self.create_escrow(
+            escrow_id.clone(),
+            beneficiary,
+            release_time,
+            arbiter,
+            metadata,
+            arbiter_fee,
+            None,
+        );
+        escrow.milestones = milestones
+            .into_iter()
+            .map(|milestone| Milestone {
+                amount: milestone.amount,
+                description: milestone.description,
+                condition: milestone.condition,
+                released_at: None,
+            })
+            .collect();
+        self.escrows.insert(&escrow_id, &escrow);
+        
+        env::log_str(&format!(
+            "Milestone escrow created: {} | Milestones: {}",
+            escrow_id, escrow.milestones.len()
+        ));
+        
+        escrow
+    }
+
+    // Release the next tranche - milestones are approved strictly in order
+    pub fn approve_milestone(&mut self, escrow_id: String, index: u32) -> Promise {
+        let mut escrow = self.escrows.get(&escrow_id).or_fail(ErrorCode::NotFound, "Escrow not found");
+        
+        let caller = env::predecessor_account_id();
+        let depositor: AccountId = escrow.depositor.parse().or_fail(ErrorCode::Internal, "Invalid depositor");
+        let is_arbiter = escrow.arbiter.as_ref().is_some_and(|a| {
+            let arbiter: AccountId = a.parse().or_fail(ErrorCode::Internal, "Invalid arbiter");
+            arbiter == caller
+        });
+        ensure!(
+            caller == depositor || is_arbiter,
+            Unauthorized,
+            "Only depositor or arbiter can approve milestones"
+        );
+        ensure!(
+            matches!(escrow.status, EscrowStatus::Active),
+            InvalidState,
+            "Escrow not active"
+        );
+        
+        let index = index as usize;
+        let next = escrow.milestones
+            .iter()
+            .position(|milestone| milestone.released_at.is_none())
+            .or_fail(ErrorCode::InvalidState, "All milestones released");
+        ensure!(index == next, InvalidState, "Milestones must be approved in order");
+        
+        let proof_verified = escrow.proofs_satisfied();
+        let uses_proof = matches!(escrow.milestones[index].condition, ReleaseCondition::ProofVerified);
+        let milestone = &mut escrow.milestones[index];
+        match milestone.condition {
+            ReleaseCondition::Approval => {}
+            ReleaseCondition::AfterTime(not_before) => ensure!(
+                env::block_timestamp() >= not_before,
+                TooEarly,
+                "Milestone is not releasable yet"
+            ),
+            ReleaseCondition::ProofVerified => ensure!(proof_verified, InvalidState, "Proof not verified"),
+        }
+        milestone.released_at = Some(env::block_timestamp());
+        
+        let amount_yocto: u128 = milestone.amount.0;
+        let released_yocto: u128 = escrow.released.0;
+        escrow.released = U128(released_yocto + amount_yocto);
+        if index == escrow.milestones.len() - 1 {
+            self.settle_escrow(&mut escrow, EscrowStatus::Completed);
+        }
+        if uses_proof {
+            self.consume_proofs(&escrow);
+        }
+        self.escrows.insert(&escrow_id, &escrow);
+        
+        env::log_str(&format!(
+            "Milestone released: {} | Index: {} | Amount: {}",
+            escrow_id, index, amount_yocto
+        ));
+        emit_event("released", json!({
+            "escrow_id": escrow_id,
+            "beneficiary": escrow.beneficiary,
+            "amount": amount_yocto.to_string(),
+            "milestone_index": index,
+        }));
+        
+        self.release_payout(&escrow, amount_yocto)
+    }
+}
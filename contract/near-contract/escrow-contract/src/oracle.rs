@@ -0,0 +1,177 @@
+use crate::*;
+use near_sdk::json_types::U64;
+use near_sdk::{ext_contract, Gas, PromiseError};
+
+const GAS_FOR_PRICE_QUERY: Gas = Gas::from_tgas(10);
+const GAS_FOR_PRICE_CALLBACK: Gas = Gas::from_tgas(30);
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub enum PriceComparison {
+    AtLeast,
+    AtMost,
+}
+
+// Release once the oracle price of `asset_id` crosses multiplier / 10^decimals before `deadline`
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PriceCondition {
+    pub asset_id: String,
+    pub comparison: PriceComparison,
+    #[borsh(serialize_with = "decimal_borsh::serialize", deserialize_with = "decimal_borsh::deserialize")]
+    #[schemars(with = "String")]
+    pub multiplier: U128,
+    pub decimals: u8,
+    pub deadline: u64,
+}
+
+// Subset of the price oracle's get_price_data response
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct OraclePrice {
+    pub multiplier: U128,
+    pub decimals: u8,
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct OracleAssetPrice {
+    pub asset_id: String,
+    pub price: Option<OraclePrice>,
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct OraclePriceData {
+    pub timestamp: U64,
+    pub recency_duration_sec: u32,
+    pub prices: Vec<OracleAssetPrice>,
+}
+
+impl PriceCondition {
+    // Compare multiplier_a / 10^decimals_a against multiplier_b / 10^decimals_b without division
+    fn is_met(&self, price: &OraclePrice) -> bool {
+        let threshold: u128 = self.multiplier.0;
+        let scale = |value: u128, decimals: u8| value.saturating_mul(10u128.saturating_pow(decimals as u32));
+        let observed = scale(price.multiplier.0, self.decimals);
+        let target = scale(threshold, price.decimals);
+        match self.comparison {
+            PriceComparison::AtLeast => observed >= target,
+            PriceComparison::AtMost => observed <= target,
+        }
+    }
+}
+
+// Only the generated ext_price_oracle module is used
+#[allow(dead_code)]
+#[ext_contract(ext_price_oracle)]
+trait PriceOracle {
+    fn get_price_data(&self, asset_ids: Option<Vec<String>>) -> OraclePriceData;
+}
+
+#[near_bindgen]
+impl EscrowContract {
+    // Price escrow - releases to the beneficiary if the condition is met before its deadline;
+    // after the deadline the depositor can refund as usual
+    #[payable]
+    pub fn create_price_escrow(
+        &mut self,
+        escrow_id: String,
+        beneficiary: AccountId,
+        condition: PriceCondition,
+        arbiter: Option<AccountId>,
+        metadata: EscrowMetadata,
+        arbiter_fee: Option<ArbiterFee>,
+    ) -> Escrow {
+        ensure!(self.price_oracle.is_some(), NotFound, "Price oracle not configured");
+        
+        let mut escrow = self.create_escrow(
+            escrow_id.clone(),
+            beneficiary,
+            condition.deadline,
+            arbiter,
+            metadata,
+            arbiter_fee,
+            None,
+        );
+        escrow.price_condition = Some(condition);
+        self.escrows.insert(&escrow_id, &escrow);
+        
+        env::log_str(&format!("Price escrow created: {}", escrow_id));
+        
+        escrow
+    }
+
+    // Anyone can ask the oracle whether the escrow's price condition holds right now
+    pub fn release_on_price(&mut self, escrow_id: String) -> Promise {
+        let escrow = self.escrows.get(&escrow_id).or_fail(ErrorCode::NotFound, "Escrow not found");
+        let condition = escrow.price_condition.as_ref().or_fail(ErrorCode::InvalidState, "Not a price escrow");
+        
+        ensure!(
+            matches!(escrow.status, EscrowStatus::Active),
+            InvalidState,
+            "Escrow not active"
+        );
+        ensure!(env::block_timestamp() < condition.deadline, Expired, "Price condition has expired");
+        
+        let oracle = self.price_oracle.clone().or_fail(ErrorCode::NotFound, "Price oracle not configured");
+        ext_price_oracle::ext(oracle)
+            .with_static_gas(GAS_FOR_PRICE_QUERY)
+            .get_price_data(Some(vec![condition.asset_id.clone()]))
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_PRICE_CALLBACK)
+                    .on_price_checked(escrow_id),
+            )
+    }
+
+    // Callback - release if the escrow is still active and a fresh price meets the condition
+    #[private]
+    pub fn on_price_checked(
+        &mut self,
+        escrow_id: String,
+        #[callback_result] price_data: Result<OraclePriceData, PromiseError>,
+    ) -> PromiseOrValue<bool> {
+        let mut escrow = self.escrows.get(&escrow_id).or_fail(ErrorCode::NotFound, "Escrow not found");
+        let condition = escrow.price_condition.clone().or_fail(ErrorCode::InvalidState, "Not a price escrow");
+        
+        let now = env::block_timestamp();
+        let met = price_data.ok().is_some_and(|data| {
+            let fresh = now <= data.timestamp.0 + data.recency_duration_sec as u64 * 1_000_000_000;
+            fresh && data.prices.iter().any(|asset| {
+                asset.asset_id == condition.asset_id && asset.price.as_ref().is_some_and(|price| condition.is_met(price))
+            })
+        });
+        if !met || !matches!(escrow.status, EscrowStatus::Active) || now >= condition.deadline {
+            env::log_str(&format!("Price condition not met: {}", escrow_id));
+            return PromiseOrValue::Value(false);
+        }
+        
+        self.settle_escrow(&mut escrow, EscrowStatus::Completed);
+        self.escrows.insert(&escrow_id, &escrow);
+        
+        let amount_yocto = Self::unreleased_amount(&escrow);
+        
+        env::log_str(&format!(
+            "Funds released on price: {} | Amount: {}",
+            escrow_id, amount_yocto
+        ));
+        emit_event("released", json!({
+            "escrow_id": escrow_id,
+            "beneficiary": escrow.beneficiary,
+            "amount": amount_yocto.to_string(),
+            "asset_id": condition.asset_id,
+        }));
+        
+        PromiseOrValue::Promise(self.release_payout(&escrow, amount_yocto))
+    }
+
+    pub fn set_price_oracle(&mut self, price_oracle: Option<AccountId>) {
+        assert_owner(&self.owner);
+        self.price_oracle = price_oracle;
+    }
+
+    pub fn get_price_oracle(&self) -> Option<AccountId> {
+        self.price_oracle.clone()
+    }
+}
@@ -0,0 +1,45 @@
+use crate::*;
+
+#[near_bindgen]
+impl EscrowContract {
+    // Incident response: halts escrow creation, top-ups and proof handling; releases,
+    // refunds and disputes keep working
+    pub fn pause(&mut self) {
+        let caller = self.pause_guard.pause(&self.owner);
+        
+        env::log_str(&format!("Contract paused by: {}", caller));
+        emit_event("paused", json!({ "account_id": caller }));
+    }
+
+    // Guardians can only pause; resuming is an owner decision
+    pub fn unpause(&mut self) {
+        self.pause_guard.unpause(&self.owner);
+        
+        env::log_str("Contract unpaused");
+        emit_event("unpaused", json!({ "account_id": self.owner }));
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.pause_guard.paused
+    }
+
+    pub fn get_pause_status(&self) -> PauseStatus {
+        self.pause_guard.status()
+    }
+
+    pub fn add_guardian(&mut self, account_id: AccountId) {
+        self.pause_guard.add_guardian(&self.owner, account_id);
+    }
+
+    pub fn remove_guardian(&mut self, account_id: AccountId) {
+        self.pause_guard.remove_guardian(&self.owner, &account_id);
+    }
+
+    pub fn get_guardians(&self) -> Vec<AccountId> {
+        self.pause_guard.guardians.clone()
+    }
+
+    pub(crate) fn assert_not_paused(&self) {
+        self.pause_guard.assert_not_paused();
+    }
+}
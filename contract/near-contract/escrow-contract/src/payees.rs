@@ -0,0 +1,152 @@
+use crate::*;
+
+// Upper bound on payees sharing one escrow
+const MAX_PAYEES: usize = 10;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Payee {
+    pub account_id: String,
+    pub share_bps: u16,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PayeeInput {
+    pub account_id: AccountId,
+    pub share_bps: u16,
+}
+
+impl Escrow {
+    // The beneficiary, or any payee of a multi-beneficiary escrow
+    pub(crate) fn is_payee(&self, account: &AccountId) -> bool {
+        account.as_str() == self.beneficiary || self.payees.iter().any(|p| p.account_id == account.as_str())
+    }
+
+    // Every account a payout of this escrow may have gone to
+    pub(crate) fn paid_accounts(&self) -> Vec<String> {
+        let mut accounts = vec![self.depositor.clone(), self.beneficiary.clone()];
+        accounts.extend(self.arbiter.clone());
+        accounts.extend(self.payout_address.clone());
+        accounts.extend(self.committee.iter().cloned());
+        accounts.extend(self.payees.iter().map(|payee| payee.account_id.clone()));
+        accounts
+    }
+
+    // Where the beneficiary's share is sent - its alternate payout address when one is registered
+    pub(crate) fn beneficiary_receiver(&self) -> AccountId {
+        self.payout_address.as_ref().unwrap_or(&self.beneficiary).parse().or_fail(ErrorCode::Internal, "Invalid beneficiary")
+    }
+
+    // Split a beneficiary-side amount across the payees; rounding dust goes to the last payee
+    pub(crate) fn payee_amounts(&self, amount_yocto: u128) -> Vec<(AccountId, u128)> {
+        if self.payees.is_empty() {
+            return vec![(self.beneficiary_receiver(), amount_yocto)];
+        }
+        
+        let mut remaining_yocto = amount_yocto;
+        let last = self.payees.len() - 1;
+        self.payees
+            .iter()
+            .enumerate()
+            .map(|(i, payee)| {
+                let share_yocto = if i == last {
+                    remaining_yocto
+                } else {
+                    (amount_yocto * payee.share_bps as u128) / 10000
+                };
+                remaining_yocto -= share_yocto;
+                let receiver = if payee.account_id == self.beneficiary {
+                    self.beneficiary_receiver()
+                } else {
+                    payee.account_id.parse().or_fail(ErrorCode::Internal, "Invalid payee")
+                };
+                (receiver, share_yocto)
+            })
+            .collect()
+    }
+}
+
+#[near_bindgen]
+impl EscrowContract {
+    // Multi-beneficiary escrow - every release is split across `payees` by basis-point share.
+    // The first payee is recorded as the escrow's beneficiary.
+    #[payable]
+    pub fn create_split_escrow(
+        &mut self,
+        escrow_id: String,
+        payees: Vec<PayeeInput>,
+        release_time: u64,
+        arbiter: Option<AccountId>,
+        metadata: EscrowMetadata,
+        arbiter_fee: Option<ArbiterFee>,
+    ) -> Escrow {
+        ensure!(
+            payees.len() >= 2 && payees.len() <= MAX_PAYEES,
+            InvalidArgument,
+            "Escrow must have 2-10 payees"
+        );
+        ensure!(payees.iter().all(|p| p.share_bps > 0), InvalidArgument, "Payee shares must be positive");
+        let total_bps: u32 = payees.iter().map(|p| p.share_bps as u32).sum();
+        ensure!(total_bps == 10000, InvalidArgument, "Payee shares must sum to 10000 bps");
+        let mut accounts: Vec<&AccountId> = payees.iter().map(|p| &p.account_id).collect();
+        accounts.sort();
+        accounts.dedup();
+        ensure!(accounts.len() == payees.len(), InvalidArgument, "Duplicate payee");
+        
+        let mut escrow = self.create_escrow(
+            escrow_id.clone(),
+            payees[0].account_id.clone(),
+            release_time,
+            arbiter,
+            metadata,
+            arbiter_fee,
+            None,
+        );
+        escrow.payees = payees
+            .iter()
+            .map(|p| Payee {
+                account_id: p.account_id.to_string(),
+                share_bps: p.share_bps,
+            })
+            .collect();
+        self.escrows.insert(&escrow_id, &escrow);
+        for payee in payees.iter().skip(1) {
+            self.index_beneficiary(&escrow_id, &payee.account_id);
+        }
+        
+        env::log_str(&format!(
+            "Split escrow created: {} | Payees: {}",
+            escrow_id, escrow.payees.len()
+        ));
+        
+        escrow
+    }
+
+    // Beneficiary routes its releases to another account (e.g. a cold wallet); None pays the beneficiary again
+    pub fn set_payout_address(&mut self, escrow_id: String, payout_address: Option<AccountId>) {
+        let mut escrow = self.escrows.get(&escrow_id).or_fail(ErrorCode::NotFound, "Escrow not found");
+        
+        let caller = env::predecessor_account_id();
+        ensure!(caller.as_str() == escrow.beneficiary, Unauthorized, "Only beneficiary can set a payout address");
+        ensure!(
+            matches!(escrow.status, EscrowStatus::Active | EscrowStatus::Disputed),
+            InvalidState,
+            "Escrow already settled"
+        );
+        
+        let previous = std::mem::replace(&mut escrow.payout_address, payout_address.map(|a| a.to_string()));
+        self.escrows.insert(&escrow_id, &escrow);
+        
+        env::log_str(&format!(
+            "Payout address set: {} | Address: {:?}",
+            escrow_id, escrow.payout_address
+        ));
+        emit_event("payout_address_set", json!({
+            "escrow_id": escrow_id,
+            "beneficiary": escrow.beneficiary,
+            "previous": previous,
+            "payout_address": escrow.payout_address,
+        }));
+    }
+}
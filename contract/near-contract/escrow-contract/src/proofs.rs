@@ -0,0 +1,898 @@
+use crate::*;
+use near_sdk::{ext_contract, Gas, PromiseError, PromiseOrValue};
+
+const GAS_FOR_BLOCK_HASH: Gas = Gas::from_tgas(10);
+const GAS_FOR_BLOCK_HASH_CALLBACK: Gas = Gas::from_tgas(15);
+
+// Header index of receiptsRoot and number in an RLP-encoded EVM block header
+const HEADER_RECEIPTS_ROOT: usize = 5;
+const HEADER_NUMBER: usize = 8;
+
+// Receipt field holding the logs list
+const RECEIPT_LOGS: usize = 3;
+
+// Log fields: emitting contract, topics, data
+const LOG_ADDRESS: usize = 0;
+const LOG_TOPICS: usize = 1;
+const LOG_DATA: usize = 2;
+
+// keccak256("Transfer(address,address,uint256)")
+const TRANSFER_TOPIC: &str = "ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef";
+
+// ERC-20 transfer a light client proof must evidence. Set by the depositor, whose funds the proof
+// releases, and fixed once set so the beneficiary knows what to pay.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ExpectedPayment {
+    pub proof_id: String,
+    pub chain_id: String,
+    pub token: String,     // ERC-20 contract emitting the Transfer log
+    pub recipient: String, // Address the transfer must credit
+    #[borsh(serialize_with = "decimal_borsh::serialize", deserialize_with = "decimal_borsh::deserialize")]
+    #[schemars(with = "String")]
+    pub amount: U128, // Token base units
+}
+
+impl ExpectedPayment {
+    fn validate(&self) {
+        ensure!(
+            ChainAdapter::for_chain(&self.chain_id) == Some(ChainAdapter::Evm),
+            InvalidArgument,
+            "Expected payments are only checked on EVM chains"
+        );
+        validate_chain_address(&self.chain_id, &self.token).unwrap_or_else(|err| fail(ErrorCode::InvalidArgument, err));
+        validate_chain_address(&self.chain_id, &self.recipient)
+            .unwrap_or_else(|err| fail(ErrorCode::InvalidArgument, err));
+        ensure!(self.amount.0 > 0, InvalidArgument, "Expected amount must be positive");
+    }
+}
+
+impl Escrow {
+    pub(crate) fn expected_payment(&self, proof_id: &str) -> Option<&ExpectedPayment> {
+        self.expected_payments.iter().find(|p| p.proof_id == proof_id)
+    }
+
+    pub(crate) fn any_proof_verified(&self) -> bool {
+        self.proofs.iter().any(|p| p.verified)
+    }
+
+    // Release condition: every required proof verified, or any verified proof when none are required
+    pub(crate) fn proofs_satisfied(&self) -> bool {
+        if self.required_proofs.is_empty() {
+            return self.any_proof_verified();
+        }
+        self.required_proofs
+            .iter()
+            .all(|id| self.proofs.iter().any(|p| &p.proof_id == id && p.verified))
+    }
+}
+
+// Only the generated ext_light_client module is used
+#[allow(dead_code)]
+#[ext_contract(ext_light_client)]
+trait LightClient {
+    fn block_hash_safe(&self, index: u64) -> Option<String>;
+}
+
+#[near_bindgen]
+impl EscrowContract {
+    // Register the Rainbow Bridge style light client for an EVM chain; None reverts it to trusted verifiers
+    pub fn set_light_client(&mut self, chain_id: String, light_client: Option<AccountId>) {
+        assert_owner(&self.owner);
+        match light_client {
+            Some(light_client) => self.light_clients.insert(&chain_id, &light_client),
+            None => self.light_clients.remove(&chain_id),
+        };
+    }
+
+    pub fn get_light_client(&self, chain_id: String) -> Option<AccountId> {
+        self.light_clients.get(&chain_id)
+    }
+
+    // Depositor opts the escrow out of replay protection, e.g. for one payment covering several escrows
+    pub fn set_allow_shared_proofs(&mut self, escrow_id: String, allow: bool) {
+        let mut escrow = self.escrows.get(&escrow_id).or_fail(ErrorCode::NotFound, "Escrow not found");
+        ensure!(
+            env::predecessor_account_id().as_str() == escrow.depositor,
+            Unauthorized,
+            "Only depositor can change proof sharing"
+        );
+        
+        escrow.allow_shared_proofs = allow;
+        self.escrows.insert(&escrow_id, &escrow);
+    }
+
+    // Depositor fixes the transfer a proof id must evidence before light clients can verify it
+    pub fn set_expected_payment(&mut self, escrow_id: String, payment: ExpectedPayment) {
+        let mut escrow = self.escrows.get(&escrow_id).or_fail(ErrorCode::NotFound, "Escrow not found");
+        ensure!(
+            env::predecessor_account_id().as_str() == escrow.depositor,
+            Unauthorized,
+            "Only depositor can set expected payments"
+        );
+        ensure!(
+            matches!(escrow.status, EscrowStatus::Active),
+            InvalidState,
+            "Escrow must be active"
+        );
+        payment.validate();
+        ensure!(
+            escrow.expected_payment(&payment.proof_id).is_none(),
+            AlreadyExists,
+            "Expected payment already set"
+        );
+        ensure!(escrow.expected_payments.len() < MAX_PROOFS, LimitExceeded, "Too many expected payments");
+        
+        escrow.expected_payments.push(payment.clone());
+        self.escrows.insert(&escrow_id, &escrow);
+        
+        env::log_str(&format!(
+            "Expected payment set for escrow: {} | Proof: {} | Amount: {}",
+            escrow_id, payment.proof_id, payment.amount.0
+        ));
+        emit_event("expected_payment_set", json!({
+            "escrow_id": escrow_id,
+            "proof_id": payment.proof_id,
+            "chain_id": payment.chain_id,
+            "token": payment.token,
+            "recipient": payment.recipient,
+            "amount": payment.amount,
+        }));
+    }
+
+    // Escrow that was released on the proof of this log, if any
+    pub fn get_proof_consumer(
+        &self,
+        chain_id: String,
+        block_hash: String,
+        receipt_index: u64,
+        log_index: u32,
+    ) -> Option<String> {
+        let block_hash = block_hash.trim_start_matches("0x").to_ascii_lowercase();
+        self.consumed_proofs
+            .get(&format!("{}:{}:{}:{}", chain_id, block_hash, receipt_index, log_index))
+    }
+
+    // Record the escrow's verified proofs as spent so no other escrow can release on them
+    pub(crate) fn consume_proofs(&mut self, escrow: &Escrow) {
+        if escrow.allow_shared_proofs {
+            return;
+        }
+        for proof in escrow.proofs.iter().filter(|p| p.verified) {
+            let proof_key = proof.receipt_key();
+            self.assert_proof_unconsumed(escrow, &proof_key);
+            self.consumed_proofs.insert(&proof_key, &escrow.escrow_id);
+        }
+    }
+
+    pub(crate) fn assert_valid_block_hash(block_hash: &str) {
+        ensure!(
+            decode_hex(block_hash).is_ok_and(|hash| hash.len() == 32),
+            InvalidArgument,
+            "Invalid block hash"
+        );
+    }
+
+    pub(crate) fn assert_proof_unconsumed(&self, escrow: &Escrow, proof_key: &String) {
+        if escrow.allow_shared_proofs {
+            return;
+        }
+        let consumer = self.consumed_proofs.get(proof_key);
+        ensure!(
+            consumer.is_none_or(|id| id == escrow.escrow_id),
+            AlreadyExists,
+            "Proof already used by another escrow"
+        );
+    }
+
+    // Callback - the proof stands once the light client knows the header's block hash
+    #[private]
+    pub fn on_light_client_block_hash(
+        &mut self,
+        escrow_id: String,
+        proof_id: String,
+        receipt_key: String,
+        block_hash: String,
+        verifier: AccountId,
+        #[callback_result] known_hash: Result<Option<String>, PromiseError>,
+    ) -> bool {
+        let known = known_hash
+            .ok()
+            .flatten()
+            .is_some_and(|hash| hash.trim_start_matches("0x").eq_ignore_ascii_case(&block_hash));
+        if !known {
+            env::log_str(&format!("Proof rejected for escrow: {} | Unknown block", escrow_id));
+            emit_event("proof_rejected", json!({
+                "escrow_id": escrow_id,
+                "proof_id": proof_id,
+                "verifier": verifier,
+                "receipt_key": receipt_key,
+                "reason": "unknown_block",
+            }));
+            return false;
+        }
+        // The proof may have been replaced while the light client was queried
+        let current = self
+            .escrows
+            .get(&escrow_id)
+            .and_then(|escrow| escrow.proofs.into_iter().find(|p| p.proof_id == proof_id));
+        if current.is_none_or(|proof| proof.verified || proof.receipt_key() != receipt_key) {
+            env::log_str(&format!("Proof rejected for escrow: {} | Proof replaced", escrow_id));
+            return false;
+        }
+        
+        self.mark_proof_verified(&escrow_id, &proof_id, verifier);
+        true
+    }
+
+    // Check the receipt's inclusion on-chain, then confirm the block hash with the light client
+    pub(crate) fn verify_with_light_client(
+        &self,
+        escrow_id: String,
+        light_client: AccountId,
+        proof: &CrossChainProof,
+        expected: &ExpectedPayment,
+    ) -> PromiseOrValue<bool> {
+        let (number, block_hash) =
+            check_receipt_proof(proof, Some(expected)).unwrap_or_else(|err| fail(ErrorCode::InvalidProof, err));
+        
+        PromiseOrValue::Promise(
+            Self::query_block_hash(light_client, number).then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_BLOCK_HASH_CALLBACK)
+                    .on_light_client_block_hash(
+                        escrow_id,
+                        proof.proof_id.clone(),
+                        proof.receipt_key(),
+                        block_hash,
+                        env::predecessor_account_id(),
+                    ),
+            ),
+        )
+    }
+
+    pub(crate) fn query_block_hash(light_client: AccountId, number: u64) -> Promise {
+        ext_light_client::ext(light_client)
+            .with_static_gas(GAS_FOR_BLOCK_HASH)
+            .block_hash_safe(number)
+    }
+}
+
+// Validate the receipt's inclusion under the proof's header, and that it carries the expected payment
+// when there is one; returns the block number and hex block hash
+pub(crate) fn check_receipt_proof(
+    proof: &CrossChainProof,
+    expected: Option<&ExpectedPayment>,
+) -> Result<(u64, String), &'static str> {
+    if expected.is_some_and(|expected| expected.chain_id != proof.chain_id) {
+        return Err("Proof is not on the expected chain");
+    }
+    let data = &proof.proof_data;
+    let block_hash = decode_hex(&data.block_hash)?;
+    let header = decode_hex(&data.block_header)?;
+    let receipts_root = decode_hex(&data.receipts_root)?;
+    let receipt = decode_hex(&data.receipt_data)?;
+    let nodes = data
+        .proof_nodes
+        .iter()
+        .map(|node| decode_hex(node))
+        .collect::<Result<Vec<_>, _>>()?;
+    
+    if env::keccak256(&header) != block_hash {
+        return Err("Header does not match block hash");
+    }
+    let fields = rlp_list(&header).ok_or("Invalid header")?;
+    if fields.len() <= HEADER_NUMBER {
+        return Err("Invalid header");
+    }
+    if rlp_bytes(fields[HEADER_RECEIPTS_ROOT]) != Some(receipts_root.as_slice()) {
+        return Err("Header does not match receipts root");
+    }
+    let number = rlp_bytes(fields[HEADER_NUMBER]).ok_or("Invalid header")?;
+    if number.len() > 8 {
+        return Err("Invalid header");
+    }
+    let number = number.iter().fold(0u64, |acc, byte| (acc << 8) | *byte as u64);
+    if number != proof.block_number {
+        return Err("Header does not match block number");
+    }
+    
+    let key = rlp_encode_u64(data.receipt_index);
+    let value = verify_mpt_proof(&receipts_root, &key, &nodes).ok_or("Invalid receipt proof")?;
+    if value != receipt {
+        return Err("Receipt does not match proof");
+    }
+    let logs = receipt_logs(&receipt).ok_or("Invalid receipt")?;
+    let log = logs.get(data.log_index as usize).ok_or("Log index out of range")?;
+    if let Some(expected) = expected {
+        check_transfer_log(log, expected)?;
+    }
+    
+    Ok((number, hex::encode(block_hash)))
+}
+
+fn decode_hex(value: &str) -> Result<Vec<u8>, &'static str> {
+    hex::decode(value.trim_start_matches("0x")).map_err(|_| "Invalid hex in proof data")
+}
+
+// Typed (EIP-2718) receipts carry a one-byte type ahead of the RLP body
+fn receipt_logs(receipt: &[u8]) -> Option<Vec<&[u8]>> {
+    let body = match receipt.first()? {
+        0x00..=0x7f => &receipt[1..],
+        _ => receipt,
+    };
+    let fields = rlp_list(body)?;
+    rlp_list(fields.get(RECEIPT_LOGS)?)
+}
+
+// The log must be a Transfer emitted by the expected token, crediting exactly the expected amount
+// to the expected recipient
+fn check_transfer_log(log: &[u8], expected: &ExpectedPayment) -> Result<(), &'static str> {
+    let fields = rlp_list(log).filter(|fields| fields.len() == 3).ok_or("Invalid log")?;
+    if rlp_bytes(fields[LOG_ADDRESS]) != Some(decode_hex(&expected.token)?.as_slice()) {
+        return Err("Log not emitted by the expected token");
+    }
+    let topics = rlp_list(fields[LOG_TOPICS])
+        .and_then(|topics| topics.into_iter().map(rlp_bytes).collect::<Option<Vec<_>>>())
+        .ok_or("Invalid log")?;
+    if topics.len() != 3 || hex::encode(topics[0]) != TRANSFER_TOPIC {
+        return Err("Log is not a token transfer");
+    }
+    // Indexed addresses are left-padded to 32 bytes
+    let mut recipient = vec![0u8; 12];
+    recipient.extend(decode_hex(&expected.recipient)?);
+    if topics[2] != recipient.as_slice() {
+        return Err("Transfer does not credit the expected recipient");
+    }
+    let amount = rlp_bytes(fields[LOG_DATA]).filter(|data| data.len() == 32).ok_or("Invalid log")?;
+    let (high, low) = amount.split_at(16);
+    let amount = low.try_into().map(u128::from_be_bytes).map_err(|_| "Invalid log")?;
+    if high.iter().any(|byte| *byte != 0) || amount != expected.amount.0 {
+        return Err("Transfer does not match the expected amount");
+    }
+    Ok(())
+}
+
+// Decode one RLP item at the start of `data`: (is_list, payload offset, payload length)
+fn rlp_header(data: &[u8]) -> Option<(bool, usize, usize)> {
+    let prefix = *data.first()? as usize;
+    let (is_list, offset, len) = match prefix {
+        0x00..=0x7f => (false, 0, 1),
+        0x80..=0xb7 => (false, 1, prefix - 0x80),
+        0xb8..=0xbf => {
+            let len_of_len = prefix - 0xb7;
+            (false, 1 + len_of_len, be_usize(data.get(1..1 + len_of_len)?)?)
+        }
+        0xc0..=0xf7 => (true, 1, prefix - 0xc0),
+        _ => {
+            let len_of_len = prefix - 0xf7;
+            (true, 1 + len_of_len, be_usize(data.get(1..1 + len_of_len)?)?)
+        }
+    };
+    if offset.checked_add(len)? > data.len() {
+        return None;
+    }
+    Some((is_list, offset, len))
+}
+
+fn be_usize(bytes: &[u8]) -> Option<usize> {
+    if bytes.len() > 4 {
+        return None;
+    }
+    Some(bytes.iter().fold(0usize, |acc, byte| (acc << 8) | *byte as usize))
+}
+
+// Split an RLP list into its raw encoded items
+fn rlp_list(data: &[u8]) -> Option<Vec<&[u8]>> {
+    let (is_list, offset, len) = rlp_header(data)?;
+    if !is_list || offset + len != data.len() {
+        return None;
+    }
+    let mut items = Vec::new();
+    let mut rest = &data[offset..];
+    while !rest.is_empty() {
+        let (_, item_offset, item_len) = rlp_header(rest)?;
+        let (item, tail) = rest.split_at(item_offset + item_len);
+        items.push(item);
+        rest = tail;
+    }
+    Some(items)
+}
+
+// Payload of an RLP string item
+fn rlp_bytes(item: &[u8]) -> Option<&[u8]> {
+    let (is_list, offset, len) = rlp_header(item)?;
+    if is_list {
+        return None;
+    }
+    Some(&item[offset..offset + len])
+}
+
+// Receipt trie keys are rlp(receipt_index)
+fn rlp_encode_u64(value: u64) -> Vec<u8> {
+    match value {
+        0 => vec![0x80],
+        1..=0x7f => vec![value as u8],
+        _ => {
+            let bytes: Vec<u8> = value.to_be_bytes().into_iter().skip_while(|byte| *byte == 0).collect();
+            let mut encoded = vec![0x80 + bytes.len() as u8];
+            encoded.extend(bytes);
+            encoded
+        }
+    }
+}
+
+fn to_nibbles(bytes: &[u8]) -> Vec<u8> {
+    bytes.iter().flat_map(|byte| [byte >> 4, byte & 0x0f]).collect()
+}
+
+// Walk a Merkle-Patricia proof from `root` along `key`, returning the leaf value
+fn verify_mpt_proof(root: &[u8], key: &[u8], nodes: &[Vec<u8>]) -> Option<Vec<u8>> {
+    let key = to_nibbles(key);
+    let mut position = 0;
+    // Reference to the next node: a 32-byte hash, or the node itself when embedded
+    let mut expected = root.to_vec();
+    
+    for node in nodes {
+        let matches = if expected.len() == 32 {
+            env::keccak256(node) == expected
+        } else {
+            *node == expected
+        };
+        if !matches {
+            return None;
+        }
+        
+        let items = rlp_list(node)?;
+        let next = match items.len() {
+            17 => {
+                if position == key.len() {
+                    return Some(rlp_bytes(items[16])?.to_vec());
+                }
+                let child = items[key[position] as usize];
+                position += 1;
+                child
+            }
+            2 => {
+                let path = rlp_bytes(items[0])?;
+                let flag = path.first()? >> 4;
+                let mut nibbles = to_nibbles(path);
+                // Hex-prefix encoding: odd paths keep the nibble packed with the flag
+                nibbles.drain(..if flag & 1 == 1 { 1 } else { 2 });
+                
+                let remaining = &key[position..];
+                if flag & 2 == 2 {
+                    if remaining != nibbles.as_slice() {
+                        return None;
+                    }
+                    return Some(rlp_bytes(items[1])?.to_vec());
+                }
+                if !remaining.starts_with(&nibbles) {
+                    return None;
+                }
+                position += nibbles.len();
+                items[1]
+            }
+            _ => return None,
+        };
+        
+        let (is_list, _, _) = rlp_header(next)?;
+        expected = if is_list { next.to_vec() } else { rlp_bytes(next)?.to_vec() };
+        if expected.is_empty() {
+            return None;
+        }
+    }
+    
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use ciphra_testing as host;
+
+    const BLOCK_HASH: &str = "0x7f4c5a0e6e0b1e2d3c4b5a69788796a5b4c3d2e1f00f1e2d3c4b5a6978879601";
+    const TX_HASH: &str = "0x1111111111111111111111111111111111111111111111111111111111111111";
+    const OTHER_TX_HASH: &str = "0x2222222222222222222222222222222222222222222222222222222222222222";
+
+    fn submit(contract: &mut EscrowContract, escrow_id: &str, tx_hash: &str, data: ProofData) {
+        host::set_caller(BENEFICIARY);
+        contract.submit_cross_chain_proof(
+            escrow_id.to_string(),
+            "payment".to_string(),
+            "ethereum".to_string(),
+            tx_hash.to_string(),
+            100,
+            data,
+        );
+    }
+
+    // Owner-verified proof released by the beneficiary, which consumes it
+    fn release_on_proof(contract: &mut EscrowContract, escrow_id: &str, tx_hash: &str, data: ProofData) {
+        submit(contract, escrow_id, tx_hash, data);
+        host::set_caller(OWNER);
+        contract.verify_proof(escrow_id.to_string(), "payment".to_string());
+        host::set_caller(BENEFICIARY);
+        contract.release_funds(escrow_id.to_string());
+    }
+
+    #[test]
+    fn released_proof_is_consumed_by_receipt() {
+        let mut contract = contract();
+        create_escrow(&mut contract, "a", host::NEAR);
+        release_on_proof(&mut contract, "a", TX_HASH, proof_data(BLOCK_HASH, 3, 1));
+        
+        let consumer = |block_hash: &str, log_index| {
+            contract.get_proof_consumer("ethereum".to_string(), block_hash.to_string(), 3, log_index)
+        };
+        assert_eq!(consumer(BLOCK_HASH, 1).as_deref(), Some("a"));
+        assert_eq!(consumer(&BLOCK_HASH[2..].to_ascii_uppercase(), 1).as_deref(), Some("a"));
+        assert_eq!(consumer(BLOCK_HASH, 0), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Proof already used by another escrow")]
+    fn receipt_cannot_be_replayed_under_another_tx_hash() {
+        let mut contract = contract();
+        create_escrow(&mut contract, "a", host::NEAR);
+        create_escrow(&mut contract, "b", host::NEAR);
+        release_on_proof(&mut contract, "a", TX_HASH, proof_data(BLOCK_HASH, 3, 1));
+        
+        submit(&mut contract, "b", OTHER_TX_HASH, proof_data(&BLOCK_HASH.to_ascii_uppercase()[2..], 3, 1));
+    }
+
+    #[test]
+    fn other_logs_of_a_consumed_receipt_stay_usable() {
+        let mut contract = contract();
+        create_escrow(&mut contract, "a", host::NEAR);
+        create_escrow(&mut contract, "b", host::NEAR);
+        release_on_proof(&mut contract, "a", TX_HASH, proof_data(BLOCK_HASH, 3, 1));
+        
+        release_on_proof(&mut contract, "b", TX_HASH, proof_data(BLOCK_HASH, 3, 2));
+        assert!(matches!(contract.get_escrow("b".to_string()).unwrap().status, EscrowStatus::Completed));
+    }
+
+    fn expected_payment(proof_id: &str) -> ExpectedPayment {
+        ExpectedPayment {
+            proof_id: proof_id.to_string(),
+            chain_id: "ethereum".to_string(),
+            token: format!("0x{}", "a0".repeat(20)),
+            recipient: format!("0x{}", "b1".repeat(20)),
+            amount: U128(5_000_000),
+        }
+    }
+
+    #[test]
+    fn depositor_sets_expected_payment_once_per_proof() {
+        let mut contract = contract();
+        create_escrow(&mut contract, "a", host::NEAR);
+        host::set_caller(DEPOSITOR);
+        contract.set_expected_payment("a".to_string(), expected_payment("payment"));
+        contract.set_expected_payment("a".to_string(), expected_payment("delivery"));
+        
+        let escrow = contract.get_escrow("a".to_string()).unwrap();
+        assert_eq!(escrow.expected_payment("payment").unwrap().amount, U128(5_000_000));
+        assert_eq!(escrow.expected_payments.len(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "Expected payment already set")]
+    fn expected_payment_cannot_be_changed() {
+        let mut contract = contract();
+        create_escrow(&mut contract, "a", host::NEAR);
+        host::set_caller(DEPOSITOR);
+        contract.set_expected_payment("a".to_string(), expected_payment("payment"));
+        contract.set_expected_payment("a".to_string(), expected_payment("payment"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only depositor can set expected payments")]
+    fn beneficiary_cannot_set_expected_payment() {
+        let mut contract = contract();
+        create_escrow(&mut contract, "a", host::NEAR);
+        host::set_caller(BENEFICIARY);
+        contract.set_expected_payment("a".to_string(), expected_payment("payment"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Expected payments are only checked on EVM chains")]
+    fn expected_payment_needs_an_evm_chain() {
+        let mut contract = contract();
+        create_escrow(&mut contract, "a", host::NEAR);
+        host::set_caller(DEPOSITOR);
+        let payment = ExpectedPayment { chain_id: "near".to_string(), ..expected_payment("payment") };
+        contract.set_expected_payment("a".to_string(), payment);
+    }
+
+    #[test]
+    #[should_panic(expected = "No expected payment for proof")]
+    fn light_client_verification_needs_expected_payment() {
+        let mut contract = contract();
+        create_escrow(&mut contract, "a", host::NEAR);
+        host::set_caller(OWNER);
+        contract.set_light_client("ethereum".to_string(), Some(account("client.near")));
+        submit(&mut contract, "a", TX_HASH, proof_data(BLOCK_HASH, 3, 1));
+        contract.verify_proof("a".to_string(), "payment".to_string());
+    }
+
+    fn rlp_encode(prefix: u8, payload: &[u8]) -> Vec<u8> {
+        let mut encoded = if payload.len() < 56 {
+            vec![prefix + payload.len() as u8]
+        } else {
+            let len: Vec<u8> = payload.len().to_be_bytes().into_iter().skip_while(|byte| *byte == 0).collect();
+            [vec![prefix + 55 + len.len() as u8], len].concat()
+        };
+        encoded.extend(payload);
+        encoded
+    }
+
+    fn rlp_item(bytes: &[u8]) -> Vec<u8> {
+        match bytes {
+            [byte] if *byte < 0x80 => bytes.to_vec(),
+            _ => rlp_encode(0x80, bytes),
+        }
+    }
+
+    fn rlp_of(items: &[Vec<u8>]) -> Vec<u8> {
+        rlp_encode(0xc0, &items.concat())
+    }
+
+    fn address(byte: u8) -> Vec<u8> {
+        vec![byte; 20]
+    }
+
+    fn transfer_log(token: &[u8], topic: &str, recipient: &[u8], amount: u128) -> Vec<u8> {
+        let word = |bytes: &[u8]| [vec![0u8; 32 - bytes.len()], bytes.to_vec()].concat();
+        rlp_of(&[
+            rlp_item(token),
+            rlp_of(&[
+                rlp_item(&hex::decode(topic).unwrap()),
+                rlp_item(&word(&address(0xcc))),
+                rlp_item(&word(recipient)),
+            ]),
+            rlp_item(&word(&amount.to_be_bytes())),
+        ])
+    }
+
+    // EIP-1559 receipt holding `logs`, proven as the only leaf of its block's receipts trie
+    fn receipt_proof(logs: &[Vec<u8>], log_index: u32) -> ProofData {
+        let receipt = rlp_of(&[rlp_item(&[1]), rlp_item(&[0x52, 0x08]), rlp_item(&[0; 256]), rlp_of(logs)]);
+        let receipt = [vec![0x02], receipt].concat();
+        let receipt_index = 0;
+        let path = [vec![0x20], rlp_encode_u64(receipt_index)].concat();
+        let leaf = rlp_of(&[rlp_item(&path), rlp_item(&receipt)]);
+        let receipts_root = env::keccak256(&leaf);
+        
+        let mut header: Vec<Vec<u8>> = (0..15).map(|_| rlp_item(&[0; 32])).collect();
+        header[HEADER_RECEIPTS_ROOT] = rlp_item(&receipts_root);
+        header[HEADER_NUMBER] = rlp_item(&[100]);
+        let header = rlp_of(&header);
+        
+        ProofData {
+            block_hash: hex::encode(env::keccak256(&header)),
+            block_header: hex::encode(header),
+            receipts_root: hex::encode(receipts_root),
+            receipt_index,
+            receipt_data: hex::encode(receipt),
+            proof_nodes: vec![hex::encode(leaf)],
+            log_index,
+        }
+    }
+
+    // Approval log at index 0, then the expected transfer at index 1
+    fn payment_proof(recipient: u8, amount: u128) -> CrossChainProof {
+        let approval = "8c5be1e5ebec7d5bd14f71427d1e84f3dd0314c0f7b2291e5b200ac8c7c3b925";
+        let logs = [
+            transfer_log(&address(0xa0), approval, &address(recipient), amount),
+            transfer_log(&address(0xa0), TRANSFER_TOPIC, &address(recipient), amount),
+        ];
+        CrossChainProof {
+            proof_id: "payment".to_string(),
+            chain_id: "ethereum".to_string(),
+            tx_hash: TX_HASH.to_string(),
+            block_number: 100,
+            proof_data: receipt_proof(&logs, 1),
+            verified: false,
+            verified_at: None,
+            approvals: Vec::new(),
+        }
+    }
+
+    fn check(proof: &CrossChainProof) -> Result<(u64, String), &'static str> {
+        check_receipt_proof(proof, Some(&expected_payment("payment")))
+    }
+
+    #[test]
+    fn transfer_proof_matching_expected_payment_passes() {
+        let proof = payment_proof(0xb1, 5_000_000);
+        assert_eq!(check(&proof), Ok((100, proof.proof_data.block_hash.clone())));
+    }
+
+    #[test]
+    fn transfer_to_another_recipient_is_rejected() {
+        let proof = payment_proof(0xb2, 5_000_000);
+        assert_eq!(check(&proof), Err("Transfer does not credit the expected recipient"));
+        // Inclusion alone still holds, as used by challenges of escrows without expectations
+        assert!(check_receipt_proof(&proof, None).is_ok());
+    }
+
+    #[test]
+    fn transfer_of_another_amount_is_rejected() {
+        let proof = payment_proof(0xb1, 4_999_999);
+        assert_eq!(check(&proof), Err("Transfer does not match the expected amount"));
+    }
+
+    #[test]
+    fn transfer_of_another_token_is_rejected() {
+        let expected = ExpectedPayment { token: format!("0x{}", "a1".repeat(20)), ..expected_payment("payment") };
+        let proof = payment_proof(0xb1, 5_000_000);
+        assert_eq!(check_receipt_proof(&proof, Some(&expected)), Err("Log not emitted by the expected token"));
+    }
+
+    #[test]
+    fn other_event_in_the_receipt_is_rejected() {
+        let mut proof = payment_proof(0xb1, 5_000_000);
+        proof.proof_data.log_index = 0;
+        assert_eq!(check(&proof), Err("Log is not a token transfer"));
+        proof.proof_data.log_index = 2;
+        assert_eq!(check(&proof), Err("Log index out of range"));
+    }
+
+    #[test]
+    fn proof_on_another_chain_is_rejected() {
+        let mut proof = payment_proof(0xb1, 5_000_000);
+        proof.chain_id = "polygon".to_string();
+        assert_eq!(check(&proof), Err("Proof is not on the expected chain"));
+    }
+
+    #[test]
+    fn tampered_receipt_or_header_is_rejected() {
+        let proof = payment_proof(0xb1, 5_000_000);
+        
+        let mut tampered = proof.clone();
+        tampered.proof_data.receipt_data = payment_proof(0xb1, 6_000_000).proof_data.receipt_data;
+        assert_eq!(check(&tampered), Err("Receipt does not match proof"));
+        
+        let mut tampered = proof.clone();
+        tampered.block_number = 101;
+        assert_eq!(check(&tampered), Err("Header does not match block number"));
+        
+        let mut tampered = proof;
+        tampered.proof_data.block_hash = hex::encode([0u8; 32]);
+        assert_eq!(check(&tampered), Err("Header does not match block hash"));
+    }
+
+    // Light client escrows "a" and "b" expecting transfers to recipients 0xb1.. and 0xb2..
+    fn light_client_escrows() -> EscrowContract {
+        let mut contract = contract();
+        create_escrow(&mut contract, "a", host::NEAR);
+        create_escrow(&mut contract, "b", host::NEAR);
+        host::set_caller(OWNER);
+        contract.set_light_client("ethereum".to_string(), Some(account("client.near")));
+        host::set_caller(DEPOSITOR);
+        contract.set_expected_payment("a".to_string(), expected_payment("payment"));
+        let other = ExpectedPayment { recipient: format!("0x{}", "b2".repeat(20)), ..expected_payment("payment") };
+        contract.set_expected_payment("b".to_string(), other);
+        contract
+    }
+
+    #[test]
+    fn light_client_proof_verifies_once_block_is_known() {
+        let mut contract = light_client_escrows();
+        let proof = payment_proof(0xb1, 5_000_000);
+        submit(&mut contract, "a", TX_HASH, proof.proof_data.clone());
+        contract.verify_proof("a".to_string(), "payment".to_string());
+        assert_eq!(
+            host::function_calls(),
+            vec![
+                ("client.near".to_string(), "block_hash_safe".to_string()),
+                (host::CONTRACT.to_string(), "on_light_client_block_hash".to_string()),
+            ]
+        );
+        
+        host::set_self_caller();
+        let block_hash = proof.proof_data.block_hash.clone();
+        let verified = contract.on_light_client_block_hash(
+            "a".to_string(),
+            "payment".to_string(),
+            proof.receipt_key(),
+            block_hash.clone(),
+            account(BENEFICIARY),
+            Ok(Some(format!("0x{}", block_hash))),
+        );
+        assert!(verified);
+        assert!(contract.get_escrow("a".to_string()).unwrap().proofs[0].verified);
+    }
+
+    #[test]
+    #[should_panic(expected = "Transfer does not credit the expected recipient")]
+    fn valid_receipt_for_another_escrow_is_rejected() {
+        let mut contract = light_client_escrows();
+        submit(&mut contract, "b", TX_HASH, payment_proof(0xb1, 5_000_000).proof_data);
+        contract.verify_proof("b".to_string(), "payment".to_string());
+    }
+
+    #[test]
+    fn replaced_proof_is_not_verified_by_stale_callback() {
+        let mut contract = light_client_escrows();
+        let proof = payment_proof(0xb1, 5_000_000);
+        submit(&mut contract, "a", TX_HASH, proof.proof_data.clone());
+        contract.verify_proof("a".to_string(), "payment".to_string());
+        let mut replacement = proof.proof_data.clone();
+        replacement.log_index = 0;
+        submit(&mut contract, "a", TX_HASH, replacement);
+        
+        host::set_self_caller();
+        let block_hash = proof.proof_data.block_hash.clone();
+        let verified = contract.on_light_client_block_hash(
+            "a".to_string(),
+            "payment".to_string(),
+            proof.receipt_key(),
+            block_hash.clone(),
+            account(BENEFICIARY),
+            Ok(Some(block_hash)),
+        );
+        assert!(!verified);
+    }
+
+    // Escrow "a" with a proof holding one of two required approvals
+    fn partly_approved_proof() -> EscrowContract {
+        let mut contract = contract();
+        create_escrow(&mut contract, "a", host::NEAR);
+        host::set_caller("carol.near");
+        host::set_deposit(10 * host::NEAR);
+        contract.bond_verifier();
+        host::set_deposit(0);
+        host::set_caller(OWNER);
+        contract.add_trusted_verifier(account("carol.near"));
+        contract.set_verifier_threshold(2);
+        
+        submit(&mut contract, "a", TX_HASH, proof_data(BLOCK_HASH, 3, 1));
+        host::set_caller(OWNER);
+        contract.verify_proof("a".to_string(), "payment".to_string());
+        contract
+    }
+
+    #[test]
+    #[should_panic(expected = "Only parties can submit proofs")]
+    fn third_party_cannot_reset_approvals() {
+        let mut contract = partly_approved_proof();
+        host::set_caller("mallory.near");
+        contract.submit_cross_chain_proof(
+            "a".to_string(),
+            "payment".to_string(),
+            "ethereum".to_string(),
+            OTHER_TX_HASH.to_string(),
+            100,
+            proof_data(BLOCK_HASH, 3, 2),
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Proof already has approvals")]
+    fn approved_proof_cannot_be_replaced() {
+        let mut contract = partly_approved_proof();
+        submit(&mut contract, "a", OTHER_TX_HASH, proof_data(BLOCK_HASH, 3, 2));
+    }
+
+    #[test]
+    fn unapproved_proof_can_be_replaced() {
+        let mut contract = contract();
+        create_escrow(&mut contract, "a", host::NEAR);
+        submit(&mut contract, "a", TX_HASH, proof_data(BLOCK_HASH, 3, 1));
+        submit(&mut contract, "a", OTHER_TX_HASH, proof_data(BLOCK_HASH, 3, 2));
+        
+        let proofs = contract.get_escrow("a".to_string()).unwrap().proofs;
+        assert_eq!(proofs.len(), 1);
+        assert_eq!(proofs[0].tx_hash, OTHER_TX_HASH);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid block hash")]
+    fn proof_without_block_hash_is_rejected() {
+        let mut contract = contract();
+        create_escrow(&mut contract, "a", host::NEAR);
+        submit(&mut contract, "a", TX_HASH, proof_data("", 3, 1));
+    }
+}
@@ -0,0 +1,162 @@
+use crate::*;
+
+// Upper bound on pre-funded periods per escrow
+const MAX_PERIODS: u32 = 120;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RecurringSchedule {
+    #[borsh(serialize_with = "decimal_borsh::serialize", deserialize_with = "decimal_borsh::deserialize")]
+    #[schemars(with = "String")]
+    pub period_amount: U128,
+    pub period_duration: u64, // ns
+    pub periods: u32,         // Funded periods still owed; lowered when future periods are cancelled
+    pub start: u64,
+    pub claimed_periods: u32,
+}
+
+impl RecurringSchedule {
+    // Periods whose tranche has become releasable; period i vests at start + (i + 1) * duration
+    fn vested_periods(&self) -> u32 {
+        let elapsed = env::block_timestamp().saturating_sub(self.start);
+        ((elapsed / self.period_duration).min(self.periods as u64)) as u32
+    }
+}
+
+#[near_bindgen]
+impl EscrowContract {
+    // Recurring escrow - the depositor pre-funds `periods` tranches of `period_amount`
+    #[payable]
+    pub fn create_recurring_escrow(
+        &mut self,
+        escrow_id: String,
+        beneficiary: AccountId,
+        period_amount: U128,
+        period_seconds: u64,
+        periods: u32,
+        arbiter: Option<AccountId>,
+        metadata: EscrowMetadata,
+        arbiter_fee: Option<ArbiterFee>,
+    ) -> Escrow {
+        ensure!(period_amount.0 > 0, InvalidArgument, "Period amount must be positive");
+        ensure!(period_seconds > 0, InvalidArgument, "Period must be positive");
+        ensure!(periods > 0 && periods <= MAX_PERIODS, InvalidArgument, "Escrow must have 1-120 periods");
+        ensure!(
+            period_amount.0 * periods as u128 == env::attached_deposit().as_yoctonear(),
+            InvalidArgument,
+            "Deposit must equal period amount times periods"
+        );
+        
+        let start = env::block_timestamp();
+        let period_duration = period_seconds * 1_000_000_000;
+        let mut escrow = self.create_escrow(
+            escrow_id.clone(),
+            beneficiary,
+            start + period_duration * periods as u64,
+            arbiter,
+            metadata,
+            arbiter_fee,
+            None,
+        );
+        escrow.schedule = Some(RecurringSchedule {
+            period_amount,
+            period_duration,
+            periods,
+            start,
+            claimed_periods: 0,
+        });
+        self.escrows.insert(&escrow_id, &escrow);
+        
+        env::log_str(&format!(
+            "Recurring escrow created: {} | Periods: {} | Per period: {}",
+            escrow_id, periods, period_amount.0
+        ));
+        
+        escrow
+    }
+
+    // Beneficiary collects every vested period not yet claimed
+    pub fn claim_periods(&mut self, escrow_id: String) -> Promise {
+        let mut escrow = self.escrows.get(&escrow_id).or_fail(ErrorCode::NotFound, "Escrow not found");
+        
+        let beneficiary: AccountId = escrow.beneficiary.parse().or_fail(ErrorCode::Internal, "Invalid beneficiary");
+        ensure!(env::predecessor_account_id() == beneficiary, Unauthorized, "Only beneficiary can claim");
+        ensure!(
+            matches!(escrow.status, EscrowStatus::Active),
+            InvalidState,
+            "Escrow not active"
+        );
+        
+        let schedule = escrow.schedule.as_mut().or_fail(ErrorCode::InvalidState, "Not a recurring escrow");
+        let vested = schedule.vested_periods();
+        let claimable = vested - schedule.claimed_periods;
+        ensure!(claimable > 0, InvalidState, "No periods to claim");
+        
+        let period_yocto: u128 = schedule.period_amount.0;
+        let amount_yocto = period_yocto * claimable as u128;
+        schedule.claimed_periods = vested;
+        if vested == schedule.periods {
+            self.settle_escrow(&mut escrow, EscrowStatus::Completed);
+        }
+        let released_yocto: u128 = escrow.released.0;
+        escrow.released = U128(released_yocto + amount_yocto);
+        self.escrows.insert(&escrow_id, &escrow);
+        
+        env::log_str(&format!(
+            "Periods claimed: {} | Periods: {} | Amount: {}",
+            escrow_id, claimable, amount_yocto
+        ));
+        emit_event("released", json!({
+            "escrow_id": escrow_id,
+            "beneficiary": escrow.beneficiary,
+            "amount": amount_yocto.to_string(),
+            "periods": claimable,
+        }));
+        
+        self.release_payout(&escrow, amount_yocto)
+    }
+
+    // Depositor cancels periods that have not vested yet; vested ones stay claimable
+    pub fn cancel_future_periods(&mut self, escrow_id: String) -> Promise {
+        let mut escrow = self.escrows.get(&escrow_id).or_fail(ErrorCode::NotFound, "Escrow not found");
+        
+        let depositor: AccountId = escrow.depositor.parse().or_fail(ErrorCode::Internal, "Invalid depositor");
+        ensure!(env::predecessor_account_id() == depositor, Unauthorized, "Only depositor can cancel periods");
+        ensure!(
+            matches!(escrow.status, EscrowStatus::Active),
+            InvalidState,
+            "Escrow not active"
+        );
+        
+        let schedule = escrow.schedule.as_mut().or_fail(ErrorCode::InvalidState, "Not a recurring escrow");
+        let vested = schedule.vested_periods();
+        let cancelled = schedule.periods - vested;
+        ensure!(cancelled > 0, InvalidState, "No future periods to cancel");
+        
+        let period_yocto: u128 = schedule.period_amount.0;
+        let refund_yocto = period_yocto * cancelled as u128;
+        schedule.periods = vested;
+        let fully_claimed = schedule.claimed_periods == vested;
+        escrow.release_time = schedule.start + schedule.period_duration * vested as u64;
+        if fully_claimed {
+            self.settle_escrow(&mut escrow, EscrowStatus::Completed);
+        }
+        // The refunded tranches leave the escrow, so the held amount shrinks with them
+        let amount_yocto: u128 = escrow.amount.0;
+        escrow.amount = U128(amount_yocto - refund_yocto);
+        self.escrows.insert(&escrow_id, &escrow);
+        
+        env::log_str(&format!(
+            "Future periods cancelled: {} | Periods: {} | Refund: {}",
+            escrow_id, cancelled, refund_yocto
+        ));
+        emit_event("refunded", json!({
+            "escrow_id": escrow_id,
+            "depositor": escrow.depositor,
+            "amount": refund_yocto.to_string(),
+            "periods": cancelled,
+        }));
+        
+        self.payout(&escrow, depositor, refund_yocto)
+    }
+}
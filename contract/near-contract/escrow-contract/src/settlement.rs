@@ -0,0 +1,117 @@
+use crate::*;
+use near_sdk::{is_promise_success, Gas};
+
+const GAS_FOR_SETTLEMENT_CALLBACK: Gas = Gas::from_tgas(10);
+
+#[near_bindgen]
+impl EscrowContract {
+    // Rolls a failed NEAR payout back into a balance the receiver claims with `claim_settlement`
+    #[private]
+    pub fn on_settlement(&mut self, settlement_id: u64) -> bool {
+        let Some(settlement) = self.settlements.resolve(settlement_id, is_promise_success()) else {
+            return true;
+        };
+        self.settlements.credit(&settlement.receiver, settlement.amount.0);
+        
+        env::log_str(&format!(
+            "Settlement failed: {} | Claimable by: {} | Amount: {}",
+            settlement_id, settlement.receiver, settlement.amount.0
+        ));
+        emit_event("settlement_failed", json!({
+            "settlement_id": settlement_id,
+            "receiver": settlement.receiver,
+            "amount": settlement.amount,
+        }));
+        
+        false
+    }
+
+    // Retries a failed settlement; a failure again leaves it claimable
+    pub fn claim_settlement(&mut self) -> Promise {
+        let account_id = env::predecessor_account_id();
+        let amount_yocto = self.settlements.claim(&account_id);
+        
+        env::log_str(&format!("Settlement claimed: {} | Amount: {}", account_id, amount_yocto));
+        
+        self.settle(account_id, amount_yocto)
+    }
+
+    pub fn get_claimable_settlement(&self, account_id: AccountId) -> U128 {
+        U128(self.settlements.claimable_of(&account_id))
+    }
+
+    pub(crate) fn new_settlement_ledger() -> SettlementLedger {
+        SettlementLedger::new(StorageKey::PendingSettlements, StorageKey::ClaimableSettlements)
+    }
+
+    // Sends NEAR through the settlement ledger so a failed transfer stays claimable
+    pub(crate) fn settle(&mut self, receiver: AccountId, amount_yocto: u128) -> Promise {
+        self.settlements.send(receiver, amount_yocto, |settlement_id| {
+            Self::ext(env::current_account_id())
+                .with_static_gas(GAS_FOR_SETTLEMENT_CALLBACK)
+                .on_settlement(settlement_id)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use ciphra_testing as host;
+
+    // Released escrow whose beneficiary payout is the only settlement in flight
+    fn released_contract() -> (EscrowContract, u64, u128) {
+        let mut contract = contract();
+        create_escrow(&mut contract, "e1", 10 * host::NEAR);
+        host::advance_seconds(86_400);
+        host::set_caller(BENEFICIARY);
+        let _ = contract.release_funds("e1".to_string());
+        let settlement_id = contract.settlements.next_settlement_id - 1;
+        let payout_yocto = contract.settlements.pending.get(&settlement_id).unwrap().amount.0;
+        host::clear_receipts();
+        (contract, settlement_id, payout_yocto)
+    }
+
+    fn resolve(contract: &mut EscrowContract, settlement_id: u64, delivered: bool) -> bool {
+        host::set_self_caller();
+        host::set_promise_results(vec![delivered.then(Vec::new)]);
+        let resolved = contract.on_settlement(settlement_id);
+        host::set_promise_results(vec![]);
+        resolved
+    }
+
+    #[test]
+    fn test_delivered_payout_leaves_nothing_claimable() {
+        let (mut contract, settlement_id, _) = released_contract();
+        
+        assert!(resolve(&mut contract, settlement_id, true));
+        assert_eq!(contract.settlements.total_pending, 0);
+        assert_eq!(contract.get_claimable_settlement(account(BENEFICIARY)), U128(0));
+    }
+
+    #[test]
+    fn test_failed_payout_is_claimed_once() {
+        let (mut contract, settlement_id, payout_yocto) = released_contract();
+        assert!(!resolve(&mut contract, settlement_id, false));
+        assert_eq!(contract.get_claimable_settlement(account(BENEFICIARY)), U128(payout_yocto));
+        // A replayed callback finds nothing left to roll back
+        assert!(resolve(&mut contract, settlement_id, false));
+        assert_eq!(contract.settlements.total_claimable, payout_yocto);
+        
+        host::set_caller(BENEFICIARY);
+        let _ = contract.claim_settlement();
+        
+        assert_eq!(host::transfers(), vec![(BENEFICIARY.to_string(), payout_yocto)]);
+        assert_eq!(contract.get_claimable_settlement(account(BENEFICIARY)), U128(0));
+        assert_eq!(contract.settlements.total_pending, payout_yocto);
+    }
+
+    #[test]
+    #[should_panic(expected = "Nothing to claim")]
+    fn test_claim_without_balance() {
+        let (mut contract, _, _) = released_contract();
+        host::set_caller(DEPOSITOR);
+        let _ = contract.claim_settlement();
+    }
+}
@@ -0,0 +1,457 @@
+use crate::*;
+use near_sdk::{ext_contract, is_promise_success, Gas, PromiseError};
+
+const GAS_FOR_POOL_CALL: Gas = Gas::from_tgas(50);
+const GAS_FOR_POOL_VIEW: Gas = Gas::from_tgas(10);
+const GAS_FOR_STAKING_CALLBACK: Gas = Gas::from_tgas(80);
+
+// Staking only makes sense for escrows locked at least this long (30 days)
+const MIN_STAKING_PERIOD: u64 = 30 * 86_400 * 1_000_000_000;
+
+// Anyone may start unstaking this long before release_time, covering the pool's unbonding delay (4 days)
+const UNSTAKE_LEAD_TIME: u64 = 4 * 86_400 * 1_000_000_000;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum StakingStatus {
+    Staking,   // deposit_and_stake in flight
+    Staked,
+    Unstaking, // Waiting out the pool's unbonding period
+    Withdrawn, // Principal back in the escrow, rewards paid out
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct EscrowStaking {
+    pub pool: String,
+    #[borsh(serialize_with = "decimal_borsh::serialize", deserialize_with = "decimal_borsh::deserialize")]
+    #[schemars(with = "String")]
+    pub principal: U128,
+    #[borsh(serialize_with = "decimal_borsh::serialize", deserialize_with = "decimal_borsh::deserialize")]
+    #[schemars(with = "String")]
+    pub shares: U128, // Claim on this contract's stake in the pool, shared with other escrows
+    pub reward_beneficiary_bps: u16, // Beneficiary's share of rewards; the depositor gets the rest
+    pub status: StakingStatus,
+    #[borsh(serialize_with = "decimal_borsh::serialize", deserialize_with = "decimal_borsh::deserialize")]
+    #[schemars(with = "String")]
+    pub unstaked_amount: U128,
+    #[borsh(serialize_with = "decimal_borsh::serialize", deserialize_with = "decimal_borsh::deserialize")]
+    #[schemars(with = "String")]
+    pub rewards: U128,
+}
+
+impl Escrow {
+    // Payouts are blocked while the escrowed NEAR sits in a staking pool
+    pub(crate) fn assert_liquid(&self) {
+        ensure!(
+            self.staking.as_ref().is_none_or(|s| s.status == StakingStatus::Withdrawn),
+            InvalidState,
+            "Escrow funds are staked"
+        );
+    }
+}
+
+// Only the generated ext_staking_pool module is used
+#[allow(dead_code)]
+#[ext_contract(ext_staking_pool)]
+trait StakingPool {
+    fn deposit_and_stake(&mut self);
+    fn unstake(&mut self, amount: U128);
+    fn withdraw(&mut self, amount: U128);
+    fn get_account_staked_balance(&self, account_id: AccountId) -> U128;
+}
+
+#[near_bindgen]
+impl EscrowContract {
+    // Depositor opts a long-lived NEAR escrow into staking with a whitelisted pool
+    pub fn stake_escrow(&mut self, escrow_id: String, pool: AccountId, reward_beneficiary_bps: u16) -> Promise {
+        let mut escrow = self.escrows.get(&escrow_id).or_fail(ErrorCode::NotFound, "Escrow not found");
+        
+        ensure!(
+            env::predecessor_account_id().as_str() == escrow.depositor,
+            Unauthorized,
+            "Only depositor can stake"
+        );
+        ensure!(
+            matches!(escrow.status, EscrowStatus::Active),
+            InvalidState,
+            "Escrow not active"
+        );
+        ensure!(escrow.token.is_none(), InvalidState, "Only NEAR escrows can be staked");
+        ensure!(escrow.staking.is_none(), InvalidState, "Escrow already staked");
+        ensure!(self.staking_pools.contains(&pool), InvalidArgument, "Staking pool not whitelisted");
+        ensure!(reward_beneficiary_bps <= 10000, LimitExceeded, "Share cannot exceed 10000 bps");
+        ensure!(
+            escrow.release_time >= env::block_timestamp() + MIN_STAKING_PERIOD,
+            InvalidState,
+            "Release time too soon to stake"
+        );
+        self.lock_pool(&pool, &escrow_id);
+        
+        let principal_yocto = Self::unreleased_amount(&escrow);
+        escrow.staking = Some(EscrowStaking {
+            pool: pool.to_string(),
+            principal: U128(principal_yocto),
+            shares: U128(0),
+            reward_beneficiary_bps,
+            status: StakingStatus::Staking,
+            unstaked_amount: U128(0),
+            rewards: U128(0),
+        });
+        self.escrows.insert(&escrow_id, &escrow);
+        
+        // Price the new shares against the contract's current stake before depositing
+        ext_staking_pool::ext(pool.clone())
+            .with_static_gas(GAS_FOR_POOL_VIEW)
+            .get_account_staked_balance(env::current_account_id())
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_STAKING_CALLBACK)
+                    .on_stake_quote(escrow_id),
+            )
+    }
+
+    #[private]
+    pub fn on_stake_quote(
+        &mut self,
+        escrow_id: String,
+        #[callback_result] staked_balance: Result<U128, PromiseError>,
+    ) -> PromiseOrValue<bool> {
+        let mut escrow = self.escrows.get(&escrow_id).or_fail(ErrorCode::NotFound, "Escrow not found");
+        let staking = escrow.staking.as_mut().or_fail(ErrorCode::InvalidState, "Escrow not staked");
+        let pool: AccountId = staking.pool.parse().or_fail(ErrorCode::Internal, "Invalid pool");
+        let Ok(staked_balance) = staked_balance else {
+            escrow.staking = None;
+            self.escrows.insert(&escrow_id, &escrow);
+            self.pool_operations.remove(&pool);
+            env::log_str(&format!("Staking aborted: {} | Pool unavailable", escrow_id));
+            return PromiseOrValue::Value(false);
+        };
+        
+        // The pool lock keeps other deposits and unstakes from moving the balance until the new
+        // shares are counted, so this quote prices them fairly
+        let principal_yocto: u128 = staking.principal.0;
+        let total_shares = self.pool_shares.get(&pool).unwrap_or(0);
+        let shares = if total_shares == 0 || staked_balance.0 == 0 {
+            principal_yocto
+        } else {
+            mul_div(principal_yocto, total_shares, staked_balance.0)
+        };
+        staking.shares = U128(shares);
+        self.escrows.insert(&escrow_id, &escrow);
+        
+        PromiseOrValue::Promise(
+            ext_staking_pool::ext(pool)
+                .with_attached_deposit(NearToken::from_yoctonear(principal_yocto))
+                .with_static_gas(GAS_FOR_POOL_CALL)
+                .deposit_and_stake()
+                .then(
+                    Self::ext(env::current_account_id())
+                        .with_static_gas(GAS_FOR_STAKING_CALLBACK)
+                        .on_escrow_staked(escrow_id),
+                ),
+        )
+    }
+
+    // Callback - a failed deposit refunds the attached NEAR, so the escrow simply stays liquid
+    #[private]
+    pub fn on_escrow_staked(&mut self, escrow_id: String) -> bool {
+        let mut escrow = self.escrows.get(&escrow_id).or_fail(ErrorCode::NotFound, "Escrow not found");
+        let pool: AccountId = escrow
+            .staking
+            .as_ref()
+            .and_then(|staking| staking.pool.parse().ok())
+            .or_fail(ErrorCode::InvalidState, "Escrow not staked");
+        self.pool_operations.remove(&pool);
+        
+        if !is_promise_success() {
+            escrow.staking = None;
+            self.escrows.insert(&escrow_id, &escrow);
+            env::log_str(&format!("Staking failed: {}", escrow_id));
+            return false;
+        }
+        
+        let staking = escrow.staking.as_mut().or_fail(ErrorCode::InvalidState, "Escrow not staked");
+        staking.status = StakingStatus::Staked;
+        let shares: u128 = staking.shares.0;
+        self.pool_shares.insert(&pool, &(self.pool_shares.get(&pool).unwrap_or(0) + shares));
+        self.escrows.insert(&escrow_id, &escrow);
+        
+        env::log_str(&format!("Escrow staked: {} | Pool: {} | Shares: {}", escrow_id, pool, shares));
+        
+        true
+    }
+
+    // Start unbonding - the depositor at any time, anyone once release approaches or the escrow settles
+    pub fn unstake_escrow(&mut self, escrow_id: String) -> Promise {
+        let escrow = self.escrows.get(&escrow_id).or_fail(ErrorCode::NotFound, "Escrow not found");
+        let staking = escrow.staking.as_ref().or_fail(ErrorCode::InvalidState, "Escrow not staked");
+        
+        ensure!(staking.status == StakingStatus::Staked, InvalidState, "Escrow is not staked");
+        let is_depositor = env::predecessor_account_id().as_str() == escrow.depositor;
+        let release_near = env::block_timestamp() + UNSTAKE_LEAD_TIME >= escrow.release_time;
+        ensure!(
+            is_depositor || release_near || !matches!(escrow.status, EscrowStatus::Active),
+            TooEarly,
+            "Too early to unstake"
+        );
+        let pool: AccountId = staking.pool.parse().or_fail(ErrorCode::Internal, "Invalid pool");
+        self.lock_pool(&pool, &escrow_id);
+        
+        ext_staking_pool::ext(pool)
+            .with_static_gas(GAS_FOR_POOL_VIEW)
+            .get_account_staked_balance(env::current_account_id())
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_STAKING_CALLBACK)
+                    .on_unstake_quote(escrow_id),
+            )
+    }
+
+    #[private]
+    pub fn on_unstake_quote(
+        &mut self,
+        escrow_id: String,
+        #[callback_result] staked_balance: Result<U128, PromiseError>,
+    ) -> PromiseOrValue<bool> {
+        let mut escrow = self.escrows.get(&escrow_id).or_fail(ErrorCode::NotFound, "Escrow not found");
+        let staking = escrow.staking.as_mut().or_fail(ErrorCode::InvalidState, "Escrow not staked");
+        let pool: AccountId = staking.pool.parse().or_fail(ErrorCode::Internal, "Invalid pool");
+        let Ok(staked_balance) = staked_balance else {
+            self.pool_operations.remove(&pool);
+            env::log_str(&format!("Unstaking aborted: {} | Pool unavailable", escrow_id));
+            return PromiseOrValue::Value(false);
+        };
+        if staking.status != StakingStatus::Staked {
+            self.pool_operations.remove(&pool);
+            return PromiseOrValue::Value(false);
+        }
+        
+        // Redeem this escrow's shares at the current stake per share
+        let shares: u128 = staking.shares.0;
+        let total_shares = self.pool_shares.get(&pool).unwrap_or(0);
+        let amount_yocto = mul_div(staked_balance.0, shares, total_shares.max(1));
+        self.pool_shares.insert(&pool, &total_shares.saturating_sub(shares));
+        staking.status = StakingStatus::Unstaking;
+        staking.unstaked_amount = U128(amount_yocto);
+        self.escrows.insert(&escrow_id, &escrow);
+        
+        PromiseOrValue::Promise(
+            ext_staking_pool::ext(pool)
+                .with_static_gas(GAS_FOR_POOL_CALL)
+                .unstake(U128(amount_yocto))
+                .then(
+                    Self::ext(env::current_account_id())
+                        .with_static_gas(GAS_FOR_STAKING_CALLBACK)
+                        .on_escrow_unstaked(escrow_id),
+                ),
+        )
+    }
+
+    // Callback - on failure the shares are restored so unstaking can be retried
+    #[private]
+    pub fn on_escrow_unstaked(&mut self, escrow_id: String) -> bool {
+        let mut escrow = self.escrows.get(&escrow_id).or_fail(ErrorCode::NotFound, "Escrow not found");
+        let staking = escrow.staking.as_mut().or_fail(ErrorCode::InvalidState, "Escrow not staked");
+        let pool: AccountId = staking.pool.parse().or_fail(ErrorCode::Internal, "Invalid pool");
+        self.pool_operations.remove(&pool);
+        if is_promise_success() {
+            env::log_str(&format!("Escrow unstaking: {}", escrow_id));
+            return true;
+        }
+        
+        let shares: u128 = staking.shares.0;
+        self.pool_shares.insert(&pool, &(self.pool_shares.get(&pool).unwrap_or(0) + shares));
+        staking.status = StakingStatus::Staked;
+        staking.unstaked_amount = U128(0);
+        self.escrows.insert(&escrow_id, &escrow);
+        
+        env::log_str(&format!("Unstaking failed: {}", escrow_id));
+        false
+    }
+
+    // Anyone can pull the unbonded stake back once the pool releases it
+    pub fn withdraw_escrow_stake(&mut self, escrow_id: String) -> Promise {
+        let escrow = self.escrows.get(&escrow_id).or_fail(ErrorCode::NotFound, "Escrow not found");
+        let staking = escrow.staking.as_ref().or_fail(ErrorCode::InvalidState, "Escrow not staked");
+        ensure!(staking.status == StakingStatus::Unstaking, InvalidState, "Escrow is not unstaking");
+        
+        let amount_yocto: u128 = staking.unstaked_amount.0;
+        ext_staking_pool::ext(staking.pool.parse().or_fail(ErrorCode::Internal, "Invalid pool"))
+            .with_static_gas(GAS_FOR_POOL_CALL)
+            .withdraw(U128(amount_yocto))
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_STAKING_CALLBACK)
+                    .on_stake_withdrawn(escrow_id),
+            )
+    }
+
+    // Callback - principal returns to the escrow and rewards are split per the staking terms
+    #[private]
+    pub fn on_stake_withdrawn(&mut self, escrow_id: String) -> PromiseOrValue<bool> {
+        if !is_promise_success() {
+            env::log_str(&format!("Stake withdrawal failed: {} | Still unbonding?", escrow_id));
+            return PromiseOrValue::Value(false);
+        }
+        
+        let mut escrow = self.escrows.get(&escrow_id).or_fail(ErrorCode::NotFound, "Escrow not found");
+        let staking = escrow.staking.as_mut().or_fail(ErrorCode::InvalidState, "Escrow not staked");
+        let principal_yocto: u128 = staking.principal.0;
+        let withdrawn_yocto: u128 = staking.unstaked_amount.0;
+        let rewards_yocto = withdrawn_yocto.saturating_sub(principal_yocto);
+        let beneficiary_yocto = (rewards_yocto * staking.reward_beneficiary_bps as u128) / 10000;
+        let depositor_yocto = rewards_yocto - beneficiary_yocto;
+        staking.status = StakingStatus::Withdrawn;
+        staking.rewards = U128(rewards_yocto);
+        // Pool share rounding can return a few yocto less than was staked
+        let shortfall_yocto = principal_yocto.saturating_sub(withdrawn_yocto);
+        if shortfall_yocto > 0 {
+            let amount_yocto: u128 = escrow.amount.0;
+            escrow.amount = U128(amount_yocto - shortfall_yocto);
+            self.track_outflow(&escrow, shortfall_yocto);
+        }
+        self.escrows.insert(&escrow_id, &escrow);
+        
+        env::log_str(&format!(
+            "Escrow stake withdrawn: {} | Withdrawn: {} | Rewards: {}",
+            escrow_id, withdrawn_yocto, rewards_yocto
+        ));
+        emit_event("staking_rewards", json!({
+            "escrow_id": escrow_id,
+            "rewards": rewards_yocto.to_string(),
+            "beneficiary_amount": beneficiary_yocto.to_string(),
+            "depositor_amount": depositor_yocto.to_string(),
+        }));
+        
+        let rewards = [
+            (escrow.beneficiary.clone(), beneficiary_yocto),
+            (escrow.depositor.clone(), depositor_yocto),
+        ];
+        rewards
+            .into_iter()
+            .filter(|(_, amount_yocto)| *amount_yocto > 0)
+            .map(|(account, amount_yocto)| self.transfer_out(&escrow, account.parse().or_fail(ErrorCode::Internal, "Invalid account"), amount_yocto))
+            .reduce(|all, payout| all.and(payout))
+            .map_or(PromiseOrValue::Value(true), PromiseOrValue::Promise)
+    }
+
+    pub fn add_staking_pool(&mut self, pool: AccountId) {
+        assert_owner(&self.owner);
+        if !self.staking_pools.contains(&pool) {
+            self.staking_pools.push(pool);
+        }
+    }
+
+    pub fn remove_staking_pool(&mut self, pool: AccountId) {
+        assert_owner(&self.owner);
+        self.staking_pools.retain(|p| p != &pool);
+    }
+
+    pub fn get_staking_pools(&self) -> Vec<AccountId> {
+        self.staking_pools.clone()
+    }
+
+    // Escrow whose stake or unstake with `pool` is in flight, if any
+    pub fn get_pool_operation(&self, pool: AccountId) -> Option<String> {
+        self.pool_operations.get(&pool)
+    }
+
+    pub(crate) fn new_pool_operations() -> LookupMap<AccountId, String> {
+        LookupMap::new(StorageKey::PoolOperations)
+    }
+
+    // Share pricing reads the pool balance before moving it, so one operation per pool at a time
+    fn lock_pool(&mut self, pool: &AccountId, escrow_id: &str) {
+        ensure!(
+            self.pool_operations.get(pool).is_none(),
+            InvalidState,
+            "Another stake or unstake is in flight for this pool"
+        );
+        self.pool_operations.insert(pool, &escrow_id.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use ciphra_testing as host;
+
+    const POOL: &str = "pool.near";
+
+    fn staking_contract() -> EscrowContract {
+        let mut contract = contract();
+        contract.add_staking_pool(account(POOL));
+        create_escrow_releasing(&mut contract, "e1", 10 * host::NEAR, 60);
+        create_escrow_releasing(&mut contract, "e2", 10 * host::NEAR, 60);
+        contract
+    }
+
+    // Runs the quote and deposit callbacks of an in-flight stake as the pool would answer them
+    fn complete_stake(contract: &mut EscrowContract, escrow_id: &str, staked_balance: u128) {
+        host::set_self_caller();
+        let _ = contract.on_stake_quote(escrow_id.to_string(), Ok(U128(staked_balance)));
+        host::set_promise_results(vec![Some(vec![])]);
+        assert!(contract.on_escrow_staked(escrow_id.to_string()));
+        host::set_promise_results(vec![]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Another stake or unstake is in flight for this pool")]
+    fn test_second_stake_waits_for_first_quote() {
+        let mut contract = staking_contract();
+        host::set_caller(DEPOSITOR);
+        let _ = contract.stake_escrow("e1".to_string(), account(POOL), 0);
+        let _ = contract.stake_escrow("e2".to_string(), account(POOL), 0);
+    }
+
+    #[test]
+    fn test_second_stake_priced_after_first_counted() {
+        let mut contract = staking_contract();
+        host::set_caller(DEPOSITOR);
+        let _ = contract.stake_escrow("e1".to_string(), account(POOL), 0);
+        assert_eq!(contract.get_pool_operation(account(POOL)), Some("e1".to_string()));
+        complete_stake(&mut contract, "e1", 0);
+        assert_eq!(contract.get_pool_operation(account(POOL)), None);
+
+        // The pool has grown 10% since the first deposit, so the same principal buys fewer shares
+        host::set_caller(DEPOSITOR);
+        let _ = contract.stake_escrow("e2".to_string(), account(POOL), 0);
+        complete_stake(&mut contract, "e2", 11 * host::NEAR);
+
+        let first = contract.get_escrow("e1".to_string()).unwrap().staking.unwrap().shares.0;
+        let second = contract.get_escrow("e2".to_string()).unwrap().staking.unwrap().shares.0;
+        assert_eq!(first, 10 * host::NEAR);
+        assert_eq!(second, first * 10 / 11);
+        assert_eq!(contract.pool_shares.get(&account(POOL)), Some(first + second));
+    }
+
+    #[test]
+    fn test_failed_quote_releases_pool() {
+        let mut contract = staking_contract();
+        host::set_caller(DEPOSITOR);
+        let _ = contract.stake_escrow("e1".to_string(), account(POOL), 0);
+        host::set_self_caller();
+        let _ = contract.on_stake_quote("e1".to_string(), Err(PromiseError::Failed));
+        assert_eq!(contract.get_pool_operation(account(POOL)), None);
+        assert!(contract.get_escrow("e1".to_string()).unwrap().staking.is_none());
+
+        host::set_caller(DEPOSITOR);
+        let _ = contract.stake_escrow("e2".to_string(), account(POOL), 0);
+        assert_eq!(contract.get_pool_operation(account(POOL)), Some("e2".to_string()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Another stake or unstake is in flight for this pool")]
+    fn test_stake_waits_for_unstake() {
+        let mut contract = staking_contract();
+        host::set_caller(DEPOSITOR);
+        let _ = contract.stake_escrow("e1".to_string(), account(POOL), 0);
+        complete_stake(&mut contract, "e1", 0);
+
+        host::set_caller(DEPOSITOR);
+        let _ = contract.unstake_escrow("e1".to_string());
+        let _ = contract.stake_escrow("e2".to_string(), account(POOL), 0);
+    }
+}
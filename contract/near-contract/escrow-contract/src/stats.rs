@@ -0,0 +1,122 @@
+use crate::*;
+
+#[derive(BorshDeserialize, BorshSerialize, Default)]
+pub struct EscrowStats {
+    pub active_count: u64,
+    pub completed_count: u64,
+    pub disputed_count: u64,
+    pub refunded_count: u64,
+    pub resolved_count: u64,
+    pub value_locked: u128,   // NEAR escrows only; token amounts aren't comparable
+    pub total_released: u128, // NEAR paid out to beneficiaries
+    pub total_disputes: u64,
+    pub settled_disputes: u64,
+    pub total_resolution_time: u64,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct EscrowStatsView {
+    pub active_count: u64,
+    pub completed_count: u64,
+    pub disputed_count: u64,
+    pub refunded_count: u64,
+    pub resolved_count: u64,
+    #[schemars(with = "String")]
+    pub value_locked: U128,
+    #[schemars(with = "String")]
+    pub total_released: U128,
+    pub total_disputes: u64,
+    pub average_resolution_time: u64,
+}
+
+impl EscrowStats {
+    fn count_mut(&mut self, status: &EscrowStatus) -> &mut u64 {
+        match status {
+            EscrowStatus::Active => &mut self.active_count,
+            EscrowStatus::Completed => &mut self.completed_count,
+            EscrowStatus::Disputed => &mut self.disputed_count,
+            EscrowStatus::Refunded => &mut self.refunded_count,
+            EscrowStatus::Resolved => &mut self.resolved_count,
+        }
+    }
+
+    fn move_status(&mut self, from: &EscrowStatus, to: &EscrowStatus) {
+        let from_count = self.count_mut(from);
+        *from_count = from_count.saturating_sub(1);
+        *self.count_mut(to) += 1;
+    }
+}
+
+#[near_bindgen]
+impl EscrowContract {
+    pub fn get_stats(&self) -> EscrowStatsView {
+        EscrowStatsView {
+            active_count: self.stats.active_count,
+            completed_count: self.stats.completed_count,
+            disputed_count: self.stats.disputed_count,
+            refunded_count: self.stats.refunded_count,
+            resolved_count: self.stats.resolved_count,
+            value_locked: U128(self.stats.value_locked),
+            total_released: U128(self.stats.total_released),
+            total_disputes: self.stats.total_disputes,
+            average_resolution_time: self.stats.total_resolution_time / self.stats.settled_disputes.max(1),
+        }
+    }
+
+    pub(crate) fn track_new_escrow(&mut self, escrow: &Escrow) {
+        self.stats.active_count += 1;
+        if escrow.token.is_none() {
+            self.stats.value_locked += escrow.amount.0;
+        }
+    }
+
+    pub(crate) fn track_migrated_escrow(&mut self, escrow: &Escrow) {
+        *self.stats.count_mut(&escrow.status) += 1;
+        match escrow.status {
+            EscrowStatus::Active => self.stats.value_locked += escrow.amount.0,
+            EscrowStatus::Disputed => {
+                self.stats.value_locked += escrow.amount.0;
+                self.stats.total_disputes += 1;
+            }
+            _ => {}
+        }
+    }
+
+    pub(crate) fn track_dispute(&mut self, escrow: &Escrow) {
+        self.stats.move_status(&EscrowStatus::Active, &escrow.status);
+        self.stats.total_disputes += 1;
+    }
+
+    // Terminal transition - settled_at starts the retention clock for cleanup
+    pub(crate) fn settle_escrow(&mut self, escrow: &mut Escrow, status: EscrowStatus) {
+        let now = env::block_timestamp();
+        self.stats.move_status(&escrow.status, &status);
+        if let (EscrowStatus::Disputed, Some(disputed_at)) = (&escrow.status, escrow.disputed_at) {
+            self.stats.settled_disputes += 1;
+            self.stats.total_resolution_time += now - disputed_at;
+        }
+        
+        escrow.status = status;
+        escrow.settled_at = Some(now);
+    }
+
+    pub(crate) fn track_inflow(&mut self, escrow: &Escrow, amount_yocto: u128) {
+        if escrow.token.is_none() {
+            self.stats.value_locked += amount_yocto;
+        }
+    }
+
+    // Escrowed value leaving the contract, whether paid out or moved to the treasury
+    pub(crate) fn track_outflow(&mut self, escrow: &Escrow, amount_yocto: u128) {
+        if escrow.token.is_none() {
+            self.stats.value_locked = self.stats.value_locked.saturating_sub(amount_yocto);
+        }
+    }
+
+    pub(crate) fn track_release(&mut self, escrow: &Escrow, amount_yocto: u128) {
+        if escrow.token.is_none() {
+            self.stats.total_released += amount_yocto;
+        }
+    }
+}
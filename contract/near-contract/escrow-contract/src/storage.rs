@@ -0,0 +1,53 @@
+use crate::*;
+
+// Storage locked per escrow - covers the record with room for its evidence, attachments and
+// amendments, plus its index entries
+const STORAGE_DEPOSIT_PER_RECORD: u128 = 50_000_000_000_000_000_000_000; // 0.05 NEAR
+
+#[near_bindgen]
+impl EscrowContract {
+    // NEP-145 - prepay storage for the escrows an account creates
+    #[payable]
+    pub fn storage_deposit(
+        &mut self,
+        account_id: Option<AccountId>,
+        registration_only: Option<bool>,
+    ) -> StorageBalance {
+        let account_id = account_id.unwrap_or_else(env::predecessor_account_id);
+        self.storage_accounts.deposit(&account_id, registration_only.unwrap_or(false), STORAGE_DEPOSIT_PER_RECORD).0
+    }
+
+    // NEP-145 - withdraw storage balance that isn't locked by existing escrows
+    #[payable]
+    pub fn storage_withdraw(&mut self, amount: Option<U128>) -> StorageBalance {
+        self.storage_accounts.withdraw(amount).0
+    }
+
+    // NEP-145 - close an account with no escrows holding storage and refund its balance
+    #[payable]
+    pub fn storage_unregister(&mut self, force: Option<bool>) -> bool {
+        self.storage_accounts.unregister(force.unwrap_or(false)).is_some()
+    }
+
+    pub fn storage_balance_of(&self, account_id: AccountId) -> Option<StorageBalance> {
+        self.storage_accounts.balance_of(&account_id)
+    }
+
+    pub fn storage_balance_bounds(&self) -> StorageBalanceBounds {
+        StorageAccounts::bounds(STORAGE_DEPOSIT_PER_RECORD)
+    }
+
+    pub(crate) fn lock_escrow_storage(&mut self, depositor: &AccountId, escrow_id: &str) {
+        self.storage_accounts.lock(depositor, STORAGE_DEPOSIT_PER_RECORD);
+        self.record_storage_payers.insert(&escrow_id.to_string(), depositor);
+    }
+
+    // Unlock the deposit of a removed escrow so its depositor can withdraw it; escrows created
+    // before storage staking locked nothing
+    pub(crate) fn release_escrow_storage(&mut self, escrow_id: &str) -> u128 {
+        let Some(payer) = self.record_storage_payers.remove(&escrow_id.to_string()) else {
+            return 0;
+        };
+        self.storage_accounts.unlock(&payer, STORAGE_DEPOSIT_PER_RECORD)
+    }
+}
@@ -0,0 +1,181 @@
+use crate::*;
+
+// Swap leg an escrow settles against - the beneficiary pays the depositor through the swap contract
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SwapLink {
+    pub swap_id: String,
+    pub linked_at: u64,
+    pub attested_at: Option<u64>,
+}
+
+#[near_bindgen]
+impl EscrowContract {
+    // Depositor names the swap whose completion releases the escrow
+    pub fn link_swap(&mut self, escrow_id: String, swap_id: String) {
+        let mut escrow = self.escrows.get(&escrow_id).or_fail(ErrorCode::NotFound, "Escrow not found");
+        
+        let depositor: AccountId = escrow.depositor.parse().or_fail(ErrorCode::Internal, "Invalid depositor");
+        ensure!(env::predecessor_account_id() == depositor, Unauthorized, "Only depositor can link a swap");
+        ensure!(
+            matches!(escrow.status, EscrowStatus::Active),
+            InvalidState,
+            "Escrow not active"
+        );
+        ensure!(self.swap_contract.is_some(), NotFound, "No swap contract configured");
+        // Only plain escrows release in one piece on an attestation
+        ensure!(
+            escrow.milestones.is_empty()
+                && escrow.schedule.is_none()
+                && escrow.vesting.is_none()
+                && escrow.price_condition.is_none()
+                && escrow.hashlock.is_none()
+                && escrow.compliance.is_none(),
+            InvalidState,
+            "Escrow has its own release path"
+        );
+        
+        escrow.swap_link = Some(SwapLink {
+            swap_id: swap_id.clone(),
+            linked_at: env::block_timestamp(),
+            attested_at: None,
+        });
+        self.escrows.insert(&escrow_id, &escrow);
+        
+        env::log_str(&format!("Swap linked: {} | Swap: {}", escrow_id, swap_id));
+        emit_event("swap_linked", json!({
+            "escrow_id": escrow_id,
+            "swap_id": swap_id,
+        }));
+    }
+
+    // Called by the swap contract when a linked swap completes; releases the escrow to its payees.
+    // `amount` is what the participant received in `token` (None for NEAR).
+    pub fn attest_swap_settlement(
+        &mut self,
+        escrow_id: String,
+        swap_id: String,
+        initiator: AccountId,
+        participant: AccountId,
+        token: Option<AccountId>,
+        amount: U128,
+    ) -> Promise {
+        ensure!(
+            self.swap_contract.as_ref() == Some(&env::predecessor_account_id()),
+            Unauthorized,
+            "Only swap contract"
+        );
+        let mut escrow = self.escrows.get(&escrow_id).or_fail(ErrorCode::NotFound, "Escrow not found");
+        
+        let linked_swap = escrow.swap_link.as_ref().map(|link| link.swap_id.as_str());
+        ensure!(linked_swap == Some(swap_id.as_str()), InvalidState, "Swap is not linked to this escrow");
+        // The swap must be the beneficiary paying the depositor, not an unrelated pair
+        ensure!(escrow.is_payee(&initiator), InvalidArgument, "Swap initiator is not the beneficiary");
+        ensure!(participant.as_str() == escrow.depositor, InvalidArgument, "Swap participant is not the depositor");
+        // and must pay at least what the escrow releases, in the escrow's asset
+        ensure!(
+            token.as_ref().map(|token| token.as_str()) == escrow.token.as_deref(),
+            InvalidArgument,
+            "Swap asset does not match the escrow"
+        );
+        ensure!(amount.0 >= escrow.amount.0, InsufficientFunds, "Swap amount below the escrow amount");
+        ensure!(
+            matches!(escrow.status, EscrowStatus::Active),
+            InvalidState,
+            "Escrow not active"
+        );
+        if let Some(link) = escrow.swap_link.as_mut() {
+            link.attested_at = Some(env::block_timestamp());
+        }
+        
+        self.settle_escrow(&mut escrow, EscrowStatus::Completed);
+        self.escrows.insert(&escrow_id, &escrow);
+        
+        let amount_yocto = Self::unreleased_amount(&escrow);
+        
+        env::log_str(&format!(
+            "Swap settlement attested: {} | Swap: {} | Amount: {}",
+            escrow_id, swap_id, amount_yocto
+        ));
+        emit_event("released", json!({
+            "escrow_id": escrow_id,
+            "beneficiary": escrow.beneficiary,
+            "amount": amount_yocto.to_string(),
+            "swap_id": swap_id,
+        }));
+        
+        self.release_payout(&escrow, amount_yocto)
+    }
+
+    pub fn set_swap_contract(&mut self, swap_contract: Option<AccountId>) {
+        assert_owner(&self.owner);
+        self.swap_contract = swap_contract;
+    }
+
+    pub fn get_swap_contract(&self) -> Option<AccountId> {
+        self.swap_contract.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use ciphra_testing as host;
+
+    const SWAP_CONTRACT: &str = "swap.near";
+
+    fn linked_contract() -> EscrowContract {
+        let mut contract = contract();
+        contract.set_swap_contract(Some(account(SWAP_CONTRACT)));
+        create_escrow(&mut contract, "e1", 10 * host::NEAR);
+        host::set_caller(DEPOSITOR);
+        contract.link_swap("e1".to_string(), "s1".to_string());
+        host::set_caller(SWAP_CONTRACT);
+        contract
+    }
+
+    fn attest(contract: &mut EscrowContract, token: Option<&str>, amount: u128) {
+        let _ = contract.attest_swap_settlement(
+            "e1".to_string(),
+            "s1".to_string(),
+            account(BENEFICIARY),
+            account(DEPOSITOR),
+            token.map(account),
+            U128(amount),
+        );
+    }
+
+    #[test]
+    fn test_covering_swap_releases_escrow() {
+        let mut contract = linked_contract();
+        attest(&mut contract, None, 10 * host::NEAR);
+        
+        let escrow = contract.get_escrow("e1".to_string()).unwrap();
+        assert!(matches!(escrow.status, EscrowStatus::Completed));
+        let fee_yocto = ciphra_types::bps_of(10 * host::NEAR, contract.get_settlement_fee());
+        assert_eq!(host::transfers(), vec![(BENEFICIARY.to_string(), 10 * host::NEAR - fee_yocto)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Swap amount below the escrow amount")]
+    fn test_short_swap_rejected() {
+        let mut contract = linked_contract();
+        attest(&mut contract, None, 10 * host::NEAR - 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Swap asset does not match the escrow")]
+    fn test_swap_in_other_asset_rejected() {
+        let mut contract = linked_contract();
+        attest(&mut contract, Some("usdc.near"), 10 * host::NEAR);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only swap contract")]
+    fn test_attestation_only_from_swap_contract() {
+        let mut contract = linked_contract();
+        host::set_caller(BENEFICIARY);
+        attest(&mut contract, None, 10 * host::NEAR);
+    }
+}
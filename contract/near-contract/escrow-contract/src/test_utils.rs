@@ -0,0 +1,63 @@
+// Fixtures shared by the unit tests; contract calls run against the ciphra-testing host
+use crate::*;
+use ciphra_testing as host;
+
+pub(crate) const OWNER: &str = "owner.near";
+pub(crate) const DEPOSITOR: &str = "alice.near";
+pub(crate) const BENEFICIARY: &str = "bob.near";
+
+pub(crate) fn account(name: &str) -> AccountId {
+    name.parse().unwrap()
+}
+
+pub(crate) fn contract() -> EscrowContract {
+    host::set_caller(OWNER);
+    EscrowContract::new(account(OWNER))
+}
+
+// NEAR escrow from the depositor to the beneficiary, releasable by time after a day
+pub(crate) fn create_escrow(contract: &mut EscrowContract, escrow_id: &str, amount: u128) -> Escrow {
+    create_escrow_releasing(contract, escrow_id, amount, 1)
+}
+
+pub(crate) fn create_escrow_releasing(
+    contract: &mut EscrowContract,
+    escrow_id: &str,
+    amount: u128,
+    release_days: u64,
+) -> Escrow {
+    host::set_caller(DEPOSITOR);
+    host::set_deposit(host::NEAR);
+    contract.storage_deposit(None, None);
+    host::set_deposit(amount);
+    let escrow = contract.create_escrow(
+        escrow_id.to_string(),
+        account(BENEFICIARY),
+        host::block_timestamp() + release_days * 86_400 * 1_000_000_000,
+        None,
+        EscrowMetadata {
+            title: "Test escrow".to_string(),
+            category: "test".to_string(),
+            content_hash: None,
+            external_uri: None,
+        },
+        None,
+        None,
+    );
+    host::set_deposit(0);
+    host::clear_receipts();
+    escrow
+}
+
+// Trusted-verifier proof data locating log `log_index` of receipt `receipt_index` in `block_hash`
+pub(crate) fn proof_data(block_hash: &str, receipt_index: u64, log_index: u32) -> ProofData {
+    ProofData {
+        block_hash: block_hash.to_string(),
+        block_header: String::new(),
+        receipts_root: String::new(),
+        receipt_index,
+        receipt_data: String::new(),
+        proof_nodes: Vec::new(),
+        log_index,
+    }
+}
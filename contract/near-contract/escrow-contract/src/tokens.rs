@@ -0,0 +1,122 @@
+use crate::*;
+use near_sdk::{ext_contract, is_promise_success, Gas, PromiseOrValue};
+
+const GAS_FOR_FT_TRANSFER: Gas = Gas::from_tgas(10);
+const GAS_FOR_FT_CALLBACK: Gas = Gas::from_tgas(10);
+
+// Terms carried in the ft_transfer_call msg that funds a token escrow
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TokenEscrowMsg {
+    pub escrow_id: String,
+    pub beneficiary: AccountId,
+    pub release_time: u64,
+    pub arbiter: Option<AccountId>,
+    pub metadata: EscrowMetadata,
+    pub arbiter_fee: Option<ArbiterFee>,
+    pub required_proofs: Option<Vec<String>>,
+}
+
+// Only the generated ext_ft module is used
+#[allow(dead_code)]
+#[ext_contract(ext_ft)]
+trait FungibleToken {
+    fn ft_transfer(&mut self, receiver_id: AccountId, amount: U128, memo: Option<String>);
+}
+
+#[near_bindgen]
+impl EscrowContract {
+    // NEP-141 receiver - the calling token contract funds a new escrow described by `msg`.
+    // Panicking here makes the token contract refund the sender.
+    pub fn ft_on_transfer(&mut self, sender_id: AccountId, amount: U128, msg: String) -> PromiseOrValue<U128> {
+        let token = env::predecessor_account_id();
+        let terms: TokenEscrowMsg = near_sdk::serde_json::from_str(&msg).or_fail(ErrorCode::InvalidArgument, "Invalid escrow message");
+        
+        ensure!(amount.0 > 0, InvalidArgument, "Must transfer tokens");
+        
+        self.new_escrow(
+            terms.escrow_id,
+            sender_id,
+            amount.0,
+            Some(token),
+            terms.beneficiary,
+            terms.release_time,
+            terms.arbiter,
+            terms.metadata,
+            terms.arbiter_fee,
+            terms.required_proofs.unwrap_or_default(),
+        );
+        
+        PromiseOrValue::Value(U128(0))
+    }
+
+    // Retry a token payout that failed (e.g. the receiver wasn't registered with the token)
+    pub fn claim_token_payout(&mut self, token: AccountId) -> Promise {
+        let receiver = env::predecessor_account_id();
+        let key = Self::token_payout_key(&receiver, &token);
+        let amount_yocto = self.failed_token_payouts.get(&key).or_fail(ErrorCode::InvalidState, "No failed payout");
+        
+        self.failed_token_payouts.remove(&key);
+        
+        env::log_str(&format!(
+            "Token payout retried: {} | Token: {} | Amount: {}",
+            receiver, token, amount_yocto
+        ));
+        
+        self.token_transfer(token, receiver, amount_yocto)
+    }
+
+    pub fn get_failed_token_payout(&self, account_id: AccountId, token: AccountId) -> U128 {
+        U128(self.failed_token_payouts.get(&Self::token_payout_key(&account_id, &token)).unwrap_or(0))
+    }
+
+    // Callback for token payouts - failed transfers stay claimable via claim_token_payout
+    #[private]
+    pub fn on_token_payout(&mut self, token: AccountId, receiver: AccountId, amount: U128) -> bool {
+        if is_promise_success() {
+            return true;
+        }
+        
+        let key = Self::token_payout_key(&receiver, &token);
+        let balance = self.failed_token_payouts.get(&key).unwrap_or(0);
+        self.failed_token_payouts.insert(&key, &(balance + amount.0));
+        
+        env::log_str(&format!(
+            "Token payout failed: {} | Token: {} | Amount: {}",
+            receiver, token, amount.0
+        ));
+        
+        false
+    }
+
+    // Send escrowed value in the escrow's asset - native NEAR or its NEP-141 token
+    pub(crate) fn payout(&mut self, escrow: &Escrow, receiver: AccountId, amount_yocto: u128) -> Promise {
+        escrow.assert_liquid();
+        self.track_outflow(escrow, amount_yocto);
+        self.transfer_out(escrow, receiver, amount_yocto)
+    }
+
+    // Raw transfer in the escrow's asset, for value that was never part of the escrowed amount
+    pub(crate) fn transfer_out(&mut self, escrow: &Escrow, receiver: AccountId, amount_yocto: u128) -> Promise {
+        match escrow.token.as_ref() {
+            Some(token) => self.token_transfer(token.parse().or_fail(ErrorCode::Internal, "Invalid token"), receiver, amount_yocto),
+            None => self.settle(receiver, amount_yocto),
+        }
+    }
+
+    pub(crate) fn token_transfer(&self, token: AccountId, receiver: AccountId, amount: u128) -> Promise {
+        ext_ft::ext(token.clone())
+            .with_attached_deposit(NearToken::from_yoctonear(1))
+            .with_static_gas(GAS_FOR_FT_TRANSFER)
+            .ft_transfer(receiver.clone(), U128(amount), None)
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_FT_CALLBACK)
+                    .on_token_payout(token, receiver, U128(amount)),
+            )
+    }
+
+    fn token_payout_key(receiver: &AccountId, token: &AccountId) -> String {
+        format!("{}:{}", receiver, token)
+    }
+}
@@ -0,0 +1,157 @@
+use crate::*;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct VestingSchedule {
+    pub start: u64,
+    pub cliff: u64, // Nothing vests before this timestamp
+    pub end: u64,   // Fully vested at this timestamp
+    pub revocable: bool,
+    pub revoked_at: Option<u64>,
+}
+
+impl VestingSchedule {
+    // Linear accrual of `total_yocto` between start and end, gated by the cliff
+    fn vested_amount(&self, total_yocto: u128) -> u128 {
+        let now = env::block_timestamp();
+        if now < self.cliff {
+            return 0;
+        }
+        if now >= self.end {
+            return total_yocto;
+        }
+        let elapsed = (now - self.start) as u128;
+        let duration = (self.end - self.start) as u128;
+        // Split the multiplication so large totals cannot overflow
+        total_yocto / duration * elapsed + total_yocto % duration * elapsed / duration
+    }
+}
+
+#[near_bindgen]
+impl EscrowContract {
+    // Vesting escrow - the deposit accrues linearly to the beneficiary from now until `end`
+    #[payable]
+    pub fn create_vesting_escrow(
+        &mut self,
+        escrow_id: String,
+        beneficiary: AccountId,
+        cliff: u64,
+        end: u64,
+        revocable: bool,
+        arbiter: Option<AccountId>,
+        metadata: EscrowMetadata,
+        arbiter_fee: Option<ArbiterFee>,
+    ) -> Escrow {
+        let start = env::block_timestamp();
+        ensure!(start <= cliff && cliff <= end, InvalidArgument, "Cliff must fall between now and end");
+        
+        let mut escrow = self.create_escrow(
+            escrow_id.clone(),
+            beneficiary,
+            end,
+            arbiter,
+            metadata,
+            arbiter_fee,
+            None,
+        );
+        escrow.vesting = Some(VestingSchedule {
+            start,
+            cliff,
+            end,
+            revocable,
+            revoked_at: None,
+        });
+        self.escrows.insert(&escrow_id, &escrow);
+        
+        env::log_str(&format!(
+            "Vesting escrow created: {} | Cliff: {} | End: {}",
+            escrow_id, cliff, end
+        ));
+        
+        escrow
+    }
+
+    // Beneficiary collects whatever has vested beyond previous claims
+    pub fn claim_vested(&mut self, escrow_id: String) -> Promise {
+        let mut escrow = self.escrows.get(&escrow_id).or_fail(ErrorCode::NotFound, "Escrow not found");
+        
+        let beneficiary: AccountId = escrow.beneficiary.parse().or_fail(ErrorCode::Internal, "Invalid beneficiary");
+        ensure!(env::predecessor_account_id() == beneficiary, Unauthorized, "Only beneficiary can claim");
+        ensure!(
+            matches!(escrow.status, EscrowStatus::Active),
+            InvalidState,
+            "Escrow not active"
+        );
+        
+        let vesting = escrow.vesting.as_ref().or_fail(ErrorCode::InvalidState, "Not a vesting escrow");
+        let total_yocto: u128 = escrow.amount.0;
+        let vested_yocto = vesting.vested_amount(total_yocto);
+        let released_yocto: u128 = escrow.released.0;
+        let amount_yocto = vested_yocto - released_yocto;
+        ensure!(amount_yocto > 0, InvalidState, "Nothing vested to claim");
+        
+        escrow.released = U128(vested_yocto);
+        if vested_yocto == total_yocto {
+            self.settle_escrow(&mut escrow, EscrowStatus::Completed);
+        }
+        self.escrows.insert(&escrow_id, &escrow);
+        
+        env::log_str(&format!("Vested claimed: {} | Amount: {}", escrow_id, amount_yocto));
+        emit_event("released", json!({
+            "escrow_id": escrow_id,
+            "beneficiary": escrow.beneficiary,
+            "amount": amount_yocto.to_string(),
+        }));
+        
+        self.release_payout(&escrow, amount_yocto)
+    }
+
+    // Depositor takes back the unvested remainder of a revocable escrow
+    pub fn revoke_vesting(&mut self, escrow_id: String) -> Promise {
+        let mut escrow = self.escrows.get(&escrow_id).or_fail(ErrorCode::NotFound, "Escrow not found");
+        
+        let depositor: AccountId = escrow.depositor.parse().or_fail(ErrorCode::Internal, "Invalid depositor");
+        ensure!(env::predecessor_account_id() == depositor, Unauthorized, "Only depositor can revoke");
+        ensure!(
+            matches!(escrow.status, EscrowStatus::Active),
+            InvalidState,
+            "Escrow not active"
+        );
+        
+        let total_yocto: u128 = escrow.amount.0;
+        let released_yocto: u128 = escrow.released.0;
+        let vesting = escrow.vesting.as_mut().or_fail(ErrorCode::InvalidState, "Not a vesting escrow");
+        ensure!(vesting.revocable, InvalidState, "Vesting is not revocable");
+        let vested_yocto = vesting.vested_amount(total_yocto);
+        let refund_yocto = total_yocto - vested_yocto;
+        ensure!(refund_yocto > 0, InvalidState, "Nothing left to revoke");
+        
+        // Freeze the schedule: what has vested stays claimable, the rest leaves the escrow
+        let now = env::block_timestamp();
+        vesting.revoked_at = Some(now);
+        vesting.cliff = vesting.cliff.min(now);
+        vesting.end = now;
+        escrow.release_time = now;
+        escrow.amount = U128(vested_yocto);
+        if vested_yocto == released_yocto {
+            self.settle_escrow(&mut escrow, EscrowStatus::Completed);
+        }
+        self.escrows.insert(&escrow_id, &escrow);
+        
+        env::log_str(&format!("Vesting revoked: {} | Refund: {}", escrow_id, refund_yocto));
+        emit_event("refunded", json!({
+            "escrow_id": escrow_id,
+            "depositor": escrow.depositor,
+            "amount": refund_yocto.to_string(),
+        }));
+        
+        self.payout(&escrow, depositor, refund_yocto)
+    }
+
+    pub fn get_vested_amount(&self, escrow_id: String) -> U128 {
+        let escrow = self.escrows.get(&escrow_id).or_fail(ErrorCode::NotFound, "Escrow not found");
+        let vesting = escrow.vesting.as_ref().or_fail(ErrorCode::InvalidState, "Not a vesting escrow");
+        let total_yocto: u128 = escrow.amount.0;
+        U128(vesting.vested_amount(total_yocto))
+    }
+}
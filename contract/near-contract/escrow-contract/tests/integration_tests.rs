@@ -1,2 +0,0 @@
-// Integration tests for escrow contract
-// Placeholder - tests will be added later
\ No newline at end of file
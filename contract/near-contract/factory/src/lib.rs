@@ -0,0 +1,406 @@
+use ciphra_types::{assert_owner, decimal_borsh, ensure, ErrorCode, OrFail, Paged};
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::collections::{LookupMap, UnorderedMap};
+use near_sdk::json_types::{Base64VecU8, U128};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::serde_json::json;
+use near_sdk::{env, is_promise_success, near_bindgen, AccountId, BorshStorageKey, Gas, NearToken, PanicOnDefault, Promise};
+use schemars::JsonSchema;
+
+const GAS_FOR_INIT: Gas = Gas::from_tgas(50);
+const GAS_FOR_DEPLOY_CALLBACK: Gas = Gas::from_tgas(15);
+
+// Deposit a tenant attaches to fund the new account's code and state storage
+const MIN_DEPLOYMENT_DEPOSIT: NearToken = NearToken::from_near(10);
+
+// Longest accepted sub-account prefix
+const MAX_NAME_LENGTH: usize = 32;
+
+#[derive(BorshSerialize, BorshStorageKey)]
+pub enum StorageKey {
+    Codes,
+    CodeHashes,
+    Deployments,
+    DeploymentsByTenant,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, JsonSchema, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum ContractKind {
+    Swap,
+    P2PTransfer,
+    Escrow,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub enum DeploymentStatus {
+    Pending,
+    Active,
+}
+
+// Init parameters chosen by the tenant; `oracle_account` is required for swap deployments
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DeploymentParams {
+    pub owner: AccountId,
+    pub oracle_account: Option<AccountId>,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Deployment {
+    pub account_id: String,
+    pub kind: ContractKind,
+    pub tenant: String,
+    pub owner: String,
+    pub code_hash: String,
+    #[borsh(serialize_with = "decimal_borsh::serialize", deserialize_with = "decimal_borsh::deserialize")]
+    #[schemars(with = "String")]
+    pub deposit: U128,
+    pub status: DeploymentStatus,
+    pub deployed_at: u64,
+}
+
+#[near_bindgen]
+#[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
+pub struct FactoryContract {
+    pub owner: AccountId,
+    pub codes: LookupMap<ContractKind, Vec<u8>>,
+    pub code_hashes: LookupMap<ContractKind, String>,
+    pub deployments: UnorderedMap<String, Deployment>,
+    pub deployments_by_tenant: LookupMap<AccountId, Vec<String>>,
+}
+
+#[near_bindgen]
+impl FactoryContract {
+    #[init]
+    pub fn new(owner: AccountId) -> Self {
+        Self {
+            owner,
+            codes: LookupMap::new(StorageKey::Codes),
+            code_hashes: LookupMap::new(StorageKey::CodeHashes),
+            deployments: UnorderedMap::new(StorageKey::Deployments),
+            deployments_by_tenant: LookupMap::new(StorageKey::DeploymentsByTenant),
+        }
+    }
+
+    // Owner uploads the wasm new deployments of `kind` are created from; the attached
+    // deposit must cover the storage it takes, any excess is returned
+    #[payable]
+    pub fn set_code(&mut self, kind: ContractKind, code: Base64VecU8) -> String {
+        assert_owner(&self.owner);
+        ensure!(!code.0.is_empty(), InvalidArgument, "Code required");
+        
+        let storage_before = env::storage_usage();
+        let code_hash = hex::encode(env::sha256(&code.0));
+        self.codes.insert(&kind, &code.0);
+        self.code_hashes.insert(&kind, &code_hash);
+        self.refund_storage_excess(storage_before);
+        
+        env::log_str(&format!(
+            "Code set: {:?} | Hash: {} | Bytes: {}",
+            kind, code_hash, code.0.len()
+        ));
+        
+        code_hash
+    }
+
+    // Creates `<name>.<factory>`, deploys the current code of `kind` there and initialises it
+    // with the tenant's parameters. The new account has no access keys, so its code can only
+    // change through the contract's own upgrade path.
+    #[payable]
+    pub fn deploy(&mut self, kind: ContractKind, name: String, params: DeploymentParams) -> Promise {
+        let tenant = env::predecessor_account_id();
+        let deposit = env::attached_deposit();
+        ensure!(
+            deposit >= MIN_DEPLOYMENT_DEPOSIT,
+            InsufficientFunds,
+            "Attach at least 10 NEAR to fund the deployment"
+        );
+        ensure!(
+            !name.is_empty()
+                && name.len() <= MAX_NAME_LENGTH
+                && name.bytes().all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-' || b == b'_'),
+            InvalidArgument,
+            "Name must be 1-32 lowercase letters, digits, '-' or '_'"
+        );
+        let account_id: AccountId = format!("{}.{}", name, env::current_account_id())
+            .parse()
+            .or_fail(ErrorCode::InvalidArgument, "Invalid deployment account");
+        ensure!(
+            self.deployments.get(&account_id.to_string()).is_none(),
+            AlreadyExists,
+            "Deployment already exists"
+        );
+        
+        let code = self.codes.get(&kind).or_fail(ErrorCode::NotFound, "No code uploaded for this contract kind");
+        let code_hash =
+            self.code_hashes.get(&kind).or_fail(ErrorCode::NotFound, "No code uploaded for this contract kind");
+        let init_args = match kind {
+            ContractKind::Swap => json!({
+                "owner": params.owner,
+                "oracle_account": params
+                    .oracle_account
+                    .clone()
+                    .or_fail(ErrorCode::InvalidArgument, "Swap deployments need an oracle account"),
+            }),
+            ContractKind::P2PTransfer | ContractKind::Escrow => json!({ "owner": params.owner }),
+        };
+        
+        let deployment = Deployment {
+            account_id: account_id.to_string(),
+            kind,
+            tenant: tenant.to_string(),
+            owner: params.owner.to_string(),
+            code_hash,
+            deposit: U128(deposit.as_yoctonear()),
+            status: DeploymentStatus::Pending,
+            deployed_at: env::block_timestamp(),
+        };
+        self.deployments.insert(&deployment.account_id, &deployment);
+        self.add_tenant_deployment(&tenant, &deployment.account_id);
+        
+        env::log_str(&format!(
+            "Deployment started: {} | Kind: {:?} | Tenant: {} | Owner: {}",
+            account_id, kind, tenant, params.owner
+        ));
+        
+        Promise::new(account_id.clone())
+            .create_account()
+            .transfer(deposit)
+            .deploy_contract(code)
+            .function_call("new".to_string(), init_args.to_string().into_bytes(), NearToken::from_yoctonear(0), GAS_FOR_INIT)
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_DEPLOY_CALLBACK)
+                    .on_deployed(account_id),
+            )
+    }
+
+    // Callback - a failed batch returns its deposit to the factory, which forwards it to the tenant
+    #[private]
+    pub fn on_deployed(&mut self, account_id: AccountId) -> bool {
+        let mut deployment =
+            self.deployments.get(&account_id.to_string()).or_fail(ErrorCode::NotFound, "Deployment not found");
+        let tenant: AccountId = deployment.tenant.parse().or_fail(ErrorCode::Internal, "Invalid tenant");
+        
+        if is_promise_success() {
+            deployment.status = DeploymentStatus::Active;
+            self.deployments.insert(&deployment.account_id, &deployment);
+            env::log_str(&format!("Deployment active: {} | Kind: {:?}", account_id, deployment.kind));
+            return true;
+        }
+        
+        self.deployments.remove(&deployment.account_id);
+        self.remove_tenant_deployment(&tenant, &deployment.account_id);
+        let deposit_yocto: u128 = deployment.deposit.0;
+        Promise::new(tenant).transfer(NearToken::from_yoctonear(deposit_yocto));
+        
+        env::log_str(&format!("Deployment failed: {} | Refund: {}", account_id, deposit_yocto));
+        
+        false
+    }
+
+    pub fn get_code_hash(&self, kind: ContractKind) -> Option<String> {
+        self.code_hashes.get(&kind)
+    }
+
+    pub fn get_deployment(&self, account_id: AccountId) -> Option<Deployment> {
+        self.deployments.get(&account_id.to_string())
+    }
+
+    pub fn get_deployments(&self, from_index: Option<u64>, limit: Option<u64>) -> Paged<Deployment> {
+        let deployments = self.deployments.values_as_vector();
+        Paged::from_range(deployments.len(), from_index, limit, |index| deployments.get(index))
+    }
+
+    pub fn get_deployments_by_tenant(
+        &self,
+        tenant: AccountId,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Paged<Deployment> {
+        let account_ids = self.deployments_by_tenant.get(&tenant).unwrap_or_default();
+        Paged::from_ids(&account_ids, from_index, limit, |account_id| self.deployments.get(account_id))
+    }
+
+    pub fn get_min_deployment_deposit(&self) -> U128 {
+        U128(MIN_DEPLOYMENT_DEPOSIT.as_yoctonear())
+    }
+
+    fn add_tenant_deployment(&mut self, tenant: &AccountId, account_id: &str) {
+        let mut deployments = self.deployments_by_tenant.get(tenant).unwrap_or_default();
+        deployments.push(account_id.to_string());
+        self.deployments_by_tenant.insert(tenant, &deployments);
+    }
+
+    fn remove_tenant_deployment(&mut self, tenant: &AccountId, account_id: &str) {
+        let mut deployments = self.deployments_by_tenant.get(tenant).unwrap_or_default();
+        deployments.retain(|a| a != account_id);
+        self.deployments_by_tenant.insert(tenant, &deployments);
+    }
+
+    fn refund_storage_excess(&self, storage_before: u64) {
+        let storage_used = env::storage_usage().saturating_sub(storage_before);
+        let cost = env::storage_byte_cost().saturating_mul(storage_used as u128);
+        let deposit = env::attached_deposit();
+        ensure!(deposit >= cost, InsufficientFunds, "Attach at least {} to cover code storage", cost);
+        
+        let excess = deposit.saturating_sub(cost);
+        if !excess.is_zero() {
+            Promise::new(env::predecessor_account_id()).transfer(excess);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ciphra_testing::{self as host, Action};
+
+    const OWNER: &str = "owner.near";
+    const TENANT: &str = "tenant.near";
+
+    fn account(name: &str) -> AccountId {
+        name.parse().unwrap()
+    }
+
+    fn contract() -> FactoryContract {
+        host::set_caller(OWNER);
+        FactoryContract::new(account(OWNER))
+    }
+
+    // Factory with escrow code uploaded
+    fn contract_with_code() -> FactoryContract {
+        let mut contract = contract();
+        host::set_deposit(host::NEAR);
+        contract.set_code(ContractKind::Escrow, Base64VecU8(b"escrow wasm".to_vec()));
+        host::clear_receipts();
+        contract
+    }
+
+    fn params() -> DeploymentParams {
+        DeploymentParams { owner: account(TENANT), oracle_account: None }
+    }
+
+    fn deploy(contract: &mut FactoryContract, kind: ContractKind, name: &str, params: DeploymentParams) {
+        host::set_caller(TENANT);
+        host::set_deposit(MIN_DEPLOYMENT_DEPOSIT.as_yoctonear());
+        let _ = contract.deploy(kind, name.to_string(), params);
+    }
+
+    #[test]
+    fn test_code_upload_refunds_the_excess_deposit() {
+        let mut contract = contract();
+        host::set_deposit(host::NEAR);
+        let code_hash = contract.set_code(ContractKind::Escrow, Base64VecU8(b"escrow wasm".to_vec()));
+
+        assert_eq!(code_hash, hex::encode(env::sha256(b"escrow wasm")));
+        assert_eq!(contract.get_code_hash(ContractKind::Escrow), Some(code_hash));
+        let refunds = host::transfers();
+        assert_eq!(refunds.len(), 1);
+        assert_eq!(refunds[0].0, OWNER);
+        assert!(refunds[0].1 < host::NEAR);
+    }
+
+    #[test]
+    #[should_panic(expected = r#""code":"Unauthorized""#)]
+    fn test_only_the_owner_uploads_code() {
+        let mut contract = contract();
+        host::set_caller(TENANT);
+        host::set_deposit(host::NEAR);
+        contract.set_code(ContractKind::Escrow, Base64VecU8(b"escrow wasm".to_vec()));
+    }
+
+    #[test]
+    fn test_deploy_creates_and_initialises_the_sub_account() {
+        let mut contract = contract_with_code();
+        deploy(&mut contract, ContractKind::Escrow, "acme", params());
+
+        let receipt = &host::receipts()[0];
+        assert_eq!(receipt.receiver, "acme.contract.near");
+        assert_eq!(receipt.actions[0], Action::Other("create_account"));
+        assert_eq!(receipt.actions[1], Action::Transfer(MIN_DEPLOYMENT_DEPOSIT.as_yoctonear()));
+        assert_eq!(receipt.actions[2], Action::Other("deploy_contract"));
+        assert_eq!(
+            receipt.actions[3],
+            Action::FunctionCall {
+                method: "new".to_string(),
+                args: json!({ "owner": TENANT }).to_string().into_bytes(),
+                deposit: 0,
+            }
+        );
+        let deployment = contract.get_deployment(account("acme.contract.near")).unwrap();
+        assert!(matches!(deployment.status, DeploymentStatus::Pending));
+        assert_eq!(contract.get_deployments_by_tenant(account(TENANT), None, None).total, 1);
+    }
+
+    #[test]
+    fn test_deployment_activates_on_success() {
+        let mut contract = contract_with_code();
+        deploy(&mut contract, ContractKind::Escrow, "acme", params());
+
+        host::set_self_caller();
+        host::set_promise_results(vec![Some(Vec::new())]);
+        assert!(contract.on_deployed(account("acme.contract.near")));
+
+        let deployment = contract.get_deployment(account("acme.contract.near")).unwrap();
+        assert!(matches!(deployment.status, DeploymentStatus::Active));
+    }
+
+    #[test]
+    fn test_failed_deployment_refunds_the_tenant() {
+        let mut contract = contract_with_code();
+        deploy(&mut contract, ContractKind::Escrow, "acme", params());
+        host::clear_receipts();
+
+        host::set_self_caller();
+        host::set_promise_results(vec![None]);
+        assert!(!contract.on_deployed(account("acme.contract.near")));
+
+        assert!(contract.get_deployment(account("acme.contract.near")).is_none());
+        assert_eq!(contract.get_deployments_by_tenant(account(TENANT), None, None).total, 0);
+        assert_eq!(host::transfers(), vec![(TENANT.to_string(), MIN_DEPLOYMENT_DEPOSIT.as_yoctonear())]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Attach at least 10 NEAR to fund the deployment")]
+    fn test_deploy_requires_the_minimum_deposit() {
+        let mut contract = contract_with_code();
+        host::set_caller(TENANT);
+        host::set_deposit(host::NEAR);
+        let _ = contract.deploy(ContractKind::Escrow, "acme".to_string(), params());
+    }
+
+    #[test]
+    #[should_panic(expected = "Name must be 1-32 lowercase letters, digits, '-' or '_'")]
+    fn test_deploy_rejects_invalid_names() {
+        let mut contract = contract_with_code();
+        deploy(&mut contract, ContractKind::Escrow, "Acme.sub", params());
+    }
+
+    #[test]
+    #[should_panic(expected = "No code uploaded for this contract kind")]
+    fn test_deploy_requires_uploaded_code() {
+        let mut contract = contract_with_code();
+        deploy(&mut contract, ContractKind::P2PTransfer, "acme", params());
+    }
+
+    #[test]
+    #[should_panic(expected = "Swap deployments need an oracle account")]
+    fn test_swap_deployments_need_an_oracle() {
+        let mut contract = contract();
+        host::set_deposit(host::NEAR);
+        contract.set_code(ContractKind::Swap, Base64VecU8(b"swap wasm".to_vec()));
+        deploy(&mut contract, ContractKind::Swap, "acme", params());
+    }
+
+    #[test]
+    #[should_panic(expected = r#""code":"AlreadyExists","message":"Deployment already exists""#)]
+    fn test_deployment_names_are_unique() {
+        let mut contract = contract_with_code();
+        deploy(&mut contract, ContractKind::Escrow, "acme", params());
+        deploy(&mut contract, ContractKind::Escrow, "acme", params());
+    }
+}
@@ -0,0 +1,155 @@
+// Random call sequences against the escrow contract in a mocked env
+#![no_main]
+
+use arbitrary::Arbitrary;
+use ciphra_fuzz::*;
+use escrow_contract::{ArbiterFee, EscrowContract, EscrowMetadata, ProofData};
+use libfuzzer_sys::fuzz_target;
+
+#[derive(Arbitrary, Debug)]
+enum EscrowCall {
+    Create {
+        caller: u8,
+        escrow_id: FuzzId,
+        beneficiary: u8,
+        release_time: u64,
+        arbiter: Option<u8>,
+        title: FuzzText,
+        category: FuzzText,
+        content_hash: Option<FuzzHex>,
+        external_uri: Option<FuzzText>,
+        arbiter_fee: Option<(bool, u16, FuzzAmount)>,
+        required_proofs: Option<Vec<FuzzText>>,
+        amount: FuzzAmount,
+    },
+    TopUp { caller: u8, escrow_id: FuzzId, amount: FuzzAmount },
+    Release { caller: u8, escrow_id: FuzzId },
+    Refund { caller: u8, escrow_id: FuzzId },
+    Dispute { caller: u8, escrow_id: FuzzId },
+    Evidence { caller: u8, escrow_id: FuzzId, content_hash: FuzzHex, uri: FuzzText },
+    Resolve { caller: u8, escrow_id: FuzzId, depositor_bps: u16, beneficiary_bps: u16 },
+    Proof {
+        caller: u8,
+        escrow_id: FuzzId,
+        proof_id: FuzzText,
+        chain_id: FuzzText,
+        tx_hash: FuzzHex,
+        block_number: u64,
+        receipt_data: FuzzText,
+        proof_nodes: Vec<FuzzHex>,
+        receipt_index: u64,
+        log_index: u32,
+    },
+    Cleanup { caller: u8, escrow_ids: Vec<FuzzId> },
+    Advance { seconds: u32 },
+}
+
+fuzz_target!(|calls: Vec<EscrowCall>| {
+    let mut chain = Chain::new();
+    let mut contract = EscrowContract::new(owner());
+    for index in 0..ACCOUNTS.len() as u8 {
+        chain.call(&mut contract, &account(index), STORAGE_PREPAID, |c| c.storage_deposit(None, None));
+    }
+
+    for call in calls {
+        match call {
+            EscrowCall::Create {
+                caller,
+                escrow_id,
+                beneficiary,
+                release_time,
+                arbiter,
+                title,
+                category,
+                content_hash,
+                external_uri,
+                arbiter_fee,
+                required_proofs,
+                amount,
+            } => {
+                let metadata = EscrowMetadata {
+                    title: title.resolve(),
+                    category: category.resolve(),
+                    content_hash: content_hash.map(|hash| hash.resolve()),
+                    external_uri: external_uri.map(|uri| uri.resolve()),
+                };
+                let arbiter_fee = arbiter_fee.map(|(flat, bps, amount)| match flat {
+                    true => ArbiterFee::Flat(amount.yocto().into()),
+                    false => ArbiterFee::Bps(bps),
+                });
+                let required_proofs: Option<Vec<String>> =
+                    required_proofs.map(|ids| ids.iter().map(FuzzText::resolve).collect());
+                chain.call(&mut contract, &account(caller), amount.deposit(), |c| {
+                    c.create_escrow(
+                        escrow_id.resolve(),
+                        account(beneficiary),
+                        release_time,
+                        arbiter.map(account),
+                        metadata,
+                        arbiter_fee,
+                        required_proofs,
+                    )
+                });
+            }
+            EscrowCall::TopUp { caller, escrow_id, amount } => {
+                chain.call(&mut contract, &account(caller), amount.deposit(), |c| c.top_up(escrow_id.resolve()));
+            }
+            EscrowCall::Release { caller, escrow_id } => {
+                chain.call(&mut contract, &account(caller), 0, |c| c.release_funds(escrow_id.resolve()));
+            }
+            EscrowCall::Refund { caller, escrow_id } => {
+                chain.call(&mut contract, &account(caller), 0, |c| c.refund_escrow(escrow_id.resolve()));
+            }
+            EscrowCall::Dispute { caller, escrow_id } => {
+                chain.call(&mut contract, &account(caller), 0, |c| c.raise_dispute(escrow_id.resolve()));
+            }
+            EscrowCall::Evidence { caller, escrow_id, content_hash, uri } => {
+                chain.call(&mut contract, &account(caller), 0, |c| {
+                    c.submit_evidence(escrow_id.resolve(), content_hash.resolve(), uri.resolve())
+                });
+            }
+            EscrowCall::Resolve { caller, escrow_id, depositor_bps, beneficiary_bps } => {
+                chain.call(&mut contract, &account(caller), 0, |c| {
+                    c.resolve_dispute(escrow_id.resolve(), depositor_bps, beneficiary_bps)
+                });
+            }
+            EscrowCall::Proof {
+                caller,
+                escrow_id,
+                proof_id,
+                chain_id,
+                tx_hash,
+                block_number,
+                receipt_data,
+                proof_nodes,
+                receipt_index,
+                log_index,
+            } => {
+                let proof_data = ProofData {
+                    block_hash: tx_hash.resolve(),
+                    block_header: receipt_data.resolve(),
+                    receipts_root: tx_hash.resolve(),
+                    receipt_index,
+                    receipt_data: receipt_data.resolve(),
+                    proof_nodes: proof_nodes.iter().map(FuzzHex::resolve).collect(),
+                    log_index,
+                };
+                chain.call(&mut contract, &account(caller), 0, |c| {
+                    c.submit_cross_chain_proof(
+                        escrow_id.resolve(),
+                        proof_id.resolve(),
+                        chain_id.resolve(),
+                        tx_hash.resolve(),
+                        block_number,
+                        proof_data,
+                    )
+                });
+            }
+            EscrowCall::Cleanup { caller, escrow_ids } => {
+                let escrow_ids: Vec<String> = escrow_ids.iter().map(FuzzId::resolve).collect();
+                chain.call(&mut contract, &account(caller), 0, |c| c.cleanup_escrows(escrow_ids));
+            }
+            EscrowCall::Advance { seconds } => chain.advance(seconds),
+        }
+    }
+});
@@ -0,0 +1,139 @@
+// Random call sequences against the p2p transfer contract in a mocked env
+#![no_main]
+
+use arbitrary::Arbitrary;
+use ciphra_fuzz::*;
+use libfuzzer_sys::fuzz_target;
+use p2p_transfer::P2PTransferContract;
+
+#[derive(Arbitrary, Debug)]
+enum P2PCall {
+    SendDirect {
+        caller: u8,
+        transfer_id: FuzzId,
+        recipient: FuzzText,
+        memo: FuzzText,
+        hold: Option<bool>,
+        encrypted_memo: Option<FuzzText>,
+        reference: Option<FuzzText>,
+        amount: FuzzAmount,
+    },
+    Accept { caller: u8, transfer_id: FuzzId },
+    Cancel { caller: u8, transfer_id: FuzzId },
+    Deposit { caller: u8, amount: FuzzAmount },
+    InternalTransfer { caller: u8, transfer_id: FuzzId, recipient: u8, amount: FuzzAmount, memo: FuzzText },
+    Withdraw { caller: u8, amount: FuzzAmount },
+    ShieldDeposit { caller: u8, note_id: FuzzId, commitment: FuzzHex, amount: FuzzAmount },
+    ShieldTransfer {
+        caller: u8,
+        transfer_id: FuzzId,
+        note_id: FuzzId,
+        nullifier: FuzzHex,
+        new_commitment: FuzzHex,
+        recipient_commitment: FuzzHex,
+        root: Option<FuzzHex>,
+        proof: FuzzText,
+        memo: FuzzText,
+    },
+    ShieldWithdraw {
+        caller: u8,
+        transfer_id: FuzzId,
+        note_id: FuzzId,
+        nullifier: FuzzHex,
+        recipient: u8,
+        root: Option<FuzzHex>,
+        proof: FuzzText,
+    },
+    Advance { seconds: u32 },
+}
+
+fuzz_target!(|calls: Vec<P2PCall>| {
+    let mut chain = Chain::new();
+    let mut contract = P2PTransferContract::new(owner());
+    for index in 0..ACCOUNTS.len() as u8 {
+        chain.call(&mut contract, &account(index), STORAGE_PREPAID, |c| c.storage_deposit(None, None));
+    }
+
+    for call in calls {
+        // Spends mostly reference the live root so they get past the root check
+        let current_root = contract.get_current_root();
+        match call {
+            P2PCall::SendDirect { caller, transfer_id, recipient, memo, hold, encrypted_memo, reference, amount } => {
+                chain.call(&mut contract, &account(caller), amount.deposit(), |c| {
+                    c.send_direct(
+                        transfer_id.resolve(),
+                        recipient.resolve(),
+                        memo.resolve(),
+                        hold,
+                        encrypted_memo.map(|memo| memo.resolve()),
+                        reference.map(|reference| reference.resolve()),
+                    )
+                });
+            }
+            P2PCall::Accept { caller, transfer_id } => {
+                chain.call(&mut contract, &account(caller), 0, |c| c.accept_transfer(transfer_id.resolve()));
+            }
+            P2PCall::Cancel { caller, transfer_id } => {
+                chain.call(&mut contract, &account(caller), 0, |c| c.cancel_transfer(transfer_id.resolve()));
+            }
+            P2PCall::Deposit { caller, amount } => {
+                chain.call(&mut contract, &account(caller), amount.deposit(), |c| c.deposit_balance());
+            }
+            P2PCall::InternalTransfer { caller, transfer_id, recipient, amount, memo } => {
+                chain.call(&mut contract, &account(caller), 0, |c| {
+                    c.internal_transfer(
+                        transfer_id.resolve(),
+                        account(recipient).to_string(),
+                        amount.yocto().into(),
+                        memo.resolve(),
+                    )
+                });
+            }
+            P2PCall::Withdraw { caller, amount } => {
+                chain.call(&mut contract, &account(caller), 0, |c| c.withdraw_balance(amount.yocto().into()));
+            }
+            P2PCall::ShieldDeposit { caller, note_id, commitment, amount } => {
+                chain.call(&mut contract, &account(caller), amount.deposit(), |c| {
+                    c.shield_deposit(note_id.resolve(), commitment.resolve())
+                });
+            }
+            P2PCall::ShieldTransfer {
+                caller,
+                transfer_id,
+                note_id,
+                nullifier,
+                new_commitment,
+                recipient_commitment,
+                root,
+                proof,
+                memo,
+            } => {
+                chain.call(&mut contract, &account(caller), 0, |c| {
+                    c.shield_transfer(
+                        transfer_id.resolve(),
+                        note_id.resolve(),
+                        nullifier.resolve(),
+                        new_commitment.resolve(),
+                        recipient_commitment.resolve(),
+                        root.map_or(current_root, |root| root.resolve()),
+                        proof.resolve(),
+                        memo.resolve(),
+                    )
+                });
+            }
+            P2PCall::ShieldWithdraw { caller, transfer_id, note_id, nullifier, recipient, root, proof } => {
+                chain.call(&mut contract, &account(caller), 0, |c| {
+                    c.shield_withdraw(
+                        transfer_id.resolve(),
+                        note_id.resolve(),
+                        nullifier.resolve(),
+                        account(recipient),
+                        root.map_or(current_root, |root| root.resolve()),
+                        proof.resolve(),
+                    )
+                });
+            }
+            P2PCall::Advance { seconds } => chain.advance(seconds),
+        }
+    }
+});
@@ -0,0 +1,33 @@
+// Arbitrary bytes through the stored record layouts and JSON argument types. Decoding may
+// reject input but must never panic; amounts also go through the display helpers.
+#![no_main]
+
+use ciphra_types::{format_amount, format_near, EscrowMetadata, HashAlgorithm, ProofData};
+use escrow_contract::Escrow;
+use libfuzzer_sys::fuzz_target;
+use near_sdk::borsh::BorshDeserialize;
+use near_sdk::json_types::U128;
+use p2p_transfer::{ShieldedNote, SpendingLimit, Transfer};
+use swap_contract::AtomicSwap;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = AtomicSwap::try_from_slice(data);
+    let _ = Escrow::try_from_slice(data);
+    let _ = Transfer::try_from_slice(data);
+    let _ = ShieldedNote::try_from_slice(data);
+    let _ = SpendingLimit::try_from_slice(data);
+
+    let _ = near_sdk::serde_json::from_slice::<EscrowMetadata>(data);
+    let _ = near_sdk::serde_json::from_slice::<ProofData>(data);
+    let _ = near_sdk::serde_json::from_slice::<HashAlgorithm>(data);
+    let _ = near_sdk::serde_json::from_slice::<U128>(data);
+
+    if let Some((&decimals, rest)) = data.split_first() {
+        let mut amount = [0u8; 16];
+        let len = rest.len().min(16);
+        amount[..len].copy_from_slice(&rest[..len]);
+        let amount = U128(u128::from_le_bytes(amount));
+        let _ = format_amount(amount, decimals);
+        let _ = format_near(amount);
+    }
+});
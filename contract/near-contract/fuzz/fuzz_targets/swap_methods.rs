@@ -0,0 +1,102 @@
+// Random call sequences against the swap contract in a mocked env
+#![no_main]
+
+use arbitrary::Arbitrary;
+use ciphra_fuzz::*;
+use libfuzzer_sys::fuzz_target;
+use swap_contract::{HashAlgorithm, SwapContract};
+
+#[derive(Arbitrary, Debug)]
+enum SwapCall {
+    Initiate {
+        caller: u8,
+        swap_id: FuzzId,
+        participant: u8,
+        hash_lock: FuzzHex,
+        algorithm: u8,
+        time_lock_duration: u64,
+        target_chain: FuzzText,
+        target_address: FuzzText,
+        counterparty_swap_id: Option<FuzzId>,
+        amount: FuzzAmount,
+    },
+    Lock { caller: u8, swap_id: FuzzId },
+    Complete { caller: u8, swap_id: FuzzId, secret: FuzzText },
+    Refund { caller: u8, swap_id: FuzzId },
+    Verify { swap_id: FuzzId, poseidon_hash: FuzzHex, secret_matches: bool },
+    Cleanup { caller: u8, swap_ids: Vec<FuzzId> },
+    StorageWithdraw { caller: u8, amount: Option<FuzzAmount> },
+    Advance { seconds: u32 },
+}
+
+fn algorithm(index: u8) -> HashAlgorithm {
+    match index % 3 {
+        0 => HashAlgorithm::SHA256,
+        1 => HashAlgorithm::Keccak256,
+        _ => HashAlgorithm::Poseidon,
+    }
+}
+
+fuzz_target!(|calls: Vec<SwapCall>| {
+    let mut chain = Chain::new();
+    let mut contract = SwapContract::new(owner(), owner());
+    for index in 0..ACCOUNTS.len() as u8 {
+        chain.call(&mut contract, &account(index), STORAGE_PREPAID, |c| c.storage_deposit(None, None));
+    }
+
+    for call in calls {
+        match call {
+            SwapCall::Initiate {
+                caller,
+                swap_id,
+                participant,
+                hash_lock,
+                algorithm: index,
+                time_lock_duration,
+                target_chain,
+                target_address,
+                counterparty_swap_id,
+                amount,
+            } => {
+                chain.call(&mut contract, &account(caller), amount.deposit(), |c| {
+                    c.initiate_swap(
+                        swap_id.resolve(),
+                        account(participant),
+                        hash_lock.resolve(),
+                        algorithm(index),
+                        time_lock_duration,
+                        target_chain.resolve(),
+                        target_address.resolve(),
+                        counterparty_swap_id.map(|id| id.resolve()),
+                        None,
+                    )
+                });
+            }
+            SwapCall::Lock { caller, swap_id } => {
+                chain.call(&mut contract, &account(caller), 0, |c| c.lock_swap(swap_id.resolve()));
+            }
+            SwapCall::Complete { caller, swap_id, secret } => {
+                chain.call(&mut contract, &account(caller), 0, |c| {
+                    c.complete_swap_with_oracle_verification(swap_id.resolve(), secret.resolve())
+                });
+            }
+            SwapCall::Refund { caller, swap_id } => {
+                chain.call(&mut contract, &account(caller), 0, |c| c.refund_swap(swap_id.resolve()));
+            }
+            SwapCall::Verify { swap_id, poseidon_hash, secret_matches } => {
+                chain.call(&mut contract, &owner(), 0, |c| {
+                    c.submit_oracle_verification(swap_id.resolve(), poseidon_hash.resolve(), secret_matches)
+                });
+            }
+            SwapCall::Cleanup { caller, swap_ids } => {
+                let swap_ids: Vec<String> = swap_ids.iter().map(FuzzId::resolve).collect();
+                chain.call(&mut contract, &account(caller), 0, |c| c.cleanup_swaps(swap_ids));
+            }
+            SwapCall::StorageWithdraw { caller, amount } => {
+                let amount = amount.map(|amount| amount.yocto().into());
+                chain.call(&mut contract, &account(caller), 1, |c| c.storage_withdraw(amount));
+            }
+            SwapCall::Advance { seconds } => chain.advance(seconds),
+        }
+    }
+});
@@ -0,0 +1,205 @@
+// Shared plumbing for the fuzz targets: a mocked chain that applies calls as transactions,
+// input generators biased toward edge cases, and a panic filter that tells typed contract
+// rejections apart from real bugs.
+use arbitrary::Arbitrary;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::mock::with_mocked_blockchain;
+use near_sdk::test_utils::VMContextBuilder;
+use near_sdk::{env, test_vm_config, AccountId, MockedBlockchain, NearToken, RuntimeFeesConfig};
+use std::any::Any;
+use std::collections::HashMap;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Once;
+
+pub const CONTRACT: &str = "contract.near";
+pub const ACCOUNTS: [&str; 4] = ["owner.near", "alice.near", "bob.near", "carol.near"];
+
+// Storage every account prepays so storage accounting never masks the code under test
+pub const STORAGE_PREPAID: u128 = 100 * 1_000_000_000_000_000_000_000_000;
+
+// Contract errors panic with this marker (see ciphra_types::CiphraError)
+const TYPED_ERROR_MARKER: &str = "ERROR_JSON:";
+
+// Attached deposits can't exceed the NEAR supply (~10^9 NEAR), unlike amount arguments
+const MAX_DEPOSIT: u128 = 1_000_000_000 * 1_000_000_000_000_000_000_000_000;
+
+const GENESIS_TIMESTAMP: u64 = 1_700_000_000_000_000_000;
+
+static PANIC_FILTER: Once = Once::new();
+
+pub fn account(index: u8) -> AccountId {
+    ACCOUNTS[index as usize % ACCOUNTS.len()].parse().unwrap()
+}
+
+pub fn owner() -> AccountId {
+    account(0)
+}
+
+// libfuzzer aborts on any panic. Typed contract errors are the contract rejecting bad input,
+// the same as a failed transaction, so they unwind to `Chain::call`; anything else (overflow,
+// unwrap, out-of-bounds) still aborts and is reported as a crash.
+pub fn install_panic_filter() {
+    PANIC_FILTER.call_once(|| {
+        let crash = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !is_typed_error(info.payload()) {
+                crash(info);
+            }
+        }));
+    });
+}
+
+// The mock surfaces `env::panic_str` as a host error wrapping the message
+fn is_typed_error(payload: &(dyn Any + Send)) -> bool {
+    let message = payload
+        .downcast_ref::<String>()
+        .map(String::as_str)
+        .or_else(|| payload.downcast_ref::<&str>().copied());
+    message.is_some_and(|message| message.contains(TYPED_ERROR_MARKER))
+}
+
+// Mocked chain carried across the calls of one input
+pub struct Chain {
+    now: u64,
+}
+
+impl Default for Chain {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Chain {
+    pub fn new() -> Self {
+        install_panic_filter();
+        let chain = Self { now: GENESIS_TIMESTAMP };
+        chain.install(&owner(), 0, HashMap::new());
+        chain
+    }
+
+    pub fn advance(&mut self, seconds: u32) {
+        self.now += seconds as u64 * 1_000_000_000;
+    }
+
+    // Runs `method` as one transaction from `caller` attaching `deposit` yoctoNEAR. A typed
+    // error reverts both the contract struct and its storage, as a failed transaction would,
+    // and returns None.
+    pub fn call<T, R>(
+        &self,
+        contract: &mut T,
+        caller: &AccountId,
+        deposit: u128,
+        method: impl FnOnce(&mut T) -> R,
+    ) -> Option<R>
+    where
+        T: BorshSerialize + BorshDeserialize,
+    {
+        let storage = with_mocked_blockchain(|blockchain| blockchain.take_storage());
+        let state = borsh::to_vec(contract).unwrap();
+        self.install(caller, deposit, storage.clone());
+
+        match panic::catch_unwind(AssertUnwindSafe(|| method(contract))) {
+            Ok(result) => Some(result),
+            Err(_) => {
+                *contract = T::try_from_slice(&state).unwrap();
+                self.install(caller, deposit, storage);
+                None
+            }
+        }
+    }
+
+    fn install(&self, caller: &AccountId, deposit: u128, storage: HashMap<Vec<u8>, Vec<u8>>) {
+        let context = VMContextBuilder::new()
+            .current_account_id(CONTRACT.parse().unwrap())
+            .predecessor_account_id(caller.clone())
+            .signer_account_id(caller.clone())
+            .attached_deposit(NearToken::from_yoctonear(deposit))
+            .block_timestamp(self.now)
+            .build();
+        env::set_blockchain_interface(MockedBlockchain::new(
+            context,
+            test_vm_config(),
+            RuntimeFeesConfig::test(),
+            Vec::new(),
+            storage,
+            HashMap::new(),
+            None,
+        ));
+    }
+}
+
+// Ids mostly come from a small pool so calls find each other's records; raw ones probe
+// lookups of ids that were never created
+#[derive(Arbitrary, Debug)]
+pub enum FuzzId {
+    Known(u8),
+    Raw(String),
+}
+
+impl FuzzId {
+    pub fn resolve(&self) -> String {
+        match self {
+            FuzzId::Known(index) => format!("id-{}", index % 4),
+            FuzzId::Raw(raw) => raw.clone(),
+        }
+    }
+}
+
+// Well-formed 32-byte hex or arbitrary, usually malformed, strings
+#[derive(Arbitrary, Debug)]
+pub enum FuzzHex {
+    Digest([u8; 32]),
+    Raw(String),
+}
+
+impl FuzzHex {
+    pub fn resolve(&self) -> String {
+        match self {
+            FuzzHex::Digest(bytes) => hex::encode(bytes),
+            FuzzHex::Raw(raw) => raw.clone(),
+        }
+    }
+}
+
+// Boundary amounts in yoctoNEAR
+#[derive(Arbitrary, Debug)]
+pub enum FuzzAmount {
+    Zero,
+    One,
+    OneNear,
+    Max,
+    Raw(u128),
+}
+
+impl FuzzAmount {
+    pub fn yocto(&self) -> u128 {
+        match self {
+            FuzzAmount::Zero => 0,
+            FuzzAmount::One => 1,
+            FuzzAmount::OneNear => 1_000_000_000_000_000_000_000_000,
+            FuzzAmount::Max => u128::MAX,
+            FuzzAmount::Raw(amount) => *amount,
+        }
+    }
+
+    // The same amount as an attached deposit
+    pub fn deposit(&self) -> u128 {
+        self.yocto().min(MAX_DEPOSIT)
+    }
+}
+
+// Free text, or a string well past the contracts' length caps
+#[derive(Arbitrary, Debug)]
+pub enum FuzzText {
+    Raw(String),
+    Oversized(u16),
+}
+
+impl FuzzText {
+    pub fn resolve(&self) -> String {
+        match self {
+            FuzzText::Raw(raw) => raw.clone(),
+            FuzzText::Oversized(len) => "x".repeat(*len as usize),
+        }
+    }
+}
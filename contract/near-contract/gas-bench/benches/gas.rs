@@ -0,0 +1,513 @@
+// Gas benchmarks - seeds every contract with N records, then measures the gas a fresh account
+// burns on each benchmarked method. Fails when a method exceeds its threshold or its gas grows
+// with state size past MAX_GROWTH, which points at collections read or rewritten per call.
+// Run with `cargo bench` from this directory; needs cargo-near and a sandbox binary.
+// Sizes default to 10 and 1k records; CIPHRA_BENCH_SIZES=10,1000,100000 adds the 100k run,
+// which takes hours to seed.
+use ciphra_poseidon::Fr;
+use near_workspaces::network::Sandbox;
+use near_workspaces::operations::Function;
+use near_workspaces::result::ExecutionFinalResult;
+use near_workspaces::types::{Gas, NearToken};
+use near_workspaces::{Account, AccountId, Contract, Worker};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+
+#[path = "../../tests/common/spend_fixture.rs"]
+mod spend_fixture;
+
+const DEFAULT_SIZES: &[usize] = &[10, 1_000];
+
+// Records are spread over several seeders so no single account's indexes dominate
+const SEEDERS: usize = 50;
+// Records created per seeding transaction
+const SEED_BATCH: usize = 10;
+const SEED_CALL_GAS: Gas = Gas::from_tgas(25);
+const MAX_GAS: Gas = Gas::from_tgas(300);
+
+// NEP-145 deposit each contract locks per record
+const SWAP_RECORD_STORAGE: u128 = NearToken::from_millinear(10).as_yoctonear();
+const ESCROW_RECORD_STORAGE: u128 = NearToken::from_millinear(50).as_yoctonear();
+const P2P_RECORD_STORAGE: u128 = NearToken::from_millinear(10).as_yoctonear();
+
+const RECORD_AMOUNT: NearToken = NearToken::from_millinear(1);
+const FAR_FUTURE: u64 = 4_000_000_000_000_000_000;
+
+// Time locks and release times used by the settle-after-expiry scenarios
+const SHORT_LOCK_SECONDS: u64 = 5;
+const LONG_LOCK_SECONDS: u64 = 86_400;
+const WAIT_BLOCKS: u64 = 100;
+
+// Highest gas, in Tgas, a method may burn at any state size
+const THRESHOLDS: &[(&str, u64)] = &[
+    ("swap.initiate_swap", 15),
+    ("swap.lock_swap", 10),
+    ("swap.complete_swap_with_oracle_verification", 25),
+    ("swap.refund_swap", 15),
+    ("escrow.create_escrow", 25),
+    ("escrow.top_up", 15),
+    ("escrow.raise_dispute", 15),
+    ("escrow.release_funds", 30),
+    ("p2p.deposit_balance", 10),
+    ("p2p.internal_transfer", 20),
+    ("p2p.withdraw_balance", 20),
+    ("p2p.send_direct", 25),
+    ("p2p.shield_deposit", 25),
+    ("p2p.shield_withdraw", 35),
+];
+
+// Gas at the largest size may exceed gas at the smallest by at most this factor
+const MAX_GROWTH: f64 = 1.5;
+
+// method -> state size -> gas burnt
+type Report = BTreeMap<&'static str, BTreeMap<usize, u64>>;
+
+struct Bench {
+    worker: Worker<Sandbox>,
+    swap: Contract,
+    escrow: Contract,
+    p2p: Contract,
+    user: Account, // Sends the measured calls and starts without records
+    peer: Account, // Counterparty of the measured calls
+    seeders: Vec<Account>,
+}
+
+fn hash_hex(preimage: &str) -> String {
+    hex::encode(Sha256::digest(preimage.as_bytes()))
+}
+
+// Note commitments and nullifiers must be field elements: the first enter the Poseidon tree,
+// the second are public inputs of the spend circuit
+fn field_element_hex(preimage: &str) -> String {
+    hex::encode(Fr::from_be_bytes_reduced(&Sha256::digest(preimage.as_bytes()).into()).to_be_bytes())
+}
+
+async fn deploy(worker: &Worker<Sandbox>, crate_dir: &str) -> anyhow::Result<Contract> {
+    let wasm = near_workspaces::compile_project(&format!("../{}", crate_dir)).await?;
+    Ok(worker.dev_deploy(&wasm).await?)
+}
+
+async fn create_account(root: &Account, name: &str, balance: NearToken) -> anyhow::Result<Account> {
+    let account = root
+        .create_subaccount(name)
+        .initial_balance(balance)
+        .transact()
+        .await?
+        .into_result()?;
+    Ok(account)
+}
+
+async fn prepay_storage(account: &Account, contract: &AccountId, amount: u128) -> anyhow::Result<()> {
+    account.call(contract, "storage_deposit")
+        .args_json(json!({ "account_id": null, "registration_only": null }))
+        .deposit(NearToken::from_yoctonear(amount))
+        .transact()
+        .await?
+        .into_result()?;
+    Ok(())
+}
+
+async fn setup(size: usize) -> anyhow::Result<Bench> {
+    let worker = near_workspaces::sandbox().await?;
+    let root = worker.root_account()?;
+
+    // Each seeder funds and prepays storage for its share of the records on all three contracts
+    let seeder_count = SEEDERS.min(size).max(1);
+    let share = size.div_ceil(seeder_count) as u128;
+    let per_record = SWAP_RECORD_STORAGE + ESCROW_RECORD_STORAGE + P2P_RECORD_STORAGE + 3 * RECORD_AMOUNT.as_yoctonear();
+    let seeder_balance = NearToken::from_yoctonear(per_record * share + NearToken::from_near(10).as_yoctonear());
+
+    let owner = create_account(&root, "owner", NearToken::from_near(100)).await?;
+    let user = create_account(&root, "user", NearToken::from_near(100)).await?;
+    let peer = create_account(&root, "peer", NearToken::from_near(100)).await?;
+    let mut seeders = Vec::with_capacity(seeder_count);
+    for index in 0..seeder_count {
+        seeders.push(create_account(&root, &format!("seeder{}", index), seeder_balance).await?);
+    }
+
+    let swap = deploy(&worker, "swap-contract").await?;
+    let escrow = deploy(&worker, "escrow-contract").await?;
+    let p2p = deploy(&worker, "p2p-transfer").await?;
+
+    swap.call("new")
+        .args_json(json!({ "owner": owner.id(), "oracle_account": owner.id() }))
+        .transact()
+        .await?
+        .into_result()?;
+    escrow.call("new")
+        .args_json(json!({ "owner": owner.id() }))
+        .transact()
+        .await?
+        .into_result()?;
+    p2p.call("new")
+        .args_json(json!({ "owner": owner.id() }))
+        .transact()
+        .await?
+        .into_result()?;
+
+    owner.call(swap.id(), "set_time_lock_bounds")
+        .args_json(json!({ "min_time_lock": SHORT_LOCK_SECONDS, "max_time_lock": LONG_LOCK_SECONDS }))
+        .transact()
+        .await?
+        .into_result()?;
+    // Seeders create far more records per window than the default rate limit allows
+    for contract in [&swap, &escrow, &p2p] {
+        owner.call(contract.id(), "set_rate_limit")
+            .args_json(json!({ "config": null }))
+            .transact()
+            .await?
+            .into_result()?;
+    }
+    // Spends are checked against the verifying key of the note's circuit
+    owner.call(p2p.id(), "set_verifying_key")
+        .args_json(json!({ "circuit_version": 0, "vk_bytes": spend_fixture::spend_verifying_key() }))
+        .transact()
+        .await?
+        .into_result()?;
+
+    for seeder in &seeders {
+        prepay_storage(seeder, swap.id(), SWAP_RECORD_STORAGE * share).await?;
+        prepay_storage(seeder, escrow.id(), ESCROW_RECORD_STORAGE * share).await?;
+        prepay_storage(seeder, p2p.id(), P2P_RECORD_STORAGE * share).await?;
+    }
+    for account in [&user, &peer] {
+        for contract in [&swap, &escrow, &p2p] {
+            prepay_storage(account, contract.id(), NearToken::from_near(1).as_yoctonear()).await?;
+        }
+    }
+
+    Ok(Bench { worker, swap, escrow, p2p, user, peer, seeders })
+}
+
+// Seeders run concurrently, each creating every N-th record; counterparties rotate through
+// the other seeders so per-account indexes stay small
+async fn seed(bench: &Bench, size: usize) -> anyhow::Result<()> {
+    let jobs = bench.seeders.iter().enumerate().map(|(index, seeder)| {
+        let counterparty = bench.seeders[(index + 1) % bench.seeders.len()].id();
+        let records: Vec<usize> = (index..size).step_by(bench.seeders.len()).collect();
+        seed_account(bench, seeder, counterparty, records)
+    });
+    futures::future::try_join_all(jobs).await?;
+    Ok(())
+}
+
+async fn seed_account(
+    bench: &Bench,
+    seeder: &Account,
+    counterparty: &AccountId,
+    records: Vec<usize>,
+) -> anyhow::Result<()> {
+    for chunk in records.chunks(SEED_BATCH) {
+        let chunk_amount = NearToken::from_yoctonear(RECORD_AMOUNT.as_yoctonear() * chunk.len() as u128);
+        
+        let mut swaps = seeder.batch(bench.swap.id());
+        for record in chunk {
+            let swap_id = format!("seed-{}", record);
+            swaps = swaps.call(
+                Function::new("initiate_swap")
+                    .args_json(json!({
+                        "swap_id": swap_id,
+                        "participant": counterparty,
+                        "hash_lock": hash_hex(&swap_id),
+                        "hash_algorithm": "SHA256",
+                        "time_lock_duration": LONG_LOCK_SECONDS,
+                        "target_chain": "near",
+                        "target_address": counterparty,
+                        "counterparty_swap_id": null,
+                        "linked_escrow": null,
+                    }))
+                    .deposit(RECORD_AMOUNT)
+                    .gas(SEED_CALL_GAS),
+            );
+        }
+        swaps.transact().await?.into_result()?;
+        
+        let escrows: Vec<Value> = chunk
+            .iter()
+            .map(|record| json!({
+                "escrow_id": format!("seed-{}", record),
+                "beneficiary": counterparty,
+                "amount": RECORD_AMOUNT.as_yoctonear().to_string(),
+                "release_time": FAR_FUTURE,
+                "arbiter": null,
+                "metadata": { "title": "Seeded escrow", "category": "bench" },
+                "arbiter_fee": null,
+                "required_proofs": null,
+            }))
+            .collect();
+        seeder.call(bench.escrow.id(), "create_escrows_batch")
+            .args_json(json!({ "escrows": escrows }))
+            .deposit(chunk_amount)
+            .gas(MAX_GAS)
+            .transact()
+            .await?
+            .into_result()?;
+        
+        let notes: Vec<Value> = chunk
+            .iter()
+            .map(|record| {
+                let note_id = format!("seed-{}", record);
+                json!([note_id, field_element_hex(&note_id), RECORD_AMOUNT.as_yoctonear().to_string()])
+            })
+            .collect();
+        seeder.call(bench.p2p.id(), "shield_deposit_batch")
+            .args_json(json!({ "notes": notes }))
+            .deposit(chunk_amount)
+            .gas(MAX_GAS)
+            .transact()
+            .await?
+            .into_result()?;
+    }
+    Ok(())
+}
+
+fn record(report: &mut Report, method: &'static str, size: usize, outcome: ExecutionFinalResult) -> anyhow::Result<()> {
+    let gas = outcome.total_gas_burnt.as_gas();
+    outcome.into_result()?;
+    report.entry(method).or_default().insert(size, gas);
+    Ok(())
+}
+
+async fn now(bench: &Bench) -> anyhow::Result<u64> {
+    Ok(bench.worker.view_block().await?.timestamp())
+}
+
+fn swap_args(bench: &Bench, swap_id: &str, secret: &str, lock_seconds: u64) -> Value {
+    json!({
+        "swap_id": swap_id,
+        "participant": bench.peer.id(),
+        "hash_lock": hash_hex(secret),
+        "hash_algorithm": "SHA256",
+        "time_lock_duration": lock_seconds,
+        "target_chain": "near",
+        "target_address": bench.peer.id(),
+        "counterparty_swap_id": null,
+        "linked_escrow": null,
+    })
+}
+
+async fn bench_swaps(bench: &Bench, size: usize, report: &mut Report) -> anyhow::Result<()> {
+    let (user, peer, swap) = (&bench.user, &bench.peer, bench.swap.id());
+    let secret = "bench-secret";
+
+    let outcome = user.call(swap, "initiate_swap")
+        .args_json(swap_args(bench, "bench-1", secret, LONG_LOCK_SECONDS))
+        .deposit(RECORD_AMOUNT)
+        .gas(MAX_GAS)
+        .transact()
+        .await?;
+    record(report, "swap.initiate_swap", size, outcome)?;
+
+    let outcome = peer.call(swap, "lock_swap")
+        .args_json(json!({ "swap_id": "bench-1" }))
+        .gas(MAX_GAS)
+        .transact()
+        .await?;
+    record(report, "swap.lock_swap", size, outcome)?;
+
+    let outcome = peer.call(swap, "complete_swap_with_oracle_verification")
+        .args_json(json!({ "swap_id": "bench-1", "secret": secret }))
+        .gas(MAX_GAS)
+        .transact()
+        .await?;
+    record(report, "swap.complete_swap_with_oracle_verification", size, outcome)?;
+
+    user.call(swap, "initiate_swap")
+        .args_json(swap_args(bench, "bench-2", secret, SHORT_LOCK_SECONDS))
+        .deposit(RECORD_AMOUNT)
+        .gas(MAX_GAS)
+        .transact()
+        .await?
+        .into_result()?;
+    bench.worker.fast_forward(WAIT_BLOCKS).await?;
+    let outcome = user.call(swap, "refund_swap")
+        .args_json(json!({ "swap_id": "bench-2" }))
+        .gas(MAX_GAS)
+        .transact()
+        .await?;
+    record(report, "swap.refund_swap", size, outcome)?;
+
+    Ok(())
+}
+
+fn escrow_args(bench: &Bench, escrow_id: &str, release_time: u64) -> Value {
+    json!({
+        "escrow_id": escrow_id,
+        "beneficiary": bench.peer.id(),
+        "release_time": release_time,
+        "arbiter": null,
+        "metadata": { "title": "Benchmark escrow", "category": "bench" },
+        "arbiter_fee": null,
+        "required_proofs": null,
+    })
+}
+
+async fn bench_escrows(bench: &Bench, size: usize, report: &mut Report) -> anyhow::Result<()> {
+    let (user, peer, escrow) = (&bench.user, &bench.peer, bench.escrow.id());
+
+    let outcome = user.call(escrow, "create_escrow")
+        .args_json(escrow_args(bench, "bench-1", FAR_FUTURE))
+        .deposit(RECORD_AMOUNT)
+        .gas(MAX_GAS)
+        .transact()
+        .await?;
+    record(report, "escrow.create_escrow", size, outcome)?;
+
+    let outcome = user.call(escrow, "top_up")
+        .args_json(json!({ "escrow_id": "bench-1" }))
+        .deposit(RECORD_AMOUNT)
+        .gas(MAX_GAS)
+        .transact()
+        .await?;
+    record(report, "escrow.top_up", size, outcome)?;
+
+    let outcome = user.call(escrow, "raise_dispute")
+        .args_json(json!({ "escrow_id": "bench-1" }))
+        .gas(MAX_GAS)
+        .transact()
+        .await?;
+    record(report, "escrow.raise_dispute", size, outcome)?;
+
+    let release_time = now(bench).await? + SHORT_LOCK_SECONDS * 1_000_000_000;
+    user.call(escrow, "create_escrow")
+        .args_json(escrow_args(bench, "bench-2", release_time))
+        .deposit(RECORD_AMOUNT)
+        .gas(MAX_GAS)
+        .transact()
+        .await?
+        .into_result()?;
+    bench.worker.fast_forward(WAIT_BLOCKS).await?;
+    let outcome = peer.call(escrow, "release_funds")
+        .args_json(json!({ "escrow_id": "bench-2" }))
+        .gas(MAX_GAS)
+        .transact()
+        .await?;
+    record(report, "escrow.release_funds", size, outcome)?;
+
+    Ok(())
+}
+
+async fn bench_p2p(bench: &Bench, size: usize, report: &mut Report) -> anyhow::Result<()> {
+    let (user, peer, p2p) = (&bench.user, &bench.peer, bench.p2p.id());
+    let amount = RECORD_AMOUNT.as_yoctonear().to_string();
+
+    let outcome = user.call(p2p, "deposit_balance")
+        .deposit(NearToken::from_millinear(10))
+        .gas(MAX_GAS)
+        .transact()
+        .await?;
+    record(report, "p2p.deposit_balance", size, outcome)?;
+
+    let outcome = user.call(p2p, "internal_transfer")
+        .args_json(json!({ "transfer_id": "bench-1", "recipient": peer.id(), "amount": amount, "memo": "" }))
+        .gas(MAX_GAS)
+        .transact()
+        .await?;
+    record(report, "p2p.internal_transfer", size, outcome)?;
+
+    let outcome = user.call(p2p, "withdraw_balance")
+        .args_json(json!({ "amount": amount }))
+        .gas(MAX_GAS)
+        .transact()
+        .await?;
+    record(report, "p2p.withdraw_balance", size, outcome)?;
+
+    let outcome = user.call(p2p, "send_direct")
+        .args_json(json!({
+            "transfer_id": "bench-2",
+            "recipient": peer.id(),
+            "memo": "",
+            "hold": null,
+            "encrypted_memo": null,
+            "reference": null,
+        }))
+        .deposit(RECORD_AMOUNT)
+        .gas(MAX_GAS)
+        .transact()
+        .await?;
+    record(report, "p2p.send_direct", size, outcome)?;
+
+    let outcome = user.call(p2p, "shield_deposit")
+        .args_json(json!({ "note_id": "bench-1", "commitment": field_element_hex("bench-1 commitment") }))
+        .deposit(RECORD_AMOUNT)
+        .gas(MAX_GAS)
+        .transact()
+        .await?;
+    record(report, "p2p.shield_deposit", size, outcome)?;
+
+    let root: String = bench.p2p.view("get_current_root").await?.json()?;
+    let outcome = user.call(p2p, "shield_withdraw")
+        .args_json(json!({
+            "transfer_id": "bench-3",
+            "note_id": "bench-1",
+            "nullifier": field_element_hex("bench-1 nullifier"),
+            "recipient": peer.id(),
+            "root": root,
+            "proof": spend_fixture::spend_proof(),
+        }))
+        .gas(MAX_GAS)
+        .transact()
+        .await?;
+    record(report, "p2p.shield_withdraw", size, outcome)?;
+
+    Ok(())
+}
+
+fn print_report(report: &Report, sizes: &[usize]) {
+    let header: String = sizes.iter().map(|size| format!("{:>12}", size)).collect();
+    println!("{:<48}{}", "method (Tgas)", header);
+    for (method, by_size) in report {
+        let row: String = sizes
+            .iter()
+            .map(|size| match by_size.get(size) {
+                Some(gas) => format!("{:>12.2}", *gas as f64 / 1e12),
+                None => format!("{:>12}", "-"),
+            })
+            .collect();
+        println!("{:<48}{}", method, row);
+    }
+}
+
+fn check_report(report: &Report) -> anyhow::Result<()> {
+    let mut failures = Vec::new();
+    for (method, by_size) in report {
+        let Some((_, limit_tgas)) = THRESHOLDS.iter().find(|(name, _)| name == method) else {
+            failures.push(format!("{}: no threshold", method));
+            continue;
+        };
+        let limit = Gas::from_tgas(*limit_tgas).as_gas();
+        for (size, gas) in by_size {
+            if *gas > limit {
+                failures.push(format!("{} at {} records: {} gas > {} Tgas", method, size, gas, limit_tgas));
+            }
+        }
+        if let (Some(smallest), Some(largest)) = (by_size.values().next(), by_size.values().last()) {
+            if *largest as f64 > *smallest as f64 * MAX_GROWTH {
+                failures.push(format!("{}: gas grows from {} to {} with state size", method, smallest, largest));
+            }
+        }
+    }
+    anyhow::ensure!(failures.is_empty(), "Gas regressions:\n{}", failures.join("\n"));
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let sizes: Vec<usize> = match std::env::var("CIPHRA_BENCH_SIZES") {
+        Ok(sizes) => sizes.split(',').map(|size| size.trim().parse()).collect::<Result<_, _>>()?,
+        Err(_) => DEFAULT_SIZES.to_vec(),
+    };
+
+    let mut report = Report::new();
+    for &size in &sizes {
+        let bench = setup(size).await?;
+        seed(&bench, size).await?;
+        bench_swaps(&bench, size, &mut report).await?;
+        bench_escrows(&bench, size, &mut report).await?;
+        bench_p2p(&bench, size, &mut report).await?;
+    }
+
+    print_report(&report, &sizes);
+    check_report(&report)
+}
@@ -0,0 +1,565 @@
+use ciphra_types::{decimal_borsh, ensure, ErrorCode, OrFail, Paged};
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::collections::UnorderedMap;
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::serde_json::{self, Value};
+use near_sdk::{env, is_promise_success, near_bindgen, AccountId, BorshStorageKey, Gas, NearToken, PanicOnDefault, Promise};
+use schemars::JsonSchema;
+
+const GAS_FOR_EXECUTE_CALLBACK: Gas = Gas::from_tgas(10);
+
+// Upper bound on gas a proposal may forward to its call
+const MAX_PROPOSAL_TGAS: u64 = 200;
+
+// Upper bound on council members
+const MAX_COUNCIL_SIZE: usize = 20;
+
+// Longest accepted proposal description
+const MAX_DESCRIPTION_LENGTH: usize = 1024;
+
+#[derive(BorshSerialize, BorshStorageKey)]
+pub enum StorageKey {
+    Proposals,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum ProposalStatus {
+    Voting,
+    Queued,    // Approved; executable once its timelock has passed
+    Executing, // Call in flight
+    Executed,
+    Failed,    // Call failed; may be executed again
+    Rejected,
+    Cancelled,
+}
+
+// A single function call on a managed contract (or on this contract for its own settings)
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Proposal {
+    pub id: u64,
+    pub proposer: String,
+    pub description: String,
+    pub target: String,
+    pub method: String,
+    pub args: String, // JSON arguments
+    #[borsh(serialize_with = "decimal_borsh::serialize", deserialize_with = "decimal_borsh::deserialize")]
+    #[schemars(with = "String")]
+    pub deposit: U128,
+    pub gas_tgas: u64,
+    pub status: ProposalStatus,
+    pub approvals: Vec<String>,
+    pub rejections: Vec<String>,
+    pub created_at: u64,
+    pub voting_ends_at: u64,
+    pub executable_at: Option<u64>,
+    pub executed_at: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct GovernanceConfig {
+    pub council: Vec<String>,
+    pub threshold: u8,
+    pub voting_period: u64, // seconds
+    pub timelock: u64,      // seconds between approval and execution
+    pub managed_contracts: Vec<String>,
+}
+
+// Version and link come from the package
+#[near_bindgen(contract_metadata(standard(standard = "nep330", version = "1.1.0")))]
+#[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
+pub struct GovernanceContract {
+    pub council: Vec<AccountId>,
+    pub threshold: u8,      // Approvals needed to queue a proposal
+    pub voting_period: u64, // ns
+    pub timelock: u64,      // ns
+    pub managed_contracts: Vec<AccountId>, // Contracts whose admin role this contract holds
+    pub proposals: UnorderedMap<u64, Proposal>,
+    pub next_proposal_id: u64,
+}
+
+#[near_bindgen]
+impl GovernanceContract {
+    #[init]
+    pub fn new(
+        council: Vec<AccountId>,
+        threshold: u8,
+        voting_period_seconds: u64,
+        timelock_seconds: u64,
+        managed_contracts: Vec<AccountId>,
+    ) -> Self {
+        let mut contract = Self {
+            council: Vec::new(),
+            threshold: 0,
+            voting_period: 0,
+            timelock: 0,
+            managed_contracts,
+            proposals: UnorderedMap::new(StorageKey::Proposals),
+            next_proposal_id: 0,
+        };
+        contract.apply_council(council, threshold);
+        contract.apply_periods(voting_period_seconds, timelock_seconds);
+        contract
+    }
+
+    // Council member proposes one call; targets are the managed contracts or this contract
+    pub fn propose(
+        &mut self,
+        description: String,
+        target: AccountId,
+        method: String,
+        args: String,
+        deposit: U128,
+        gas_tgas: u64,
+    ) -> Proposal {
+        let proposer = env::predecessor_account_id();
+        ensure!(self.council.contains(&proposer), Unauthorized, "Only council members can propose");
+        ensure!(
+            target == env::current_account_id() || self.managed_contracts.contains(&target),
+            InvalidArgument,
+            "Target is not a managed contract"
+        );
+        ensure!(
+            !description.is_empty() && description.len() <= MAX_DESCRIPTION_LENGTH,
+            InvalidArgument,
+            "Description must be 1-1024 characters"
+        );
+        ensure!(!method.is_empty(), InvalidArgument, "Method required");
+        serde_json::from_str::<Value>(&args).or_fail(ErrorCode::InvalidArgument, "Args must be valid JSON");
+        ensure!(gas_tgas > 0 && gas_tgas <= MAX_PROPOSAL_TGAS, InvalidArgument, "Gas must be 1-200 Tgas");
+        
+        let now = env::block_timestamp();
+        let proposal = Proposal {
+            id: self.next_proposal_id,
+            proposer: proposer.to_string(),
+            description,
+            target: target.to_string(),
+            method,
+            args,
+            deposit,
+            gas_tgas,
+            status: ProposalStatus::Voting,
+            approvals: Vec::new(),
+            rejections: Vec::new(),
+            created_at: now,
+            voting_ends_at: now + self.voting_period,
+            executable_at: None,
+            executed_at: None,
+        };
+        self.proposals.insert(&proposal.id, &proposal);
+        self.next_proposal_id += 1;
+        
+        env::log_str(&format!(
+            "Proposal created: {} | By: {} | Call: {}.{}",
+            proposal.id, proposer, proposal.target, proposal.method
+        ));
+        
+        proposal
+    }
+
+    // Council members may change their vote while voting is open. A proposal is queued once
+    // `threshold` members approve, and rejected once approval can no longer be reached.
+    pub fn vote(&mut self, proposal_id: u64, approve: bool) -> Proposal {
+        let mut proposal = self.proposal(proposal_id);
+        
+        let caller = env::predecessor_account_id();
+        ensure!(self.council.contains(&caller), Unauthorized, "Only council members can vote");
+        ensure!(proposal.status == ProposalStatus::Voting, InvalidState, "Proposal is not open for voting");
+        ensure!(env::block_timestamp() < proposal.voting_ends_at, Expired, "Voting period has ended");
+        
+        let voter = caller.to_string();
+        proposal.approvals.retain(|a| a != &voter);
+        proposal.rejections.retain(|a| a != &voter);
+        if approve {
+            proposal.approvals.push(voter);
+        } else {
+            proposal.rejections.push(voter);
+        }
+        
+        // Votes from since-removed members don't count
+        let approvals = self.count_votes(&proposal.approvals);
+        let rejections = self.count_votes(&proposal.rejections);
+        if approvals >= self.threshold as usize {
+            proposal.status = ProposalStatus::Queued;
+            proposal.executable_at = Some(env::block_timestamp() + self.timelock);
+        } else if rejections > self.council.len() - self.threshold as usize {
+            proposal.status = ProposalStatus::Rejected;
+        }
+        self.proposals.insert(&proposal_id, &proposal);
+        
+        env::log_str(&format!(
+            "Proposal vote: {} | By: {} | Approvals: {} | Rejections: {} | Status: {:?}",
+            proposal_id, caller, approvals, rejections, proposal.status
+        ));
+        
+        proposal
+    }
+
+    // Proposer withdraws a proposal that hasn't been executed
+    pub fn cancel(&mut self, proposal_id: u64) {
+        let mut proposal = self.proposal(proposal_id);
+        
+        ensure!(
+            env::predecessor_account_id().as_str() == proposal.proposer,
+            Unauthorized,
+            "Only proposer can cancel"
+        );
+        ensure!(
+            matches!(proposal.status, ProposalStatus::Voting | ProposalStatus::Queued | ProposalStatus::Failed),
+            InvalidState,
+            "Proposal cannot be cancelled"
+        );
+        proposal.status = ProposalStatus::Cancelled;
+        self.proposals.insert(&proposal_id, &proposal);
+        
+        env::log_str(&format!("Proposal cancelled: {}", proposal_id));
+    }
+
+    // Anyone can execute a queued proposal once its timelock has passed
+    pub fn execute(&mut self, proposal_id: u64) -> Promise {
+        let mut proposal = self.proposal(proposal_id);
+        
+        ensure!(
+            matches!(proposal.status, ProposalStatus::Queued | ProposalStatus::Failed),
+            InvalidState,
+            "Proposal is not queued"
+        );
+        let executable_at = proposal.executable_at.or_fail(ErrorCode::InvalidState, "Proposal is not queued");
+        ensure!(env::block_timestamp() >= executable_at, TooEarly, "Timelock has not passed");
+        
+        proposal.status = ProposalStatus::Executing;
+        self.proposals.insert(&proposal_id, &proposal);
+        
+        let target: AccountId = proposal.target.parse().or_fail(ErrorCode::Internal, "Invalid target");
+        let deposit_yocto: u128 = proposal.deposit.0;
+        
+        env::log_str(&format!(
+            "Proposal executing: {} | Call: {}.{}",
+            proposal_id, proposal.target, proposal.method
+        ));
+        
+        Promise::new(target)
+            .function_call(
+                proposal.method,
+                proposal.args.into_bytes(),
+                NearToken::from_yoctonear(deposit_yocto),
+                Gas::from_tgas(proposal.gas_tgas),
+            )
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_EXECUTE_CALLBACK)
+                    .on_executed(proposal_id),
+            )
+    }
+
+    #[private]
+    pub fn on_executed(&mut self, proposal_id: u64) -> bool {
+        let mut proposal = self.proposal(proposal_id);
+        let success = is_promise_success();
+        
+        if success {
+            proposal.status = ProposalStatus::Executed;
+            proposal.executed_at = Some(env::block_timestamp());
+        } else {
+            proposal.status = ProposalStatus::Failed;
+        }
+        self.proposals.insert(&proposal_id, &proposal);
+        
+        env::log_str(&format!("Proposal {}: {}", if success { "executed" } else { "failed" }, proposal_id));
+        
+        success
+    }
+
+    // Governance settings only change through proposals targeting this contract
+    #[private]
+    pub fn set_council(&mut self, council: Vec<AccountId>, threshold: u8) {
+        self.apply_council(council, threshold);
+    }
+
+    #[private]
+    pub fn set_periods(&mut self, voting_period_seconds: u64, timelock_seconds: u64) {
+        self.apply_periods(voting_period_seconds, timelock_seconds);
+    }
+
+    #[private]
+    pub fn set_managed_contracts(&mut self, managed_contracts: Vec<AccountId>) {
+        self.managed_contracts = managed_contracts;
+    }
+
+    pub fn get_config(&self) -> GovernanceConfig {
+        GovernanceConfig {
+            council: self.council.iter().map(|m| m.to_string()).collect(),
+            threshold: self.threshold,
+            voting_period: self.voting_period / 1_000_000_000,
+            timelock: self.timelock / 1_000_000_000,
+            managed_contracts: self.managed_contracts.iter().map(|c| c.to_string()).collect(),
+        }
+    }
+
+    pub fn get_proposal(&self, proposal_id: u64) -> Option<Proposal> {
+        self.proposals.get(&proposal_id)
+    }
+
+    pub fn get_proposals(&self, from_index: Option<u64>, limit: Option<u64>) -> Paged<Proposal> {
+        let proposals = self.proposals.values_as_vector();
+        Paged::from_range(proposals.len(), from_index, limit, |index| proposals.get(index))
+    }
+
+    fn proposal(&self, proposal_id: u64) -> Proposal {
+        self.proposals.get(&proposal_id).or_fail(ErrorCode::NotFound, "Proposal not found")
+    }
+
+    fn apply_council(&mut self, council: Vec<AccountId>, threshold: u8) {
+        let mut council = council;
+        council.sort();
+        council.dedup();
+        ensure!(
+            !council.is_empty() && council.len() <= MAX_COUNCIL_SIZE,
+            LimitExceeded,
+            "Council must have 1-20 members"
+        );
+        ensure!(
+            threshold > 0 && threshold as usize <= council.len(),
+            InvalidArgument,
+            "Threshold must be between 1 and the number of members"
+        );
+        self.council = council;
+        self.threshold = threshold;
+    }
+
+    fn apply_periods(&mut self, voting_period_seconds: u64, timelock_seconds: u64) {
+        ensure!(voting_period_seconds > 0, InvalidArgument, "Voting period must be positive");
+        self.voting_period = voting_period_seconds * 1_000_000_000;
+        self.timelock = timelock_seconds * 1_000_000_000;
+    }
+
+    fn count_votes(&self, votes: &[String]) -> usize {
+        votes
+            .iter()
+            .filter(|v| v.parse().is_ok_and(|v: AccountId| self.council.contains(&v)))
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ciphra_testing::{self as host, Action};
+
+    const VOTING_PERIOD: u64 = 3600;
+    const TIMELOCK: u64 = 600;
+
+    fn account(name: &str) -> AccountId {
+        name.parse().unwrap()
+    }
+
+    // Three-member council needing two approvals, managing the swap contract
+    fn contract() -> GovernanceContract {
+        host::set_caller("deployer.near");
+        GovernanceContract::new(
+            vec![account("alice.near"), account("bob.near"), account("carol.near")],
+            2,
+            VOTING_PERIOD,
+            TIMELOCK,
+            vec![account("swap.near")],
+        )
+    }
+
+    fn propose(contract: &mut GovernanceContract) -> Proposal {
+        host::set_caller("alice.near");
+        contract.propose(
+            "Pause swaps".to_string(),
+            account("swap.near"),
+            "set_paused".to_string(),
+            r#"{"paused":true}"#.to_string(),
+            U128(0),
+            50,
+        )
+    }
+
+    fn vote(contract: &mut GovernanceContract, voter: &str, proposal_id: u64, approve: bool) -> Proposal {
+        host::set_caller(voter);
+        contract.vote(proposal_id, approve)
+    }
+
+    // Proposal approved by two members with its timelock passed
+    fn queued(contract: &mut GovernanceContract) -> u64 {
+        let proposal = propose(contract);
+        vote(contract, "alice.near", proposal.id, true);
+        vote(contract, "bob.near", proposal.id, true);
+        host::advance_seconds(TIMELOCK);
+        proposal.id
+    }
+
+    #[test]
+    fn test_threshold_approvals_queue_the_proposal() {
+        let mut contract = contract();
+        let proposal = propose(&mut contract);
+        assert_eq!(proposal.status, ProposalStatus::Voting);
+
+        let proposal = vote(&mut contract, "alice.near", proposal.id, true);
+        assert_eq!(proposal.status, ProposalStatus::Voting);
+        let proposal = vote(&mut contract, "bob.near", proposal.id, true);
+        assert_eq!(proposal.status, ProposalStatus::Queued);
+        assert_eq!(proposal.executable_at, Some(host::block_timestamp() + TIMELOCK * 1_000_000_000));
+    }
+
+    #[test]
+    fn test_changed_vote_replaces_the_previous_one() {
+        let mut contract = contract();
+        let proposal = propose(&mut contract);
+        vote(&mut contract, "alice.near", proposal.id, false);
+
+        let proposal = vote(&mut contract, "alice.near", proposal.id, true);
+        assert_eq!(proposal.approvals, vec!["alice.near".to_string()]);
+        assert!(proposal.rejections.is_empty());
+    }
+
+    #[test]
+    fn test_proposal_is_rejected_once_approval_is_unreachable() {
+        let mut contract = contract();
+        let proposal = propose(&mut contract);
+        vote(&mut contract, "alice.near", proposal.id, false);
+
+        let proposal = vote(&mut contract, "bob.near", proposal.id, false);
+        assert_eq!(proposal.status, ProposalStatus::Rejected);
+    }
+
+    #[test]
+    fn test_execute_forwards_the_call_after_the_timelock() {
+        let mut contract = contract();
+        let proposal_id = queued(&mut contract);
+
+        host::set_caller("anyone.near");
+        let _ = contract.execute(proposal_id);
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().status, ProposalStatus::Executing);
+        let receipt = &host::receipts()[0];
+        assert_eq!(receipt.receiver, "swap.near");
+        assert_eq!(
+            receipt.actions,
+            vec![Action::FunctionCall {
+                method: "set_paused".to_string(),
+                args: br#"{"paused":true}"#.to_vec(),
+                deposit: 0
+            }]
+        );
+
+        host::set_self_caller();
+        host::set_promise_results(vec![Some(Vec::new())]);
+        assert!(contract.on_executed(proposal_id));
+        let proposal = contract.get_proposal(proposal_id).unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Executed);
+        assert!(proposal.executed_at.is_some());
+    }
+
+    #[test]
+    fn test_failed_execution_can_be_retried() {
+        let mut contract = contract();
+        let proposal_id = queued(&mut contract);
+        let _ = contract.execute(proposal_id);
+
+        host::set_self_caller();
+        host::set_promise_results(vec![None]);
+        assert!(!contract.on_executed(proposal_id));
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().status, ProposalStatus::Failed);
+
+        host::clear_receipts();
+        let _ = contract.execute(proposal_id);
+        assert_eq!(host::function_calls()[0], ("swap.near".to_string(), "set_paused".to_string()));
+    }
+
+    #[test]
+    fn test_council_changes_apply_to_open_votes() {
+        let mut contract = contract();
+        let proposal = propose(&mut contract);
+        vote(&mut contract, "carol.near", proposal.id, true);
+
+        host::set_self_caller();
+        contract.set_council(vec![account("alice.near"), account("bob.near"), account("dave.near")], 2);
+
+        // Carol's approval no longer counts towards the threshold
+        let proposal = vote(&mut contract, "bob.near", proposal.id, true);
+        assert_eq!(proposal.status, ProposalStatus::Voting);
+        assert_eq!(contract.get_config().council, vec!["alice.near", "bob.near", "dave.near"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only council members can propose")]
+    fn test_only_council_members_propose() {
+        let mut contract = contract();
+        host::set_caller("mallory.near");
+        contract.propose("x".to_string(), account("swap.near"), "m".to_string(), "{}".to_string(), U128(0), 50);
+    }
+
+    #[test]
+    #[should_panic(expected = "Target is not a managed contract")]
+    fn test_proposals_only_target_managed_contracts() {
+        let mut contract = contract();
+        host::set_caller("alice.near");
+        contract.propose("x".to_string(), account("other.near"), "m".to_string(), "{}".to_string(), U128(0), 50);
+    }
+
+    #[test]
+    #[should_panic(expected = "Args must be valid JSON")]
+    fn test_proposal_args_must_be_json() {
+        let mut contract = contract();
+        host::set_caller("alice.near");
+        contract.propose("x".to_string(), account("swap.near"), "m".to_string(), "{".to_string(), U128(0), 50);
+    }
+
+    #[test]
+    #[should_panic(expected = "Gas must be 1-200 Tgas")]
+    fn test_proposal_gas_is_capped() {
+        let mut contract = contract();
+        host::set_caller("alice.near");
+        contract.propose("x".to_string(), account("swap.near"), "m".to_string(), "{}".to_string(), U128(0), 201);
+    }
+
+    #[test]
+    #[should_panic(expected = r#""code":"Expired","message":"Voting period has ended""#)]
+    fn test_votes_close_after_the_voting_period() {
+        let mut contract = contract();
+        let proposal = propose(&mut contract);
+        host::advance_seconds(VOTING_PERIOD);
+        vote(&mut contract, "bob.near", proposal.id, true);
+    }
+
+    #[test]
+    #[should_panic(expected = r#""code":"TooEarly","message":"Timelock has not passed""#)]
+    fn test_execute_waits_for_the_timelock() {
+        let mut contract = contract();
+        let proposal = propose(&mut contract);
+        vote(&mut contract, "alice.near", proposal.id, true);
+        vote(&mut contract, "bob.near", proposal.id, true);
+        let _ = contract.execute(proposal.id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Proposal is not queued")]
+    fn test_cancelled_proposal_cannot_execute() {
+        let mut contract = contract();
+        let proposal_id = queued(&mut contract);
+        host::set_caller("alice.near");
+        contract.cancel(proposal_id);
+        let _ = contract.execute(proposal_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only proposer can cancel")]
+    fn test_only_the_proposer_cancels() {
+        let mut contract = contract();
+        let proposal = propose(&mut contract);
+        host::set_caller("bob.near");
+        contract.cancel(proposal.id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Threshold must be between 1 and the number of members")]
+    fn test_threshold_cannot_exceed_the_council() {
+        host::set_caller("deployer.near");
+        GovernanceContract::new(vec![account("alice.near")], 2, VOTING_PERIOD, TIMELOCK, Vec::new());
+    }
+}
@@ -0,0 +1,409 @@
+use ciphra_types::{assert_owner, decimal_borsh, ensure, ErrorCode, OrFail, Paged};
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::collections::{LookupMap, UnorderedMap};
+use near_sdk::json_types::U128;
+use near_sdk::{env, near_bindgen, AccountId, BorshStorageKey, PanicOnDefault, Promise, PromiseOrValue, NearToken};
+use near_sdk::serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+
+// Upper bound on governance members voting on claims
+const MAX_GOVERNANCE_SIZE: usize = 20;
+
+// Longest accepted claim description and evidence URI
+const MAX_DESCRIPTION_LENGTH: usize = 512;
+const MAX_EVIDENCE_URI_LENGTH: usize = 256;
+
+#[derive(BorshSerialize, BorshStorageKey)]
+pub enum StorageKey {
+    Contributions,
+    Claims,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum IncidentKind {
+    OracleFailure,
+    VerifierFailure,
+    Other,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub enum ClaimStatus {
+    Pending,
+    Paid,
+    Rejected,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Claim {
+    pub claim_id: String,
+    pub claimant: String,
+    #[borsh(serialize_with = "decimal_borsh::serialize", deserialize_with = "decimal_borsh::deserialize")]
+    #[schemars(with = "String")]
+    pub amount: U128, // yoctoNEAR requested
+    pub kind: IncidentKind,
+    pub reference: String, // Affected escrow, swap or transfer id
+    pub description: String,
+    pub evidence_uri: String,
+    pub status: ClaimStatus,
+    pub approvals: Vec<String>,
+    pub rejections: Vec<String>,
+    pub filed_at: u64,
+    pub decided_at: Option<u64>,
+}
+
+#[near_bindgen]
+#[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
+pub struct InsurancePoolContract {
+    pub owner: AccountId,
+    pub governance: Vec<AccountId>,
+    pub approval_threshold: u8, // Matching votes needed to pay or reject a claim
+    pub contributions: LookupMap<AccountId, u128>, // Fees received per protocol contract
+    pub total_contributed: u128,
+    pub total_paid: u128,
+    pub claims: UnorderedMap<String, Claim>,
+}
+
+#[near_bindgen]
+impl InsurancePoolContract {
+    #[init]
+    pub fn new(owner: AccountId) -> Self {
+        Self {
+            owner: owner.clone(),
+            governance: vec![owner],
+            approval_threshold: 1,
+            contributions: LookupMap::new(StorageKey::Contributions),
+            total_contributed: 0,
+            total_paid: 0,
+            claims: UnorderedMap::new(StorageKey::Claims),
+        }
+    }
+
+    // Protocol contracts forward their fee slice here; attributed to the calling contract
+    #[payable]
+    pub fn deposit_fees(&mut self) {
+        let source = env::predecessor_account_id();
+        let amount_yocto = env::attached_deposit().as_yoctonear();
+        ensure!(amount_yocto > 0, InsufficientFunds, "Must attach NEAR tokens");
+        
+        let contributed = self.contributions.get(&source).unwrap_or(0);
+        self.contributions.insert(&source, &(contributed + amount_yocto));
+        self.total_contributed += amount_yocto;
+        
+        env::log_str(&format!("Fees deposited: {} | Amount: {}", source, amount_yocto));
+    }
+
+    // A user harmed by an oracle or verifier failure asks for compensation
+    pub fn file_claim(
+        &mut self,
+        claim_id: String,
+        amount: U128,
+        kind: IncidentKind,
+        reference: String,
+        description: String,
+        evidence_uri: String,
+    ) -> Claim {
+        ensure!(self.claims.get(&claim_id).is_none(), AlreadyExists, "Claim ID already exists");
+        ensure!(amount.0 > 0, InvalidArgument, "Claim amount must be positive");
+        ensure!(
+            !description.is_empty() && description.len() <= MAX_DESCRIPTION_LENGTH,
+            InvalidArgument,
+            "Description must be 1-512 characters"
+        );
+        ensure!(
+            evidence_uri.len() <= MAX_EVIDENCE_URI_LENGTH,
+            LimitExceeded,
+            "Evidence URI cannot exceed 256 characters"
+        );
+        
+        let claim = Claim {
+            claim_id: claim_id.clone(),
+            claimant: env::predecessor_account_id().to_string(),
+            amount,
+            kind,
+            reference,
+            description,
+            evidence_uri,
+            status: ClaimStatus::Pending,
+            approvals: Vec::new(),
+            rejections: Vec::new(),
+            filed_at: env::block_timestamp(),
+            decided_at: None,
+        };
+        self.claims.insert(&claim_id, &claim);
+        
+        env::log_str(&format!(
+            "Claim filed: {} | Claimant: {} | Amount: {}",
+            claim_id, claim.claimant, amount.0
+        ));
+        
+        claim
+    }
+
+    // Governance member votes; the claim is paid or rejected once `approval_threshold` votes agree.
+    // Members may change their vote until then.
+    pub fn vote_claim(&mut self, claim_id: String, approve: bool) -> PromiseOrValue<bool> {
+        let mut claim = self.claims.get(&claim_id).or_fail(ErrorCode::NotFound, "Claim not found");
+        
+        let caller = env::predecessor_account_id();
+        ensure!(self.governance.contains(&caller), Unauthorized, "Only governance can vote");
+        ensure!(matches!(claim.status, ClaimStatus::Pending), InvalidState, "Claim already decided");
+        
+        let voter = caller.to_string();
+        claim.approvals.retain(|a| a != &voter);
+        claim.rejections.retain(|a| a != &voter);
+        if approve {
+            claim.approvals.push(voter);
+        } else {
+            claim.rejections.push(voter);
+        }
+        
+        // Votes from since-removed members don't count
+        let approvals = self.count_votes(&claim.approvals);
+        let rejections = self.count_votes(&claim.rejections);
+        
+        env::log_str(&format!(
+            "Claim vote: {} | By: {} | Approvals: {} | Rejections: {} | Threshold: {}",
+            claim_id, caller, approvals, rejections, self.approval_threshold
+        ));
+        
+        if rejections >= self.approval_threshold as usize {
+            claim.status = ClaimStatus::Rejected;
+            claim.decided_at = Some(env::block_timestamp());
+            self.claims.insert(&claim_id, &claim);
+            env::log_str(&format!("Claim rejected: {}", claim_id));
+            return PromiseOrValue::Value(false);
+        }
+        if approvals < self.approval_threshold as usize {
+            self.claims.insert(&claim_id, &claim);
+            return PromiseOrValue::Value(false);
+        }
+        
+        let amount_yocto: u128 = claim.amount.0;
+        ensure!(amount_yocto <= self.available_balance(), InsufficientFunds, "Insufficient pool balance");
+        claim.status = ClaimStatus::Paid;
+        claim.decided_at = Some(env::block_timestamp());
+        self.claims.insert(&claim_id, &claim);
+        self.total_paid += amount_yocto;
+        
+        env::log_str(&format!("Claim paid: {} | Amount: {}", claim_id, amount_yocto));
+        
+        let claimant: AccountId = claim.claimant.parse().or_fail(ErrorCode::Internal, "Invalid claimant");
+        PromiseOrValue::Promise(Promise::new(claimant).transfer(NearToken::from_yoctonear(amount_yocto)))
+    }
+
+    pub fn set_governance(&mut self, members: Vec<AccountId>, threshold: u8) {
+        assert_owner(&self.owner);
+        ensure!(
+            !members.is_empty() && members.len() <= MAX_GOVERNANCE_SIZE,
+            LimitExceeded,
+            "Governance must have 1-20 members"
+        );
+        let mut members = members;
+        members.sort();
+        members.dedup();
+        ensure!(
+            threshold > 0 && threshold as usize <= members.len(),
+            InvalidArgument,
+            "Threshold must be between 1 and the number of members"
+        );
+        
+        self.governance = members;
+        self.approval_threshold = threshold;
+    }
+
+    pub fn get_governance(&self) -> Vec<AccountId> {
+        self.governance.clone()
+    }
+
+    pub fn get_approval_threshold(&self) -> u8 {
+        self.approval_threshold
+    }
+
+    pub fn get_claim(&self, claim_id: String) -> Option<Claim> {
+        self.claims.get(&claim_id)
+    }
+
+    pub fn get_claims(&self, from_index: Option<u64>, limit: Option<u64>) -> Paged<Claim> {
+        let claims = self.claims.values_as_vector();
+        Paged::from_range(claims.len(), from_index, limit, |index| claims.get(index))
+    }
+
+    // Contributed fees not yet paid out on claims
+    pub fn get_pool_balance(&self) -> U128 {
+        U128(self.available_balance())
+    }
+
+    pub fn get_contribution(&self, source: AccountId) -> U128 {
+        U128(self.contributions.get(&source).unwrap_or(0))
+    }
+
+    pub fn get_total_paid(&self) -> U128 {
+        U128(self.total_paid)
+    }
+
+    fn available_balance(&self) -> u128 {
+        self.total_contributed - self.total_paid
+    }
+
+    fn count_votes(&self, votes: &[String]) -> usize {
+        votes
+            .iter()
+            .filter(|v| v.parse().is_ok_and(|v: AccountId| self.governance.contains(&v)))
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ciphra_testing as host;
+
+    const OWNER: &str = "owner.near";
+    const CLAIMANT: &str = "alice.near";
+
+    fn account(name: &str) -> AccountId {
+        name.parse().unwrap()
+    }
+
+    // Pool funded by the swap contract, with two of three governance members needed to decide
+    fn contract() -> InsurancePoolContract {
+        host::set_caller(OWNER);
+        let mut contract = InsurancePoolContract::new(account(OWNER));
+        contract.set_governance(vec![account("gov1.near"), account("gov2.near"), account("gov3.near")], 2);
+        host::set_caller("swap.near");
+        host::set_deposit(10 * host::NEAR);
+        contract.deposit_fees();
+        host::set_deposit(0);
+        contract
+    }
+
+    fn file_claim(contract: &mut InsurancePoolContract, claim_id: &str, amount: u128) -> Claim {
+        host::set_caller(CLAIMANT);
+        contract.file_claim(
+            claim_id.to_string(),
+            U128(amount),
+            IncidentKind::OracleFailure,
+            "swap-1".to_string(),
+            "Oracle never answered".to_string(),
+            String::new(),
+        )
+    }
+
+    fn vote(contract: &mut InsurancePoolContract, voter: &str, claim_id: &str, approve: bool) -> bool {
+        host::set_caller(voter);
+        matches!(contract.vote_claim(claim_id.to_string(), approve), PromiseOrValue::Promise(_))
+    }
+
+    #[test]
+    fn test_fees_are_attributed_to_the_contributor() {
+        let contract = contract();
+        assert_eq!(contract.get_contribution(account("swap.near")), U128(10 * host::NEAR));
+        assert_eq!(contract.get_pool_balance(), U128(10 * host::NEAR));
+    }
+
+    #[test]
+    fn test_approved_claim_pays_the_claimant() {
+        let mut contract = contract();
+        file_claim(&mut contract, "c1", 4 * host::NEAR);
+
+        assert!(!vote(&mut contract, "gov1.near", "c1", true));
+        assert!(vote(&mut contract, "gov2.near", "c1", true));
+
+        assert_eq!(host::transfers(), vec![(CLAIMANT.to_string(), 4 * host::NEAR)]);
+        assert!(matches!(contract.get_claim("c1".to_string()).unwrap().status, ClaimStatus::Paid));
+        assert_eq!(contract.get_pool_balance(), U128(6 * host::NEAR));
+    }
+
+    #[test]
+    fn test_claim_is_rejected_at_the_threshold() {
+        let mut contract = contract();
+        file_claim(&mut contract, "c1", host::NEAR);
+
+        vote(&mut contract, "gov1.near", "c1", false);
+        vote(&mut contract, "gov2.near", "c1", false);
+
+        let claim = contract.get_claim("c1".to_string()).unwrap();
+        assert!(matches!(claim.status, ClaimStatus::Rejected));
+        assert!(claim.decided_at.is_some());
+        assert!(host::transfers().is_empty());
+    }
+
+    #[test]
+    fn test_changed_vote_replaces_the_previous_one() {
+        let mut contract = contract();
+        file_claim(&mut contract, "c1", host::NEAR);
+
+        vote(&mut contract, "gov1.near", "c1", false);
+        vote(&mut contract, "gov1.near", "c1", true);
+
+        let claim = contract.get_claim("c1".to_string()).unwrap();
+        assert_eq!(claim.approvals, vec!["gov1.near".to_string()]);
+        assert!(claim.rejections.is_empty());
+    }
+
+    #[test]
+    fn test_votes_of_removed_members_do_not_count() {
+        let mut contract = contract();
+        file_claim(&mut contract, "c1", host::NEAR);
+        vote(&mut contract, "gov1.near", "c1", true);
+
+        host::set_caller(OWNER);
+        contract.set_governance(vec![account("gov2.near"), account("gov3.near")], 2);
+
+        assert!(!vote(&mut contract, "gov2.near", "c1", true));
+        assert!(vote(&mut contract, "gov3.near", "c1", true));
+    }
+
+    #[test]
+    #[should_panic(expected = r#""code":"InsufficientFunds","message":"Insufficient pool balance""#)]
+    fn test_claim_cannot_exceed_the_pool() {
+        let mut contract = contract();
+        file_claim(&mut contract, "c1", 11 * host::NEAR);
+        vote(&mut contract, "gov1.near", "c1", true);
+        vote(&mut contract, "gov2.near", "c1", true);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only governance can vote")]
+    fn test_only_governance_votes() {
+        let mut contract = contract();
+        file_claim(&mut contract, "c1", host::NEAR);
+        vote(&mut contract, CLAIMANT, "c1", true);
+    }
+
+    #[test]
+    #[should_panic(expected = "Claim already decided")]
+    fn test_decided_claim_takes_no_votes() {
+        let mut contract = contract();
+        file_claim(&mut contract, "c1", host::NEAR);
+        vote(&mut contract, "gov1.near", "c1", true);
+        vote(&mut contract, "gov2.near", "c1", true);
+        vote(&mut contract, "gov3.near", "c1", false);
+    }
+
+    #[test]
+    #[should_panic(expected = "Claim ID already exists")]
+    fn test_claim_ids_are_unique() {
+        let mut contract = contract();
+        file_claim(&mut contract, "c1", host::NEAR);
+        file_claim(&mut contract, "c1", host::NEAR);
+    }
+
+    #[test]
+    #[should_panic(expected = "Claim amount must be positive")]
+    fn test_claim_amount_must_be_positive() {
+        let mut contract = contract();
+        file_claim(&mut contract, "c1", 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Threshold must be between 1 and the number of members")]
+    fn test_threshold_cannot_exceed_the_governance() {
+        let mut contract = contract();
+        host::set_caller(OWNER);
+        contract.set_governance(vec![account("gov1.near"), account("gov1.near")], 2);
+    }
+}
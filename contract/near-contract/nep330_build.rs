@@ -0,0 +1,35 @@
+// Shared build script for the deployed contracts. near-sdk fills the NEP-330
+// `contract_source_metadata()` version from CARGO_PKG_VERSION, so the commit the
+// wasm was built from is appended as semver build metadata (0.1.0+<commit>).
+// CIPHRA_SOURCE_COMMIT overrides git, e.g. for builds from a source tarball.
+use std::process::Command;
+
+fn git(args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let value = String::from_utf8(output.stdout).ok()?.trim().to_string();
+    (!value.is_empty()).then_some(value)
+}
+
+fn main() {
+    println!("cargo:rerun-if-env-changed=CIPHRA_SOURCE_COMMIT");
+    
+    // Rebuild when HEAD moves to another commit
+    if let Some(git_dir) = git(&["rev-parse", "--absolute-git-dir"]) {
+        println!("cargo:rerun-if-changed={}/HEAD", git_dir);
+        if let Some(head_ref) = git(&["symbolic-ref", "-q", "HEAD"]) {
+            println!("cargo:rerun-if-changed={}/{}", git_dir, head_ref);
+        }
+    }
+    
+    let commit = std::env::var("CIPHRA_SOURCE_COMMIT")
+        .ok()
+        .filter(|commit| !commit.is_empty())
+        .or_else(|| git(&["rev-parse", "HEAD"]));
+    if let Some(commit) = commit {
+        let version = std::env::var("CARGO_PKG_VERSION").unwrap_or_default();
+        println!("cargo:rustc-env=CARGO_PKG_VERSION={}+{}", version, commit);
+    }
+}
@@ -0,0 +1,27 @@
+use crate::*;
+use crate::events::EVENTS;
+
+#[near_bindgen]
+impl OracleContract {
+    // Configuration and counters in the shape every Ciphra contract shares
+    pub fn get_contract_info(&self) -> ContractInfo {
+        ContractInfo::new(
+            EVENTS.contract,
+            env!("CARGO_PKG_VERSION"),
+            format!("{:?}", self.get_state_version()),
+            &self.owner,
+            self.access.pending_owner.as_ref(),
+            &self.pause_guard,
+        )
+        .bound("request_timeout", self.request_timeout / 1_000_000_000)
+        .bound("max_rounds", MAX_ROUNDS as u64)
+        .bound("liveness_timeout", self.liveness_timeout / 1_000_000_000)
+        .counter("requests", self.next_request_id)
+        .counter("pending", self.pending_requests.len())
+        .counter("operators", self.operators.len())
+        .counter("fulfilled", self.stats.fulfilled_count)
+        .counter("cancelled", self.stats.cancelled_count)
+        .counter("timed_out_rounds", self.stats.timed_out_rounds)
+        .counter("settlements", self.settlements.next_settlement_id)
+    }
+}
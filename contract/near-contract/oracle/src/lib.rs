@@ -0,0 +1,630 @@
+use ciphra_types::{assert_owner, decimal_borsh, ensure, fail, ErrorCode, OrFail};
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::collections::{LookupMap, UnorderedMap, UnorderedSet};
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::serde_json::json;
+use near_sdk::{
+    env, ext_contract, is_promise_success, near_bindgen, AccountId, BorshStorageKey, Gas, PanicOnDefault, Promise,
+};
+use schemars::JsonSchema;
+
+pub use ciphra_types::{
+    AccessControl, AccessStatus, ContractInfo, Paged, PauseGuard, PauseStatus, Role, SettlementLedger,
+};
+
+mod access;
+mod events;
+mod info;
+mod migration;
+mod operators;
+mod settlement;
+#[cfg(test)]
+mod test_utils;
+
+use events::emit_event;
+
+pub use migration::StateVersion;
+pub use operators::{OperatorRecord, OperatorStatus};
+
+const GAS_FOR_SUBMIT_VERIFICATION: Gas = Gas::from_tgas(20);
+const GAS_FOR_SUBMIT_CALLBACK: Gas = Gas::from_tgas(20);
+
+// Default fee a requester must offer per verification (0.01 NEAR)
+const DEFAULT_MIN_FEE: u128 = 10_000_000_000_000_000_000_000;
+
+// Default time the operator has to fulfil a round (10 minutes)
+const DEFAULT_REQUEST_TIMEOUT: u64 = 600 * 1_000_000_000;
+
+// Bounds on the request timeout (seconds)
+const MIN_REQUEST_TIMEOUT: u64 = 60;
+const MAX_REQUEST_TIMEOUT: u64 = 86_400;
+
+// Upper bound on rounds per request, so a swap can't keep a fee parked forever
+const MAX_ROUNDS: u32 = 10;
+
+// Longest accepted Poseidon hash (hex)
+const MAX_HASH_LENGTH: usize = 128;
+
+#[derive(BorshSerialize, BorshStorageKey)]
+pub enum StorageKey {
+    Requests,
+    RequestsBySwap,
+    PendingRequests,
+    PendingSettlements,
+    ClaimableSettlements,
+    Operators,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum RequestStatus {
+    Pending,    // Waiting for the operator in the current round
+    Submitting, // Result on its way to the swap contract
+    Fulfilled,  // Result recorded on the swap contract; fee paid to the operator
+    Cancelled,  // Timed out and withdrawn; fee refunded to the requester
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct VerificationResult {
+    pub poseidon_hash: String,
+    pub secret_matches: bool,
+}
+
+// One paid request for a Poseidon verification of a swap's secret. A round is one deadline
+// for the operator; a timed-out request is either reopened for a new round or cancelled.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct VerificationRequest {
+    pub request_id: u64,
+    pub swap_id: String,
+    pub requester: String,
+    #[borsh(serialize_with = "decimal_borsh::serialize", deserialize_with = "decimal_borsh::deserialize")]
+    #[schemars(with = "String")]
+    pub fee: U128, // Held until fulfilment, then paid to the fulfilling operator
+    pub round: u32,
+    pub created_at: u64,
+    pub deadline: u64, // End of the current round
+    pub status: RequestStatus,
+    pub result: Option<VerificationResult>,
+    pub fulfilled_by: Option<String>,
+    pub fulfilled_at: Option<u64>,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Default)]
+pub struct OracleStats {
+    pub fulfilled_count: u64,
+    pub cancelled_count: u64,
+    pub timed_out_rounds: u64,
+    pub fees_paid: u128,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct OracleConfig {
+    pub swap_contract: String,
+    pub operator: String,
+    #[schemars(with = "String")]
+    pub min_fee: U128,
+    pub request_timeout: u64,  // seconds
+    pub liveness_timeout: u64, // seconds
+}
+
+// Only the generated ext_swap module is used
+#[allow(dead_code)]
+#[ext_contract(ext_swap)]
+trait AtomicSwapContract {
+    fn submit_oracle_verification(&mut self, swap_id: String, poseidon_hash: String, secret_matches: bool);
+}
+
+// Request/response oracle for the swap contract's Poseidon secrets. This contract is the swap
+// contract's `oracle_account`: swaps pay for verifications here and the operator earns the fee
+// of each request it fulfils, so oracle economics stay out of the swap contract.
+#[near_bindgen(contract_metadata(
+    standard(standard = "nep330", version = "1.1.0"),
+    standard(standard = "nep297", version = "1.0.0"),
+))]
+#[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
+pub struct OracleContract {
+    pub owner: AccountId,
+    pub swap_contract: AccountId,
+    pub operator: AccountId,
+    pub requests: UnorderedMap<u64, VerificationRequest>,
+    pub requests_by_swap: LookupMap<String, u64>, // Latest request per swap
+    pub pending_requests: UnorderedSet<u64>,       // Pending and submitting, for operators to poll
+    pub next_request_id: u64,
+    pub min_fee: u128,
+    pub request_timeout: u64,
+    pub stats: OracleStats,
+    pub pause_guard: PauseGuard,       // Guardians may pause new requests but not unpause
+    pub access: AccessControl,         // Pending owner transfer and delegated roles
+    pub settlements: SettlementLedger, // Fee payouts and refunds awaiting their transfer callback
+    pub operators: UnorderedMap<AccountId, OperatorRecord>, // Active operator and standbys
+    pub liveness_timeout: u64,                              // Heartbeat silence before an operator counts as dead
+}
+
+#[near_bindgen]
+impl OracleContract {
+    #[init]
+    pub fn new(owner: AccountId, swap_contract: AccountId, operator: AccountId) -> Self {
+        Self::write_state_version();
+        let mut operators = Self::new_operator_registry();
+        operators.insert(&operator, &Self::new_operator_record(&operator));
+        Self {
+            owner,
+            swap_contract,
+            operator,
+            requests: UnorderedMap::new(StorageKey::Requests),
+            requests_by_swap: LookupMap::new(StorageKey::RequestsBySwap),
+            pending_requests: UnorderedSet::new(StorageKey::PendingRequests),
+            next_request_id: 0,
+            min_fee: DEFAULT_MIN_FEE,
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+            stats: OracleStats::default(),
+            pause_guard: PauseGuard::default(),
+            access: AccessControl::default(),
+            settlements: Self::new_settlement_ledger(),
+            operators,
+            liveness_timeout: operators::DEFAULT_LIVENESS_TIMEOUT,
+        }
+    }
+
+    // Pays `fee` (attached exactly) for a verification of `swap_id`; one open request per swap
+    #[payable]
+    pub fn request_verification(&mut self, swap_id: String, fee: U128) -> VerificationRequest {
+        let requester = env::predecessor_account_id();
+        
+        self.pause_guard.assert_not_paused();
+        ensure!(
+            env::attached_deposit().as_yoctonear() == fee.0,
+            InvalidArgument,
+            "Attached deposit must equal the fee"
+        );
+        ensure!(fee.0 >= self.min_fee, InsufficientFunds, "Fee below the minimum of {}", self.min_fee);
+        if let Some(previous) = self.requests_by_swap.get(&swap_id).and_then(|id| self.requests.get(&id)) {
+            ensure!(
+                matches!(previous.status, RequestStatus::Fulfilled | RequestStatus::Cancelled),
+                AlreadyExists,
+                "Swap already has an open request"
+            );
+        }
+        
+        let request_id = self.next_request_id;
+        self.next_request_id += 1;
+        let request = VerificationRequest {
+            request_id,
+            swap_id: swap_id.clone(),
+            requester: requester.to_string(),
+            fee,
+            round: 1,
+            created_at: env::block_timestamp(),
+            deadline: env::block_timestamp() + self.request_timeout,
+            status: RequestStatus::Pending,
+            result: None,
+            fulfilled_by: None,
+            fulfilled_at: None,
+        };
+        self.requests.insert(&request_id, &request);
+        self.requests_by_swap.insert(&swap_id, &request_id);
+        self.pending_requests.insert(&request_id);
+        
+        env::log_str(&format!(
+            "Verification requested: {} | Swap: {} | Fee: {}",
+            request_id, swap_id, fee.0
+        ));
+        emit_event("verification_requested", json!({
+            "request_id": request_id,
+            "swap_id": swap_id,
+            "requester": requester,
+            "fee": fee,
+            "deadline": request.deadline,
+        }));
+        
+        request
+    }
+
+    // Operator answers the current round; the result goes on to the swap contract and the fee
+    // is paid once it is recorded there
+    pub fn fulfill(&mut self, request_id: u64, result: VerificationResult) -> Promise {
+        let operator = env::predecessor_account_id();
+        ensure!(operator == self.operator, Unauthorized, "Only operator can fulfill");
+        ensure!(
+            !result.poseidon_hash.is_empty() && result.poseidon_hash.len() <= MAX_HASH_LENGTH,
+            InvalidArgument,
+            "Poseidon hash must be 1-128 characters"
+        );
+        
+        let mut request = self.requests.get(&request_id).or_fail(ErrorCode::NotFound, "Request not found");
+        ensure!(request.status == RequestStatus::Pending, InvalidState, "Request not pending");
+        ensure!(env::block_timestamp() < request.deadline, Expired, "Round has timed out");
+        
+        request.status = RequestStatus::Submitting;
+        request.result = Some(result.clone());
+        request.fulfilled_by = Some(operator.to_string());
+        self.requests.insert(&request_id, &request);
+        self.touch_operator(&operator);
+        
+        ext_swap::ext(self.swap_contract.clone())
+            .with_static_gas(GAS_FOR_SUBMIT_VERIFICATION)
+            .submit_oracle_verification(request.swap_id, result.poseidon_hash, result.secret_matches)
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_SUBMIT_CALLBACK)
+                    .on_verification_submitted(request_id),
+            )
+    }
+
+    // A rejected submission reopens the round so the operator can answer again before the deadline
+    #[private]
+    pub fn on_verification_submitted(&mut self, request_id: u64) -> bool {
+        let mut request = self.requests.get(&request_id).or_fail(ErrorCode::NotFound, "Request not found");
+        if !is_promise_success() {
+            request.status = RequestStatus::Pending;
+            request.result = None;
+            request.fulfilled_by = None;
+            self.requests.insert(&request_id, &request);
+            env::log_str(&format!("Verification submission failed: {}", request_id));
+            return false;
+        }
+        
+        let operator: AccountId = request
+            .fulfilled_by
+            .as_ref()
+            .or_fail(ErrorCode::Internal, "Missing operator")
+            .parse()
+            .or_fail(ErrorCode::Internal, "Invalid operator");
+        request.status = RequestStatus::Fulfilled;
+        request.fulfilled_at = Some(env::block_timestamp());
+        self.requests.insert(&request_id, &request);
+        self.pending_requests.remove(&request_id);
+        self.stats.fulfilled_count += 1;
+        self.stats.fees_paid += request.fee.0;
+        self.record_fulfilment(&operator, request.fee.0);
+        
+        let secret_matches = request.result.as_ref().is_some_and(|result| result.secret_matches);
+        env::log_str(&format!(
+            "Verification fulfilled: {} | Swap: {} | Round: {} | Verified: {}",
+            request_id, request.swap_id, request.round, secret_matches
+        ));
+        emit_event("verification_fulfilled", json!({
+            "request_id": request_id,
+            "swap_id": request.swap_id,
+            "operator": operator,
+            "round": request.round,
+            "verified": secret_matches,
+            "fee": request.fee,
+        }));
+        
+        self.settle(operator, request.fee.0);
+        true
+    }
+
+    // Requester starts a new round after the operator missed the deadline
+    pub fn reopen_request(&mut self, request_id: u64) -> VerificationRequest {
+        let mut request = self.timed_out_request(request_id);
+        ensure!(request.round < MAX_ROUNDS, LimitExceeded, "Round limit reached");
+        
+        request.round += 1;
+        request.deadline = env::block_timestamp() + self.request_timeout;
+        self.requests.insert(&request_id, &request);
+        self.stats.timed_out_rounds += 1;
+        
+        env::log_str(&format!("Verification reopened: {} | Round: {}", request_id, request.round));
+        emit_event("verification_reopened", json!({
+            "request_id": request_id,
+            "swap_id": request.swap_id,
+            "round": request.round,
+            "deadline": request.deadline,
+        }));
+        
+        request
+    }
+
+    // Requester withdraws a timed-out request and gets the fee back
+    pub fn cancel_request(&mut self, request_id: u64) -> Promise {
+        let mut request = self.timed_out_request(request_id);
+        
+        request.status = RequestStatus::Cancelled;
+        self.requests.insert(&request_id, &request);
+        self.pending_requests.remove(&request_id);
+        self.stats.timed_out_rounds += 1;
+        self.stats.cancelled_count += 1;
+        
+        env::log_str(&format!("Verification cancelled: {} | Refund: {}", request_id, request.fee.0));
+        emit_event("verification_cancelled", json!({
+            "request_id": request_id,
+            "swap_id": request.swap_id,
+            "round": request.round,
+            "fee": request.fee,
+        }));
+        
+        let requester: AccountId = request.requester.parse().or_fail(ErrorCode::Internal, "Invalid requester");
+        self.settle(requester, request.fee.0)
+    }
+
+    pub fn get_request(&self, request_id: u64) -> Option<VerificationRequest> {
+        self.requests.get(&request_id)
+    }
+
+    pub fn get_request_for_swap(&self, swap_id: String) -> Option<VerificationRequest> {
+        self.requests_by_swap.get(&swap_id).and_then(|request_id| self.requests.get(&request_id))
+    }
+
+    // Open requests in no particular order, for operators to poll
+    pub fn get_pending_requests(&self, from_index: Option<u64>, limit: Option<u64>) -> Paged<VerificationRequest> {
+        let pending = self.pending_requests.as_vector();
+        Paged::from_range(pending.len(), from_index, limit, |index| {
+            pending.get(index).and_then(|request_id| self.requests.get(&request_id))
+        })
+    }
+
+    pub fn get_config(&self) -> OracleConfig {
+        OracleConfig {
+            swap_contract: self.swap_contract.to_string(),
+            operator: self.operator.to_string(),
+            min_fee: U128(self.min_fee),
+            request_timeout: self.request_timeout / 1_000_000_000,
+            liveness_timeout: self.liveness_timeout / 1_000_000_000,
+        }
+    }
+
+    pub fn set_swap_contract(&mut self, swap_contract: AccountId) {
+        assert_owner(&self.owner);
+        ensure!(self.pending_requests.is_empty(), InvalidState, "Requests still open");
+        self.swap_contract = swap_contract;
+    }
+
+    pub fn set_min_fee(&mut self, min_fee: U128) {
+        self.access.assert_owner_or_role(&self.owner, Role::FeeManager);
+        self.min_fee = min_fee.0;
+        emit_event("min_fee_updated", json!({ "min_fee": min_fee }));
+    }
+
+    // Applies to rounds opened from now on
+    pub fn set_request_timeout(&mut self, timeout_seconds: u64) {
+        self.access.assert_owner_or_role(&self.owner, Role::Operator);
+        ensure!(
+            (MIN_REQUEST_TIMEOUT..=MAX_REQUEST_TIMEOUT).contains(&timeout_seconds),
+            InvalidArgument,
+            "Request timeout out of bounds"
+        );
+        self.request_timeout = timeout_seconds * 1_000_000_000;
+    }
+
+    pub fn get_owner(&self) -> AccountId {
+        self.owner.clone()
+    }
+
+    // Owner or guardian; only stops new requests - open ones can still be fulfilled or cancelled
+    pub fn pause(&mut self) {
+        let caller = self.pause_guard.pause(&self.owner);
+        env::log_str(&format!("Contract paused by: {}", caller));
+        emit_event("paused", json!({ "account_id": caller }));
+    }
+
+    // Guardians can only pause; resuming is an owner decision
+    pub fn unpause(&mut self) {
+        self.pause_guard.unpause(&self.owner);
+        env::log_str("Contract unpaused");
+        emit_event("unpaused", json!({ "account_id": self.owner }));
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.pause_guard.paused
+    }
+
+    pub fn get_pause_status(&self) -> PauseStatus {
+        self.pause_guard.status()
+    }
+
+    pub fn add_guardian(&mut self, account_id: AccountId) {
+        self.pause_guard.add_guardian(&self.owner, account_id);
+    }
+
+    pub fn remove_guardian(&mut self, account_id: AccountId) {
+        self.pause_guard.remove_guardian(&self.owner, &account_id);
+    }
+
+    pub fn get_guardians(&self) -> Vec<AccountId> {
+        self.pause_guard.guardians.clone()
+    }
+
+    // Pending request of the caller whose current round has passed its deadline
+    fn timed_out_request(&self, request_id: u64) -> VerificationRequest {
+        let request = self.requests.get(&request_id).or_fail(ErrorCode::NotFound, "Request not found");
+        ensure!(
+            env::predecessor_account_id().as_str() == request.requester,
+            Unauthorized,
+            "Only requester"
+        );
+        ensure!(request.status == RequestStatus::Pending, InvalidState, "Request not pending");
+        ensure!(env::block_timestamp() >= request.deadline, TooEarly, "Round has not timed out");
+        request
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use ciphra_testing as host;
+
+    const TIMEOUT_SECONDS: u64 = DEFAULT_REQUEST_TIMEOUT / 1_000_000_000;
+
+    #[test]
+    fn test_request_opens_the_first_round() {
+        let mut contract = contract();
+        let request = request(&mut contract, "s1");
+
+        assert_eq!(request.round, 1);
+        assert_eq!(request.status, RequestStatus::Pending);
+        assert_eq!(request.deadline, host::block_timestamp() + DEFAULT_REQUEST_TIMEOUT);
+        assert_eq!(contract.get_pending_requests(None, None).total, 1);
+        assert_eq!(contract.get_request_for_swap("s1".to_string()).unwrap().request_id, request.request_id);
+    }
+
+    #[test]
+    fn test_fulfill_submits_the_result_to_the_swap_contract() {
+        let mut contract = contract();
+        let request = request(&mut contract, "s1");
+
+        host::set_caller(OPERATOR);
+        let _ = contract.fulfill(request.request_id, result());
+
+        assert_eq!(host::function_calls()[0], (SWAP.to_string(), "submit_oracle_verification".to_string()));
+        assert_eq!(contract.get_request(request.request_id).unwrap().status, RequestStatus::Submitting);
+    }
+
+    #[test]
+    fn test_recorded_result_pays_the_operator() {
+        let mut contract = contract();
+        let request = request(&mut contract, "s1");
+        fulfill(&mut contract, request.request_id);
+
+        let request = contract.get_request(request.request_id).unwrap();
+        assert_eq!(request.status, RequestStatus::Fulfilled);
+        assert_eq!(request.fulfilled_by.as_deref(), Some(OPERATOR));
+        assert!(host::transfers().contains(&(OPERATOR.to_string(), FEE)));
+        assert_eq!(contract.get_pending_requests(None, None).total, 0);
+        assert_eq!(contract.stats.fees_paid, FEE);
+    }
+
+    #[test]
+    fn test_rejected_submission_reopens_the_round() {
+        let mut contract = contract();
+        let request = request(&mut contract, "s1");
+        host::set_caller(OPERATOR);
+        let _ = contract.fulfill(request.request_id, result());
+
+        host::set_self_caller();
+        host::set_promise_results(vec![None]);
+        assert!(!contract.on_verification_submitted(request.request_id));
+
+        let request = contract.get_request(request.request_id).unwrap();
+        assert_eq!(request.status, RequestStatus::Pending);
+        assert!(request.result.is_none() && request.fulfilled_by.is_none());
+    }
+
+    #[test]
+    fn test_swap_can_request_again_after_fulfilment() {
+        let mut contract = contract();
+        let first = request(&mut contract, "s1");
+        fulfill(&mut contract, first.request_id);
+
+        let second = request(&mut contract, "s1");
+        assert_eq!(second.request_id, first.request_id + 1);
+    }
+
+    #[test]
+    fn test_timed_out_request_is_reopened_for_a_new_round() {
+        let mut contract = contract();
+        let request = request(&mut contract, "s1");
+        host::advance_seconds(TIMEOUT_SECONDS);
+
+        host::set_caller(SWAP);
+        let reopened = contract.reopen_request(request.request_id);
+
+        assert_eq!(reopened.round, 2);
+        assert_eq!(reopened.deadline, host::block_timestamp() + DEFAULT_REQUEST_TIMEOUT);
+        assert_eq!(contract.stats.timed_out_rounds, 1);
+    }
+
+    #[test]
+    fn test_cancelled_request_refunds_the_requester() {
+        let mut contract = contract();
+        let request = request(&mut contract, "s1");
+        host::advance_seconds(TIMEOUT_SECONDS);
+
+        host::set_caller(SWAP);
+        let _ = contract.cancel_request(request.request_id);
+
+        assert_eq!(contract.get_request(request.request_id).unwrap().status, RequestStatus::Cancelled);
+        assert_eq!(host::transfers(), vec![(SWAP.to_string(), FEE)]);
+        assert_eq!(contract.get_pending_requests(None, None).total, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Attached deposit must equal the fee")]
+    fn test_request_fee_must_be_attached() {
+        let mut contract = contract();
+        host::set_caller(SWAP);
+        host::set_deposit(FEE);
+        contract.request_verification("s1".to_string(), U128(2 * FEE));
+    }
+
+    #[test]
+    #[should_panic(expected = r#""code":"InsufficientFunds""#)]
+    fn test_request_fee_must_meet_the_minimum() {
+        let mut contract = contract();
+        host::set_caller(SWAP);
+        host::set_deposit(FEE - 1);
+        contract.request_verification("s1".to_string(), U128(FEE - 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "Swap already has an open request")]
+    fn test_one_open_request_per_swap() {
+        let mut contract = contract();
+        request(&mut contract, "s1");
+        request(&mut contract, "s1");
+    }
+
+    #[test]
+    #[should_panic(expected = r#""code":"Paused""#)]
+    fn test_paused_oracle_takes_no_requests() {
+        let mut contract = contract();
+        contract.pause();
+        request(&mut contract, "s1");
+    }
+
+    #[test]
+    #[should_panic(expected = "Only operator can fulfill")]
+    fn test_only_the_active_operator_fulfils() {
+        let mut contract = contract();
+        let request = request(&mut contract, "s1");
+        host::set_caller(STANDBY);
+        let _ = contract.fulfill(request.request_id, result());
+    }
+
+    #[test]
+    #[should_panic(expected = r#""code":"Expired","message":"Round has timed out""#)]
+    fn test_timed_out_round_cannot_be_fulfilled() {
+        let mut contract = contract();
+        let request = request(&mut contract, "s1");
+        host::advance_seconds(TIMEOUT_SECONDS);
+        host::set_caller(OPERATOR);
+        let _ = contract.fulfill(request.request_id, result());
+    }
+
+    #[test]
+    #[should_panic(expected = r#""code":"TooEarly","message":"Round has not timed out""#)]
+    fn test_open_round_cannot_be_cancelled() {
+        let mut contract = contract();
+        let request = request(&mut contract, "s1");
+        host::set_caller(SWAP);
+        let _ = contract.cancel_request(request.request_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only requester")]
+    fn test_only_the_requester_reopens() {
+        let mut contract = contract();
+        let request = request(&mut contract, "s1");
+        host::advance_seconds(TIMEOUT_SECONDS);
+        host::set_caller(OPERATOR);
+        contract.reopen_request(request.request_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Round limit reached")]
+    fn test_rounds_are_capped() {
+        let mut contract = contract();
+        let request = request(&mut contract, "s1");
+        host::set_caller(SWAP);
+        for _ in 0..MAX_ROUNDS {
+            host::advance_seconds(TIMEOUT_SECONDS);
+            contract.reopen_request(request.request_id);
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use crate::*;
+use ciphra_types::{migrate_state, state_read_appending, stored_state_version, write_state_version, VersionedState};
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, PartialEq, Debug, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub enum StateVersion {
+    V1, // Initial layout
+    V2, // Appended `operators` and `liveness_timeout`; the active operator registers on its next heartbeat
+}
+
+// Add a new variant and a `migrate_from` arm with every layout change
+impl VersionedState for OracleContract {
+    type Version = StateVersion;
+
+    const CURRENT_VERSION: StateVersion = StateVersion::V2;
+    const UNVERSIONED: StateVersion = StateVersion::V1;
+
+    fn migrate_from(version: &StateVersion) -> Self {
+        match version {
+            StateVersion::V1 => state_read_appending(&(
+                Self::new_operator_registry(),
+                operators::DEFAULT_LIVENESS_TIMEOUT,
+            )),
+            StateVersion::V2 => fail(ErrorCode::Internal, "Already at the current version"),
+        }
+    }
+}
+
+#[near_bindgen]
+impl OracleContract {
+    // Called with the upgrade deploy; rewrites state from whichever version is stored
+    #[private]
+    #[init(ignore_state)]
+    pub fn migrate() -> Self {
+        migrate_state()
+    }
+
+    pub fn get_state_version(&self) -> StateVersion {
+        stored_state_version::<Self>()
+    }
+
+    pub(crate) fn write_state_version() {
+        write_state_version::<Self>();
+    }
+}
@@ -0,0 +1,273 @@
+use crate::*;
+
+// Default silence after which an operator counts as dead (1 hour)
+pub(crate) const DEFAULT_LIVENESS_TIMEOUT: u64 = 3600 * 1_000_000_000;
+
+// Bounds on the liveness timeout (seconds)
+const MIN_LIVENESS_TIMEOUT: u64 = 60;
+const MAX_LIVENESS_TIMEOUT: u64 = 7 * 86_400;
+
+// Upper bound on registered operators, active one included
+const MAX_OPERATORS: u64 = 10;
+
+// A key allowed to serve as oracle operator. Standbys heartbeat like the active operator, so
+// the owner can see which replacement is alive before rotating to it.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct OperatorRecord {
+    pub account_id: String,
+    pub registered_at: u64,
+    pub last_heartbeat: Option<u64>, // Heartbeat or fulfilment, whichever came last
+    pub fulfilled_count: u64,
+    #[borsh(serialize_with = "decimal_borsh::serialize", deserialize_with = "decimal_borsh::deserialize")]
+    #[schemars(with = "String")]
+    pub fees_earned: U128,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct OperatorStatus {
+    pub operator: OperatorRecord,
+    pub active: bool,
+    pub live: bool,
+}
+
+#[near_bindgen]
+impl OracleContract {
+    // Owner (or the governance contract once it owns the oracle) registers a standby key
+    pub fn register_operator(&mut self, account_id: AccountId) {
+        assert_owner(&self.owner);
+        ensure!(self.operators.get(&account_id).is_none(), AlreadyExists, "Operator already registered");
+        ensure!(self.operators.len() < MAX_OPERATORS, LimitExceeded, "Too many operators");
+        
+        self.operators.insert(&account_id, &Self::new_operator_record(&account_id));
+        
+        env::log_str(&format!("Operator registered: {}", account_id));
+        emit_event("operator_registered", json!({ "account_id": account_id }));
+    }
+
+    pub fn deregister_operator(&mut self, account_id: AccountId) {
+        assert_owner(&self.owner);
+        ensure!(account_id != self.operator, InvalidState, "Rotate away from the active operator first");
+        self.operators.remove(&account_id).or_fail(ErrorCode::NotFound, "Operator not registered");
+        
+        env::log_str(&format!("Operator deregistered: {}", account_id));
+        emit_event("operator_deregistered", json!({ "account_id": account_id }));
+    }
+
+    // Registered operators prove their key is alive; fulfilments count as heartbeats too
+    pub fn heartbeat(&mut self) -> u64 {
+        let account_id = env::predecessor_account_id();
+        ensure!(
+            account_id == self.operator || self.operators.get(&account_id).is_some(),
+            Unauthorized,
+            "Not a registered operator"
+        );
+        self.touch_operator(&account_id);
+        env::block_timestamp()
+    }
+
+    // Hands the active role to a live standby. Open requests carry over: the new operator can
+    // fulfil them within their current round, and timed-out ones are reopened by their requesters
+    // as usual, so Poseidon swaps keep flowing without a pause.
+    pub fn rotate_operator(&mut self, new_operator: AccountId) {
+        assert_owner(&self.owner);
+        ensure!(new_operator != self.operator, InvalidArgument, "Already the active operator");
+        ensure!(self.operators.get(&new_operator).is_some(), NotFound, "Operator not registered");
+        ensure!(self.is_operator_live(new_operator.clone()), InvalidState, "Operator has no recent heartbeat");
+        
+        let old_operator = std::mem::replace(&mut self.operator, new_operator.clone());
+        let old_live = self.is_operator_live(old_operator.clone());
+        
+        env::log_str(&format!("Operator rotated: {} -> {} | Old live: {}", old_operator, new_operator, old_live));
+        emit_event("operator_rotated", json!({
+            "old_operator": old_operator,
+            "new_operator": new_operator,
+            "old_operator_live": old_live,
+        }));
+    }
+
+    pub fn set_liveness_timeout(&mut self, timeout_seconds: u64) {
+        self.access.assert_owner_or_role(&self.owner, Role::Operator);
+        ensure!(
+            (MIN_LIVENESS_TIMEOUT..=MAX_LIVENESS_TIMEOUT).contains(&timeout_seconds),
+            InvalidArgument,
+            "Liveness timeout out of bounds"
+        );
+        self.liveness_timeout = timeout_seconds * 1_000_000_000;
+    }
+
+    pub fn is_operator_live(&self, account_id: AccountId) -> bool {
+        self.operators
+            .get(&account_id)
+            .and_then(|record| record.last_heartbeat)
+            .is_some_and(|last_heartbeat| env::block_timestamp() < last_heartbeat + self.liveness_timeout)
+    }
+
+    pub fn get_operator(&self, account_id: AccountId) -> Option<OperatorStatus> {
+        self.operators.get(&account_id).map(|record| self.operator_status(record))
+    }
+
+    pub fn get_operators(&self, from_index: Option<u64>, limit: Option<u64>) -> Paged<OperatorStatus> {
+        let accounts = self.operators.keys_as_vector();
+        Paged::from_range(accounts.len(), from_index, limit, |index| {
+            accounts.get(index).and_then(|account_id| self.get_operator(account_id))
+        })
+    }
+
+    pub(crate) fn new_operator_registry() -> UnorderedMap<AccountId, OperatorRecord> {
+        UnorderedMap::new(StorageKey::Operators)
+    }
+
+    pub(crate) fn new_operator_record(account_id: &AccountId) -> OperatorRecord {
+        OperatorRecord {
+            account_id: account_id.to_string(),
+            registered_at: env::block_timestamp(),
+            last_heartbeat: None,
+            fulfilled_count: 0,
+            fees_earned: U128(0),
+        }
+    }
+
+    // Records a sign of life; registers the active operator if it predates the registry
+    pub(crate) fn touch_operator(&mut self, account_id: &AccountId) {
+        let mut record = self.operators.get(account_id).unwrap_or_else(|| Self::new_operator_record(account_id));
+        record.last_heartbeat = Some(env::block_timestamp());
+        self.operators.insert(account_id, &record);
+    }
+
+    pub(crate) fn record_fulfilment(&mut self, account_id: &AccountId, fee: u128) {
+        if let Some(mut record) = self.operators.get(account_id) {
+            record.fulfilled_count += 1;
+            record.fees_earned = U128(record.fees_earned.0 + fee);
+            self.operators.insert(account_id, &record);
+        }
+    }
+
+    fn operator_status(&self, record: OperatorRecord) -> OperatorStatus {
+        let account_id: AccountId = record.account_id.parse().or_fail(ErrorCode::Internal, "Invalid operator");
+        OperatorStatus {
+            active: account_id == self.operator,
+            live: self.is_operator_live(account_id),
+            operator: record,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use ciphra_testing as host;
+
+    const LIVENESS_SECONDS: u64 = DEFAULT_LIVENESS_TIMEOUT / 1_000_000_000;
+
+    // Registers the standby and has it heartbeat
+    fn live_standby(contract: &mut OracleContract) {
+        host::set_caller(OWNER);
+        contract.register_operator(account(STANDBY));
+        host::set_caller(STANDBY);
+        contract.heartbeat();
+    }
+
+    #[test]
+    fn test_heartbeat_keeps_an_operator_live() {
+        let mut contract = contract();
+        assert!(!contract.is_operator_live(account(OPERATOR)));
+
+        host::set_caller(OPERATOR);
+        assert_eq!(contract.heartbeat(), host::block_timestamp());
+        assert!(contract.is_operator_live(account(OPERATOR)));
+
+        host::advance_seconds(LIVENESS_SECONDS);
+        assert!(!contract.is_operator_live(account(OPERATOR)));
+    }
+
+    #[test]
+    fn test_fulfilment_counts_towards_the_operator_record() {
+        let mut contract = contract();
+        let request = request(&mut contract, "s1");
+        fulfill(&mut contract, request.request_id);
+
+        let status = contract.get_operator(account(OPERATOR)).unwrap();
+        assert!(status.active && status.live);
+        assert_eq!(status.operator.fulfilled_count, 1);
+        assert_eq!(status.operator.fees_earned, U128(FEE));
+    }
+
+    #[test]
+    fn test_rotation_hands_open_requests_to_the_new_operator() {
+        let mut contract = contract();
+        let request = request(&mut contract, "s1");
+        live_standby(&mut contract);
+
+        host::set_caller(OWNER);
+        contract.rotate_operator(account(STANDBY));
+        assert_eq!(contract.get_config().operator, STANDBY);
+        assert!(!contract.get_operator(account(OPERATOR)).unwrap().active);
+
+        fulfill(&mut contract, request.request_id);
+        assert_eq!(contract.get_request(request.request_id).unwrap().fulfilled_by.as_deref(), Some(STANDBY));
+    }
+
+    #[test]
+    fn test_standby_can_be_deregistered() {
+        let mut contract = contract();
+        live_standby(&mut contract);
+
+        host::set_caller(OWNER);
+        contract.deregister_operator(account(STANDBY));
+        assert!(contract.get_operator(account(STANDBY)).is_none());
+        assert_eq!(contract.get_operators(None, None).total, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = r#""code":"InvalidState","message":"Operator has no recent heartbeat""#)]
+    fn test_rotation_requires_a_live_standby() {
+        let mut contract = contract();
+        live_standby(&mut contract);
+        host::advance_seconds(LIVENESS_SECONDS);
+        host::set_caller(OWNER);
+        contract.rotate_operator(account(STANDBY));
+    }
+
+    #[test]
+    #[should_panic(expected = r#""code":"NotFound","message":"Operator not registered""#)]
+    fn test_rotation_requires_a_registered_operator() {
+        let mut contract = contract();
+        host::set_caller(OWNER);
+        contract.rotate_operator(account(STANDBY));
+    }
+
+    #[test]
+    #[should_panic(expected = "Rotate away from the active operator first")]
+    fn test_active_operator_cannot_be_deregistered() {
+        let mut contract = contract();
+        host::set_caller(OWNER);
+        contract.deregister_operator(account(OPERATOR));
+    }
+
+    #[test]
+    #[should_panic(expected = "Not a registered operator")]
+    fn test_only_registered_operators_heartbeat() {
+        let mut contract = contract();
+        host::set_caller(STANDBY);
+        contract.heartbeat();
+    }
+
+    #[test]
+    #[should_panic(expected = "Operator already registered")]
+    fn test_operators_register_once() {
+        let mut contract = contract();
+        host::set_caller(OWNER);
+        contract.register_operator(account(OPERATOR));
+    }
+
+    #[test]
+    #[should_panic(expected = "Liveness timeout out of bounds")]
+    fn test_liveness_timeout_is_bounded() {
+        let mut contract = contract();
+        host::set_caller(OWNER);
+        contract.set_liveness_timeout(MIN_LIVENESS_TIMEOUT - 1);
+    }
+}
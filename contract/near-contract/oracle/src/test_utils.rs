@@ -0,0 +1,41 @@
+// Fixtures shared by the unit tests; contract calls run against the ciphra-testing host
+use crate::*;
+use ciphra_testing as host;
+
+pub(crate) const OWNER: &str = "owner.near";
+pub(crate) const SWAP: &str = "swap.near";
+pub(crate) const OPERATOR: &str = "operator.near";
+pub(crate) const STANDBY: &str = "standby.near";
+pub(crate) const FEE: u128 = DEFAULT_MIN_FEE;
+
+pub(crate) fn account(name: &str) -> AccountId {
+    name.parse().unwrap()
+}
+
+pub(crate) fn contract() -> OracleContract {
+    host::set_caller(OWNER);
+    OracleContract::new(account(OWNER), account(SWAP), account(OPERATOR))
+}
+
+// The swap contract pays the minimum fee for a verification of `swap_id`
+pub(crate) fn request(contract: &mut OracleContract, swap_id: &str) -> VerificationRequest {
+    host::set_caller(SWAP);
+    host::set_deposit(FEE);
+    let request = contract.request_verification(swap_id.to_string(), U128(FEE));
+    host::set_deposit(0);
+    request
+}
+
+// The active operator answers and the swap contract records the result
+pub(crate) fn fulfill(contract: &mut OracleContract, request_id: u64) {
+    host::set_caller(contract.operator.as_str());
+    let _ = contract.fulfill(request_id, result());
+    host::set_self_caller();
+    host::set_promise_results(vec![Some(Vec::new())]);
+    contract.on_verification_submitted(request_id);
+    host::set_promise_results(Vec::new());
+}
+
+pub(crate) fn result() -> VerificationResult {
+    VerificationResult { poseidon_hash: "0a1b2c".to_string(), secret_matches: true }
+}
@@ -0,0 +1,26 @@
+use crate::*;
+use crate::events::EVENTS;
+
+#[near_bindgen]
+impl OrchestratorContract {
+    // Configuration and counters in the shape every Ciphra contract shares
+    pub fn get_contract_info(&self) -> ContractInfo {
+        ContractInfo::new(
+            EVENTS.contract,
+            env!("CARGO_PKG_VERSION"),
+            format!("{:?}", self.get_state_version()),
+            &self.owner,
+            self.access.pending_owner.as_ref(),
+            &self.pause_guard,
+        )
+        .bound("min_order_duration", MIN_ORDER_DURATION)
+        .bound("max_order_duration", MAX_ORDER_DURATION)
+        .counter("orders", self.orders.len())
+        .counter("open", self.stats.open_count)
+        .counter("settled", self.stats.settled_count)
+        .counter("cancelled", self.stats.cancelled_count)
+        .counter("failed", self.stats.failed_count)
+        .counter("settlements", self.settlements.next_settlement_id)
+        .rate_limit(self.rate_limiter.config)
+    }
+}
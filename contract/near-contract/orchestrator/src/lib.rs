@@ -0,0 +1,601 @@
+use ciphra_types::{assert_owner, decimal_borsh, ensure, fail, ErrorCode, OrFail};
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::collections::{LookupMap, LookupSet, UnorderedMap};
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::serde_json::json;
+use near_sdk::{
+    env, ext_contract, is_promise_success, near_bindgen, AccountId, BorshStorageKey, Gas, PanicOnDefault, Promise,
+    PromiseError,
+};
+use schemars::JsonSchema;
+
+pub use ciphra_types::{
+    AccessControl, AccessStatus, ContractInfo, EscrowMetadata, Paged, PauseGuard, PauseStatus, RateLimitConfig,
+    RateLimiter, Role, SettlementLedger, SwapStatus,
+};
+
+mod access;
+mod events;
+mod info;
+mod migration;
+mod rate_limit;
+mod settlement;
+
+use events::emit_event;
+
+pub use migration::StateVersion;
+
+const GAS_FOR_ESCROW_CREATE: Gas = Gas::from_tgas(30);
+const GAS_FOR_ESCROW_SETTLE: Gas = Gas::from_tgas(60);
+const GAS_FOR_SWAP_VIEW: Gas = Gas::from_tgas(10);
+const GAS_FOR_STORAGE_DEPOSIT: Gas = Gas::from_tgas(10);
+const GAS_FOR_CREATE_CALLBACK: Gas = Gas::from_tgas(20);
+const GAS_FOR_SETTLE_CALLBACK: Gas = Gas::from_tgas(20);
+// The swap check forwards the escrow call and its callback
+const GAS_FOR_CHECK_CALLBACK: Gas = Gas::from_tgas(110);
+
+// Bounds on how long an order may wait for its swap before the buyer can cancel (seconds)
+const MIN_ORDER_DURATION: u64 = 3_600;
+const MAX_ORDER_DURATION: u64 = 90 * 86_400;
+
+#[derive(BorshSerialize, BorshStorageKey)]
+pub enum StorageKey {
+    Orders,
+    OrdersByBuyer,
+    OrdersBySeller,
+    PendingSettlements,
+    ClaimableSettlements,
+    RateLimits,
+    UsedSwaps,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum OrderStatus {
+    Opening,   // Escrow creation in flight
+    Open,      // Escrow funded, waiting for the swap
+    Settled,   // Swap completed and the escrow released to the seller
+    Cancelled, // Deadline passed without delivery; escrow refunded to the buyer
+    Failed,    // Escrow creation failed; deposit returned to the buyer
+}
+
+// Pay on delivery across chains: the buyer's payment sits in an escrow this contract arbitrates,
+// and is released to the seller once the linked swap - the seller's delivery to the buyer - completes
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Order {
+    pub order_id: String,
+    pub buyer: String,
+    pub seller: String,
+    #[borsh(serialize_with = "decimal_borsh::serialize", deserialize_with = "decimal_borsh::deserialize")]
+    #[schemars(with = "String")]
+    pub amount: U128,
+    pub escrow_id: String,
+    pub swap_id: String,
+    #[borsh(serialize_with = "decimal_borsh::serialize", deserialize_with = "decimal_borsh::deserialize")]
+    #[schemars(with = "String")]
+    pub min_swap_amount: U128, // Smallest delivery the buyer accepts
+    pub deadline: u64,         // Escrow release time; the order can be cancelled after it
+    pub status: OrderStatus,
+    pub created_at: u64,
+    pub closed_at: Option<u64>,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Default)]
+pub struct OrderStats {
+    pub open_count: u64,
+    pub settled_count: u64,
+    pub cancelled_count: u64,
+    pub failed_count: u64,
+}
+
+// Subset of the swap contract's AtomicSwap record
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SwapSnapshot {
+    pub initiator: String,
+    pub participant: String,
+    pub amount: U128,
+    pub time_lock: u64,
+    pub status: SwapStatus,
+    pub created_at: u64,
+}
+
+// Only the generated ext_swap module is used
+#[allow(dead_code)]
+#[ext_contract(ext_swap)]
+trait AtomicSwapContract {
+    fn get_swap(&self, swap_id: String) -> Option<SwapSnapshot>;
+}
+
+// Only the generated ext_escrow module is used; optional escrow arguments left out default to None
+#[allow(dead_code)]
+#[ext_contract(ext_escrow)]
+trait EscrowContract {
+    fn create_escrow(
+        &mut self,
+        escrow_id: String,
+        beneficiary: AccountId,
+        release_time: u64,
+        arbiter: Option<AccountId>,
+        metadata: EscrowMetadata,
+    );
+    fn release_funds(&mut self, escrow_id: String);
+    fn refund_escrow(&mut self, escrow_id: String);
+    fn storage_deposit(&mut self, account_id: Option<AccountId>, registration_only: Option<bool>);
+}
+
+// Version and link come from the package; orders also emit NEP-297 events
+#[near_bindgen(contract_metadata(
+    standard(standard = "nep330", version = "1.1.0"),
+    standard(standard = "nep297", version = "1.0.0"),
+))]
+#[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
+pub struct OrchestratorContract {
+    pub owner: AccountId,
+    pub swap_contract: AccountId,
+    pub escrow_contract: AccountId,
+    pub orders: UnorderedMap<String, Order>,
+    pub orders_by_buyer: LookupMap<AccountId, Vec<String>>,
+    pub orders_by_seller: LookupMap<AccountId, Vec<String>>,
+    pub stats: OrderStats,
+    pub pause_guard: PauseGuard,       // Guardians may pause new orders but not unpause
+    pub access: AccessControl,         // Pending owner transfer and delegated roles
+    pub settlements: SettlementLedger, // Refunds to buyers awaiting their transfer callback
+    pub rate_limiter: RateLimiter,     // Bounds orders opened per buyer
+    pub used_swaps: LookupSet<String>, // Swaps that released an order's escrow, or are releasing one
+}
+
+#[near_bindgen]
+impl OrchestratorContract {
+    #[init]
+    pub fn new(owner: AccountId, swap_contract: AccountId, escrow_contract: AccountId) -> Self {
+        Self::write_state_version();
+        Self {
+            owner,
+            swap_contract,
+            escrow_contract,
+            orders: UnorderedMap::new(StorageKey::Orders),
+            orders_by_buyer: LookupMap::new(StorageKey::OrdersByBuyer),
+            orders_by_seller: LookupMap::new(StorageKey::OrdersBySeller),
+            stats: OrderStats::default(),
+            pause_guard: PauseGuard::default(),
+            access: AccessControl::default(),
+            settlements: Self::new_settlement_ledger(),
+            rate_limiter: Self::new_rate_limiter(),
+            used_swaps: Self::new_used_swaps(),
+        }
+    }
+
+    // Buyer pays into an escrow released by the completion of `swap_id`, in which the seller
+    // must deliver at least `min_swap_amount` to the buyer. The order only opens if the escrow
+    // is created; otherwise the deposit comes back.
+    #[payable]
+    pub fn open_order(
+        &mut self,
+        order_id: String,
+        seller: AccountId,
+        swap_id: String,
+        min_swap_amount: U128,
+        duration_seconds: u64,
+        metadata: EscrowMetadata,
+    ) -> Promise {
+        let buyer = env::predecessor_account_id();
+        let amount = env::attached_deposit();
+        
+        self.pause_guard.assert_not_paused();
+        ensure!(amount.as_yoctonear() > 0, InsufficientFunds, "Must attach NEAR tokens");
+        self.rate_limiter.record(&buyer, 1);
+        ensure!(self.orders.get(&order_id).is_none(), AlreadyExists, "Order ID already exists");
+        ensure!(seller != buyer, InvalidArgument, "Seller cannot be the buyer");
+        ensure!(!self.used_swaps.contains(&swap_id), AlreadyExists, "Swap already settled an order");
+        ensure!(
+            (MIN_ORDER_DURATION..=MAX_ORDER_DURATION).contains(&duration_seconds),
+            InvalidArgument,
+            "Order duration out of bounds"
+        );
+        metadata.validate();
+        
+        let order = Order {
+            order_id: order_id.clone(),
+            buyer: buyer.to_string(),
+            seller: seller.to_string(),
+            amount: U128(amount.as_yoctonear()),
+            escrow_id: format!("{}:{}", env::current_account_id(), order_id),
+            swap_id: swap_id.clone(),
+            min_swap_amount,
+            deadline: env::block_timestamp() + duration_seconds * 1_000_000_000,
+            status: OrderStatus::Opening,
+            created_at: env::block_timestamp(),
+            closed_at: None,
+        };
+        self.orders.insert(&order_id, &order);
+        Self::index_order(&mut self.orders_by_buyer, &buyer, &order_id);
+        Self::index_order(&mut self.orders_by_seller, &seller, &order_id);
+        
+        env::log_str(&format!(
+            "Order opened: {} | Swap: {} | Seller: {} | Amount: {}",
+            order_id, swap_id, seller, amount
+        ));
+        emit_event("order_opened", json!({
+            "order_id": order_id,
+            "buyer": order.buyer,
+            "seller": order.seller,
+            "amount": order.amount,
+            "escrow_id": order.escrow_id,
+            "swap_id": swap_id,
+            "min_swap_amount": min_swap_amount,
+            "deadline": order.deadline,
+        }));
+        
+        // This contract arbitrates the escrow, so only the swap check below can release it early
+        ext_escrow::ext(self.escrow_contract.clone())
+            .with_attached_deposit(amount)
+            .with_static_gas(GAS_FOR_ESCROW_CREATE)
+            .create_escrow(order.escrow_id, seller, order.deadline, Some(env::current_account_id()), metadata)
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_CREATE_CALLBACK)
+                    .on_escrow_created(order_id),
+            )
+    }
+
+    // A failed creation returns the deposit to this contract, which passes it back to the buyer
+    #[private]
+    pub fn on_escrow_created(&mut self, order_id: String) -> bool {
+        let mut order = self.orders.get(&order_id).or_fail(ErrorCode::NotFound, "Order not found");
+        if is_promise_success() {
+            order.status = OrderStatus::Open;
+            self.orders.insert(&order_id, &order);
+            self.stats.open_count += 1;
+            return true;
+        }
+        
+        order.status = OrderStatus::Failed;
+        order.closed_at = Some(env::block_timestamp());
+        self.orders.insert(&order_id, &order);
+        self.stats.failed_count += 1;
+        
+        env::log_str(&format!("Order failed: {} | Escrow not created", order_id));
+        emit_event("order_failed", json!({ "order_id": order_id, "buyer": order.buyer, "amount": order.amount }));
+        
+        let buyer: AccountId = order.buyer.parse().or_fail(ErrorCode::Internal, "Invalid buyer");
+        self.settle(buyer, order.amount.0);
+        false
+    }
+
+    // Anyone may settle once the linked swap has completed; the swap state is read from the
+    // swap contract before the escrow is released
+    pub fn settle_order(&mut self, order_id: String) -> Promise {
+        let order = self.open_order_record(&order_id);
+        ensure!(!self.used_swaps.contains(&order.swap_id), AlreadyExists, "Swap already settled an order");
+        self.check_swap(&order).then(
+            Self::ext(env::current_account_id())
+                .with_static_gas(GAS_FOR_CHECK_CALLBACK)
+                .on_swap_checked(order_id, true),
+        )
+    }
+
+    // Anyone may cancel after the deadline, unless the linked swap completed or still can
+    pub fn cancel_order(&mut self, order_id: String) -> Promise {
+        let order = self.open_order_record(&order_id);
+        ensure!(env::block_timestamp() >= order.deadline, TooEarly, "Order deadline not reached");
+        self.check_swap(&order).then(
+            Self::ext(env::current_account_id())
+                .with_static_gas(GAS_FOR_CHECK_CALLBACK)
+                .on_swap_checked(order_id, false),
+        )
+    }
+
+    #[private]
+    pub fn on_swap_checked(
+        &mut self,
+        order_id: String,
+        settle: bool,
+        #[callback_result] swap: Result<Option<SwapSnapshot>, PromiseError>,
+    ) -> Promise {
+        let order = self.open_order_record(&order_id);
+        let swap = swap.or_fail(ErrorCode::Internal, "Swap contract call failed");
+        // Only a swap from the seller to the buyer of at least the agreed amount, started for this
+        // order, counts as delivery
+        let delivery = swap.filter(|swap| {
+            swap.initiator == order.seller
+                && swap.participant == order.buyer
+                && swap.amount.0 >= order.min_swap_amount.0
+                && swap.created_at >= order.created_at
+        });
+        let escrow = ext_escrow::ext(self.escrow_contract.clone()).with_static_gas(GAS_FOR_ESCROW_SETTLE);
+        let callback = Self::ext(env::current_account_id()).with_static_gas(GAS_FOR_SETTLE_CALLBACK);
+        
+        if settle {
+            ensure!(
+                delivery.is_some_and(|swap| swap.status == SwapStatus::Completed),
+                InvalidState,
+                "Linked swap has not completed"
+            );
+            // Reserved until the release resolves, so one delivery can't settle two orders
+            ensure!(self.used_swaps.insert(&order.swap_id), AlreadyExists, "Swap already settled an order");
+            return escrow.release_funds(order.escrow_id).then(callback.on_escrow_settled(order_id, true));
+        }
+        
+        let pending = delivery.is_some_and(|swap| {
+            swap.status == SwapStatus::Completed
+                || (matches!(swap.status, SwapStatus::Initiated | SwapStatus::Locked)
+                    && env::block_timestamp() < swap.time_lock)
+        });
+        ensure!(!pending, InvalidState, "Linked swap completed or can still complete");
+        escrow.refund_escrow(order.escrow_id).then(callback.on_escrow_settled(order_id, false))
+    }
+
+    // Closes the order once the escrow paid out; a refund arrives here and goes on to the buyer
+    #[private]
+    pub fn on_escrow_settled(&mut self, order_id: String, released: bool) -> bool {
+        let mut order = self.open_order_record(&order_id);
+        if !is_promise_success() {
+            if released {
+                self.used_swaps.remove(&order.swap_id);
+            }
+            env::log_str(&format!("Order escrow settlement failed: {}", order_id));
+            return false;
+        }
+        order.status = if released { OrderStatus::Settled } else { OrderStatus::Cancelled };
+        order.closed_at = Some(env::block_timestamp());
+        self.orders.insert(&order_id, &order);
+        self.stats.open_count -= 1;
+        
+        if released {
+            self.stats.settled_count += 1;
+            env::log_str(&format!("Order settled: {} | Swap: {}", order_id, order.swap_id));
+            emit_event("order_settled", json!({
+                "order_id": order_id,
+                "seller": order.seller,
+                "amount": order.amount,
+                "swap_id": order.swap_id,
+            }));
+        } else {
+            self.stats.cancelled_count += 1;
+            env::log_str(&format!("Order cancelled: {} | Refunded: {}", order_id, order.amount.0));
+            emit_event("order_cancelled", json!({
+                "order_id": order_id,
+                "buyer": order.buyer,
+                "amount": order.amount,
+            }));
+            let buyer: AccountId = order.buyer.parse().or_fail(ErrorCode::Internal, "Invalid buyer");
+            self.settle(buyer, order.amount.0);
+        }
+        true
+    }
+
+    // Prepays this contract's storage on the escrow contract, which every order's escrow locks
+    #[payable]
+    pub fn fund_escrow_storage(&mut self) -> Promise {
+        ensure!(env::attached_deposit().as_yoctonear() > 0, InsufficientFunds, "Must attach NEAR tokens");
+        ext_escrow::ext(self.escrow_contract.clone())
+            .with_attached_deposit(env::attached_deposit())
+            .with_static_gas(GAS_FOR_STORAGE_DEPOSIT)
+            .storage_deposit(Some(env::current_account_id()), None)
+    }
+
+    pub fn get_order(&self, order_id: String) -> Option<Order> {
+        self.orders.get(&order_id)
+    }
+
+    pub fn get_orders_by_buyer(
+        &self,
+        account_id: AccountId,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Paged<Order> {
+        let order_ids = self.orders_by_buyer.get(&account_id).unwrap_or_default();
+        Paged::from_ids(&order_ids, from_index, limit, |order_id| self.orders.get(order_id))
+    }
+
+    pub fn get_orders_by_seller(
+        &self,
+        account_id: AccountId,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Paged<Order> {
+        let order_ids = self.orders_by_seller.get(&account_id).unwrap_or_default();
+        Paged::from_ids(&order_ids, from_index, limit, |order_id| self.orders.get(order_id))
+    }
+
+    pub fn set_contracts(&mut self, swap_contract: AccountId, escrow_contract: AccountId) {
+        assert_owner(&self.owner);
+        ensure!(self.stats.open_count == 0, InvalidState, "Orders still open");
+        self.swap_contract = swap_contract;
+        self.escrow_contract = escrow_contract;
+    }
+
+    pub fn get_swap_contract(&self) -> AccountId {
+        self.swap_contract.clone()
+    }
+
+    pub fn get_escrow_contract(&self) -> AccountId {
+        self.escrow_contract.clone()
+    }
+
+    pub fn get_owner(&self) -> AccountId {
+        self.owner.clone()
+    }
+
+    // Owner or guardian; only stops new orders - open orders still settle and cancel
+    pub fn pause(&mut self) {
+        let caller = self.pause_guard.pause(&self.owner);
+        env::log_str(&format!("Contract paused by: {}", caller));
+        emit_event("paused", json!({ "account_id": caller }));
+    }
+
+    // Guardians can only pause; resuming is an owner decision
+    pub fn unpause(&mut self) {
+        self.pause_guard.unpause(&self.owner);
+        env::log_str("Contract unpaused");
+        emit_event("unpaused", json!({ "account_id": self.owner }));
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.pause_guard.paused
+    }
+
+    pub fn get_pause_status(&self) -> PauseStatus {
+        self.pause_guard.status()
+    }
+
+    pub fn add_guardian(&mut self, account_id: AccountId) {
+        self.pause_guard.add_guardian(&self.owner, account_id);
+    }
+
+    pub fn remove_guardian(&mut self, account_id: AccountId) {
+        self.pause_guard.remove_guardian(&self.owner, &account_id);
+    }
+
+    pub fn get_guardians(&self) -> Vec<AccountId> {
+        self.pause_guard.guardians.clone()
+    }
+
+    pub fn is_swap_used(&self, swap_id: String) -> bool {
+        self.used_swaps.contains(&swap_id)
+    }
+
+    pub(crate) fn new_used_swaps() -> LookupSet<String> {
+        LookupSet::new(StorageKey::UsedSwaps)
+    }
+
+    fn open_order_record(&self, order_id: &String) -> Order {
+        let order = self.orders.get(order_id).or_fail(ErrorCode::NotFound, "Order not found");
+        ensure!(order.status == OrderStatus::Open, InvalidState, "Order not open");
+        order
+    }
+
+    fn check_swap(&self, order: &Order) -> Promise {
+        ext_swap::ext(self.swap_contract.clone())
+            .with_static_gas(GAS_FOR_SWAP_VIEW)
+            .get_swap(order.swap_id.clone())
+    }
+
+    fn index_order(index: &mut LookupMap<AccountId, Vec<String>>, account_id: &AccountId, order_id: &str) {
+        let mut order_ids = index.get(account_id).unwrap_or_default();
+        order_ids.push(order_id.to_string());
+        index.insert(account_id, &order_ids);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ciphra_testing as host;
+
+    const BUYER: &str = "alice.near";
+    const SELLER: &str = "bob.near";
+
+    fn account(name: &str) -> AccountId {
+        name.parse().unwrap()
+    }
+
+    fn contract() -> OrchestratorContract {
+        host::set_caller("owner.near");
+        OrchestratorContract::new(account("owner.near"), account("swap.near"), account("escrow.near"))
+    }
+
+    // Order for `swap_id` whose escrow was created
+    fn open(contract: &mut OrchestratorContract, order_id: &str, swap_id: &str) -> Order {
+        host::set_caller(BUYER);
+        host::set_deposit(host::NEAR);
+        contract.open_order(
+            order_id.to_string(),
+            account(SELLER),
+            swap_id.to_string(),
+            U128(1_000),
+            86_400,
+            EscrowMetadata {
+                title: "Order".to_string(),
+                category: "test".to_string(),
+                content_hash: None,
+                external_uri: None,
+            },
+        );
+        host::set_deposit(0);
+        host::set_self_caller();
+        host::set_promise_results(vec![Some(Vec::new())]);
+        assert!(contract.on_escrow_created(order_id.to_string()));
+        host::clear_receipts();
+        contract.get_order(order_id.to_string()).unwrap()
+    }
+
+    fn delivery(created_at: u64) -> SwapSnapshot {
+        SwapSnapshot {
+            initiator: SELLER.to_string(),
+            participant: BUYER.to_string(),
+            amount: U128(1_000),
+            time_lock: created_at + 3_600 * 1_000_000_000,
+            status: SwapStatus::Completed,
+            created_at,
+        }
+    }
+
+    fn swap_checked(contract: &mut OrchestratorContract, order_id: &str, swap: SwapSnapshot) {
+        host::set_self_caller();
+        contract.on_swap_checked(order_id.to_string(), true, Ok(Some(swap)));
+    }
+
+    fn escrow_settled(contract: &mut OrchestratorContract, order_id: &str, success: bool) -> bool {
+        host::set_self_caller();
+        host::set_promise_results(vec![success.then(Vec::new)]);
+        contract.on_escrow_settled(order_id.to_string(), true)
+    }
+
+    #[test]
+    fn completed_swap_settles_the_order_once() {
+        let mut contract = contract();
+        open(&mut contract, "o1", "swap-1");
+        host::advance_seconds(60);
+        swap_checked(&mut contract, "o1", delivery(host::block_timestamp()));
+        assert_eq!(host::function_calls()[0], ("escrow.near".to_string(), "release_funds".to_string()));
+        assert!(contract.is_swap_used("swap-1".to_string()));
+        
+        assert!(escrow_settled(&mut contract, "o1", true));
+        assert_eq!(contract.get_order("o1".to_string()).unwrap().status, OrderStatus::Settled);
+    }
+
+    #[test]
+    #[should_panic(expected = "Linked swap has not completed")]
+    fn swap_created_before_the_order_is_rejected() {
+        let mut contract = contract();
+        let order = open(&mut contract, "o1", "swap-1");
+        swap_checked(&mut contract, "o1", delivery(order.created_at - 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "Swap already settled an order")]
+    fn swap_cannot_settle_a_second_order() {
+        let mut contract = contract();
+        open(&mut contract, "o1", "swap-1");
+        open(&mut contract, "o2", "swap-1");
+        swap_checked(&mut contract, "o1", delivery(host::block_timestamp()));
+        
+        swap_checked(&mut contract, "o2", delivery(host::block_timestamp()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Swap already settled an order")]
+    fn used_swap_cannot_back_a_new_order() {
+        let mut contract = contract();
+        open(&mut contract, "o1", "swap-1");
+        swap_checked(&mut contract, "o1", delivery(host::block_timestamp()));
+        escrow_settled(&mut contract, "o1", true);
+        
+        open(&mut contract, "o2", "swap-1");
+    }
+
+    #[test]
+    fn failed_release_frees_the_swap() {
+        let mut contract = contract();
+        open(&mut contract, "o1", "swap-1");
+        swap_checked(&mut contract, "o1", delivery(host::block_timestamp()));
+        
+        assert!(!escrow_settled(&mut contract, "o1", false));
+        assert!(!contract.is_swap_used("swap-1".to_string()));
+        assert_eq!(contract.get_order("o1".to_string()).unwrap().status, OrderStatus::Open);
+    }
+}
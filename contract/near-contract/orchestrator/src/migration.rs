@@ -0,0 +1,42 @@
+use crate::*;
+use ciphra_types::{migrate_state, state_read_appending, stored_state_version, write_state_version, VersionedState};
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, PartialEq, Debug, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub enum StateVersion {
+    V1, // Initial layout
+    V2, // Appended `used_swaps`
+}
+
+// Add a new variant and a `migrate_from` arm with every layout change
+impl VersionedState for OrchestratorContract {
+    type Version = StateVersion;
+
+    const CURRENT_VERSION: StateVersion = StateVersion::V2;
+    const UNVERSIONED: StateVersion = StateVersion::V1;
+
+    fn migrate_from(version: &StateVersion) -> Self {
+        match version {
+            StateVersion::V1 => state_read_appending(&Self::new_used_swaps()),
+            StateVersion::V2 => fail(ErrorCode::Internal, "Already at the current version"),
+        }
+    }
+}
+
+#[near_bindgen]
+impl OrchestratorContract {
+    // Called with the upgrade deploy; rewrites state from whichever version is stored
+    #[private]
+    #[init(ignore_state)]
+    pub fn migrate() -> Self {
+        migrate_state()
+    }
+
+    pub fn get_state_version(&self) -> StateVersion {
+        stored_state_version::<Self>()
+    }
+
+    pub(crate) fn write_state_version() {
+        write_state_version::<Self>();
+    }
+}
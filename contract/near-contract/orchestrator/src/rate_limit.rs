@@ -0,0 +1,28 @@
+use crate::*;
+use ciphra_types::DEFAULT_RATE_LIMIT;
+
+#[near_bindgen]
+impl OrchestratorContract {
+    // Bounds the orders one buyer opens; the escrow contract should exempt this contract, as
+    // every escrow it opens has the orchestrator as depositor. None turns limiting off.
+    pub fn set_rate_limit(&mut self, config: Option<RateLimitConfig>) {
+        self.access.assert_owner_or_role(&self.owner, Role::Operator);
+        self.rate_limiter.set_config(config);
+        
+        env::log_str(&format!("Rate limit updated: {:?}", config));
+        emit_event("rate_limit_updated", json!({ "config": config }));
+    }
+
+    pub fn get_rate_limit(&self) -> Option<RateLimitConfig> {
+        self.rate_limiter.config
+    }
+
+    // Orders the account can still open in the current window; None when limiting is off
+    pub fn get_rate_limit_remaining(&self, account_id: AccountId) -> Option<u16> {
+        self.rate_limiter.remaining(&account_id)
+    }
+
+    pub(crate) fn new_rate_limiter() -> RateLimiter {
+        RateLimiter::new(StorageKey::RateLimits, Some(DEFAULT_RATE_LIMIT))
+    }
+}
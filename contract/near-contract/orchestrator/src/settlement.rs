@@ -0,0 +1,54 @@
+use crate::*;
+
+const GAS_FOR_SETTLEMENT_CALLBACK: Gas = Gas::from_tgas(10);
+
+#[near_bindgen]
+impl OrchestratorContract {
+    // Rolls a failed refund back into a balance the receiver claims with `claim_settlement`
+    #[private]
+    pub fn on_settlement(&mut self, settlement_id: u64) -> bool {
+        let Some(settlement) = self.settlements.resolve(settlement_id, is_promise_success()) else {
+            return true;
+        };
+        self.settlements.credit(&settlement.receiver, settlement.amount.0);
+        
+        env::log_str(&format!(
+            "Settlement failed: {} | Claimable by: {} | Amount: {}",
+            settlement_id, settlement.receiver, settlement.amount.0
+        ));
+        emit_event("settlement_failed", json!({
+            "settlement_id": settlement_id,
+            "receiver": settlement.receiver,
+            "amount": settlement.amount,
+        }));
+        
+        false
+    }
+
+    // Retries a failed settlement; a failure again leaves it claimable
+    pub fn claim_settlement(&mut self) -> Promise {
+        let account_id = env::predecessor_account_id();
+        let amount_yocto = self.settlements.claim(&account_id);
+        
+        env::log_str(&format!("Settlement claimed: {} | Amount: {}", account_id, amount_yocto));
+        
+        self.settle(account_id, amount_yocto)
+    }
+
+    pub fn get_claimable_settlement(&self, account_id: AccountId) -> U128 {
+        U128(self.settlements.claimable_of(&account_id))
+    }
+
+    pub(crate) fn new_settlement_ledger() -> SettlementLedger {
+        SettlementLedger::new(StorageKey::PendingSettlements, StorageKey::ClaimableSettlements)
+    }
+
+    // Sends NEAR through the settlement ledger so a failed transfer stays claimable
+    pub(crate) fn settle(&mut self, receiver: AccountId, amount_yocto: u128) -> Promise {
+        self.settlements.send(receiver, amount_yocto, |settlement_id| {
+            Self::ext(env::current_account_id())
+                .with_static_gas(GAS_FOR_SETTLEMENT_CALLBACK)
+                .on_settlement(settlement_id)
+        })
+    }
+}
@@ -0,0 +1,140 @@
+use crate::*;
+
+const MIN_HANDLE_LENGTH: usize = 3;
+const MAX_HANDLE_LENGTH: usize = 32;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct HandleRecord {
+    pub handle: String,
+    pub owner: String,
+    pub registered_at: u64,
+}
+
+// Handles are stored lowercase without the leading '@'
+fn normalize_handle(handle: &str) -> String {
+    let name = handle.strip_prefix('@').unwrap_or(handle).to_lowercase();
+    ensure!(
+        name.len() >= MIN_HANDLE_LENGTH && name.len() <= MAX_HANDLE_LENGTH,
+        InvalidArgument,
+        "Handle must be 3-32 characters"
+    );
+    ensure!(
+        name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_'),
+        InvalidArgument,
+        "Handle may only contain a-z, 0-9 and _"
+    );
+    name
+}
+
+#[near_bindgen]
+impl P2PTransferContract {
+    // Register a payment handle ("@name") for the caller; excess deposit is refunded
+    #[payable]
+    pub fn register_handle(&mut self, handle: String) -> HandleRecord {
+        let owner = env::predecessor_account_id();
+        let deposit_yocto = env::attached_deposit().as_yoctonear();
+        let handle = normalize_handle(&handle);
+        
+        ensure!(self.handles.get(&handle).is_none(), AlreadyExists, "Handle already registered");
+        ensure!(
+            deposit_yocto >= self.handle_registration_fee,
+            InsufficientFunds,
+            "Attached deposit does not cover the registration fee"
+        );
+        
+        let record = HandleRecord {
+            handle: handle.clone(),
+            owner: owner.to_string(),
+            registered_at: env::block_timestamp(),
+        };
+        self.handles.insert(&handle, &record);
+        
+        env::log_str(&format!("Handle registered: @{} | Owner: {}", handle, owner));
+        
+        self.distribute_fee(self.handle_registration_fee);
+        
+        let excess_yocto = deposit_yocto - self.handle_registration_fee;
+        if excess_yocto > 0 {
+            Promise::new(owner).transfer(NearToken::from_yoctonear(excess_yocto));
+        }
+        
+        record
+    }
+
+    pub fn transfer_handle(&mut self, handle: String, new_owner: AccountId) {
+        let handle = normalize_handle(&handle);
+        let mut record = self.handles.get(&handle).or_fail(ErrorCode::NotFound, "Handle not found");
+        ensure!(
+            env::predecessor_account_id() == record.owner,
+            Unauthorized,
+            "Only handle owner"
+        );
+        
+        record.owner = new_owner.to_string();
+        self.handles.insert(&handle, &record);
+        
+        env::log_str(&format!("Handle transferred: @{} | New owner: {}", handle, new_owner));
+    }
+
+    pub fn release_handle(&mut self, handle: String) {
+        let handle = normalize_handle(&handle);
+        let record = self.handles.get(&handle).or_fail(ErrorCode::NotFound, "Handle not found");
+        ensure!(
+            env::predecessor_account_id() == record.owner,
+            Unauthorized,
+            "Only handle owner"
+        );
+        
+        self.handles.remove(&handle);
+        
+        env::log_str(&format!("Handle released: @{}", handle));
+    }
+
+    pub fn resolve_handle(&self, handle: String) -> Option<AccountId> {
+        self.handles
+            .get(&normalize_handle(&handle))
+            .map(|record| record.owner.parse().or_fail(ErrorCode::InvalidArgument, "Invalid owner"))
+    }
+
+    pub fn get_handle(&self, handle: String) -> Option<HandleRecord> {
+        self.handles.get(&normalize_handle(&handle))
+    }
+
+    pub fn set_handle_registration_fee(&mut self, fee: U128) {
+        assert_owner(&self.owner);
+        self.handle_registration_fee = fee.0;
+    }
+
+    // Accepts either an account id or a registered "@handle"
+    pub(crate) fn resolve_recipient(&self, recipient: &str) -> AccountId {
+        if recipient.starts_with('@') {
+            self.resolve_handle(recipient.to_string()).or_fail(ErrorCode::NotFound, "Handle not registered")
+        } else {
+            recipient.parse().or_fail(ErrorCode::InvalidArgument, "Invalid recipient account")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::*;
+
+    #[test]
+    fn test_resolve_account_id() {
+        let contract = contract();
+        assert_eq!(contract.resolve_recipient(RECIPIENT), account(RECIPIENT));
+    }
+
+    #[test]
+    #[should_panic(expected = r#""code":"InvalidArgument","message":"Invalid recipient account""#)]
+    fn test_malformed_recipient_is_invalid_argument() {
+        contract().resolve_recipient("Not An Account!");
+    }
+
+    #[test]
+    #[should_panic(expected = r#""code":"NotFound""#)]
+    fn test_unregistered_handle() {
+        contract().resolve_recipient("@nobody");
+    }
+}
@@ -0,0 +1,48 @@
+use crate::*;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub enum AuditAction {
+    TransferCreated,
+    TransferStatusChanged,
+    NoteCreated,
+    NullifierSpent,
+    BalanceDeposited,
+    BalanceWithdrawn,
+    ConfigChanged,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AuditEntry {
+    pub sequence: u64,
+    pub action: AuditAction,
+    pub actor: String,
+    pub ids: Vec<String>,
+    pub detail: Option<String>,
+    pub timestamp: u64,
+}
+
+#[near_bindgen]
+impl P2PTransferContract {
+    // Append-only history in sequence order - page through with from_index
+    pub fn get_audit_log(&self, from_index: Option<u64>, limit: Option<u64>) -> Paged<AuditEntry> {
+        Paged::from_range(self.audit_log.len(), from_index, limit, |index| self.audit_log.get(index))
+    }
+
+    pub fn get_audit_log_length(&self) -> u64 {
+        self.audit_log.len()
+    }
+
+    pub(crate) fn record_audit(&mut self, action: AuditAction, ids: Vec<String>, detail: Option<String>) {
+        let entry = AuditEntry {
+            sequence: self.audit_log.len(),
+            action,
+            actor: env::predecessor_account_id().to_string(),
+            ids,
+            detail,
+            timestamp: env::block_timestamp(),
+        };
+        self.audit_log.push(&entry);
+    }
+}
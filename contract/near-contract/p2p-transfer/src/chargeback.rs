@@ -0,0 +1,526 @@
+use crate::*;
+use near_sdk::{ext_contract, PromiseError};
+
+const GAS_FOR_CREATE_ESCROW: Gas = Gas::from_tgas(20);
+const GAS_FOR_ESCROW_CALL: Gas = Gas::from_tgas(10);
+const GAS_FOR_GET_ESCROW: Gas = Gas::from_tgas(10);
+const GAS_FOR_DISPUTE_CALLBACK: Gas = Gas::from_tgas(15);
+const GAS_FOR_STORAGE_DEPOSIT: Gas = Gas::from_tgas(10);
+
+// Arbitration settings for protected (chargeback-eligible) payments; durations in nanoseconds
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ChargebackConfig {
+    pub escrow_contract: String,
+    pub arbiter: String,
+    pub dispute_window: u64,
+    pub arbitration_period: u64,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ChargebackHold {
+    pub transfer_id: String,
+    pub dispute_deadline: u64,
+    pub escrow_id: Option<String>, // Set once the sender disputes
+    pub disputed_at: Option<u64>,
+}
+
+// Subset of the escrow contract's Escrow record needed to follow a dispute
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct EscrowSnapshot {
+    pub status: EscrowStatus,
+    pub amount: U128,
+    #[serde(default)]
+    pub dispute_payout: Option<DisputePayoutSnapshot>,
+}
+
+// Share of a settled dispute the escrow paid back to this contract as depositor
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DisputePayoutSnapshot {
+    pub depositor: U128,
+}
+
+// Only the generated ext_escrow module is used
+#[allow(dead_code)]
+#[ext_contract(ext_escrow)]
+trait EscrowArbitration {
+    fn create_escrow(
+        &mut self,
+        escrow_id: String,
+        beneficiary: AccountId,
+        release_time: u64,
+        arbiter: Option<AccountId>,
+        metadata: EscrowMetadata,
+    );
+    fn raise_dispute(&mut self, escrow_id: String);
+    fn storage_deposit(&mut self, account_id: Option<AccountId>, registration_only: Option<bool>);
+    fn submit_evidence(&mut self, escrow_id: String, content_hash: String, uri: String);
+    fn appeal_ruling(&mut self, escrow_id: String);
+    fn get_escrow(&self, escrow_id: String) -> Option<EscrowSnapshot>;
+}
+
+#[near_bindgen]
+impl P2PTransferContract {
+    // Protected payment - funds are held for the dispute window before they settle to the recipient
+    #[payable]
+    pub fn send_protected(&mut self, transfer_id: String, recipient: String, memo: String) -> Transfer {
+        self.assert_not_paused();
+        let config = self.chargeback_config.clone().or_fail(ErrorCode::InvalidState, "Chargeback payments are not enabled");
+        let sender = env::predecessor_account_id();
+        let recipient = self.resolve_recipient(&recipient);
+        let amount = env::attached_deposit();
+        
+        ensure!(amount.as_yoctonear() > 0, InsufficientFunds, "Must attach NEAR tokens");
+        self.assert_new_transfer_id(&transfer_id);
+        ensure!(sender != recipient, InvalidArgument, "Cannot transfer to yourself");
+        self.enforce_spending_limit(&sender, amount.as_yoctonear());
+        
+        let transfer = Transfer {
+            transfer_id: transfer_id.clone(),
+            sender: sender.to_string(),
+            recipient: recipient.to_string(),
+            amount: U128(amount.as_yoctonear()),
+            transfer_type: TransferType::Protected,
+            status: TransferStatus::Pending,
+            commitment: None,
+            nullifier: None,
+            memo,
+            encrypted_memo: None,
+            signer: self.relaying_signer(),
+            reference: None,
+            timestamp: env::block_timestamp(),
+        };
+        
+        let hold = ChargebackHold {
+            transfer_id: transfer_id.clone(),
+            dispute_deadline: env::block_timestamp() + config.dispute_window,
+            escrow_id: None,
+            disputed_at: None,
+        };
+        
+        self.transfers.insert(&transfer_id, &transfer);
+        self.track_new_transfer(&transfer);
+        self.lock_record_storage(&transfer);
+        self.chargeback_holds.insert(&transfer_id, &hold);
+        self.add_user_transfer(&sender, &transfer_id);
+        self.add_user_transfer(&recipient, &transfer_id);
+        
+        env::log_str(&format!(
+            "Protected transfer: {} | From: {} | To: {} | Amount: {} | Dispute until: {}",
+            transfer_id, sender, recipient, amount, hold.dispute_deadline
+        ));
+        
+        transfer
+    }
+
+    // Settle an undisputed protected payment once its window has passed - callable by anyone
+    pub fn settle_protected(&mut self, transfer_id: String) -> Promise {
+        let mut transfer = self.transfers.get(&transfer_id).or_fail(ErrorCode::NotFound, "Transfer not found");
+        let hold = self.chargeback_holds.get(&transfer_id).or_fail(ErrorCode::NotFound, "Protected transfer not found");
+        
+        ensure!(
+            matches!(transfer.status, TransferStatus::Pending),
+            InvalidState,
+            "Transfer is not pending"
+        );
+        ensure!(hold.escrow_id.is_none(), InvalidState, "Transfer is under dispute");
+        ensure!(
+            env::block_timestamp() >= hold.dispute_deadline,
+            TooEarly,
+            "Dispute window has not passed"
+        );
+        
+        transfer.status = TransferStatus::Completed;
+        transfer.timestamp = env::block_timestamp();
+        self.transfers.insert(&transfer_id, &transfer);
+        self.track_status_change(&transfer, &TransferStatus::Pending);
+        
+        let sender: AccountId = transfer.sender.parse().or_fail(ErrorCode::Internal, "Invalid sender");
+        let recipient: AccountId = transfer.recipient.parse().or_fail(ErrorCode::Internal, "Invalid recipient");
+        let amount_yocto: u128 = transfer.amount.0;
+        let fee_yocto = self.calculate_fee(amount_yocto, self.fee_config.direct_bps, &sender);
+        let payout_yocto = amount_yocto - fee_yocto;
+        
+        env::log_str(&format!(
+            "Protected transfer settled: {} | To: {} | Amount: {}",
+            transfer_id, recipient, payout_yocto
+        ));
+        
+        // Send fee
+        self.distribute_fee(fee_yocto);
+        
+        self.payout_with_refund(&transfer_id, recipient, sender, payout_yocto)
+    }
+
+    // Sender disputes within the window - the payment moves into an arbitrated escrow, which
+    // this contract opens as depositor and immediately puts into dispute so the recipient
+    // can't release it once the arbitration period passes. The escrow contract charges the
+    // record to this contract's storage (see fund_escrow_storage) and should exempt it from
+    // its rate limit, since every sender's disputes are created from this one account.
+    pub fn dispute_protected(&mut self, transfer_id: String) -> Promise {
+        let config = self.chargeback_config.clone().or_fail(ErrorCode::InvalidState, "Chargeback payments are not enabled");
+        let transfer = self.transfers.get(&transfer_id).or_fail(ErrorCode::NotFound, "Transfer not found");
+        let mut hold = self.chargeback_holds.get(&transfer_id).or_fail(ErrorCode::NotFound, "Protected transfer not found");
+        
+        let sender: AccountId = transfer.sender.parse().or_fail(ErrorCode::Internal, "Invalid sender");
+        ensure!(env::predecessor_account_id() == sender, Unauthorized, "Only sender can dispute");
+        ensure!(
+            matches!(transfer.status, TransferStatus::Pending),
+            InvalidState,
+            "Transfer is not pending"
+        );
+        ensure!(hold.escrow_id.is_none(), InvalidState, "Transfer is already disputed");
+        ensure!(
+            env::block_timestamp() < hold.dispute_deadline,
+            Expired,
+            "Dispute window has passed"
+        );
+        
+        let escrow_id = format!("chargeback:{}:{}", env::current_account_id(), transfer_id);
+        hold.escrow_id = Some(escrow_id.clone());
+        hold.disputed_at = Some(env::block_timestamp());
+        self.chargeback_holds.insert(&transfer_id, &hold);
+        
+        let amount_yocto: u128 = transfer.amount.0;
+        let escrow_contract: AccountId = config.escrow_contract.parse().or_fail(ErrorCode::Internal, "Invalid escrow contract");
+        let arbiter: AccountId = config.arbiter.parse().or_fail(ErrorCode::Internal, "Invalid arbiter");
+        let recipient: AccountId = transfer.recipient.parse().or_fail(ErrorCode::Internal, "Invalid recipient");
+        
+        env::log_str(&format!(
+            "Protected transfer disputed: {} | Escrow: {} | Amount: {}",
+            transfer_id, escrow_id, amount_yocto
+        ));
+        
+        ext_escrow::ext(escrow_contract.clone())
+            .with_attached_deposit(NearToken::from_yoctonear(amount_yocto))
+            .with_static_gas(GAS_FOR_CREATE_ESCROW)
+            .create_escrow(
+                escrow_id.clone(),
+                recipient,
+                env::block_timestamp() + config.arbitration_period,
+                Some(arbiter),
+                EscrowMetadata {
+                    // The escrow id already names the transfer; ids are unbounded but titles are capped
+                    title: "Chargeback dispute".to_string(),
+                    category: "chargeback".to_string(),
+                    content_hash: None,
+                    external_uri: None,
+                },
+            )
+            .then(ext_escrow::ext(escrow_contract).with_static_gas(GAS_FOR_ESCROW_CALL).raise_dispute(escrow_id))
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_PAYOUT_CALLBACK)
+                    .on_dispute_escrowed(transfer_id),
+            )
+    }
+
+    // Prepays this contract's storage on the escrow contract, which every dispute escrow locks
+    // until the escrow is cleaned up
+    #[payable]
+    pub fn fund_escrow_storage(&mut self) -> Promise {
+        let config = self.chargeback_config.clone().or_fail(ErrorCode::InvalidState, "Chargeback payments are not enabled");
+        let escrow_contract: AccountId = config.escrow_contract.parse().or_fail(ErrorCode::Internal, "Invalid escrow contract");
+        ensure!(env::attached_deposit().as_yoctonear() > 0, InsufficientFunds, "Must attach NEAR tokens");
+        
+        ext_escrow::ext(escrow_contract)
+            .with_attached_deposit(env::attached_deposit())
+            .with_static_gas(GAS_FOR_STORAGE_DEPOSIT)
+            .storage_deposit(Some(env::current_account_id()), None)
+    }
+
+    // The escrow only hears from this contract, so the sender's evidence goes through here
+    pub fn submit_dispute_evidence(&mut self, transfer_id: String, content_hash: String, uri: String) -> Promise {
+        let (escrow_contract, escrow_id) = self.sender_dispute_escrow(&transfer_id);
+        
+        env::log_str(&format!(
+            "Dispute evidence forwarded: {} | Escrow: {} | Hash: {}",
+            transfer_id, escrow_id, content_hash
+        ));
+        
+        ext_escrow::ext(escrow_contract)
+            .with_static_gas(GAS_FOR_ESCROW_CALL)
+            .submit_evidence(escrow_id, content_hash, uri)
+    }
+
+    // Appeals a pending arbiter ruling on the sender's behalf
+    pub fn appeal_dispute(&mut self, transfer_id: String) -> Promise {
+        let (escrow_contract, escrow_id) = self.sender_dispute_escrow(&transfer_id);
+        
+        env::log_str(&format!("Dispute ruling appealed: {} | Escrow: {}", transfer_id, escrow_id));
+        
+        ext_escrow::ext(escrow_contract)
+            .with_static_gas(GAS_FOR_ESCROW_CALL)
+            .appeal_ruling(escrow_id)
+    }
+
+    // Callback for dispute escrows - if the escrow wasn't created (the dispute call then
+    // finds nothing to dispute) the deposit came back and the hold returns to its
+    // undisputed state
+    #[private]
+    pub fn on_dispute_escrowed(&mut self, transfer_id: String) -> bool {
+        if is_promise_success() {
+            return true;
+        }
+        
+        if let Some(mut hold) = self.chargeback_holds.get(&transfer_id) {
+            hold.escrow_id = None;
+            hold.disputed_at = None;
+            self.chargeback_holds.insert(&transfer_id, &hold);
+        }
+        
+        env::log_str(&format!("Dispute escrow failed: {}", transfer_id));
+        
+        false
+    }
+
+    // Sync a disputed payment with its escrow outcome - callable by anyone once arbitration ends
+    pub fn resolve_dispute(&mut self, transfer_id: String) -> Promise {
+        let config = self.chargeback_config.clone().or_fail(ErrorCode::InvalidState, "Chargeback payments are not enabled");
+        let transfer = self.transfers.get(&transfer_id).or_fail(ErrorCode::NotFound, "Transfer not found");
+        let hold = self.chargeback_holds.get(&transfer_id).or_fail(ErrorCode::NotFound, "Protected transfer not found");
+        
+        ensure!(
+            matches!(transfer.status, TransferStatus::Pending),
+            InvalidState,
+            "Transfer is not pending"
+        );
+        let escrow_id = hold.escrow_id.or_fail(ErrorCode::InvalidState, "Transfer is not disputed");
+        let escrow_contract: AccountId = config.escrow_contract.parse().or_fail(ErrorCode::Internal, "Invalid escrow contract");
+        
+        ext_escrow::ext(escrow_contract)
+            .with_static_gas(GAS_FOR_GET_ESCROW)
+            .get_escrow(escrow_id)
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_DISPUTE_CALLBACK)
+                    .on_dispute_resolved(transfer_id),
+            )
+    }
+
+    // Released escrows complete the payment. Refunded and split (Resolved) escrows paid this
+    // contract's share back as depositor, so that share is forwarded to the sender; a
+    // refund cancels the payment, a split completes it.
+    #[private]
+    pub fn on_dispute_resolved(
+        &mut self,
+        transfer_id: String,
+        #[callback_result] escrow: Result<Option<EscrowSnapshot>, PromiseError>,
+    ) -> bool {
+        let escrow = match escrow {
+            Ok(Some(escrow)) => escrow,
+            _ => {
+                env::log_str(&format!("Dispute escrow not found: {}", transfer_id));
+                return false;
+            }
+        };
+        
+        let mut transfer = self.transfers.get(&transfer_id).or_fail(ErrorCode::NotFound, "Transfer not found");
+        if !matches!(transfer.status, TransferStatus::Pending) {
+            return false;
+        }
+        
+        transfer.status = match escrow.status {
+            EscrowStatus::Completed | EscrowStatus::Resolved => TransferStatus::Completed,
+            EscrowStatus::Refunded => TransferStatus::Cancelled,
+            _ => {
+                env::log_str(&format!(
+                    "Dispute unresolved: {} | Escrow status: {:?}",
+                    transfer_id, escrow.status
+                ));
+                return false;
+            }
+        };
+        transfer.timestamp = env::block_timestamp();
+        self.transfers.insert(&transfer_id, &transfer);
+        self.track_status_change(&transfer, &TransferStatus::Pending);
+        
+        env::log_str(&format!(
+            "Dispute resolved: {} | Outcome: {:?}",
+            transfer_id, escrow.status
+        ));
+        
+        // Escrows settled before payouts were recorded were full refunds
+        let refund_yocto = match (&escrow.status, &escrow.dispute_payout) {
+            (_, Some(payout)) => payout.depositor.0,
+            (EscrowStatus::Refunded, None) => escrow.amount.0,
+            _ => 0,
+        };
+        if refund_yocto > 0 {
+            let sender: AccountId = transfer.sender.parse().or_fail(ErrorCode::Internal, "Invalid sender");
+            self.settle(sender, refund_yocto);
+        }
+        
+        true
+    }
+
+    // Escrow a disputed payment sits in; only its sender may act on it
+    fn sender_dispute_escrow(&self, transfer_id: &str) -> (AccountId, String) {
+        let config = self.chargeback_config.clone().or_fail(ErrorCode::InvalidState, "Chargeback payments are not enabled");
+        let transfer_id = transfer_id.to_string();
+        let transfer = self.transfers.get(&transfer_id).or_fail(ErrorCode::NotFound, "Transfer not found");
+        let hold = self.chargeback_holds.get(&transfer_id).or_fail(ErrorCode::NotFound, "Protected transfer not found");
+        
+        let sender: AccountId = transfer.sender.parse().or_fail(ErrorCode::Internal, "Invalid sender");
+        ensure!(env::predecessor_account_id() == sender, Unauthorized, "Only sender can act on the dispute");
+        ensure!(
+            matches!(transfer.status, TransferStatus::Pending),
+            InvalidState,
+            "Transfer is not pending"
+        );
+        let escrow_id = hold.escrow_id.or_fail(ErrorCode::InvalidState, "Transfer is not disputed");
+        let escrow_contract = config.escrow_contract.parse().or_fail(ErrorCode::Internal, "Invalid escrow contract");
+        (escrow_contract, escrow_id)
+    }
+
+    pub fn get_chargeback_hold(&self, transfer_id: String) -> Option<ChargebackHold> {
+        self.chargeback_holds.get(&transfer_id)
+    }
+
+    pub fn get_chargeback_config(&self) -> Option<ChargebackConfig> {
+        self.chargeback_config.clone()
+    }
+
+    pub fn set_chargeback_config(
+        &mut self,
+        escrow_contract: AccountId,
+        arbiter: AccountId,
+        dispute_window_seconds: u64,
+        arbitration_period_seconds: u64,
+    ) {
+        assert_owner(&self.owner);
+        ensure!(
+            dispute_window_seconds > 0 && arbitration_period_seconds > 0,
+            InvalidArgument,
+            "Periods must be positive"
+        );
+        
+        self.chargeback_config = Some(ChargebackConfig {
+            escrow_contract: escrow_contract.to_string(),
+            arbiter: arbiter.to_string(),
+            dispute_window: dispute_window_seconds * 1_000_000_000,
+            arbitration_period: arbitration_period_seconds * 1_000_000_000,
+        });
+        self.record_audit(AuditAction::ConfigChanged, vec!["chargeback_config".to_string()], None);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use ciphra_testing as host;
+
+    const ESCROW: &str = "escrow.near";
+    const ARBITER: &str = "arbiter.near";
+
+    // A protected 10 NEAR payment still inside its dispute window
+    fn protected_contract() -> P2PTransferContract {
+        let mut contract = contract();
+        contract.set_chargeback_config(account(ESCROW), account(ARBITER), 3600, 86_400);
+        
+        host::set_caller(SENDER);
+        host::set_deposit(host::NEAR);
+        contract.storage_deposit(None, None);
+        host::set_deposit(10 * host::NEAR);
+        contract.send_protected("t1".to_string(), RECIPIENT.to_string(), String::new());
+        host::set_deposit(0);
+        contract
+    }
+
+    fn disputed_contract() -> P2PTransferContract {
+        let mut contract = protected_contract();
+        let _ = contract.dispute_protected("t1".to_string());
+        host::clear_receipts();
+        contract
+    }
+
+    fn resolve(contract: &mut P2PTransferContract, status: EscrowStatus, depositor_yocto: Option<u128>) -> bool {
+        host::set_self_caller();
+        contract.on_dispute_resolved(
+            "t1".to_string(),
+            Ok(Some(EscrowSnapshot {
+                status,
+                amount: U128(10 * host::NEAR),
+                dispute_payout: depositor_yocto.map(|depositor| DisputePayoutSnapshot { depositor: U128(depositor) }),
+            })),
+        )
+    }
+
+    #[test]
+    fn test_dispute_opens_a_disputed_escrow() {
+        let mut contract = protected_contract();
+        let _ = contract.dispute_protected("t1".to_string());
+        
+        let calls = host::function_calls();
+        assert_eq!(calls[0], (ESCROW.to_string(), "create_escrow".to_string()));
+        assert_eq!(calls[1], (ESCROW.to_string(), "raise_dispute".to_string()));
+        assert!(contract.get_chargeback_hold("t1".to_string()).unwrap().escrow_id.is_some());
+    }
+
+    #[test]
+    fn test_escrow_storage_is_prepaid_for_this_contract() {
+        let mut contract = protected_contract();
+        host::set_caller(OWNER);
+        host::set_deposit(host::NEAR);
+        let _ = contract.fund_escrow_storage();
+        
+        assert_eq!(host::function_calls(), vec![(ESCROW.to_string(), "storage_deposit".to_string())]);
+    }
+
+    #[test]
+    fn test_released_escrow_completes_the_payment() {
+        let mut contract = disputed_contract();
+        assert!(resolve(&mut contract, EscrowStatus::Completed, Some(0)));
+        
+        assert!(matches!(contract.get_transfer("t1".to_string()).unwrap().status, TransferStatus::Completed));
+        assert!(host::transfers().is_empty());
+    }
+
+    #[test]
+    fn test_refunded_escrow_returns_the_payment_to_the_sender() {
+        let mut contract = disputed_contract();
+        assert!(resolve(&mut contract, EscrowStatus::Refunded, Some(10 * host::NEAR)));
+        
+        assert!(matches!(contract.get_transfer("t1".to_string()).unwrap().status, TransferStatus::Cancelled));
+        assert_eq!(host::transfers(), vec![(SENDER.to_string(), 10 * host::NEAR)]);
+    }
+
+    #[test]
+    fn test_split_escrow_forwards_the_sender_share() {
+        let mut contract = disputed_contract();
+        assert!(resolve(&mut contract, EscrowStatus::Resolved, Some(4 * host::NEAR)));
+        
+        assert!(matches!(contract.get_transfer("t1".to_string()).unwrap().status, TransferStatus::Completed));
+        assert_eq!(host::transfers(), vec![(SENDER.to_string(), 4 * host::NEAR)]);
+        assert_eq!(contract.settlements.total_pending, 4 * host::NEAR);
+    }
+
+    #[test]
+    fn test_open_dispute_leaves_the_payment_pending() {
+        let mut contract = disputed_contract();
+        assert!(!resolve(&mut contract, EscrowStatus::Disputed, None));
+        
+        assert!(matches!(contract.get_transfer("t1".to_string()).unwrap().status, TransferStatus::Pending));
+    }
+
+    #[test]
+    fn test_sender_evidence_is_forwarded() {
+        let mut contract = disputed_contract();
+        host::set_caller(SENDER);
+        let _ = contract.submit_dispute_evidence("t1".to_string(), "a".repeat(64), "ipfs://evidence".to_string());
+        
+        assert_eq!(host::function_calls(), vec![(ESCROW.to_string(), "submit_evidence".to_string())]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only sender can act on the dispute")]
+    fn test_recipient_cannot_use_the_proxy() {
+        let mut contract = disputed_contract();
+        host::set_caller(RECIPIENT);
+        let _ = contract.appeal_dispute("t1".to_string());
+    }
+}
@@ -0,0 +1,292 @@
+use crate::*;
+use crate::merkle::{decode_field_element, decode_hash, Hash};
+use ciphra_poseidon::Fr;
+use near_sdk::borsh;
+use near_sdk::json_types::Base64VecU8;
+
+// Spend proofs are Groth16 over BN254, checked with the alt_bn128 host functions. Points use
+// their encoding: little-endian coordinates, G1 as x | y and G2 as x.c0 | x.c1 | y.c0 | y.c1.
+const G1_BYTES: usize = 64;
+const G2_BYTES: usize = 128;
+const SCALAR_BYTES: usize = 32;
+
+// Public inputs of the spend circuit, in order: root, nullifier, note commitment, spend context
+const PUBLIC_INPUTS: usize = 4;
+
+// alpha (G1) | beta, gamma, delta (G2) | IC[0..=PUBLIC_INPUTS] (G1)
+const VERIFYING_KEY_BYTES: usize = G1_BYTES + 3 * G2_BYTES + (PUBLIC_INPUTS + 1) * G1_BYTES;
+
+// A (G1) | B (G2) | C (G1), hex encoded
+const PROOF_BYTES: usize = 2 * G1_BYTES + G2_BYTES;
+
+// Circuit of the notes created before any verifying key was registered. Its key is
+// registered like any other version's and then checks spends of those notes.
+pub(crate) const LEGACY_CIRCUIT_VERSION: u16 = 0;
+
+#[near_bindgen]
+impl P2PTransferContract {
+    // Register the verifying key for a circuit version. Registering a version newer than
+    // the current one makes it current for new notes; older keys stay available so notes
+    // created under them can still be spent. Keys can't be replaced: notes are bound to
+    // the key their version had when they were created.
+    pub fn set_verifying_key(&mut self, circuit_version: u16, vk_bytes: Base64VecU8) {
+        assert_owner(&self.owner);
+        ensure!(
+            vk_bytes.0.len() == VERIFYING_KEY_BYTES,
+            InvalidArgument,
+            "Verifying key must be {} bytes",
+            VERIFYING_KEY_BYTES
+        );
+        ensure!(
+            !self.verifying_keys.contains_key(&circuit_version),
+            AlreadyExists,
+            "Verifying key already registered for v{}",
+            circuit_version
+        );
+        
+        self.verifying_keys.insert(&circuit_version, &vk_bytes.0);
+        self.record_audit(
+            AuditAction::ConfigChanged,
+            vec!["verifying_key".to_string(), circuit_version.to_string()],
+            None,
+        );
+        if circuit_version > self.current_circuit_version {
+            self.current_circuit_version = circuit_version;
+        }
+        
+        env::log_str(&format!(
+            "Verifying key set: v{} | Bytes: {} | Current: v{}",
+            circuit_version, vk_bytes.0.len(), self.current_circuit_version
+        ));
+    }
+
+    pub fn get_verifying_key(&self, circuit_version: u16) -> Option<Base64VecU8> {
+        self.verifying_keys.get(&circuit_version).map(Base64VecU8)
+    }
+
+    pub fn get_current_circuit_version(&self) -> u16 {
+        self.current_circuit_version
+    }
+
+    // Spends are checked against the key of the circuit the note was created under. The
+    // context is what the spend pays out to (recipient, output commitments, amounts); its
+    // SHA-256, reduced into the field, is a public input so a proof can't be replayed with
+    // other outputs by whoever relays it.
+    pub(crate) fn assert_spend_proof(
+        &self,
+        note: &ShieldedNote,
+        root: &str,
+        nullifier: &str,
+        context: &impl BorshSerialize,
+        proof: &str,
+    ) {
+        let verifying_key = self
+            .verifying_keys
+            .get(&note.circuit_version)
+            .or_fail(ErrorCode::NotFound, "No verifying key for the note's circuit version");
+        let proof = hex::decode(proof)
+            .ok()
+            .filter(|bytes| bytes.len() == PROOF_BYTES)
+            .or_fail(ErrorCode::InvalidArgument, "Proof must be 256 bytes hex");
+        let commitment = self
+            .commitment_tree
+            .nodes
+            .get(&(0, note.leaf_index))
+            .or_fail(ErrorCode::Internal, "Note commitment missing from the tree");
+        let context = borsh::to_vec(context).or_fail(ErrorCode::Internal, "Failed to serialize spend context");
+        let public_inputs = [
+            decode_hash(root, "Root"),
+            decode_field_element(nullifier, "Nullifier"),
+            commitment,
+            Fr::from_be_bytes_reduced(&env::sha256_array(&context)).to_be_bytes(),
+        ];
+        
+        ensure!(
+            verify_groth16(&verifying_key, &proof, &public_inputs),
+            InvalidProof,
+            "Invalid spend proof"
+        );
+    }
+}
+
+// e(-A, B) * e(alpha, beta) * e(vk_x, gamma) * e(C, delta) == 1, where
+// vk_x = IC[0] + sum(input_i * IC[i + 1])
+fn verify_groth16(verifying_key: &[u8], proof: &[u8], public_inputs: &[Hash; PUBLIC_INPUTS]) -> bool {
+    let (alpha, rest) = verifying_key.split_at(G1_BYTES);
+    let (beta, rest) = rest.split_at(G2_BYTES);
+    let (gamma, rest) = rest.split_at(G2_BYTES);
+    let (delta, ic) = rest.split_at(G2_BYTES);
+    let (a, rest) = proof.split_at(G1_BYTES);
+    let (b, c) = rest.split_at(G2_BYTES);
+    
+    // Multiexp items are (point, little-endian scalar)
+    let mut one = [0u8; SCALAR_BYTES];
+    one[0] = 1;
+    let scalars = std::iter::once(one).chain(public_inputs.iter().map(|input| {
+        let mut scalar = *input;
+        scalar.reverse();
+        scalar
+    }));
+    let terms: Vec<u8> = ic
+        .chunks(G1_BYTES)
+        .zip(scalars)
+        .flat_map(|(point, scalar)| [point, &scalar].concat())
+        .collect();
+    let vk_x = env::alt_bn128_g1_multiexp(&terms);
+    
+    // Sum items are (sign, point); sign 1 negates
+    let neg_a = env::alt_bn128_g1_sum(&[&[1u8], a].concat());
+    
+    env::alt_bn128_pairing_check(&[&neg_a, b, alpha, beta, &vk_x, gamma, c, delta].concat())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use ciphra_testing as host;
+
+    const NULLIFIER: &str = "0000000000000000000000000000000000000000000000000000000000000042";
+
+    // Points filled with distinct bytes so each shows where it lands in the host calls
+    fn verifying_key() -> Vec<u8> {
+        let ic = (0..=PUBLIC_INPUTS).flat_map(|index| vec![10 + index as u8; G1_BYTES]);
+        [vec![1; G1_BYTES], vec![2; G2_BYTES], vec![3; G2_BYTES], vec![4; G2_BYTES], ic.collect()].concat()
+    }
+
+    fn proof() -> String {
+        hex::encode([vec![5; G1_BYTES], vec![6; G2_BYTES], vec![7; G1_BYTES]].concat())
+    }
+
+    // Contract with the legacy key registered and a note "n1" deposited by the sender
+    fn contract_with_note() -> P2PTransferContract {
+        let mut contract = contract();
+        contract.set_verifying_key(LEGACY_CIRCUIT_VERSION, Base64VecU8(verifying_key()));
+        host::set_caller(SENDER);
+        host::set_deposit(host::NEAR);
+        contract.storage_deposit(None, None);
+        host::set_deposit(host::NEAR);
+        contract.shield_deposit("n1".to_string(), "11".repeat(32));
+        host::set_deposit(0);
+        contract
+    }
+
+    fn withdraw(contract: &mut P2PTransferContract, nullifier: &str) {
+        let root = contract.get_current_root();
+        let _ = contract.shield_withdraw(
+            "w1".to_string(),
+            "n1".to_string(),
+            nullifier.to_string(),
+            account(RECIPIENT),
+            root,
+            proof(),
+        );
+    }
+
+    fn little_endian(hash: Hash) -> Vec<u8> {
+        hash.iter().rev().copied().collect()
+    }
+
+    #[test]
+    fn test_spend_is_verified_against_the_note_key() {
+        let mut contract = contract_with_note();
+        let root = contract.commitment_tree.current_root;
+        host::set_pairing_result(true);
+        
+        withdraw(&mut contract, NULLIFIER);
+        
+        let calls = host::alt_bn128_calls();
+        let (_, terms) = &calls[0];
+        let context = borsh::to_vec(&("shield_withdraw", &account(RECIPIENT))).unwrap();
+        let nullifier: Hash = hex::decode(NULLIFIER).unwrap().try_into().unwrap();
+        let context = Fr::from_be_bytes_reduced(&env::sha256_array(&context)).to_be_bytes();
+        let inputs = [root, nullifier, [0x11; 32], context];
+        let mut one = vec![0; SCALAR_BYTES];
+        one[0] = 1;
+        let expected_terms: Vec<u8> = std::iter::once(one)
+            .chain(inputs.into_iter().map(little_endian))
+            .enumerate()
+            .flat_map(|(index, scalar)| [vec![10 + index as u8; G1_BYTES], scalar].concat())
+            .collect();
+        assert_eq!(calls[0].0, "alt_bn128_g1_multiexp");
+        assert_eq!(terms, &expected_terms);
+        assert_eq!(calls[1], ("alt_bn128_g1_sum", [vec![1], vec![5; G1_BYTES]].concat()));
+        let zero = vec![0; G1_BYTES];
+        let pairs = [
+            zero.clone(), vec![6; G2_BYTES], vec![1; G1_BYTES], vec![2; G2_BYTES],
+            zero, vec![3; G2_BYTES], vec![7; G1_BYTES], vec![4; G2_BYTES],
+        ];
+        assert_eq!(calls[2], ("alt_bn128_pairing_check", pairs.concat()));
+        assert!(contract.get_shielded_note("n1".to_string()).unwrap().spent);
+    }
+
+    #[test]
+    #[should_panic(expected = r#""code":"InvalidProof","message":"Invalid spend proof""#)]
+    fn test_failed_pairing_rejects_the_spend() {
+        let mut contract = contract_with_note();
+        withdraw(&mut contract, NULLIFIER);
+    }
+
+    #[test]
+    #[should_panic(expected = "Nullifier must be a BN254 field element")]
+    fn test_nullifier_outside_the_field_is_rejected() {
+        let mut contract = contract_with_note();
+        host::set_pairing_result(true);
+        withdraw(&mut contract, &"ff".repeat(32));
+    }
+
+    #[test]
+    #[should_panic(expected = "Nullifier must be lowercase hex")]
+    fn test_nullifier_has_one_spelling() {
+        let mut contract = contract_with_note();
+        host::set_pairing_result(true);
+        withdraw(&mut contract, &NULLIFIER.replace("42", "4A"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Proof must be 256 bytes hex")]
+    fn test_malformed_proof_is_rejected() {
+        let mut contract = contract_with_note();
+        let root = contract.get_current_root();
+        let _ = contract.shield_withdraw(
+            "w1".to_string(),
+            "n1".to_string(),
+            NULLIFIER.to_string(),
+            account(RECIPIENT),
+            root,
+            "proof".to_string(),
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = r#""code":"AlreadyExists","message":"Verifying key already registered for v0""#)]
+    fn test_registered_keys_are_immutable() {
+        let mut contract = contract();
+        contract.set_verifying_key(LEGACY_CIRCUIT_VERSION, Base64VecU8(verifying_key()));
+        contract.set_verifying_key(LEGACY_CIRCUIT_VERSION, Base64VecU8(verifying_key()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Verifying key must be 768 bytes")]
+    fn test_key_length_is_checked() {
+        let mut contract = contract();
+        contract.set_verifying_key(1, Base64VecU8(vec![1; 16]));
+    }
+
+    #[test]
+    fn test_notes_before_any_key_are_legacy() {
+        let mut contract = contract();
+        host::set_caller(SENDER);
+        host::set_deposit(host::NEAR);
+        contract.storage_deposit(None, None);
+        host::set_deposit(host::NEAR);
+        contract.shield_deposit("n0".to_string(), "11".repeat(32));
+        
+        host::set_caller(OWNER);
+        contract.set_verifying_key(2, Base64VecU8(verifying_key()));
+        
+        let note = contract.get_shielded_note("n0".to_string()).unwrap();
+        assert_eq!(note.circuit_version, LEGACY_CIRCUIT_VERSION);
+        assert_eq!(contract.get_current_circuit_version(), 2);
+    }
+}
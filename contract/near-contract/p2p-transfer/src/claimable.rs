@@ -0,0 +1,150 @@
+use crate::*;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ClaimableTransfer {
+    pub transfer_id: String,
+    pub claim_hash: String,
+    pub expiry: u64,
+    pub claimed_by: Option<String>,
+}
+
+#[near_bindgen]
+impl P2PTransferContract {
+    // Claim-link transfer - funds are held until someone presents the preimage of claim_hash.
+    // claim_hash is the hex SHA-256 of the secret shared with the recipient (link / QR code).
+    #[payable]
+    pub fn send_claimable(
+        &mut self,
+        transfer_id: String,
+        claim_hash: String,
+        expiry: u64,
+        memo: String,
+    ) -> Transfer {
+        self.assert_not_paused();
+        let sender = env::predecessor_account_id();
+        let amount = env::attached_deposit();
+        
+        ensure!(amount.as_yoctonear() > 0, InsufficientFunds, "Must attach NEAR tokens");
+        self.assert_new_transfer_id(&transfer_id);
+        ensure!(claim_hash.len() == 64, InvalidArgument, "Claim hash must be 64 characters (32 bytes hex)");
+        ensure!(expiry > env::block_timestamp(), InvalidArgument, "Expiry must be in future");
+        
+        let transfer = Transfer {
+            transfer_id: transfer_id.clone(),
+            sender: sender.to_string(),
+            recipient: "unclaimed".to_string(),
+            amount: U128(amount.as_yoctonear()),
+            transfer_type: TransferType::Claimable,
+            status: TransferStatus::Pending,
+            commitment: None,
+            nullifier: None,
+            memo,
+            encrypted_memo: None,
+            signer: None,
+            reference: None,
+            timestamp: env::block_timestamp(),
+        };
+        
+        let claimable = ClaimableTransfer {
+            transfer_id: transfer_id.clone(),
+            claim_hash: claim_hash.to_lowercase(),
+            expiry,
+            claimed_by: None,
+        };
+        
+        self.transfers.insert(&transfer_id, &transfer);
+        self.track_new_transfer(&transfer);
+        self.lock_record_storage(&transfer);
+        self.claimable_transfers.insert(&transfer_id, &claimable);
+        self.add_user_transfer(&sender, &transfer_id);
+        
+        env::log_str(&format!(
+            "Claimable transfer: {} | From: {} | Amount: {} | Expiry: {}",
+            transfer_id, sender, amount, expiry
+        ));
+        
+        transfer
+    }
+
+    // Claim a held transfer by revealing the secret; funds go to the given recipient
+    pub fn claim(&mut self, transfer_id: String, secret: String, recipient: AccountId) -> Promise {
+        let mut transfer = self.transfers.get(&transfer_id).or_fail(ErrorCode::NotFound, "Transfer not found");
+        let mut claimable = self.claimable_transfers
+            .get(&transfer_id)
+            .or_fail(ErrorCode::NotFound, "Claimable transfer not found");
+        
+        ensure!(
+            matches!(transfer.status, TransferStatus::Pending),
+            InvalidState,
+            "Transfer is not pending"
+        );
+        ensure!(env::block_timestamp() < claimable.expiry, Expired, "Claim link has expired");
+        ensure!(
+            hex::encode(env::sha256(secret.as_bytes())) == claimable.claim_hash,
+            InvalidProof,
+            "Invalid claim secret"
+        );
+        
+        // Track the transition while the recipient is still unclaimed, then credit the claimer
+        transfer.status = TransferStatus::Completed;
+        transfer.timestamp = env::block_timestamp();
+        self.track_status_change(&transfer, &TransferStatus::Pending);
+        transfer.recipient = recipient.to_string();
+        self.transfers.insert(&transfer_id, &transfer);
+        self.track_received(&recipient, transfer.amount.0);
+        self.add_user_transfer(&recipient, &transfer_id);
+        
+        claimable.claimed_by = Some(env::predecessor_account_id().to_string());
+        self.claimable_transfers.insert(&transfer_id, &claimable);
+        
+        let amount_yocto: u128 = transfer.amount.0;
+        let fee_yocto = self.calculate_fee(
+            amount_yocto,
+            self.fee_config.direct_bps,
+            &transfer.sender.parse().or_fail(ErrorCode::Internal, "Invalid sender"),
+        );
+        let payout_yocto = amount_yocto - fee_yocto;
+        
+        env::log_str(&format!(
+            "Transfer claimed: {} | To: {} | Amount: {}",
+            transfer_id, recipient, payout_yocto
+        ));
+        
+        // Send fee
+        self.distribute_fee(fee_yocto);
+        
+        let sender: AccountId = transfer.sender.parse().or_fail(ErrorCode::Internal, "Invalid sender");
+        self.payout_with_refund(&transfer_id, recipient, sender, payout_yocto)
+    }
+
+    // Return an unclaimed transfer to its sender once expired - callable by anyone
+    pub fn reclaim_expired(&mut self, transfer_id: String) -> Promise {
+        let mut transfer = self.transfers.get(&transfer_id).or_fail(ErrorCode::NotFound, "Transfer not found");
+        let claimable = self.claimable_transfers
+            .get(&transfer_id)
+            .or_fail(ErrorCode::NotFound, "Claimable transfer not found");
+        
+        ensure!(
+            matches!(transfer.status, TransferStatus::Pending),
+            InvalidState,
+            "Transfer is not pending"
+        );
+        ensure!(env::block_timestamp() >= claimable.expiry, TooEarly, "Claim link has not expired yet");
+        
+        transfer.status = TransferStatus::Cancelled;
+        transfer.timestamp = env::block_timestamp();
+        self.transfers.insert(&transfer_id, &transfer);
+        self.track_status_change(&transfer, &TransferStatus::Pending);
+        
+        env::log_str(&format!("Claimable transfer expired: {}", transfer_id));
+        
+        let amount_yocto: u128 = transfer.amount.0;
+        let sender: AccountId = transfer.sender.parse().or_fail(ErrorCode::Internal, "Invalid sender");
+        self.settle(sender, amount_yocto)
+    }
+
+    pub fn get_claimable_transfer(&self, transfer_id: String) -> Option<ClaimableTransfer> {
+        self.claimable_transfers.get(&transfer_id)
+    }
+}
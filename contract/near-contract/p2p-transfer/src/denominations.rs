@@ -0,0 +1,73 @@
+use crate::*;
+
+// Standard pool denominations: 0.1, 1, 10 and 100 NEAR
+pub(crate) fn default_denominations() -> Vec<u128> {
+    vec![
+        100_000_000_000_000_000_000_000,
+        1_000_000_000_000_000_000_000_000,
+        10_000_000_000_000_000_000_000_000,
+        100_000_000_000_000_000_000_000_000,
+    ]
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DenominatedDeposit {
+    pub note_id: String,
+    pub commitment: String,
+    pub denomination: U128,
+}
+
+#[near_bindgen]
+impl P2PTransferContract {
+    // Deposit an arbitrary amount split client-side into standard denominations
+    // (e.g. 23.4 NEAR -> 2x10 + 3x1 + 4x0.1), one commitment per note
+    #[payable]
+    pub fn shield_deposit_denominated(&mut self, notes: Vec<DenominatedDeposit>) -> Vec<ShieldedNote> {
+        self.assert_not_paused();
+        let amount = env::attached_deposit();
+        
+        ensure!(amount.as_yoctonear() > 0, InsufficientFunds, "Must attach NEAR tokens");
+        ensure!(!notes.is_empty(), InvalidArgument, "At least one note required");
+        ensure!(notes.len() <= MAX_NOTES_PER_DEPOSIT, LimitExceeded, "Too many notes in one deposit");
+        ensure!(
+            notes.iter().all(|note| self.denominations.contains(&note.denomination.0)),
+            InvalidState,
+            "Note amount is not a standard denomination"
+        );
+        let total_yocto: u128 = notes.iter().map(|note| note.denomination.0).sum();
+        ensure!(
+            total_yocto == amount.as_yoctonear(),
+            InvalidArgument,
+            "Denominations must sum to the attached deposit"
+        );
+        self.rate_limiter.record(&env::predecessor_account_id(), notes.len() as u16);
+        
+        let notes: Vec<ShieldedNote> = notes
+            .into_iter()
+            .map(|note| self.insert_note(note.note_id, note.commitment, note.denomination.0))
+            .collect();
+        
+        env::log_str(&format!(
+            "Denominated shielded deposit: {} notes | Amount: {}",
+            notes.len(), amount
+        ));
+        
+        notes
+    }
+
+    pub fn get_denominations(&self) -> Vec<U128> {
+        self.denominations.iter().map(|denomination| U128(*denomination)).collect()
+    }
+
+    pub fn set_denominations(&mut self, denominations: Vec<U128>) {
+        assert_owner(&self.owner);
+        ensure!(!denominations.is_empty(), InvalidArgument, "At least one denomination required");
+        ensure!(denominations.iter().all(|d| d.0 > 0), InvalidArgument, "Denominations must be positive");
+        let mut denominations: Vec<u128> = denominations.into_iter().map(|d| d.0).collect();
+        denominations.sort_unstable();
+        denominations.dedup();
+        self.denominations = denominations;
+        self.record_audit(AuditAction::ConfigChanged, vec!["denominations".to_string()], None);
+    }
+}
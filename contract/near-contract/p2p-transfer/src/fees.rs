@@ -0,0 +1,223 @@
+use crate::*;
+use ciphra_types::bps_of;
+use near_sdk::ext_contract;
+
+const GAS_FOR_FEE_DEPOSIT: Gas = Gas::from_tgas(10);
+const GAS_FOR_FEE_DEPOSIT_CALLBACK: Gas = Gas::from_tgas(10);
+
+// Fee shares with this label, or paid to the fee treasury, are forwarded through the
+// receiving contract's `deposit_fees`
+const INSURANCE_LABEL: &str = "insurance";
+
+// Upper bound for any single fee rate (5%)
+const MAX_FEE_BPS: u16 = 500;
+
+// Upper bound on fee distribution entries so fee payouts stay cheap
+const MAX_FEE_SHARES: usize = 5;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FeeConfig {
+    pub direct_bps: u16,
+    pub shielded_withdraw_bps: u16,
+    pub batch_bps: u16,
+    pub internal_bps: u16,
+}
+
+impl Default for FeeConfig {
+    fn default() -> Self {
+        Self {
+            direct_bps: 10, // 0.1% for direct transfers
+            shielded_withdraw_bps: 10,
+            batch_bps: 10,
+            internal_bps: 0, // Ledger transfers are free by default
+        }
+    }
+}
+
+// One entry of the fee distribution table (e.g. treasury, development fund, insurance pool)
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FeeShare {
+    pub label: String,
+    pub recipient: String,
+    pub share_bps: u16,
+}
+
+// Implemented by both the insurance pool and the fee treasury
+// Only the generated ext_fee_contract module is used
+#[allow(dead_code)]
+#[ext_contract(ext_fee_contract)]
+trait FeeContract {
+    fn deposit_fees(&mut self);
+}
+
+// All fees go to the owner until a distribution table is configured
+pub(crate) fn default_fee_distribution(owner: &AccountId) -> Vec<FeeShare> {
+    vec![FeeShare {
+        label: "treasury".to_string(),
+        recipient: owner.to_string(),
+        share_bps: 10000,
+    }]
+}
+
+#[near_bindgen]
+impl P2PTransferContract {
+    pub fn set_fee_config(&mut self, fee_config: FeeConfig) {
+        self.access.assert_owner_or_role(&self.owner, Role::FeeManager);
+        ensure!(
+            fee_config.direct_bps <= MAX_FEE_BPS
+                && fee_config.shielded_withdraw_bps <= MAX_FEE_BPS
+                && fee_config.batch_bps <= MAX_FEE_BPS
+                && fee_config.internal_bps <= MAX_FEE_BPS,
+            LimitExceeded,
+            "Fee cannot exceed 5%"
+        );
+        self.fee_config = fee_config;
+        self.record_audit(AuditAction::ConfigChanged, vec!["fee_config".to_string()], None);
+    }
+
+    pub fn get_fee_config(&self) -> FeeConfig {
+        self.fee_config.clone()
+    }
+
+    // Replace the fee distribution table - shares must sum to 10000 bps
+    pub fn set_fee_distribution(&mut self, fee_distribution: Vec<FeeShare>) {
+        assert_owner(&self.owner);
+        ensure!(
+            !fee_distribution.is_empty() && fee_distribution.len() <= MAX_FEE_SHARES,
+            InvalidArgument,
+            "Invalid number of fee shares"
+        );
+        
+        let total_bps: u32 = fee_distribution.iter().map(|share| share.share_bps as u32).sum();
+        ensure!(total_bps == 10000, InvalidArgument, "Shares must sum to 10000 bps");
+        for share in fee_distribution.iter() {
+            ensure!(share.share_bps > 0, InvalidArgument, "Share must be positive");
+            share.recipient.parse::<AccountId>().or_fail(ErrorCode::InvalidArgument, "Invalid fee recipient");
+        }
+        
+        self.fee_distribution = fee_distribution;
+        self.record_audit(AuditAction::ConfigChanged, vec!["fee_distribution".to_string()], None);
+    }
+
+    // A fee contract that rejects its deposit can't claim it back, so the amount becomes
+    // claimable by the owner instead
+    #[private]
+    pub fn on_fee_deposit_settled(&mut self, settlement_id: u64) -> bool {
+        let Some(settlement) = self.settlements.resolve(settlement_id, is_promise_success()) else {
+            return true;
+        };
+        let owner = self.owner.clone();
+        self.settlements.credit(&owner, settlement.amount.0);
+        
+        env::log_str(&format!(
+            "Fee deposit failed: {} | Fee contract: {} | Claimable by: {} | Amount: {}",
+            settlement_id, settlement.receiver, owner, settlement.amount.0
+        ));
+        emit_event("fee_deposit_failed", json!({
+            "settlement_id": settlement_id,
+            "fee_contract": settlement.receiver,
+            "claimable_by": owner,
+            "amount": settlement.amount,
+        }));
+        
+        false
+    }
+
+    pub fn get_fee_distribution(&self) -> Vec<FeeShare> {
+        self.fee_distribution.clone()
+    }
+
+    // Fee treasury contract; distribution shares paid to it are credited through `deposit_fees`
+    pub fn set_fee_treasury(&mut self, fee_treasury: Option<AccountId>) {
+        assert_owner(&self.owner);
+        self.fee_treasury = fee_treasury;
+        self.record_audit(AuditAction::ConfigChanged, vec!["fee_treasury".to_string()], None);
+    }
+
+    pub fn get_fee_treasury(&self) -> Option<AccountId> {
+        self.fee_treasury.clone()
+    }
+
+    // Exempt accounts (e.g. treasury or internal wallets) pay no transfer fees
+    pub fn add_fee_exemption(&mut self, account_id: AccountId) {
+        self.access.assert_owner_or_role(&self.owner, Role::FeeManager);
+        self.fee_exempt.insert(&account_id);
+    }
+
+    pub fn remove_fee_exemption(&mut self, account_id: AccountId) {
+        self.access.assert_owner_or_role(&self.owner, Role::FeeManager);
+        self.fee_exempt.remove(&account_id);
+    }
+
+    pub fn is_fee_exempt(&self, account_id: AccountId) -> bool {
+        self.fee_exempt.contains(&account_id)
+    }
+
+    pub fn get_fee_exemptions(&self) -> Vec<AccountId> {
+        self.fee_exempt.to_vec()
+    }
+
+    // Fee owed on `amount_yocto` at `fee_bps`, waived for exempt payers
+    pub(crate) fn calculate_fee(&self, amount_yocto: u128, fee_bps: u16, payer: &AccountId) -> u128 {
+        if self.fee_exempt.contains(payer) {
+            return 0;
+        }
+        bps_of(amount_yocto, fee_bps)
+    }
+
+    // Split a collected fee across the distribution table; rounding dust goes to the last entry
+    pub(crate) fn fee_shares(&self, fee_yocto: u128) -> Vec<(AccountId, u128)> {
+        let mut remaining_yocto = fee_yocto;
+        let last = self.fee_distribution.len() - 1;
+        
+        self.fee_distribution
+            .iter()
+            .enumerate()
+            .map(|(i, share)| {
+                let share_yocto = if i == last {
+                    remaining_yocto
+                } else {
+                    (fee_yocto * share.share_bps as u128) / 10000
+                };
+                remaining_yocto -= share_yocto;
+                (share.recipient.parse().or_fail(ErrorCode::Internal, "Invalid fee recipient"), share_yocto)
+            })
+            .filter(|(_, share_yocto)| *share_yocto > 0)
+            .collect()
+    }
+
+    pub(crate) fn distribute_fee(&mut self, fee_yocto: u128) {
+        for (fee_recipient, share_yocto) in self.fee_shares(fee_yocto) {
+            if self.is_fee_contract(&fee_recipient) {
+                self.deposit_to_fee_contract(fee_recipient, share_yocto);
+            } else {
+                self.settle(fee_recipient, share_yocto);
+            }
+        }
+    }
+
+    // The insurance pool and fee treasury attribute deposits to this contract, so they are
+    // paid by call rather than transfer
+    pub(crate) fn is_fee_contract(&self, account_id: &AccountId) -> bool {
+        self.fee_treasury.as_ref() == Some(account_id)
+            || self.fee_distribution
+                .iter()
+                .any(|share| share.label == INSURANCE_LABEL && share.recipient == account_id.as_str())
+    }
+
+    // Pays through the settlement ledger like any other payout, so a rejected deposit is
+    // rolled back by `on_fee_deposit_settled`
+    pub(crate) fn deposit_to_fee_contract(&mut self, fee_contract: AccountId, amount_yocto: u128) -> Promise {
+        let deposit = ext_fee_contract::ext(fee_contract.clone())
+            .with_attached_deposit(NearToken::from_yoctonear(amount_yocto))
+            .with_static_gas(GAS_FOR_FEE_DEPOSIT)
+            .deposit_fees();
+        self.settlements.send_call(fee_contract, amount_yocto, deposit, |settlement_id| {
+            Self::ext(env::current_account_id())
+                .with_static_gas(GAS_FOR_FEE_DEPOSIT_CALLBACK)
+                .on_fee_deposit_settled(settlement_id)
+        })
+    }
+}
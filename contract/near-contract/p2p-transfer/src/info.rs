@@ -0,0 +1,32 @@
+use crate::*;
+use crate::events::EVENTS;
+
+#[near_bindgen]
+impl P2PTransferContract {
+    // Configuration and counters in the shape every Ciphra contract shares
+    pub fn get_contract_info(&self) -> ContractInfo {
+        ContractInfo::new(
+            EVENTS.contract,
+            env!("CARGO_PKG_VERSION"),
+            format!("{:?}", self.get_state_version()),
+            &self.owner,
+            self.access.pending_owner.as_ref(),
+            &self.pause_guard,
+        )
+        .fee("direct_bps", self.fee_config.direct_bps)
+        .fee("shielded_withdraw_bps", self.fee_config.shielded_withdraw_bps)
+        .fee("batch_bps", self.fee_config.batch_bps)
+        .fee("internal_bps", self.fee_config.internal_bps)
+        .bound("max_notes_per_deposit", MAX_NOTES_PER_DEPOSIT as u64)
+        .bound("max_split_recipients", MAX_SPLIT_RECIPIENTS as u64)
+        .bound("retention_period", self.retention_period / 1_000_000_000)
+        .bound("current_circuit_version", self.current_circuit_version as u64)
+        .counter("transfers", self.transfers.len())
+        .counter("shielded_notes", self.shielded_pool.len())
+        .counter("unspent_notes", self.pool_stats.unspent_notes)
+        .counter("nullifiers", self.pool_stats.nullifier_count)
+        .counter("audit_entries", self.audit_log.len())
+        .counter("settlements", self.settlements.next_settlement_id)
+        .rate_limit(self.rate_limiter.config)
+    }
+}
@@ -0,0 +1,203 @@
+use crate::*;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum RequestStatus {
+    Requested,
+    Paid,
+    Expired,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PaymentRequest {
+    pub request_id: String,
+    pub payee: String,
+    pub payer: Option<String>,
+    #[borsh(serialize_with = "decimal_borsh::serialize", deserialize_with = "decimal_borsh::deserialize")]
+    #[schemars(with = "String")]
+    pub amount: U128,
+    pub memo: String,
+    pub expiry: u64,
+    pub status: RequestStatus,
+    pub transfer_id: Option<String>,
+    pub created_at: u64,
+    pub paid_at: Option<u64>,
+}
+
+impl PaymentRequest {
+    // Requests past their expiry are reported as Expired even before expire_request is called
+    fn with_effective_status(mut self, now: u64) -> Self {
+        if self.status == RequestStatus::Requested && now >= self.expiry {
+            self.status = RequestStatus::Expired;
+        }
+        self
+    }
+}
+
+#[near_bindgen]
+impl P2PTransferContract {
+    // Payment request / invoice - the caller is the payee, optionally addressed to a single payer
+    pub fn create_request(
+        &mut self,
+        request_id: String,
+        amount: U128,
+        memo: String,
+        expiry: u64,
+        payer: Option<AccountId>,
+    ) -> PaymentRequest {
+        let payee = env::predecessor_account_id();
+        
+        ensure!(amount.0 > 0, InvalidArgument, "Amount must be positive");
+        ensure!(self.payment_requests.get(&request_id).is_none(), AlreadyExists, "Request ID already exists");
+        ensure!(expiry > env::block_timestamp(), InvalidArgument, "Expiry must be in future");
+        
+        let request = PaymentRequest {
+            request_id: request_id.clone(),
+            payee: payee.to_string(),
+            payer: payer.as_ref().map(|p| p.to_string()),
+            amount,
+            memo,
+            expiry,
+            status: RequestStatus::Requested,
+            transfer_id: None,
+            created_at: env::block_timestamp(),
+            paid_at: None,
+        };
+        
+        self.payment_requests.insert(&request_id, &request);
+        self.add_request_to_payee(&payee, &request_id);
+        if let Some(payer) = payer.as_ref() {
+            self.add_request_to_payer(payer, &request_id);
+        }
+        
+        env::log_str(&format!(
+            "Payment request created: {} | Payee: {} | Amount: {} | Expiry: {}",
+            request_id, payee, amount.0, expiry
+        ));
+        
+        request
+    }
+
+    // Settle a request by id - the attached deposit must match the requested amount exactly
+    #[payable]
+    pub fn pay_request(&mut self, request_id: String) -> Promise {
+        self.assert_not_paused();
+        let payer = env::predecessor_account_id();
+        let amount = env::attached_deposit();
+        let mut request = self.payment_requests.get(&request_id).or_fail(ErrorCode::NotFound, "Request not found");
+        
+        ensure!(request.status == RequestStatus::Requested, InvalidState, "Request is not payable");
+        ensure!(env::block_timestamp() < request.expiry, Expired, "Request has expired");
+        if let Some(expected_payer) = request.payer.as_ref() {
+            ensure!(&payer.to_string() == expected_payer, Unauthorized, "Request is addressed to another payer");
+        }
+        
+        let amount_yocto: u128 = request.amount.0;
+        ensure!(amount.as_yoctonear() == amount_yocto, InvalidArgument, "Attached deposit must equal requested amount");
+        
+        let transfer_id = format!("request:{}", request_id);
+        self.assert_new_transfer_id(&transfer_id);
+        
+        let payee: AccountId = request.payee.parse().or_fail(ErrorCode::Internal, "Invalid payee");
+        
+        let transfer = Transfer {
+            transfer_id: transfer_id.clone(),
+            sender: payer.to_string(),
+            recipient: payee.to_string(),
+            amount: U128(amount_yocto),
+            transfer_type: TransferType::Invoice,
+            status: TransferStatus::Completed,
+            commitment: None,
+            nullifier: None,
+            memo: request.memo.clone(),
+            encrypted_memo: None,
+            signer: None,
+            reference: None,
+            timestamp: env::block_timestamp(),
+        };
+        
+        self.transfers.insert(&transfer_id, &transfer);
+        self.track_new_transfer(&transfer);
+        self.lock_record_storage(&transfer);
+        self.add_user_transfer(&payer, &transfer_id);
+        self.add_user_transfer(&payee, &transfer_id);
+        
+        if request.payer.is_none() {
+            request.payer = Some(payer.to_string());
+            self.add_request_to_payer(&payer, &request_id);
+        }
+        request.status = RequestStatus::Paid;
+        request.transfer_id = Some(transfer_id);
+        request.paid_at = Some(env::block_timestamp());
+        self.payment_requests.insert(&request_id, &request);
+        
+        let fee_yocto = self.calculate_fee(amount_yocto, self.fee_config.direct_bps, &payer);
+        let payout_yocto = amount_yocto - fee_yocto;
+        
+        env::log_str(&format!(
+            "Payment request paid: {} | From: {} | To: {} | Amount: {}",
+            request_id, payer, payee, payout_yocto
+        ));
+        
+        // Send fee
+        self.distribute_fee(fee_yocto);
+        
+        self.settle(payee, payout_yocto)
+    }
+
+    // Persist the Expired state of an overdue request - callable by anyone
+    pub fn expire_request(&mut self, request_id: String) {
+        let mut request = self.payment_requests.get(&request_id).or_fail(ErrorCode::NotFound, "Request not found");
+        
+        ensure!(request.status == RequestStatus::Requested, InvalidState, "Request is not open");
+        ensure!(env::block_timestamp() >= request.expiry, TooEarly, "Request has not expired yet");
+        
+        request.status = RequestStatus::Expired;
+        self.payment_requests.insert(&request_id, &request);
+        
+        env::log_str(&format!("Payment request expired: {}", request_id));
+    }
+
+    pub fn get_request(&self, request_id: String) -> Option<PaymentRequest> {
+        self.payment_requests
+            .get(&request_id)
+            .map(|request| request.with_effective_status(env::block_timestamp()))
+    }
+
+    pub fn get_requests_by_payer(
+        &self,
+        account_id: AccountId,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Paged<PaymentRequest> {
+        let now = env::block_timestamp();
+        let request_ids = self.requests_by_payer.get(&account_id).unwrap_or_default();
+        Paged::from_ids(&request_ids, from_index, limit, |request_id| self.payment_requests.get(request_id))
+            .map(|request| request.with_effective_status(now))
+    }
+
+    pub fn get_requests_by_payee(
+        &self,
+        account_id: AccountId,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Paged<PaymentRequest> {
+        let now = env::block_timestamp();
+        let request_ids = self.requests_by_payee.get(&account_id).unwrap_or_default();
+        Paged::from_ids(&request_ids, from_index, limit, |request_id| self.payment_requests.get(request_id))
+            .map(|request| request.with_effective_status(now))
+    }
+
+    fn add_request_to_payer(&mut self, payer: &AccountId, request_id: &str) {
+        let mut requests = self.requests_by_payer.get(payer).unwrap_or_default();
+        requests.push(request_id.to_string());
+        self.requests_by_payer.insert(payer, &requests);
+    }
+
+    fn add_request_to_payee(&mut self, payee: &AccountId, request_id: &str) {
+        let mut requests = self.requests_by_payee.get(payee).unwrap_or_default();
+        requests.push(request_id.to_string());
+        self.requests_by_payee.insert(payee, &requests);
+    }
+}
@@ -0,0 +1,201 @@
+use crate::*;
+
+#[near_bindgen]
+impl P2PTransferContract {
+    // Internal ledger - deposit once, then pay other ledger accounts without a Promise per payment
+    #[payable]
+    pub fn deposit_balance(&mut self) -> U128 {
+        self.assert_not_paused();
+        let account_id = env::predecessor_account_id();
+        let amount = env::attached_deposit();
+        
+        ensure!(amount.as_yoctonear() > 0, InsufficientFunds, "Must attach NEAR tokens");
+        
+        let balance_yocto = self.balances.get(&account_id).unwrap_or(0) + amount.as_yoctonear();
+        self.balances.insert(&account_id, &balance_yocto);
+        self.liability_totals.ledger_balances += amount.as_yoctonear();
+        self.record_audit(AuditAction::BalanceDeposited, vec![], Some(amount.as_yoctonear().to_string()));
+        
+        env::log_str(&format!(
+            "Balance deposit: {} | Amount: {} | Balance: {}",
+            account_id, amount, balance_yocto
+        ));
+        
+        U128(balance_yocto)
+    }
+
+    // Move funds between ledger balances; the fee is credited to the fee recipients' balances
+    pub fn internal_transfer(
+        &mut self,
+        transfer_id: String,
+        recipient: String,
+        amount: U128,
+        memo: String,
+    ) -> Transfer {
+        let sender = env::predecessor_account_id();
+        let recipient = self.resolve_recipient(&recipient);
+        let amount_yocto = amount.0;
+        
+        ensure!(amount_yocto > 0, InvalidArgument, "Amount must be positive");
+        self.assert_new_transfer_id(&transfer_id);
+        ensure!(sender != recipient, InvalidArgument, "Cannot transfer to yourself");
+        self.enforce_spending_limit(&sender, amount_yocto);
+        
+        let sender_balance = self.balances.get(&sender).unwrap_or(0);
+        ensure!(sender_balance >= amount_yocto, InsufficientFunds, "Insufficient balance");
+        
+        let fee_yocto = self.calculate_fee(amount_yocto, self.fee_config.internal_bps, &sender);
+        let payout_yocto = amount_yocto - fee_yocto;
+        
+        self.balances.insert(&sender, &(sender_balance - amount_yocto));
+        self.credit_balance(&recipient, payout_yocto);
+        for (fee_recipient, share_yocto) in self.fee_shares(fee_yocto) {
+            if self.is_fee_contract(&fee_recipient) {
+                // Shares forwarded to a fee contract leave the ledger
+                self.liability_totals.ledger_balances -= share_yocto;
+                self.deposit_to_fee_contract(fee_recipient, share_yocto);
+            } else {
+                self.credit_balance(&fee_recipient, share_yocto);
+            }
+        }
+        
+        let transfer = Transfer {
+            transfer_id: transfer_id.clone(),
+            sender: sender.to_string(),
+            recipient: recipient.to_string(),
+            amount: U128(amount_yocto),
+            transfer_type: TransferType::Internal,
+            status: TransferStatus::Completed,
+            commitment: None,
+            nullifier: None,
+            memo,
+            encrypted_memo: None,
+            signer: None,
+            reference: None,
+            timestamp: env::block_timestamp(),
+        };
+        
+        self.transfers.insert(&transfer_id, &transfer);
+        self.track_new_transfer(&transfer);
+        self.lock_record_storage(&transfer);
+        self.add_user_transfer(&sender, &transfer_id);
+        self.add_user_transfer(&recipient, &transfer_id);
+        
+        env::log_str(&format!(
+            "Internal transfer: {} | From: {} | To: {} | Amount: {}",
+            transfer_id, sender, recipient, payout_yocto
+        ));
+        
+        transfer
+    }
+
+    // Withdraw ledger balance to native NEAR; the balance is restored if the transfer fails
+    pub fn withdraw_balance(&mut self, amount: U128) -> Promise {
+        let account_id = env::predecessor_account_id();
+        let balance_yocto = self.balances.get(&account_id).unwrap_or(0);
+        
+        ensure!(amount.0 > 0, InvalidArgument, "Amount must be positive");
+        ensure!(balance_yocto >= amount.0, InsufficientFunds, "Insufficient balance");
+        
+        self.balances.insert(&account_id, &(balance_yocto - amount.0));
+        self.liability_totals.ledger_balances -= amount.0;
+        self.record_audit(AuditAction::BalanceWithdrawn, vec![], Some(amount.0.to_string()));
+        
+        env::log_str(&format!(
+            "Balance withdrawal: {} | Amount: {}",
+            account_id, amount.0
+        ));
+        
+        Promise::new(account_id.clone())
+            .transfer(NearToken::from_yoctonear(amount.0))
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_PAYOUT_CALLBACK)
+                    .on_balance_withdrawn(account_id, amount),
+            )
+    }
+
+    #[private]
+    pub fn on_balance_withdrawn(&mut self, account_id: AccountId, amount: U128) -> bool {
+        if is_promise_success() {
+            return true;
+        }
+        
+        self.credit_balance(&account_id, amount.0);
+        self.liability_totals.ledger_balances += amount.0;
+        
+        env::log_str(&format!(
+            "Balance withdrawal failed: {} | Restored: {}",
+            account_id, amount.0
+        ));
+        
+        false
+    }
+
+    pub fn get_balance(&self, account_id: AccountId) -> U128 {
+        U128(self.balances.get(&account_id).unwrap_or(0))
+    }
+
+    fn credit_balance(&mut self, account_id: &AccountId, amount_yocto: u128) {
+        let balance_yocto = self.balances.get(account_id).unwrap_or(0);
+        self.balances.insert(account_id, &(balance_yocto + amount_yocto));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use ciphra_testing as host;
+
+    const TREASURY: &str = "treasury.near";
+
+    fn funded_contract(fee_distribution: Vec<FeeShare>) -> P2PTransferContract {
+        let mut contract = contract();
+        contract.set_fee_config(FeeConfig { internal_bps: 100, ..FeeConfig::default() });
+        contract.set_fee_distribution(fee_distribution);
+        contract.set_fee_treasury(Some(account(TREASURY)));
+        
+        host::set_caller(SENDER);
+        host::set_deposit(host::NEAR);
+        contract.storage_deposit(None, None);
+        host::set_deposit(100 * host::NEAR);
+        contract.deposit_balance();
+        host::set_deposit(0);
+        contract
+    }
+
+    fn share(label: &str, recipient: &str, share_bps: u16) -> FeeShare {
+        FeeShare { label: label.to_string(), recipient: recipient.to_string(), share_bps }
+    }
+
+    #[test]
+    fn test_totals_exclude_fee_contract_shares() {
+        let mut contract = funded_contract(vec![share("treasury", TREASURY, 5000), share("owner", OWNER, 5000)]);
+        contract.internal_transfer("t1".to_string(), RECIPIENT.to_string(), U128(10 * host::NEAR), String::new());
+        
+        // 1% fee: half stays on the ledger with the owner, half goes to the fee treasury
+        assert_eq!(contract.get_balance(account(SENDER)), U128(90 * host::NEAR));
+        assert_eq!(contract.get_balance(account(RECIPIENT)), U128(9_900 * host::NEAR / 1000));
+        assert_eq!(contract.get_balance(account(OWNER)), U128(host::NEAR / 20));
+        assert_eq!(contract.liability_totals.ledger_balances, 99_950 * host::NEAR / 1000);
+        assert_eq!(host::function_calls()[0], (TREASURY.to_string(), "deposit_fees".to_string()));
+        assert_eq!(contract.settlements.total_pending, host::NEAR / 20);
+    }
+
+    #[test]
+    fn test_totals_unchanged_when_fees_stay_on_ledger() {
+        let mut contract = funded_contract(vec![share("owner", OWNER, 10000)]);
+        contract.internal_transfer("t1".to_string(), RECIPIENT.to_string(), U128(10 * host::NEAR), String::new());
+        
+        assert_eq!(contract.get_balance(account(OWNER)), U128(host::NEAR / 10));
+        assert_eq!(contract.liability_totals.ledger_balances, 100 * host::NEAR);
+    }
+
+    #[test]
+    #[should_panic(expected = "Insufficient balance")]
+    fn test_transfer_above_balance() {
+        let mut contract = funded_contract(vec![share("owner", OWNER, 10000)]);
+        contract.internal_transfer("t1".to_string(), RECIPIENT.to_string(), U128(101 * host::NEAR), String::new());
+    }
+}
@@ -1,31 +1,158 @@
 use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
-use near_sdk::collections::UnorderedMap;
+use near_sdk::collections::{LookupMap, LookupSet, UnorderedMap, UnorderedSet, Vector};
 use near_sdk::json_types::U128;
-use near_sdk::{env, near_bindgen, AccountId, BorshStorageKey, PanicOnDefault, Promise, NearToken};
+use near_sdk::{env, is_promise_success, near_bindgen, AccountId, BorshStorageKey, Gas, PanicOnDefault, Promise, PromiseOrValue, NearToken};
 use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::serde_json::json;
 use schemars::JsonSchema;
 
+use ciphra_types::{assert_owner, decimal_borsh, decimal_borsh_option, ensure, fail, ErrorCode, OrFail};
+pub use ciphra_types::{
+    AccessControl, AccessStatus, ContractInfo, EscrowMetadata, EscrowStatus, HashAlgorithm, Paged, PauseGuard,
+    PauseStatus, RateLimitConfig, RateLimiter, Role, SettlementLedger, StorageAccounts, StorageBalance,
+    StorageBalanceBounds, SwapStatus, TransferStatus,
+};
+
+mod access;
+mod aliases;
+mod audit;
+mod chargeback;
+mod circuits;
+mod claimable;
+mod denominations;
+mod events;
+mod fees;
+mod invoices;
+mod ledger;
+mod limits;
+mod merkle;
+mod info;
+mod migration;
+mod pause;
+mod pool_stats;
+mod pruning;
+mod rate_limit;
+mod relayers;
+mod scheduled;
+mod settlement;
+mod solvency;
+mod stats;
+mod storage;
+mod streams;
+mod subscriptions;
+#[cfg(test)]
+mod test_utils;
+
+// Shielded spends take each proof input and output as an argument, and ext_swap mirrors the
+// swap contract's initiate_swap. The generated ext wrappers don't keep a method's #[allow],
+// so these modules allow the lint.
+#[allow(clippy::too_many_arguments)]
+mod shielded;
+#[allow(clippy::too_many_arguments)]
+mod shielded_swaps;
+
+use circuits::LEGACY_CIRCUIT_VERSION;
+use events::emit_event;
+use fees::default_fee_distribution;
+use merkle::decode_field_element;
+
+pub use aliases::HandleRecord;
+pub use audit::{AuditAction, AuditEntry};
+pub use chargeback::{ChargebackConfig, ChargebackHold, EscrowSnapshot};
+pub use claimable::ClaimableTransfer;
+pub use denominations::DenominatedDeposit;
+pub use fees::{FeeConfig, FeeShare};
+pub use invoices::{PaymentRequest, RequestStatus};
+pub use limits::SpendingLimit;
+pub use merkle::{CommitmentTree, MerklePath};
+pub use migration::StateVersion;
+pub use pool_stats::{PoolStats, PoolStatsView};
+pub use scheduled::ScheduledTransfer;
+pub use shielded_swaps::{ShieldedSwap, ShieldedSwapRequest, SwapSnapshot};
+pub use solvency::{LiabilityTotals, SolvencyReport};
+pub use stats::{AccountStats, AccountStatsView};
+pub use streams::{Stream, StreamStatus};
+pub use subscriptions::{Subscription, SubscriptionStatus};
+
 #[derive(BorshSerialize, BorshStorageKey)]
 pub enum StorageKey {
     Transfers,
     UserTransfers,
     ShieldedPool,
+    SplitPayouts,
+    ScheduledTransfers,
+    Subscriptions,
+    UserSubscriptions,
+    Streams,
+    UserStreams,
+    ClaimableTransfers,
+    PaymentRequests,
+    RequestsByPayer,
+    RequestsByPayee,
+    ClaimableRefunds, // Dropped in V5; kept so later keys keep their prefixes
+    SpendingLimits,
+    Handles,
+    FeeExempt,
+    DepositsByDenomination,
+    Nullifiers,
+    TreeNodes,
+    TreeRoots, // SHA-256 roots, left behind by the V8 rebuild
+    AccountStats,
+    TransfersByReference,
+    Balances,
+    StorageAccounts,
+    RecordStoragePayers,
+    ChargebackHolds,
+    ShieldedSwaps,
+    VerifyingKeys,
+    AuditLog,
+    PendingSettlements,
+    ClaimableSettlements,
+    RateLimits,
+    Relayers,
+    TreeRootSlots, // Unused since V8
+    PoseidonTreeRoots,
+    PoseidonTreeRootSlots,
 }
 
+// Upper bound on recipients per split so payouts fit in a single call's gas
+const MAX_SPLIT_RECIPIENTS: usize = 20;
+
+// Default reward paid to whoever executes a due scheduled transfer (0.001 NEAR)
+const DEFAULT_EXECUTOR_BOUNTY: u128 = 1_000_000_000_000_000_000_000;
+
+// Default fee for registering a payment handle (0.1 NEAR)
+const DEFAULT_HANDLE_REGISTRATION_FEE: u128 = 100_000_000_000_000_000_000_000;
+
+// Default minimum age before a settled transfer record can be pruned (90 days)
+const DEFAULT_RETENTION_PERIOD: u64 = 90 * 86_400 * 1_000_000_000;
+
+// Upper bound on notes created by one batch or denominated deposit
+const MAX_NOTES_PER_DEPOSIT: usize = 20;
+
+// Cap on encrypted memo blobs (base64/hex ciphertext) to bound per-transfer storage
+const MAX_ENCRYPTED_MEMO_LENGTH: usize = 1024;
+
+const MAX_REFERENCE_LENGTH: usize = 128;
+
+const GAS_FOR_PAYOUT_CALLBACK: Gas = Gas::from_tgas(10);
+
+// Ids the contract generates for its own transfer records; callers can't claim them
+const RESERVED_TRANSFER_ID_PREFIX: &str = "subscription:";
+
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
 #[serde(crate = "near_sdk::serde")]
 pub enum TransferType {
     Direct,
     Shielded,
-}
-
-#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
-#[serde(crate = "near_sdk::serde")]
-pub enum TransferStatus {
-    Pending,
-    Completed,
-    Failed,
-    Cancelled,
+    Split,
+    Scheduled,
+    Subscription,
+    Claimable,
+    Invoice,
+    Internal,
+    Protected,
+    Swap,
 }
 
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, JsonSchema)]
@@ -34,12 +161,17 @@ pub struct Transfer {
     pub transfer_id: String,
     pub sender: String,
     pub recipient: String,
-    pub amount: String,
+    #[borsh(serialize_with = "decimal_borsh::serialize", deserialize_with = "decimal_borsh::deserialize")]
+    #[schemars(with = "String")]
+    pub amount: U128,
     pub transfer_type: TransferType,
     pub status: TransferStatus,
     pub commitment: Option<String>, // For shielded transactions
     pub nullifier: Option<String>,  // For shielded transactions
     pub memo: String,
+    pub encrypted_memo: Option<String>, // Encrypted to the recipient's public key
+    pub signer: Option<String>,         // Transaction signer when it differs from the sender (e.g. NEP-366 relayer)
+    pub reference: Option<String>,      // External reference such as a merchant order id
     pub timestamp: u64,
 }
 
@@ -48,274 +180,593 @@ pub struct Transfer {
 pub struct ShieldedNote {
     pub note_id: String,
     pub commitment: String,
-    pub amount: String,
+    #[borsh(serialize_with = "decimal_borsh::serialize", deserialize_with = "decimal_borsh::deserialize")]
+    #[schemars(with = "String")]
+    pub amount: U128,
     pub spent: bool,
     pub nullifier: Option<String>,
+    pub leaf_index: u64,
+    pub circuit_version: u16, // Circuit whose verifying key checks spends of this note
     pub created_at: u64,
 }
 
-#[near_bindgen]
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SplitShare {
+    pub recipient: String,
+    pub share_bps: u16,
+    #[borsh(serialize_with = "decimal_borsh::serialize", deserialize_with = "decimal_borsh::deserialize")]
+    #[schemars(with = "String")]
+    pub amount: U128,
+}
+
+// Version and link come from the package; transfers also emit NEP-297 events
+#[near_bindgen(contract_metadata(
+    standard(standard = "nep330", version = "1.1.0"),
+    standard(standard = "nep297", version = "1.0.0"),
+))]
 #[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
 pub struct P2PTransferContract {
     pub transfers: UnorderedMap<String, Transfer>,
     pub user_transfers: UnorderedMap<AccountId, Vec<String>>,
     pub shielded_pool: UnorderedMap<String, ShieldedNote>,
+    pub split_payouts: UnorderedMap<String, Vec<SplitShare>>,
+    pub scheduled_transfers: UnorderedMap<String, ScheduledTransfer>,
+    pub subscriptions: UnorderedMap<String, Subscription>,
+    pub user_subscriptions: UnorderedMap<AccountId, Vec<String>>,
+    pub streams: UnorderedMap<String, Stream>,
+    pub user_streams: UnorderedMap<AccountId, Vec<String>>,
+    pub claimable_transfers: UnorderedMap<String, ClaimableTransfer>,
+    pub payment_requests: UnorderedMap<String, PaymentRequest>,
+    pub requests_by_payer: UnorderedMap<AccountId, Vec<String>>,
+    pub requests_by_payee: UnorderedMap<AccountId, Vec<String>>,
+    pub spending_limits: LookupMap<AccountId, SpendingLimit>,
+    pub handles: UnorderedMap<String, HandleRecord>,
+    pub pool_stats: PoolStats,
+    pub deposits_by_denomination: UnorderedMap<u128, u64>,
+    pub nullifiers: LookupSet<String>,
+    pub denominations: Vec<u128>,
+    pub commitment_tree: CommitmentTree,
+    pub account_stats: LookupMap<AccountId, AccountStats>,
+    pub transfers_by_reference: LookupMap<String, String>,
+    pub balances: LookupMap<AccountId, u128>,
+    pub storage_accounts: StorageAccounts,
+    pub record_storage_payers: LookupMap<String, AccountId>,
+    pub chargeback_holds: LookupMap<String, ChargebackHold>,
+    pub chargeback_config: Option<ChargebackConfig>,
+    pub shielded_swaps: LookupMap<String, ShieldedSwap>,
+    pub swap_contract: Option<AccountId>,
+    pub verifying_keys: LookupMap<u16, Vec<u8>>,
+    pub current_circuit_version: u16,
+    pub audit_log: Vector<AuditEntry>,
+    pub liability_totals: LiabilityTotals,
     pub owner: AccountId,
-    pub fee_percentage: u16,
-    pub fee_recipient: AccountId,
+    pub fee_config: FeeConfig,
+    pub fee_exempt: UnorderedSet<AccountId>,
+    pub fee_distribution: Vec<FeeShare>,
+    pub executor_bounty: u128,
+    pub handle_registration_fee: u128,
+    pub retention_period: u64,
+    pub pause_guard: PauseGuard, // Guardians may pause new deposits but not unpause
+    pub fee_treasury: Option<AccountId>,
+    pub access: AccessControl, // Pending owner transfer and delegated roles
+    pub settlements: SettlementLedger, // Payouts and refunds awaiting their transfer callback
+    pub rate_limiter: RateLimiter,     // Bounds shielded notes deposited per account
+    pub relayers: UnorderedSet<AccountId>, // NEP-366 relayers recorded as transfer signers
+    pub prune_cursor: u64, // Position in `transfers` where the next prune call resumes scanning
 }
 
 #[near_bindgen]
 impl P2PTransferContract {
     #[init]
     pub fn new(owner: AccountId) -> Self {
-        let fee_recipient = owner.clone();
+        let fee_distribution = default_fee_distribution(&owner);
+        Self::write_state_version();
         Self {
             transfers: UnorderedMap::new(StorageKey::Transfers),
             user_transfers: UnorderedMap::new(StorageKey::UserTransfers),
             shielded_pool: UnorderedMap::new(StorageKey::ShieldedPool),
+            split_payouts: UnorderedMap::new(StorageKey::SplitPayouts),
+            scheduled_transfers: UnorderedMap::new(StorageKey::ScheduledTransfers),
+            subscriptions: UnorderedMap::new(StorageKey::Subscriptions),
+            user_subscriptions: UnorderedMap::new(StorageKey::UserSubscriptions),
+            streams: UnorderedMap::new(StorageKey::Streams),
+            user_streams: UnorderedMap::new(StorageKey::UserStreams),
+            claimable_transfers: UnorderedMap::new(StorageKey::ClaimableTransfers),
+            payment_requests: UnorderedMap::new(StorageKey::PaymentRequests),
+            requests_by_payer: UnorderedMap::new(StorageKey::RequestsByPayer),
+            requests_by_payee: UnorderedMap::new(StorageKey::RequestsByPayee),
+            spending_limits: LookupMap::new(StorageKey::SpendingLimits),
+            handles: UnorderedMap::new(StorageKey::Handles),
+            pool_stats: PoolStats::default(),
+            deposits_by_denomination: UnorderedMap::new(StorageKey::DepositsByDenomination),
+            nullifiers: LookupSet::new(StorageKey::Nullifiers),
+            denominations: denominations::default_denominations(),
+            commitment_tree: CommitmentTree::new(),
+            account_stats: LookupMap::new(StorageKey::AccountStats),
+            transfers_by_reference: LookupMap::new(StorageKey::TransfersByReference),
+            balances: LookupMap::new(StorageKey::Balances),
+            storage_accounts: StorageAccounts::new(StorageKey::StorageAccounts),
+            record_storage_payers: LookupMap::new(StorageKey::RecordStoragePayers),
+            chargeback_holds: LookupMap::new(StorageKey::ChargebackHolds),
+            chargeback_config: None,
+            shielded_swaps: LookupMap::new(StorageKey::ShieldedSwaps),
+            swap_contract: None,
+            verifying_keys: LookupMap::new(StorageKey::VerifyingKeys),
+            current_circuit_version: LEGACY_CIRCUIT_VERSION,
+            audit_log: Vector::new(StorageKey::AuditLog),
+            liability_totals: LiabilityTotals::default(),
             owner,
-            fee_percentage: 10, // 0.1% for direct transfers
-            fee_recipient,
+            fee_config: FeeConfig::default(),
+            fee_exempt: UnorderedSet::new(StorageKey::FeeExempt),
+            fee_distribution,
+            executor_bounty: DEFAULT_EXECUTOR_BOUNTY,
+            handle_registration_fee: DEFAULT_HANDLE_REGISTRATION_FEE,
+            retention_period: DEFAULT_RETENTION_PERIOD,
+            pause_guard: PauseGuard::default(),
+            fee_treasury: None,
+            access: AccessControl::default(),
+            settlements: Self::new_settlement_ledger(),
+            rate_limiter: Self::new_rate_limiter(),
+            relayers: Self::new_relayers(),
+            prune_cursor: 0,
         }
     }
 
-    // Direct P2P transfer
+    // Direct P2P transfer. `recipient` is an account id or a registered "@handle".
+    // Works with NEP-366 meta-transactions: the sender is always the predecessor
+    // (the delegating user), while a relayer that paid the gas is recorded as `signer`.
+    // With `hold` set, funds stay in the contract until the recipient accepts
+    // (or the sender cancels) instead of being sent instantly.
     #[payable]
     pub fn send_direct(
         &mut self,
         transfer_id: String,
-        recipient: AccountId,
+        recipient: String,
         memo: String,
-    ) -> Promise {
+        hold: Option<bool>,
+        encrypted_memo: Option<String>,
+        reference: Option<String>,
+    ) -> PromiseOrValue<Transfer> {
+        self.assert_not_paused();
         let sender = env::predecessor_account_id();
         let amount = env::attached_deposit();
+        let hold = hold.unwrap_or(false);
+        let recipient = self.resolve_recipient(&recipient);
         
-        assert!(amount.as_yoctonear() > 0, "Must attach NEAR tokens");
-        assert!(self.transfers.get(&transfer_id).is_none(), "Transfer ID already exists");
+        ensure!(amount.as_yoctonear() > 0, InsufficientFunds, "Must attach NEAR tokens");
+        self.assert_new_transfer_id(&transfer_id);
+        self.enforce_spending_limit(&sender, amount.as_yoctonear());
+        if let Some(encrypted_memo) = encrypted_memo.as_ref() {
+            ensure!(
+                encrypted_memo.len() <= MAX_ENCRYPTED_MEMO_LENGTH,
+                LimitExceeded,
+                "Encrypted memo exceeds 1024 characters"
+            );
+        }
+        if let Some(reference) = reference.as_ref() {
+            ensure!(
+                !reference.is_empty() && reference.len() <= MAX_REFERENCE_LENGTH,
+                InvalidArgument,
+                "Reference must be 1-128 characters"
+            );
+            ensure!(
+                self.transfers_by_reference.get(reference).is_none(),
+                AlreadyExists,
+                "Reference already used"
+            );
+        }
         
         let transfer = Transfer {
             transfer_id: transfer_id.clone(),
             sender: sender.to_string(),
             recipient: recipient.to_string(),
-            amount: amount.as_yoctonear().to_string(),
+            amount: U128(amount.as_yoctonear()),
             transfer_type: TransferType::Direct,
-            status: TransferStatus::Completed,
+            status: if hold { TransferStatus::Pending } else { TransferStatus::Completed },
             commitment: None,
             nullifier: None,
             memo,
+            encrypted_memo,
+            signer: self.relaying_signer(),
+            reference,
             timestamp: env::block_timestamp(),
         };
         
         self.transfers.insert(&transfer_id, &transfer);
+        self.track_new_transfer(&transfer);
+        self.lock_record_storage(&transfer);
         self.add_user_transfer(&sender, &transfer_id);
         self.add_user_transfer(&recipient, &transfer_id);
         
+        if let Some(reference) = transfer.reference.as_ref() {
+            self.transfers_by_reference.insert(reference, &transfer_id);
+        }
+        
+        if let Some(signer) = transfer.signer.as_ref() {
+            env::log_str(&format!(
+                "Relayed transfer: {} | Sender: {} | Relayer: {}",
+                transfer_id, sender, signer
+            ));
+        }
+        
+        if let Some(encrypted_memo) = transfer.encrypted_memo.as_ref() {
+            env::log_str(&format!(
+                "Encrypted memo: {} | To: {} | Memo: {}",
+                transfer_id, recipient, encrypted_memo
+            ));
+        }
+        
+        if hold {
+            env::log_str(&format!(
+                "Direct transfer held: {} | From: {} | To: {} | Amount: {}",
+                transfer_id, sender, recipient, amount
+            ));
+            emit_event("transfer_held", json!({
+                "transfer_id": transfer_id,
+                "sender": sender,
+                "recipient": recipient,
+                "amount": transfer.amount,
+            }));
+            return PromiseOrValue::Value(transfer);
+        }
+        
         // Calculate fee
         let amount_yocto = amount.as_yoctonear();
-        let fee_yocto = (amount_yocto * self.fee_percentage as u128) / 10000;
+        let fee_yocto = self.calculate_fee(amount_yocto, self.fee_config.direct_bps, &sender);
         let payout_yocto = amount_yocto - fee_yocto;
         
         env::log_str(&format!(
             "Direct transfer: {} | From: {} | To: {} | Amount: {}",
             transfer_id, sender, recipient, payout_yocto
         ));
+        emit_event("transfer_completed", json!({
+            "transfer_id": transfer_id,
+            "sender": sender,
+            "recipient": recipient,
+            "amount": U128(payout_yocto),
+            "fee": U128(fee_yocto),
+        }));
         
         // Send fee
-        if fee_yocto > 0 {
-            let fee = NearToken::from_yoctonear(fee_yocto);
-            Promise::new(self.fee_recipient.clone()).transfer(fee);
-        }
+        self.distribute_fee(fee_yocto);
         
-        // Send to recipient
-        let payout = NearToken::from_yoctonear(payout_yocto);
-        Promise::new(recipient).transfer(payout)
+        // Send to recipient, refunding the sender if the transfer fails
+        PromiseOrValue::Promise(self.payout_with_refund(&transfer_id, recipient, sender, payout_yocto))
     }
 
-    // Shielded deposit - create commitment
-    #[payable]
-    pub fn shield_deposit(
-        &mut self,
-        note_id: String,
-        commitment: String,
-    ) -> ShieldedNote {
-        let sender = env::predecessor_account_id();
-        let amount = env::attached_deposit();
+    // Recipient accepts a held direct transfer and receives the funds
+    pub fn accept_transfer(&mut self, transfer_id: String) -> Promise {
+        let mut transfer = self.transfers.get(&transfer_id).or_fail(ErrorCode::NotFound, "Transfer not found");
         
-        assert!(amount.as_yoctonear() > 0, "Must attach NEAR tokens");
-        assert!(self.shielded_pool.get(&note_id).is_none(), "Note ID already exists");
-        assert!(commitment.len() == 64, "Commitment must be 64 characters");
+        let recipient: AccountId = transfer.recipient.parse().or_fail(ErrorCode::Internal, "Invalid recipient");
+        ensure!(env::predecessor_account_id() == recipient, Unauthorized, "Only recipient can accept");
+        ensure!(
+            matches!(transfer.transfer_type, TransferType::Direct)
+                && matches!(transfer.status, TransferStatus::Pending),
+            InvalidState,
+            "Transfer is not a held direct transfer"
+        );
         
-        let note = ShieldedNote {
-            note_id: note_id.clone(),
-            commitment: commitment.clone(),
-            amount: amount.as_yoctonear().to_string(),
-            spent: false,
-            nullifier: None,
-            created_at: env::block_timestamp(),
-        };
+        transfer.status = TransferStatus::Completed;
+        transfer.timestamp = env::block_timestamp();
+        self.transfers.insert(&transfer_id, &transfer);
+        self.track_status_change(&transfer, &TransferStatus::Pending);
         
-        self.shielded_pool.insert(&note_id, &note);
+        let sender: AccountId = transfer.sender.parse().or_fail(ErrorCode::Internal, "Invalid sender");
+        let amount_yocto: u128 = transfer.amount.0;
+        let fee_yocto = self.calculate_fee(amount_yocto, self.fee_config.direct_bps, &sender);
+        let payout_yocto = amount_yocto - fee_yocto;
         
         env::log_str(&format!(
-            "Shielded deposit: {} | Commitment: {} | Amount: {}",
-            note_id, commitment, amount
+            "Held transfer accepted: {} | To: {} | Amount: {}",
+            transfer_id, recipient, payout_yocto
         ));
+        emit_event("transfer_completed", json!({
+            "transfer_id": transfer_id,
+            "sender": sender,
+            "recipient": recipient,
+            "amount": U128(payout_yocto),
+            "fee": U128(fee_yocto),
+        }));
         
-        note
+        // Send fee
+        self.distribute_fee(fee_yocto);
+        
+        self.payout_with_refund(&transfer_id, recipient, sender, payout_yocto)
     }
 
-    // Shielded transfer - spend commitment, create new one
-    pub fn shield_transfer(
-        &mut self,
-        transfer_id: String,
-        input_note_id: String,
-        nullifier: String,
-        new_commitment: String,
-        recipient_commitment: String,
-        proof: String, // ZK proof (simplified for hackathon)
-        memo: String,
-    ) -> Promise {
-        assert!(self.transfers.get(&transfer_id).is_none(), "Transfer ID already exists");
-        assert!(proof.len() > 0, "Proof required");
+    // Sender cancels a pending transfer (held direct, scheduled, or unclaimed claim-link)
+    // before it completes and gets the escrowed funds back
+    pub fn cancel_transfer(&mut self, transfer_id: String) -> Promise {
+        let mut transfer = self.transfers.get(&transfer_id).or_fail(ErrorCode::NotFound, "Transfer not found");
         
-        // Get and verify input note
-        let mut input_note = self.shielded_pool.get(&input_note_id)
-            .expect("Input note not found");
-        assert!(!input_note.spent, "Note already spent");
+        let sender: AccountId = transfer.sender.parse().or_fail(ErrorCode::Internal, "Invalid sender");
+        ensure!(env::predecessor_account_id() == sender, Unauthorized, "Only sender can cancel");
+        ensure!(
+            matches!(transfer.status, TransferStatus::Pending),
+            InvalidState,
+            "Only pending transfers can be cancelled"
+        );
         
-        // Mark as spent
-        input_note.spent = true;
-        input_note.nullifier = Some(nullifier.clone());
-        self.shielded_pool.insert(&input_note_id, &input_note);
+        let amount_yocto: u128 = transfer.amount.0;
+        let refund_yocto = match transfer.transfer_type {
+            TransferType::Direct | TransferType::Claimable => amount_yocto,
+            // The executor bounty was escrowed alongside the payment
+            TransferType::Scheduled => {
+                let scheduled = self.scheduled_transfers
+                    .get(&transfer_id)
+                    .or_fail(ErrorCode::NotFound, "Scheduled transfer not found");
+                let bounty_yocto: u128 = scheduled.executor_bounty.0;
+                amount_yocto + bounty_yocto
+            }
+            _ => fail(ErrorCode::InvalidState, "Transfer type cannot be cancelled"),
+        };
         
-        // In production: Verify ZK proof here
-        // For hackathon: Simple validation
-        assert!(nullifier.len() == 64, "Invalid nullifier");
-        assert!(new_commitment.len() == 64, "Invalid new commitment");
-        assert!(recipient_commitment.len() == 64, "Invalid recipient commitment");
+        transfer.status = TransferStatus::Cancelled;
+        transfer.timestamp = env::block_timestamp();
+        self.transfers.insert(&transfer_id, &transfer);
+        self.track_status_change(&transfer, &TransferStatus::Pending);
         
-        let amount_yocto: u128 = input_note.amount.parse().expect("Invalid amount");
+        env::log_str(&format!(
+            "Transfer cancelled: {} | Refund: {}",
+            transfer_id, refund_yocto
+        ));
+        emit_event("transfer_cancelled", json!({
+            "transfer_id": transfer_id,
+            "refund": U128(refund_yocto),
+        }));
         
-        // Create transfer record (sender/recipient hidden)
-        let transfer = Transfer {
-            transfer_id: transfer_id.clone(),
-            sender: "shielded".to_string(),
-            recipient: "shielded".to_string(),
-            amount: amount_yocto.to_string(),
-            transfer_type: TransferType::Shielded,
-            status: TransferStatus::Completed,
-            commitment: Some(recipient_commitment.clone()),
-            nullifier: Some(nullifier.clone()),
-            memo,
-            timestamp: env::block_timestamp(),
+        self.settle(sender, refund_yocto)
+    }
+
+    // Callback for payouts - a payout to a missing account is marked Failed and the amount
+    // is settled back to the sender, becoming claimable if that fails too
+    #[private]
+    pub fn on_payout_settled(&mut self, settlement_id: u64, transfer_id: String, refund_to: AccountId) -> bool {
+        let Some(settlement) = self.settlements.resolve(settlement_id, is_promise_success()) else {
+            return true;
         };
         
-        self.transfers.insert(&transfer_id, &transfer);
+        if let Some(mut transfer) = self.transfers.get(&transfer_id) {
+            let previous = transfer.status.clone();
+            transfer.status = TransferStatus::Failed;
+            self.transfers.insert(&transfer_id, &transfer);
+            self.track_status_change(&transfer, &previous);
+        }
         
         env::log_str(&format!(
-            "Shielded transfer: {} | Nullifier: {}",
-            transfer_id, nullifier
+            "Transfer failed: {} | Refunding: {} | Amount: {}",
+            transfer_id, refund_to, settlement.amount.0
         ));
+        emit_event("transfer_failed", json!({
+            "transfer_id": transfer_id,
+            "refund_to": refund_to,
+            "amount": settlement.amount,
+        }));
         
-        Promise::new(env::current_account_id())
+        self.settle(refund_to, settlement.amount.0);
+        
+        false
     }
 
-    // Shielded withdrawal - reveal recipient
-    pub fn shield_withdraw(
+    // Split payment - divide the deposit between recipients by basis-point shares
+    #[payable]
+    pub fn send_split(
         &mut self,
         transfer_id: String,
-        note_id: String,
-        nullifier: String,
-        recipient: AccountId,
-        proof: String,
+        recipients: Vec<(AccountId, u16)>,
+        memo: String,
     ) -> Promise {
-        assert!(self.transfers.get(&transfer_id).is_none(), "Transfer ID already exists");
+        self.assert_not_paused();
+        let sender = env::predecessor_account_id();
+        let amount = env::attached_deposit();
         
-        let mut note = self.shielded_pool.get(&note_id)
-            .expect("Note not found");
-        assert!(!note.spent, "Note already spent");
+        ensure!(amount.as_yoctonear() > 0, InsufficientFunds, "Must attach NEAR tokens");
+        self.assert_new_transfer_id(&transfer_id);
+        ensure!(!recipients.is_empty(), InvalidArgument, "At least one recipient required");
+        ensure!(recipients.len() <= MAX_SPLIT_RECIPIENTS, LimitExceeded, "Too many recipients");
+        ensure!(
+            recipients.iter().all(|(_, share_bps)| *share_bps > 0),
+            InvalidArgument,
+            "Each share must be greater than zero"
+        );
+        let total_bps: u32 = recipients.iter().map(|(_, share_bps)| *share_bps as u32).sum();
+        ensure!(total_bps == 10000, InvalidArgument, "Shares must sum to 10000 basis points");
         
-        // Mark as spent
-        note.spent = true;
-        note.nullifier = Some(nullifier.clone());
-        self.shielded_pool.insert(&note_id, &note);
+        // Calculate fee on the whole payment, then split the remainder
+        let amount_yocto = amount.as_yoctonear();
+        let fee_yocto = self.calculate_fee(amount_yocto, self.fee_config.batch_bps, &sender);
+        let distributable_yocto = amount_yocto - fee_yocto;
         
-        let amount_yocto: u128 = note.amount.parse().expect("Invalid amount");
-        let fee_yocto = (amount_yocto * self.fee_percentage as u128) / 10000;
-        let payout_yocto = amount_yocto - fee_yocto;
+        // Rounding dust goes to the last recipient so the full amount is paid out
+        let mut shares = Vec::with_capacity(recipients.len());
+        let mut allocated_yocto: u128 = 0;
+        for (index, (recipient, share_bps)) in recipients.iter().enumerate() {
+            let share_yocto = if index == recipients.len() - 1 {
+                distributable_yocto - allocated_yocto
+            } else {
+                (distributable_yocto * *share_bps as u128) / 10000
+            };
+            allocated_yocto += share_yocto;
+            shares.push(SplitShare {
+                recipient: recipient.to_string(),
+                share_bps: *share_bps,
+                amount: U128(share_yocto),
+            });
+        }
         
         let transfer = Transfer {
             transfer_id: transfer_id.clone(),
-            sender: "shielded".to_string(),
-            recipient: recipient.to_string(),
-            amount: payout_yocto.to_string(),
-            transfer_type: TransferType::Shielded,
+            sender: sender.to_string(),
+            recipient: "split".to_string(),
+            amount: U128(amount_yocto),
+            transfer_type: TransferType::Split,
             status: TransferStatus::Completed,
             commitment: None,
-            nullifier: Some(nullifier),
-            memo: "Shielded withdrawal".to_string(),
+            nullifier: None,
+            memo,
+            encrypted_memo: None,
+            signer: None,
+            reference: None,
             timestamp: env::block_timestamp(),
         };
         
         self.transfers.insert(&transfer_id, &transfer);
-        self.add_user_transfer(&recipient, &transfer_id);
+        self.track_new_transfer(&transfer);
+        self.lock_record_storage(&transfer);
+        self.split_payouts.insert(&transfer_id, &shares);
+        self.add_user_transfer(&sender, &transfer_id);
+        for ((recipient, _), share) in recipients.iter().zip(shares.iter()) {
+            self.add_user_transfer(recipient, &transfer_id);
+            self.track_received(recipient, share.amount.0);
+        }
         
         env::log_str(&format!(
-            "Shielded withdrawal: {} | To: {} | Amount: {}",
-            transfer_id, recipient, payout_yocto
+            "Split transfer: {} | From: {} | Recipients: {} | Amount: {}",
+            transfer_id, sender, shares.len(), distributable_yocto
         ));
+        emit_event("split_transfer", json!({
+            "transfer_id": transfer_id,
+            "sender": sender,
+            "recipients": shares.len(),
+            "amount": U128(distributable_yocto),
+        }));
         
         // Send fee
-        if fee_yocto > 0 {
-            let fee = NearToken::from_yoctonear(fee_yocto);
-            Promise::new(self.fee_recipient.clone()).transfer(fee);
-        }
+        self.distribute_fee(fee_yocto);
         
-        let payout = NearToken::from_yoctonear(payout_yocto);
-        Promise::new(recipient).transfer(payout)
+        // Send each share
+        recipients
+            .into_iter()
+            .zip(shares.iter())
+            .map(|((recipient, _), share)| {
+                let payout_yocto: u128 = share.amount.0;
+                self.settle(recipient, payout_yocto)
+            })
+            .reduce(|joint, payout| joint.and(payout))
+            .or_fail(ErrorCode::InvalidArgument, "At least one recipient required")
     }
 
     pub fn get_transfer(&self, transfer_id: String) -> Option<Transfer> {
         self.transfers.get(&transfer_id)
     }
 
-    pub fn get_user_transfers(&self, account_id: AccountId) -> Vec<Transfer> {
-        self.user_transfers
-            .get(&account_id)
-            .unwrap_or_default()
+    // Look up a transfer by the external reference supplied at send time
+    pub fn get_transfer_by_reference(&self, reference: String) -> Option<Transfer> {
+        self.transfers_by_reference
+            .get(&reference)
+            .and_then(|transfer_id| self.transfers.get(&transfer_id))
+    }
+
+    pub fn get_user_transfers(
+        &self,
+        account_id: AccountId,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Paged<Transfer> {
+        let transfer_ids = self.user_transfers.get(&account_id).unwrap_or_default();
+        Paged::from_ids(&transfer_ids, from_index, limit, |transfer_id| self.transfers.get(transfer_id))
+    }
+
+    // Paged over the pending transfers only, so `total` counts pending ones
+    pub fn get_pending_transfers(
+        &self,
+        account_id: AccountId,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Paged<Transfer> {
+        let transfer_ids = self.user_transfers.get(&account_id).unwrap_or_default();
+        let pending = transfer_ids
             .iter()
             .filter_map(|transfer_id| self.transfers.get(transfer_id))
-            .collect()
+            .filter(|transfer| matches!(transfer.status, TransferStatus::Pending));
+        Paged::from_iter(pending, from_index, limit)
+    }
+
+    pub fn get_split_payouts(&self, transfer_id: String) -> Option<Vec<SplitShare>> {
+        self.split_payouts.get(&transfer_id)
     }
 
     pub fn get_shielded_note(&self, note_id: String) -> Option<ShieldedNote> {
         self.shielded_pool.get(&note_id)
     }
 
+    // Notes in deposit order, for clients rebuilding the commitment tree
+    pub fn get_shielded_notes(&self, from_index: Option<u64>, limit: Option<u64>) -> Paged<ShieldedNote> {
+        let notes = self.shielded_pool.values_as_vector();
+        Paged::from_range(notes.len(), from_index, limit, |index| notes.get(index))
+    }
+
     pub fn is_nullifier_used(&self, nullifier: String) -> bool {
-        // Check all notes for this nullifier
-        for note_id in self.shielded_pool.keys() {
-            if let Some(note) = self.shielded_pool.get(&note_id) {
-                if let Some(used_nullifier) = note.nullifier {
-                    if used_nullifier == nullifier {
-                        return true;
-                    }
-                }
-            }
-        }
-        false
+        self.nullifiers.contains(&nullifier)
+    }
+
+    pub fn get_owner(&self) -> AccountId {
+        self.owner.clone()
+    }
+
+    // Send a payout through the settlement ledger, settling it back to refund_to if the transfer fails
+    fn payout_with_refund(
+        &mut self,
+        transfer_id: &str,
+        recipient: AccountId,
+        refund_to: AccountId,
+        amount_yocto: u128,
+    ) -> Promise {
+        let transfer_id = transfer_id.to_string();
+        self.settlements.send(recipient, amount_yocto, |settlement_id| {
+            Self::ext(env::current_account_id())
+                .with_static_gas(GAS_FOR_PAYOUT_CALLBACK)
+                .on_payout_settled(settlement_id, transfer_id, refund_to)
+        })
+    }
+
+    fn insert_note(&mut self, note_id: String, commitment: String, amount_yocto: u128) -> ShieldedNote {
+        ensure!(self.shielded_pool.get(&note_id).is_none(), AlreadyExists, "Note ID already exists");
+        let leaf_index = self.append_commitment(&commitment);
+        let note = ShieldedNote {
+            note_id: note_id.clone(),
+            commitment,
+            amount: U128(amount_yocto),
+            spent: false,
+            nullifier: None,
+            leaf_index,
+            circuit_version: self.current_circuit_version,
+            created_at: env::block_timestamp(),
+        };
+        
+        self.shielded_pool.insert(&note_id, &note);
+        self.lock_note_storage(&note_id);
+        self.record_note_created(amount_yocto);
+        self.record_audit(AuditAction::NoteCreated, vec![note_id], None);
+        note
     }
 
-    pub fn set_fee_percentage(&mut self, fee_percentage: u16) {
-        assert_eq!(env::predecessor_account_id(), self.owner, "Only owner");
-        assert!(fee_percentage <= 500, "Fee cannot exceed 5%");
-        self.fee_percentage = fee_percentage;
+    // Every spending path goes through the global nullifier set, so a nullifier
+    // revealed by one method can never be replayed through another
+    fn consume_nullifier(&mut self, nullifier: &str) {
+        // Lowercase only, so one nullifier has one spelling in the set
+        ensure!(
+            hex::encode(decode_field_element(nullifier, "Nullifier")) == nullifier,
+            InvalidArgument,
+            "Nullifier must be lowercase hex"
+        );
+        ensure!(
+            self.nullifiers.insert(&nullifier.to_string()),
+            AlreadyExists,
+            "Nullifier already used"
+        );
+        self.record_audit(AuditAction::NullifierSpent, vec![nullifier.to_string()], None);
     }
 
-    pub fn set_fee_recipient(&mut self, fee_recipient: AccountId) {
-        assert_eq!(env::predecessor_account_id(), self.owner, "Only owner");
-        self.fee_recipient = fee_recipient;
+    pub(crate) fn assert_new_transfer_id(&self, transfer_id: &str) {
+        ensure!(
+            !transfer_id.starts_with(RESERVED_TRANSFER_ID_PREFIX),
+            InvalidArgument,
+            "Transfer ID prefix is reserved"
+        );
+        ensure!(self.transfers.get(&transfer_id.to_string()).is_none(), AlreadyExists, "Transfer ID already exists");
     }
 
     fn add_user_transfer(&mut self, user: &AccountId, transfer_id: &str) {
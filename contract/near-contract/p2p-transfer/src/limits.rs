@@ -0,0 +1,110 @@
+use crate::*;
+
+// Spending windows are rolling 24h periods
+const SPENDING_WINDOW: u64 = 86_400 * 1_000_000_000;
+// Raising a limit only takes effect after this delay, so a stolen key can't lift it instantly
+const LIMIT_INCREASE_COOLDOWN: u64 = 86_400 * 1_000_000_000;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SpendingLimit {
+    #[borsh(serialize_with = "decimal_borsh::serialize", deserialize_with = "decimal_borsh::deserialize")]
+    #[schemars(with = "String")]
+    pub daily_cap: U128,
+    #[borsh(serialize_with = "decimal_borsh::serialize", deserialize_with = "decimal_borsh::deserialize")]
+    #[schemars(with = "String")]
+    pub spent_in_window: U128,
+    pub window_start: u64,
+    #[borsh(serialize_with = "decimal_borsh_option::serialize", deserialize_with = "decimal_borsh_option::deserialize")]
+    #[schemars(with = "Option<String>")]
+    pub pending_cap: Option<U128>,
+    pub pending_cap_effective_at: Option<u64>,
+}
+
+impl SpendingLimit {
+    // Promote a pending cap increase once its cooldown has elapsed
+    fn apply_pending(&mut self, now: u64) {
+        if let (Some(cap), Some(effective_at)) = (&self.pending_cap, self.pending_cap_effective_at) {
+            if now >= effective_at {
+                self.daily_cap = *cap;
+                self.pending_cap = None;
+                self.pending_cap_effective_at = None;
+            }
+        }
+    }
+}
+
+#[near_bindgen]
+impl P2PTransferContract {
+    // Opt-in daily spending cap. Lowering applies immediately, raising after a cooldown.
+    pub fn set_daily_limit(&mut self, daily_cap: U128) -> SpendingLimit {
+        let account_id = env::predecessor_account_id();
+        let now = env::block_timestamp();
+        
+        let limit = match self.spending_limits.get(&account_id) {
+            None => SpendingLimit {
+                daily_cap,
+                spent_in_window: U128(0),
+                window_start: now,
+                pending_cap: None,
+                pending_cap_effective_at: None,
+            },
+            Some(mut limit) => {
+                limit.apply_pending(now);
+                let current_cap: u128 = limit.daily_cap.0;
+                if daily_cap.0 <= current_cap {
+                    limit.daily_cap = daily_cap;
+                    limit.pending_cap = None;
+                    limit.pending_cap_effective_at = None;
+                } else {
+                    limit.pending_cap = Some(daily_cap);
+                    limit.pending_cap_effective_at = Some(now + LIMIT_INCREASE_COOLDOWN);
+                }
+                limit
+            }
+        };
+        
+        self.spending_limits.insert(&account_id, &limit);
+        
+        env::log_str(&format!(
+            "Daily limit updated: {} | Cap: {} | Pending: {:?}",
+            account_id, limit.daily_cap.0, limit.pending_cap.map(|cap| cap.0)
+        ));
+        
+        limit
+    }
+
+    pub fn get_spending_limit(&self, account_id: AccountId) -> Option<SpendingLimit> {
+        self.spending_limits.get(&account_id).map(|mut limit| {
+            let now = env::block_timestamp();
+            limit.apply_pending(now);
+            if now >= limit.window_start + SPENDING_WINDOW {
+                limit.spent_in_window = U128(0);
+                limit.window_start = now;
+            }
+            limit
+        })
+    }
+
+    // Record spending against the account's limit, panicking if it would exceed the cap
+    pub(crate) fn enforce_spending_limit(&mut self, account_id: &AccountId, amount_yocto: u128) {
+        let mut limit = match self.spending_limits.get(account_id) {
+            Some(limit) => limit,
+            None => return,
+        };
+        
+        let now = env::block_timestamp();
+        limit.apply_pending(now);
+        if now >= limit.window_start + SPENDING_WINDOW {
+            limit.spent_in_window = U128(0);
+            limit.window_start = now;
+        }
+        
+        let cap: u128 = limit.daily_cap.0;
+        let spent: u128 = limit.spent_in_window.0;
+        ensure!(spent + amount_yocto <= cap, LimitExceeded, "Daily spending limit exceeded");
+        
+        limit.spent_in_window = U128(spent + amount_yocto);
+        self.spending_limits.insert(account_id, &limit);
+    }
+}
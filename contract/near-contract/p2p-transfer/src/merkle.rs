@@ -0,0 +1,314 @@
+use crate::*;
+use ciphra_poseidon::{hash2, Fr};
+
+// Commitment tree depth - supports 2^20 notes
+pub(crate) const TREE_DEPTH: u8 = 20;
+const DEFAULT_ROOT_HISTORY_SIZE: u64 = 100;
+const MAX_ROOT_HISTORY_SIZE: u64 = 1000;
+
+pub(crate) type Hash = [u8; 32];
+
+// circomlib poseidon([left, right]), as the spend circuit hashes the tree. Nodes are field
+// elements: leaves are checked on append and every other node is a Poseidon output.
+fn hash_pair(left: &Hash, right: &Hash) -> Hash {
+    hash2(Fr::from_be_bytes_reduced(left), Fr::from_be_bytes_reduced(right)).to_be_bytes()
+}
+
+// Roots of empty subtrees at each level, starting from an all-zero leaf. Poseidon is costly
+// in wasm, so they're precomputed (`ciphra-poseidon hash <zero> <zero>` per level).
+const ZERO_HASHES: [Hash; TREE_DEPTH as usize + 1] = [
+    hash_from_hex("0000000000000000000000000000000000000000000000000000000000000000"),
+    hash_from_hex("2098f5fb9e239eab3ceac3f27b81e481dc3124d55ffed523a839ee8446b64864"),
+    hash_from_hex("1069673dcdb12263df301a6ff584a7ec261a44cb9dc68df067a4774460b1f1e1"),
+    hash_from_hex("18f43331537ee2af2e3d758d50f72106467c6eea50371dd528d57eb2b856d238"),
+    hash_from_hex("07f9d837cb17b0d36320ffe93ba52345f1b728571a568265caac97559dbc952a"),
+    hash_from_hex("2b94cf5e8746b3f5c9631f4c5df32907a699c58c94b2ad4d7b5cec1639183f55"),
+    hash_from_hex("2dee93c5a666459646ea7d22cca9e1bcfed71e6951b953611d11dda32ea09d78"),
+    hash_from_hex("078295e5a22b84e982cf601eb639597b8b0515a88cb5ac7fa8a4aabe3c87349d"),
+    hash_from_hex("2fa5e5f18f6027a6501bec864564472a616b2e274a41211a444cbe3a99f3cc61"),
+    hash_from_hex("0e884376d0d8fd21ecb780389e941f66e45e7acce3e228ab3e2156a614fcd747"),
+    hash_from_hex("1b7201da72494f1e28717ad1a52eb469f95892f957713533de6175e5da190af2"),
+    hash_from_hex("1f8d8822725e36385200c0b201249819a6e6e1e4650808b5bebc6bface7d7636"),
+    hash_from_hex("2c5d82f66c914bafb9701589ba8cfcfb6162b0a12acf88a8d0879a0471b5f85a"),
+    hash_from_hex("14c54148a0940bb820957f5adf3fa1134ef5c4aaa113f4646458f270e0bfbfd0"),
+    hash_from_hex("190d33b12f986f961e10c0ee44d8b9af11be25588cad89d416118e4bf4ebe80c"),
+    hash_from_hex("22f98aa9ce704152ac17354914ad73ed1167ae6596af510aa5b3649325e06c92"),
+    hash_from_hex("2a7c7c9b6ce5880b9f6f228d72bf6a575a526f29c66ecceef8b753d38bba7323"),
+    hash_from_hex("2e8186e558698ec1c67af9c14d463ffc470043c9c2988b954d75dd643f36b992"),
+    hash_from_hex("0f57c5571e9a4eab49e2c8cf050dae948aef6ead647392273546249d1c1ff10f"),
+    hash_from_hex("1830ee67b5fb554ad5f63d4388800e1cfe78e310697d46e43c9ce36134f72cca"),
+    hash_from_hex("2134e76ac5d21aab186c2be1dd8f84ee880a1e46eaf712f9d371b6df22191f3e"),
+];
+
+pub(crate) fn zero_hashes() -> &'static [Hash] {
+    &ZERO_HASHES
+}
+
+const fn hash_from_hex(hex: &str) -> Hash {
+    const fn nibble(digit: u8) -> u8 {
+        match digit {
+            b'0'..=b'9' => digit - b'0',
+            _ => digit - b'a' + 10,
+        }
+    }
+    let digits = hex.as_bytes();
+    let mut hash = [0u8; 32];
+    let mut i = 0;
+    while i < 32 {
+        hash[i] = nibble(digits[2 * i]) << 4 | nibble(digits[2 * i + 1]);
+        i += 1;
+    }
+    hash
+}
+
+pub(crate) fn decode_hash(value: &str, name: &str) -> Hash {
+    hex::decode(value)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .unwrap_or_else(|| fail(ErrorCode::InvalidArgument, &format!("{} must be 32 bytes hex", name)))
+}
+
+// Note commitments are Poseidon outputs (`ciphra_poseidon::commitment`) and nullifiers are
+// public inputs of the spend circuit, so both must be canonical big-endian field elements
+pub(crate) fn decode_field_element(value: &str, name: &str) -> Hash {
+    let hash = decode_hash(value, name);
+    ensure!(
+        Fr::from_be_bytes(&hash).is_some(),
+        InvalidArgument,
+        "{} must be a BN254 field element",
+        name
+    );
+    hash
+}
+
+// Membership witness for a leaf against the current root - hashes are hex,
+// path_bits[i] is true when the node at level i is a right child
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MerklePath {
+    pub leaf_index: u64,
+    pub leaf: String,
+    pub siblings: Vec<String>,
+    pub path_bits: Vec<bool>,
+    pub root: String,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct CommitmentTree {
+    // Every node keyed by (level, index); level 0 holds the leaves
+    pub nodes: LookupMap<(u8, u64), Hash>,
+    pub next_leaf_index: u64,
+    pub current_root: Hash,
+    // Root -> sequence number at which it became current, for the last MAX_ROOT_HISTORY_SIZE roots
+    pub root_sequence: LookupMap<Hash, u64>,
+    pub root_count: u64,
+    pub root_history_size: u64,
+    // Sequence number modulo MAX_ROOT_HISTORY_SIZE -> root, so the root a new one displaces can
+    // be dropped from `root_sequence`
+    pub root_slots: LookupMap<u64, Hash>,
+}
+
+impl CommitmentTree {
+    pub(crate) fn new() -> Self {
+        Self {
+            nodes: LookupMap::new(StorageKey::TreeNodes),
+            next_leaf_index: 0,
+            current_root: zero_hashes()[TREE_DEPTH as usize],
+            root_sequence: LookupMap::new(StorageKey::PoseidonTreeRoots),
+            root_count: 0,
+            root_history_size: DEFAULT_ROOT_HISTORY_SIZE,
+            root_slots: Self::new_root_slots(),
+        }
+    }
+
+    pub(crate) fn new_root_slots() -> LookupMap<u64, Hash> {
+        LookupMap::new(StorageKey::PoseidonTreeRootSlots)
+    }
+
+    pub(crate) fn node(&self, level: u8, index: u64, zeros: &[Hash]) -> Hash {
+        self.nodes.get(&(level, index)).unwrap_or(zeros[level as usize])
+    }
+
+    // Append a leaf, updating its path to the root, and return the leaf index
+    fn append(&mut self, leaf: Hash) -> u64 {
+        let leaf_index = self.next_leaf_index;
+        ensure!(leaf_index < 1u64 << TREE_DEPTH, LimitExceeded, "Commitment tree is full");
+        
+        let zeros = zero_hashes();
+        let mut index = leaf_index;
+        let mut node = leaf;
+        self.nodes.insert(&(0, index), &node);
+        for level in 0..TREE_DEPTH {
+            node = if index.is_multiple_of(2) {
+                hash_pair(&node, &zeros[level as usize])
+            } else {
+                hash_pair(&self.node(level, index - 1, zeros), &node)
+            };
+            index /= 2;
+            self.nodes.insert(&(level + 1, index), &node);
+        }
+        
+        self.next_leaf_index += 1;
+        self.root_count += 1;
+        self.current_root = node;
+        self.root_sequence.insert(&node, &self.root_count);
+        // No window reaches past MAX_ROOT_HISTORY_SIZE roots, so the root this one displaces is
+        // never accepted again. Roots recorded before the slots existed aren't dropped, but
+        // still expire by sequence.
+        if let Some(displaced) = self.root_slots.insert(&(self.root_count % MAX_ROOT_HISTORY_SIZE), &node) {
+            self.root_sequence.remove(&displaced);
+        }
+        leaf_index
+    }
+
+    // Re-hashes the tree from its stored leaves, for trees built with SHA-256 before Poseidon.
+    // Their leaves can exceed the field and are reduced into it. Roots move to fresh maps so
+    // none of the SHA-256 roots stays accepted.
+    pub(crate) fn rebuild(&mut self) {
+        let leaves: Vec<Hash> = (0..self.next_leaf_index)
+            .map(|index| self.nodes.get(&(0, index)).unwrap_or_default())
+            .collect();
+        let root_history_size = self.root_history_size;
+        
+        *self = Self::new();
+        self.root_history_size = root_history_size;
+        for leaf in leaves {
+            self.append(Fr::from_be_bytes_reduced(&leaf).to_be_bytes());
+        }
+    }
+
+    // Sibling hashes from the leaf up to (but excluding) the root
+    fn siblings(&self, leaf_index: u64) -> Vec<Hash> {
+        let zeros = zero_hashes();
+        let mut index = leaf_index;
+        (0..TREE_DEPTH)
+            .map(|level| {
+                let sibling = self.node(level, index ^ 1, zeros);
+                index /= 2;
+                sibling
+            })
+            .collect()
+    }
+
+    // A root is accepted if it is one of the last `root_history_size` roots
+    fn is_known_root(&self, root: &Hash) -> bool {
+        self.root_sequence
+            .get(root)
+            .is_some_and(|sequence| sequence + self.root_history_size > self.root_count)
+    }
+}
+
+#[near_bindgen]
+impl P2PTransferContract {
+    pub fn is_known_root(&self, root: String) -> bool {
+        self.commitment_tree.is_known_root(&decode_hash(&root, "Root"))
+    }
+
+    pub fn get_current_root(&self) -> String {
+        hex::encode(self.commitment_tree.current_root)
+    }
+
+    // Lets client-side provers build witnesses without an indexer replaying the tree
+    pub fn get_merkle_path(&self, commitment_index: u64) -> MerklePath {
+        ensure!(
+            commitment_index < self.commitment_tree.next_leaf_index,
+            InvalidArgument,
+            "Commitment index out of range"
+        );
+        
+        let zeros = zero_hashes();
+        MerklePath {
+            leaf_index: commitment_index,
+            leaf: hex::encode(self.commitment_tree.node(0, commitment_index, zeros)),
+            siblings: self.commitment_tree
+                .siblings(commitment_index)
+                .iter()
+                .map(hex::encode)
+                .collect(),
+            path_bits: (0..TREE_DEPTH).map(|level| (commitment_index >> level) & 1 == 1).collect(),
+            root: hex::encode(self.commitment_tree.current_root),
+        }
+    }
+
+    pub fn get_commitment_count(&self) -> u64 {
+        self.commitment_tree.next_leaf_index
+    }
+
+    pub fn get_root_history_size(&self) -> u64 {
+        self.commitment_tree.root_history_size
+    }
+
+    // How many recent roots spend proofs may reference
+    pub fn set_root_history_size(&mut self, root_history_size: u64) {
+        assert_owner(&self.owner);
+        ensure!(
+            root_history_size > 0 && root_history_size <= MAX_ROOT_HISTORY_SIZE,
+            InvalidArgument,
+            "Root history size must be between 1 and 1000"
+        );
+        self.commitment_tree.root_history_size = root_history_size;
+    }
+
+    pub(crate) fn append_commitment(&mut self, commitment: &str) -> u64 {
+        self.commitment_tree.append(decode_field_element(commitment, "Commitment"))
+    }
+
+    pub(crate) fn assert_known_root(&self, root: &str) {
+        ensure!(
+            self.commitment_tree.is_known_root(&decode_hash(root, "Root")),
+            InvalidProof,
+            "Unknown or expired Merkle root"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+
+    fn leaf(index: u64) -> String {
+        format!("{:064x}", index + 1)
+    }
+
+    #[test]
+    fn test_zero_hashes_chain_with_poseidon() {
+        for level in 0..TREE_DEPTH as usize {
+            assert_eq!(ZERO_HASHES[level + 1], hash_pair(&ZERO_HASHES[level], &ZERO_HASHES[level]));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Commitment must be a BN254 field element")]
+    fn test_commitments_outside_the_field_are_rejected() {
+        let mut contract = contract();
+        contract.append_commitment(&"ff".repeat(32));
+    }
+
+    #[test]
+    fn test_roots_expire_with_the_window() {
+        let mut contract = contract();
+        contract.set_root_history_size(2);
+        contract.append_commitment(&leaf(0));
+        let first_root = contract.get_current_root();
+        contract.append_commitment(&leaf(1));
+        assert!(contract.is_known_root(first_root.clone()));
+        
+        contract.append_commitment(&leaf(2));
+        assert!(!contract.is_known_root(first_root));
+    }
+
+    #[test]
+    fn test_displaced_roots_are_dropped() {
+        let mut contract = contract();
+        contract.append_commitment(&leaf(0));
+        let first_root = contract.commitment_tree.current_root;
+        for index in 1..MAX_ROOT_HISTORY_SIZE {
+            contract.append_commitment(&leaf(index));
+        }
+        assert!(contract.commitment_tree.root_sequence.get(&first_root).is_some());
+        
+        contract.append_commitment(&leaf(MAX_ROOT_HISTORY_SIZE));
+        assert!(contract.commitment_tree.root_sequence.get(&first_root).is_none());
+    }
+}
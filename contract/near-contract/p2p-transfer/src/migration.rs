@@ -0,0 +1,348 @@
+use crate::*;
+use crate::merkle::Hash;
+use ciphra_types::{
+    insert_stored_field, migrate_state, state_read_appending, state_read_rewriting, stored_state_version,
+    take_stored_field, write_state_version, VersionedState,
+};
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, PartialEq, Debug, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub enum StateVersion {
+    V1, // Layout at the introduction of versioning
+    V2, // Appended `access`
+    V3, // Appended `settlements`
+    V4, // Appended `rate_limiter`
+    V5, // Dropped `claimable_refunds` and its liability total; balances moved into `settlements`
+    V6, // Appended `relayers`
+    V7, // Appended `root_slots` to `commitment_tree`
+    V8, // Rebuilt `commitment_tree` with Poseidon hashing
+    V9, // Appended `prune_cursor`
+}
+
+// `commitment_tree` as stored in layouts V1 to V6, before `root_slots`
+type CommitmentTreeV6 = (LookupMap<(u8, u64), Hash>, u64, Hash, LookupMap<Hash, u64>, u64, u64);
+
+// Fields stored ahead of `claimable_refunds` in layouts V1 to V4
+type FieldsBeforeClaimableRefunds = (
+    (
+        UnorderedMap<String, Transfer>,
+        UnorderedMap<AccountId, Vec<String>>,
+        UnorderedMap<String, ShieldedNote>,
+        UnorderedMap<String, Vec<SplitShare>>,
+        UnorderedMap<String, ScheduledTransfer>,
+        UnorderedMap<String, Subscription>,
+        UnorderedMap<AccountId, Vec<String>>,
+        UnorderedMap<String, Stream>,
+        UnorderedMap<AccountId, Vec<String>>,
+        UnorderedMap<String, ClaimableTransfer>,
+        UnorderedMap<String, PaymentRequest>,
+        UnorderedMap<AccountId, Vec<String>>,
+    ),
+    UnorderedMap<AccountId, Vec<String>>,
+);
+
+// Fields stored between `claimable_refunds` and `liability_totals`
+type FieldsBeforeLiabilityTotals = (
+    (
+        LookupMap<AccountId, SpendingLimit>,
+        UnorderedMap<String, HandleRecord>,
+        PoolStats,
+        UnorderedMap<u128, u64>,
+        LookupSet<String>,
+        Vec<u128>,
+        CommitmentTreeV6,
+        LookupMap<AccountId, AccountStats>,
+        LookupMap<String, String>,
+        LookupMap<AccountId, u128>,
+    ),
+    (
+        StorageAccounts,
+        LookupMap<String, AccountId>,
+        LookupMap<String, ChargebackHold>,
+        Option<ChargebackConfig>,
+        LookupMap<String, ShieldedSwap>,
+        Option<AccountId>,
+        LookupMap<u16, Vec<u8>>,
+        u16,
+        Vector<AuditEntry>,
+    ),
+);
+
+// Fields stored ahead of the `claimable_refunds` total in `liability_totals`, once the map is cut out
+type FieldsBeforeRefundTotal = (FieldsBeforeClaimableRefunds, FieldsBeforeLiabilityTotals, u128);
+
+// Fields stored up to the end of `commitment_tree` once `claimable_refunds` is cut out, as in
+// layouts V5 and V6
+type FieldsThroughCommitmentTree = (
+    FieldsBeforeClaimableRefunds,
+    (
+        LookupMap<AccountId, SpendingLimit>,
+        UnorderedMap<String, HandleRecord>,
+        PoolStats,
+        UnorderedMap<u128, u64>,
+        LookupSet<String>,
+        Vec<u128>,
+        CommitmentTreeV6,
+    ),
+);
+
+// Add a new variant and a `migrate_from` arm with every layout change
+impl VersionedState for P2PTransferContract {
+    type Version = StateVersion;
+
+    const CURRENT_VERSION: StateVersion = StateVersion::V9;
+    const UNVERSIONED: StateVersion = StateVersion::V1;
+
+    fn migrate_from(version: &StateVersion) -> Self {
+        match version {
+            StateVersion::V1 => Self::migrate_dropping_claimable_refunds(&(
+                AccessControl::default(),
+                Self::new_settlement_ledger(),
+                Self::new_rate_limiter(),
+                Self::new_relayers(),
+                0u64,
+            )),
+            StateVersion::V2 => Self::migrate_dropping_claimable_refunds(&(
+                Self::new_settlement_ledger(),
+                Self::new_rate_limiter(),
+                Self::new_relayers(),
+                0u64,
+            )),
+            StateVersion::V3 => {
+                Self::migrate_dropping_claimable_refunds(&(Self::new_rate_limiter(), Self::new_relayers(), 0u64))
+            }
+            StateVersion::V4 => Self::migrate_dropping_claimable_refunds(&(Self::new_relayers(), 0u64)),
+            StateVersion::V5 => state_read_rewriting(Self::insert_root_slots, &(Self::new_relayers(), 0u64)).0,
+            StateVersion::V6 => state_read_rewriting(Self::insert_root_slots, &0u64).0,
+            StateVersion::V7 | StateVersion::V8 => state_read_appending(&0u64),
+            StateVersion::V9 => fail(ErrorCode::Internal, "Already at the current version"),
+        }
+    }
+
+    // Every layout before V8 hashed the tree with SHA-256
+    fn post_migrate(&mut self, from: &StateVersion) {
+        if !matches!(from, StateVersion::V8 | StateVersion::V9) {
+            self.commitment_tree.rebuild();
+        }
+    }
+}
+
+#[near_bindgen]
+impl P2PTransferContract {
+    // Called with the upgrade deploy; rewrites state from whichever version is stored
+    #[private]
+    #[init(ignore_state)]
+    pub fn migrate() -> Self {
+        migrate_state()
+    }
+
+    pub fn get_state_version(&self) -> StateVersion {
+        stored_state_version::<Self>()
+    }
+
+    pub(crate) fn write_state_version() {
+        write_state_version::<Self>();
+    }
+
+    fn insert_root_slots(state: &mut Vec<u8>) {
+        insert_stored_field::<FieldsThroughCommitmentTree>(state, &CommitmentTree::new_root_slots());
+    }
+
+    // Refunds that failed before the settlement ledger become claimable settlements. The map
+    // can't be iterated; those refunds went to senders of failed transfers and subscription
+    // payers, so it is looked up for every account with transfers or subscriptions.
+    fn migrate_dropping_claimable_refunds(appended: &impl BorshSerialize) -> Self {
+        let (mut contract, (mut claimable_refunds, legacy_total)): (Self, (LookupMap<AccountId, u128>, u128)) =
+            state_read_rewriting(
+                |state| {
+                    let refunds = take_stored_field::<FieldsBeforeClaimableRefunds, LookupMap<AccountId, u128>>(state);
+                    let total = take_stored_field::<FieldsBeforeRefundTotal, u128>(state);
+                    Self::insert_root_slots(state);
+                    (refunds, total)
+                },
+                appended,
+            );
+        
+        let accounts: Vec<AccountId> = contract
+            .user_transfers
+            .keys()
+            .chain(contract.user_subscriptions.keys())
+            .collect();
+        let mut moved_yocto = 0;
+        for account_id in accounts {
+            if let Some(amount_yocto) = claimable_refunds.remove(&account_id) {
+                contract.settlements.credit(&account_id, amount_yocto);
+                moved_yocto += amount_yocto;
+            }
+        }
+        
+        env::log_str(&format!(
+            "Claimable refunds moved to settlements | Amount: {} | Unmatched: {}",
+            moved_yocto,
+            legacy_total.saturating_sub(moved_yocto)
+        ));
+        
+        contract
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::merkle::{zero_hashes, TREE_DEPTH};
+    use crate::test_utils::*;
+    use ciphra_poseidon::{hash2, Fr};
+    use ciphra_testing as host;
+    use ciphra_types::STATE_VERSION_KEY;
+    use near_sdk::borsh;
+
+    fn head(c: &P2PTransferContract) -> Vec<u8> {
+        borsh::to_vec(&(
+            &c.transfers, &c.user_transfers, &c.shielded_pool, &c.split_payouts, &c.scheduled_transfers,
+            &c.subscriptions, &c.user_subscriptions, &c.streams, &c.user_streams, &c.claimable_transfers,
+            &c.payment_requests, &c.requests_by_payer, &c.requests_by_payee,
+        ))
+        .unwrap()
+    }
+
+    // Fields from `spending_limits` through `commitment_tree` as V6 and earlier stored them
+    fn fields_through_tree_v6(c: &P2PTransferContract) -> Vec<u8> {
+        let tree = &c.commitment_tree;
+        borsh::to_vec(&(
+            (&c.spending_limits, &c.handles, &c.pool_stats, &c.deposits_by_denomination),
+            (&c.nullifiers, &c.denominations),
+            (&tree.nodes, tree.next_leaf_index, tree.current_root, &tree.root_sequence, tree.root_count),
+            tree.root_history_size,
+        ))
+        .unwrap()
+    }
+
+    // `contract` serialized without `prune_cursor`
+    fn without_prune_cursor(c: &P2PTransferContract) -> Vec<u8> {
+        let mut current = borsh::to_vec(c).unwrap();
+        current.truncate(current.len() - borsh::to_vec(&c.prune_cursor).unwrap().len());
+        current
+    }
+
+    // `contract` serialized without `commitment_tree.root_slots` and `prune_cursor`
+    fn without_root_slots(c: &P2PTransferContract) -> Vec<u8> {
+        let mut current = without_prune_cursor(c);
+        let start = head(c).len() + fields_through_tree_v6(c).len();
+        current.drain(start..start + borsh::to_vec(&c.commitment_tree.root_slots).unwrap().len());
+        current
+    }
+
+    // `contract` as a V6 deployment stored it
+    fn store_v6_state(contract: &P2PTransferContract) {
+        env::storage_write(b"STATE", &without_root_slots(contract));
+        env::storage_write(STATE_VERSION_KEY, &borsh::to_vec(&StateVersion::V6).unwrap());
+    }
+
+    // `contract` as a V7 deployment stored it
+    fn store_v7_state(contract: &P2PTransferContract) {
+        env::storage_write(b"STATE", &without_prune_cursor(contract));
+        env::storage_write(STATE_VERSION_KEY, &borsh::to_vec(&StateVersion::V7).unwrap());
+    }
+
+    // `contract` as a V4 deployment stored it, with `claimable_refunds` after `requests_by_payee`,
+    // its total after `liability_totals.ledger_balances` and no `relayers`
+    fn store_v4_state(contract: &P2PTransferContract, claimable_refunds: &LookupMap<AccountId, u128>, total: u128) {
+        let c = contract;
+        let head = head(c);
+        let mut middle = fields_through_tree_v6(c);
+        middle.extend(
+            borsh::to_vec(&(
+                (&c.account_stats, &c.transfers_by_reference, &c.balances),
+                (
+                    &c.storage_accounts, &c.record_storage_payers, &c.chargeback_holds, &c.chargeback_config,
+                    &c.shielded_swaps, &c.swap_contract, &c.verifying_keys, &c.current_circuit_version, &c.audit_log,
+                ),
+                &c.liability_totals.ledger_balances,
+            ))
+            .unwrap(),
+        );
+        let mut current = without_root_slots(c);
+        current.truncate(current.len() - borsh::to_vec(&c.relayers).unwrap().len());
+        assert!(current[head.len()..].starts_with(&middle));
+        
+        let mut stored = head.clone();
+        stored.extend(borsh::to_vec(claimable_refunds).unwrap());
+        stored.extend(&middle);
+        stored.extend(borsh::to_vec(&total).unwrap());
+        stored.extend(&current[head.len() + middle.len()..]);
+        env::storage_write(b"STATE", &stored);
+        env::storage_write(STATE_VERSION_KEY, &borsh::to_vec(&StateVersion::V4).unwrap());
+    }
+
+    #[test]
+    fn test_v4_claimable_refunds_become_settlements() {
+        let mut contract = contract();
+        send_direct(&mut contract, "t1", 10 * host::NEAR);
+        let storage_deposits = contract.liability_totals.storage_deposits;
+        let mut claimable_refunds = LookupMap::new(StorageKey::ClaimableRefunds);
+        claimable_refunds.insert(&account(SENDER), &(3 * host::NEAR));
+        store_v4_state(&contract, &claimable_refunds, 3 * host::NEAR);
+        
+        host::set_self_caller();
+        let migrated = P2PTransferContract::migrate();
+        
+        assert_eq!(migrated.get_state_version(), StateVersion::V9);
+        assert_eq!(migrated.get_claimable_settlement(account(SENDER)), U128(3 * host::NEAR));
+        assert_eq!(migrated.liability_totals.storage_deposits, storage_deposits);
+        assert!(migrated.get_transfer("t1".to_string()).is_some());
+        assert!(claimable_refunds.get(&account(SENDER)).is_none());
+        assert!(host::logs().iter().any(|log| log.ends_with("Unmatched: 0")));
+    }
+
+    #[test]
+    fn test_v6_tree_gains_root_slots() {
+        let mut contract = contract();
+        contract.commitment_tree.root_sequence = LookupMap::new(StorageKey::TreeRoots);
+        contract.commitment_tree.root_slots = LookupMap::new(StorageKey::TreeRootSlots);
+        host::set_deposit(host::NEAR);
+        contract.storage_deposit(None, None);
+        host::set_deposit(host::NEAR);
+        contract.shield_deposit("n1".to_string(), "11".repeat(32));
+        let root = contract.get_current_root();
+        store_v6_state(&contract);
+        
+        host::set_self_caller();
+        let mut migrated = P2PTransferContract::migrate();
+        
+        assert_eq!(migrated.get_state_version(), StateVersion::V9);
+        assert_eq!(migrated.get_current_root(), root);
+        assert!(migrated.is_known_root(root));
+        migrated.append_commitment(&"22".repeat(32));
+        assert_eq!(migrated.commitment_tree.root_slots.get(&2), Some(migrated.commitment_tree.current_root));
+    }
+
+    #[test]
+    fn test_v7_tree_is_rebuilt_with_poseidon() {
+        let mut contract = contract();
+        // A SHA-256 era tree: roots under the old prefixes and a leaf beyond the field
+        let sha_root = [7u8; 32];
+        contract.commitment_tree.root_sequence = LookupMap::new(StorageKey::TreeRoots);
+        contract.commitment_tree.root_slots = LookupMap::new(StorageKey::TreeRootSlots);
+        contract.commitment_tree.nodes.insert(&(0, 0), &[0xff; 32]);
+        contract.commitment_tree.next_leaf_index = 1;
+        contract.commitment_tree.current_root = sha_root;
+        contract.commitment_tree.root_sequence.insert(&sha_root, &1);
+        contract.commitment_tree.root_count = 1;
+        store_v7_state(&contract);
+        
+        host::set_self_caller();
+        let migrated = P2PTransferContract::migrate();
+        
+        let leaf = Fr::from_be_bytes_reduced(&[0xff; 32]);
+        let root = zero_hashes()[..TREE_DEPTH as usize]
+            .iter()
+            .fold(leaf, |node, zero| hash2(node, Fr::from_be_bytes(zero).unwrap()))
+            .to_be_bytes();
+        assert_eq!(migrated.get_state_version(), StateVersion::V9);
+        assert_eq!(migrated.commitment_tree.nodes.get(&(0, 0)), Some(leaf.to_be_bytes()));
+        assert_eq!(migrated.commitment_tree.current_root, root);
+        assert!(migrated.is_known_root(hex::encode(root)));
+        assert!(!migrated.is_known_root(hex::encode(sha_root)));
+        assert_eq!(migrated.commitment_tree.next_leaf_index, 1);
+    }
+}
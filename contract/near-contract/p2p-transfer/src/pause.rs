@@ -0,0 +1,57 @@
+use crate::*;
+
+#[near_bindgen]
+impl P2PTransferContract {
+    // Incident response: halts every path that takes in new funds; withdrawals, claims,
+    // settlements and refunds keep working
+    pub fn pause(&mut self) {
+        let caller = self.pause_guard.pause(&self.owner);
+        self.record_audit(AuditAction::ConfigChanged, vec!["paused".to_string()], None);
+        
+        env::log_str(&format!("Contract paused by: {}", caller));
+        emit_event("paused", json!({ "account_id": caller }));
+    }
+
+    // Guardians can only pause; resuming is an owner decision
+    pub fn unpause(&mut self) {
+        self.pause_guard.unpause(&self.owner);
+        self.record_audit(AuditAction::ConfigChanged, vec!["unpaused".to_string()], None);
+        
+        env::log_str("Contract unpaused");
+        emit_event("unpaused", json!({ "account_id": self.owner }));
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.pause_guard.paused
+    }
+
+    pub fn get_pause_status(&self) -> PauseStatus {
+        self.pause_guard.status()
+    }
+
+    pub fn add_guardian(&mut self, account_id: AccountId) {
+        self.pause_guard.add_guardian(&self.owner, account_id.clone());
+        self.record_audit(
+            AuditAction::ConfigChanged,
+            vec!["guardian_added".to_string(), account_id.to_string()],
+            None,
+        );
+    }
+
+    pub fn remove_guardian(&mut self, account_id: AccountId) {
+        self.pause_guard.remove_guardian(&self.owner, &account_id);
+        self.record_audit(
+            AuditAction::ConfigChanged,
+            vec!["guardian_removed".to_string(), account_id.to_string()],
+            None,
+        );
+    }
+
+    pub fn get_guardians(&self) -> Vec<AccountId> {
+        self.pause_guard.guardians.clone()
+    }
+
+    pub(crate) fn assert_not_paused(&self) {
+        self.pause_guard.assert_not_paused();
+    }
+}
@@ -0,0 +1,48 @@
+use crate::*;
+
+#[derive(BorshDeserialize, BorshSerialize, Default)]
+pub struct PoolStats {
+    pub total_value_locked: u128,
+    pub unspent_notes: u64,
+    pub nullifier_count: u64,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PoolStatsView {
+    #[schemars(with = "String")]
+    pub total_value_locked: U128,
+    pub unspent_notes: u64,
+    pub nullifier_count: u64,
+    pub deposits_by_denomination: Vec<(String, u64)>,
+}
+
+#[near_bindgen]
+impl P2PTransferContract {
+    // Shielded pool summary - unspent_notes is the anonymity set size shown before deposit
+    pub fn get_pool_stats(&self) -> PoolStatsView {
+        PoolStatsView {
+            total_value_locked: U128(self.pool_stats.total_value_locked),
+            unspent_notes: self.pool_stats.unspent_notes,
+            nullifier_count: self.pool_stats.nullifier_count,
+            deposits_by_denomination: self.deposits_by_denomination
+                .iter()
+                .map(|(denomination, count)| (denomination.to_string(), count))
+                .collect(),
+        }
+    }
+
+    pub(crate) fn record_note_created(&mut self, amount_yocto: u128) {
+        self.pool_stats.total_value_locked += amount_yocto;
+        self.pool_stats.unspent_notes += 1;
+        let count = self.deposits_by_denomination.get(&amount_yocto).unwrap_or(0);
+        self.deposits_by_denomination.insert(&amount_yocto, &(count + 1));
+    }
+
+    // `withdrawn_yocto` is the value leaving the pool (zero for shielded-to-shielded spends)
+    pub(crate) fn record_note_spent(&mut self, withdrawn_yocto: u128) {
+        self.pool_stats.total_value_locked -= withdrawn_yocto;
+        self.pool_stats.unspent_notes -= 1;
+        self.pool_stats.nullifier_count += 1;
+    }
+}
@@ -0,0 +1,144 @@
+use crate::*;
+
+// Upper bound on records removed by one prune call
+const MAX_PRUNE_BATCH: u32 = 100;
+// Upper bound on records one prune call looks at, pruned or not
+const MAX_PRUNE_SCAN: u64 = 500;
+
+#[near_bindgen]
+impl P2PTransferContract {
+    // Delete settled records older than before_timestamp - callable by anyone, the
+    // freed storage deposits are released to whoever paid for each record. Each call scans
+    // at most MAX_PRUNE_SCAN records, resuming where the previous call stopped and starting
+    // over from the first record once it reaches the end.
+    pub fn prune_transfers(&mut self, before_timestamp: u64, limit: u32) -> u32 {
+        ensure!(limit > 0 && limit <= MAX_PRUNE_BATCH, InvalidArgument, "Invalid prune limit");
+        ensure!(
+            before_timestamp <= env::block_timestamp().saturating_sub(self.retention_period),
+            TooEarly,
+            "Cannot prune records inside the retention period"
+        );
+        
+        // Removing a record moves the last one into its slot, so the cursor only advances past
+        // records that stay
+        let mut cursor = self.prune_cursor;
+        let mut pruned = 0;
+        for _ in 0..MAX_PRUNE_SCAN {
+            let Some(transfer) = self.transfers.values_as_vector().get(cursor) else {
+                cursor = 0;
+                break;
+            };
+            if transfer.timestamp < before_timestamp
+                && matches!(transfer.status, TransferStatus::Completed | TransferStatus::Failed)
+            {
+                self.remove_transfer_record(&transfer);
+                pruned += 1;
+                if pruned == limit {
+                    break;
+                }
+            } else {
+                cursor += 1;
+            }
+        }
+        self.prune_cursor = cursor;
+        
+        env::log_str(&format!(
+            "Transfers pruned: {} | Before: {} | Cursor: {}",
+            pruned, before_timestamp, cursor
+        ));
+        
+        pruned
+    }
+
+    pub fn get_prune_cursor(&self) -> u64 {
+        self.prune_cursor
+    }
+
+    pub fn get_retention_period(&self) -> u64 {
+        self.retention_period / 1_000_000_000
+    }
+
+    pub fn set_retention_period(&mut self, retention_seconds: u64) {
+        self.access.assert_owner_or_role(&self.owner, Role::Operator);
+        self.retention_period = retention_seconds * 1_000_000_000;
+    }
+
+    fn remove_transfer_record(&mut self, transfer: &Transfer) {
+        let transfer_id = &transfer.transfer_id;
+        self.transfers.remove(transfer_id);
+        
+        // Drop the id from every party's index
+        let mut parties = vec![transfer.sender.clone(), transfer.recipient.clone()];
+        if let Some(shares) = self.split_payouts.remove(transfer_id) {
+            parties.extend(shares.into_iter().map(|share| share.recipient));
+        }
+        for party in parties {
+            if let Ok(account_id) = party.parse::<AccountId>() {
+                self.remove_user_transfer(&account_id, transfer_id);
+            }
+        }
+        
+        if let Some(reference) = &transfer.reference {
+            self.transfers_by_reference.remove(reference);
+        }
+        self.scheduled_transfers.remove(transfer_id);
+        self.claimable_transfers.remove(transfer_id);
+        self.chargeback_holds.remove(transfer_id);
+        self.release_record_storage(transfer_id);
+    }
+
+    fn remove_user_transfer(&mut self, user: &AccountId, transfer_id: &str) {
+        if let Some(mut transfers) = self.user_transfers.get(user) {
+            transfers.retain(|id| id != transfer_id);
+            if transfers.is_empty() {
+                self.user_transfers.remove(user);
+            } else {
+                self.user_transfers.insert(user, &transfers);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use ciphra_testing as host;
+
+    fn contract_with_transfers(count: u64) -> P2PTransferContract {
+        let mut contract = contract();
+        contract.set_retention_period(0);
+        for index in 0..count {
+            send_direct(&mut contract, &format!("t{}", index), host::NEAR);
+        }
+        host::advance_seconds(1);
+        contract
+    }
+
+    #[test]
+    fn test_pruning_stops_at_the_limit_and_resumes() {
+        let mut contract = contract_with_transfers(3);
+        let now = host::block_timestamp();
+        
+        assert_eq!(contract.prune_transfers(now, 2), 2);
+        assert_eq!(contract.prune_transfers(now, 2), 1);
+        assert_eq!(contract.transfers.len(), 0);
+        assert!(contract.get_transfer("t0".to_string()).is_none());
+    }
+
+    #[test]
+    fn test_pruning_scans_a_bounded_window() {
+        let mut contract = contract_with_transfers(MAX_PRUNE_SCAN + 1);
+        let before_any = host::block_timestamp() - 2_000_000_000;
+        
+        assert_eq!(contract.prune_transfers(before_any, MAX_PRUNE_BATCH), 0);
+        assert_eq!(contract.get_prune_cursor(), MAX_PRUNE_SCAN);
+        // The rest of the records, then back to the start
+        assert_eq!(contract.prune_transfers(before_any, MAX_PRUNE_BATCH), 0);
+        assert_eq!(contract.get_prune_cursor(), 0);
+        
+        let now = host::block_timestamp();
+        assert_eq!(contract.prune_transfers(now, MAX_PRUNE_BATCH), MAX_PRUNE_BATCH);
+        assert_eq!(contract.transfers.len(), MAX_PRUNE_SCAN + 1 - MAX_PRUNE_BATCH as u64);
+    }
+}
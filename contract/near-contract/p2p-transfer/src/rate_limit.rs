@@ -0,0 +1,47 @@
+use crate::*;
+use ciphra_types::DEFAULT_RATE_LIMIT;
+
+#[near_bindgen]
+impl P2PTransferContract {
+    // Each shielded note counts as one action, so batch deposits use up the window per note.
+    // None turns limiting off.
+    pub fn set_rate_limit(&mut self, config: Option<RateLimitConfig>) {
+        self.access.assert_owner_or_role(&self.owner, Role::Operator);
+        self.rate_limiter.set_config(config);
+        self.record_audit(AuditAction::ConfigChanged, vec!["rate_limit".to_string()], None);
+        
+        env::log_str(&format!("Rate limit updated: {:?}", config));
+        emit_event("rate_limit_updated", json!({ "config": config }));
+    }
+
+    // For contracts such as the orchestrator that act for many users and limit them themselves
+    pub fn set_rate_limit_exemption(&mut self, account_id: AccountId, exempt: bool) {
+        self.access.assert_owner_or_role(&self.owner, Role::Operator);
+        self.rate_limiter.set_exempt(account_id.clone(), exempt);
+        self.record_audit(
+            AuditAction::ConfigChanged,
+            vec!["rate_limit_exemption".to_string(), account_id.to_string()],
+            None,
+        );
+        
+        env::log_str(&format!("Rate limit exemption: {} | Exempt: {}", account_id, exempt));
+        emit_event("rate_limit_exemption_updated", json!({ "account_id": account_id, "exempt": exempt }));
+    }
+
+    pub fn get_rate_limit_exemptions(&self) -> Vec<AccountId> {
+        self.rate_limiter.exempt.clone()
+    }
+
+    pub fn get_rate_limit(&self) -> Option<RateLimitConfig> {
+        self.rate_limiter.config
+    }
+
+    // Notes the account can still deposit in the current window; None when limiting is off or the account is exempt
+    pub fn get_rate_limit_remaining(&self, account_id: AccountId) -> Option<u16> {
+        self.rate_limiter.remaining(&account_id)
+    }
+
+    pub(crate) fn new_rate_limiter() -> RateLimiter {
+        RateLimiter::new(StorageKey::RateLimits, Some(DEFAULT_RATE_LIMIT))
+    }
+}
@@ -0,0 +1,102 @@
+use crate::*;
+
+// Upper bound on allow-listed relayers
+const MAX_RELAYERS: u64 = 20;
+
+#[near_bindgen]
+impl P2PTransferContract {
+    // NEP-366 relayers whose signatures mark a transfer as relayed
+    pub fn set_relayer(&mut self, account_id: AccountId, allowed: bool) {
+        self.access.assert_owner_or_role(&self.owner, Role::Operator);
+        if allowed {
+            ensure!(
+                self.relayers.contains(&account_id) || self.relayers.len() < MAX_RELAYERS,
+                LimitExceeded,
+                "Too many relayers"
+            );
+            self.relayers.insert(&account_id);
+        } else {
+            self.relayers.remove(&account_id);
+        }
+        self.record_audit(
+            AuditAction::ConfigChanged,
+            vec!["relayer".to_string(), account_id.to_string()],
+            None,
+        );
+        
+        env::log_str(&format!("Relayer updated: {} | Allowed: {}", account_id, allowed));
+        emit_event("relayer_updated", json!({ "account_id": account_id, "allowed": allowed }));
+    }
+
+    pub fn is_relayer(&self, account_id: AccountId) -> bool {
+        self.relayers.contains(&account_id)
+    }
+
+    pub fn get_relayers(&self) -> Vec<AccountId> {
+        self.relayers.to_vec()
+    }
+
+    pub(crate) fn new_relayers() -> UnorderedSet<AccountId> {
+        UnorderedSet::new(StorageKey::Relayers)
+    }
+
+    // For a delegate action the relayer signs the outer transaction while the delegating user
+    // becomes the predecessor of the inner call. Any other signer (e.g. a user calling through
+    // another contract) is not a relayer, so only allow-listed signers are recorded.
+    pub(crate) fn relaying_signer(&self) -> Option<String> {
+        let signer = env::signer_account_id();
+        (signer != env::predecessor_account_id() && self.relayers.contains(&signer)).then(|| signer.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use ciphra_testing as host;
+
+    const RELAYER: &str = "relayer.near";
+
+    fn relayed_transfer(contract: &mut P2PTransferContract, signer: &str) -> Transfer {
+        host::set_caller(SENDER);
+        host::set_deposit(host::NEAR);
+        contract.storage_deposit(None, None);
+        host::set_relayed_caller(SENDER, signer);
+        host::set_deposit(host::NEAR);
+        let _ = contract.send_direct("t1".to_string(), RECIPIENT.to_string(), String::new(), None, None, None);
+        contract.get_transfer("t1".to_string()).unwrap()
+    }
+
+    #[test]
+    fn test_allow_listed_relayer_is_recorded() {
+        let mut contract = contract();
+        contract.set_relayer(account(RELAYER), true);
+        
+        assert_eq!(relayed_transfer(&mut contract, RELAYER).signer, Some(RELAYER.to_string()));
+    }
+
+    #[test]
+    fn test_unlisted_signer_is_not_a_relayer() {
+        let mut contract = contract();
+        
+        assert_eq!(relayed_transfer(&mut contract, "someone.near").signer, None);
+    }
+
+    #[test]
+    fn test_removed_relayer_is_not_recorded() {
+        let mut contract = contract();
+        contract.set_relayer(account(RELAYER), true);
+        contract.set_relayer(account(RELAYER), false);
+        
+        assert!(!contract.is_relayer(account(RELAYER)));
+        assert_eq!(relayed_transfer(&mut contract, RELAYER).signer, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized")]
+    fn test_only_owner_or_operator_sets_relayers() {
+        let mut contract = contract();
+        host::set_caller(SENDER);
+        contract.set_relayer(account(RELAYER), true);
+    }
+}
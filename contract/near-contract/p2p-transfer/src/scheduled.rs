@@ -0,0 +1,140 @@
+use crate::*;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ScheduledTransfer {
+    pub transfer_id: String,
+    pub execute_at: u64,
+    #[borsh(serialize_with = "decimal_borsh::serialize", deserialize_with = "decimal_borsh::deserialize")]
+    #[schemars(with = "String")]
+    pub executor_bounty: U128,
+    pub executed_by: Option<String>,
+}
+
+#[near_bindgen]
+impl P2PTransferContract {
+    // Scheduled transfer - funds are held until execute_at, then anyone can execute.
+    // The attached deposit covers the payment plus the executor bounty.
+    #[payable]
+    pub fn schedule_transfer(
+        &mut self,
+        transfer_id: String,
+        recipient: AccountId,
+        execute_at: u64,
+        memo: String,
+    ) -> Transfer {
+        self.assert_not_paused();
+        let sender = env::predecessor_account_id();
+        let deposit_yocto = env::attached_deposit().as_yoctonear();
+        
+        ensure!(
+            deposit_yocto > self.executor_bounty,
+            InvalidArgument,
+            "Deposit must exceed the executor bounty"
+        );
+        self.assert_new_transfer_id(&transfer_id);
+        ensure!(execute_at > env::block_timestamp(), InvalidArgument, "Execution time must be in future");
+        
+        let amount_yocto = deposit_yocto - self.executor_bounty;
+        
+        let transfer = Transfer {
+            transfer_id: transfer_id.clone(),
+            sender: sender.to_string(),
+            recipient: recipient.to_string(),
+            amount: U128(amount_yocto),
+            transfer_type: TransferType::Scheduled,
+            status: TransferStatus::Pending,
+            commitment: None,
+            nullifier: None,
+            memo,
+            encrypted_memo: None,
+            signer: None,
+            reference: None,
+            timestamp: env::block_timestamp(),
+        };
+        
+        let scheduled = ScheduledTransfer {
+            transfer_id: transfer_id.clone(),
+            execute_at,
+            executor_bounty: U128(self.executor_bounty),
+            executed_by: None,
+        };
+        
+        self.transfers.insert(&transfer_id, &transfer);
+        self.track_new_transfer(&transfer);
+        self.lock_record_storage(&transfer);
+        self.scheduled_transfers.insert(&transfer_id, &scheduled);
+        self.add_user_transfer(&sender, &transfer_id);
+        self.add_user_transfer(&recipient, &transfer_id);
+        
+        env::log_str(&format!(
+            "Transfer scheduled: {} | From: {} | To: {} | Amount: {} | Execute at: {}",
+            transfer_id, sender, recipient, amount_yocto, execute_at
+        ));
+        
+        transfer
+    }
+
+    // Execute a due scheduled transfer - callable by anyone, caller earns the bounty
+    pub fn execute_scheduled(&mut self, transfer_id: String) -> Promise {
+        let executor = env::predecessor_account_id();
+        let mut transfer = self.transfers.get(&transfer_id).or_fail(ErrorCode::NotFound, "Transfer not found");
+        let mut scheduled = self.scheduled_transfers
+            .get(&transfer_id)
+            .or_fail(ErrorCode::NotFound, "Scheduled transfer not found");
+        
+        ensure!(
+            matches!(transfer.status, TransferStatus::Pending),
+            InvalidState,
+            "Transfer is not pending"
+        );
+        ensure!(
+            env::block_timestamp() >= scheduled.execute_at,
+            TooEarly,
+            "Transfer is not due yet"
+        );
+        
+        transfer.status = TransferStatus::Completed;
+        transfer.timestamp = env::block_timestamp();
+        self.transfers.insert(&transfer_id, &transfer);
+        self.track_status_change(&transfer, &TransferStatus::Pending);
+        
+        scheduled.executed_by = Some(executor.to_string());
+        self.scheduled_transfers.insert(&transfer_id, &scheduled);
+        
+        let amount_yocto: u128 = transfer.amount.0;
+        let fee_yocto = self.calculate_fee(
+            amount_yocto,
+            self.fee_config.direct_bps,
+            &transfer.sender.parse().or_fail(ErrorCode::Internal, "Invalid sender"),
+        );
+        let payout_yocto = amount_yocto - fee_yocto;
+        let bounty_yocto: u128 = scheduled.executor_bounty.0;
+        
+        env::log_str(&format!(
+            "Scheduled transfer executed: {} | To: {} | Amount: {} | Executor: {}",
+            transfer_id, transfer.recipient, payout_yocto, executor
+        ));
+        
+        // Send fee
+        self.distribute_fee(fee_yocto);
+        
+        // Reward the executor
+        if bounty_yocto > 0 {
+            self.settle(executor, bounty_yocto);
+        }
+        
+        let recipient: AccountId = transfer.recipient.parse().or_fail(ErrorCode::Internal, "Invalid recipient");
+        let sender: AccountId = transfer.sender.parse().or_fail(ErrorCode::Internal, "Invalid sender");
+        self.payout_with_refund(&transfer_id, recipient, sender, payout_yocto)
+    }
+
+    pub fn get_scheduled_transfer(&self, transfer_id: String) -> Option<ScheduledTransfer> {
+        self.scheduled_transfers.get(&transfer_id)
+    }
+
+    pub fn set_executor_bounty(&mut self, executor_bounty: U128) {
+        assert_owner(&self.owner);
+        self.executor_bounty = executor_bounty.0;
+    }
+}
@@ -0,0 +1,143 @@
+use crate::*;
+
+const GAS_FOR_SETTLEMENT_CALLBACK: Gas = Gas::from_tgas(10);
+
+#[near_bindgen]
+impl P2PTransferContract {
+    // Rolls a failed refund or payout back into a balance the receiver claims with `claim_settlement`
+    #[private]
+    pub fn on_settlement(&mut self, settlement_id: u64) -> bool {
+        let Some(settlement) = self.settlements.resolve(settlement_id, is_promise_success()) else {
+            return true;
+        };
+        self.settlements.credit(&settlement.receiver, settlement.amount.0);
+        
+        env::log_str(&format!(
+            "Settlement failed: {} | Claimable by: {} | Amount: {}",
+            settlement_id, settlement.receiver, settlement.amount.0
+        ));
+        emit_event("settlement_failed", json!({
+            "settlement_id": settlement_id,
+            "receiver": settlement.receiver,
+            "amount": settlement.amount,
+        }));
+        
+        false
+    }
+
+    // Retries a failed settlement; a failure again leaves it claimable
+    pub fn claim_settlement(&mut self) -> Promise {
+        let account_id = env::predecessor_account_id();
+        let amount_yocto = self.settlements.claim(&account_id);
+        
+        env::log_str(&format!("Settlement claimed: {} | Amount: {}", account_id, amount_yocto));
+        
+        self.settle(account_id, amount_yocto)
+    }
+
+    pub fn get_claimable_settlement(&self, account_id: AccountId) -> U128 {
+        U128(self.settlements.claimable_of(&account_id))
+    }
+
+    pub(crate) fn new_settlement_ledger() -> SettlementLedger {
+        SettlementLedger::new(StorageKey::PendingSettlements, StorageKey::ClaimableSettlements)
+    }
+
+    // Sends NEAR through the settlement ledger so a failed transfer stays claimable
+    pub(crate) fn settle(&mut self, receiver: AccountId, amount_yocto: u128) -> Promise {
+        self.settlements.send(receiver, amount_yocto, |settlement_id| {
+            Self::ext(env::current_account_id())
+                .with_static_gas(GAS_FOR_SETTLEMENT_CALLBACK)
+                .on_settlement(settlement_id)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use ciphra_testing as host;
+    use ciphra_types::Settlement;
+
+    // Settlements still awaiting their callback, in the order they were sent
+    fn sent_settlements(contract: &P2PTransferContract) -> Vec<Settlement> {
+        (0..contract.settlements.next_settlement_id).filter_map(|id| contract.settlements.pending.get(&id)).collect()
+    }
+
+    fn fail_settlement(contract: &mut P2PTransferContract, settlement_id: u64) {
+        host::set_self_caller();
+        host::set_promise_results(vec![None]);
+        assert!(!contract.on_settlement(settlement_id));
+        host::set_promise_results(vec![]);
+    }
+
+    #[test]
+    fn test_fee_share_and_payout_go_through_ledger() {
+        let mut contract = contract();
+        send_direct(&mut contract, "t1", 1000 * host::NEAR);
+        
+        let sent: Vec<(String, u128)> = sent_settlements(&contract)
+            .into_iter()
+            .map(|settlement| (settlement.receiver.to_string(), settlement.amount.0))
+            .collect();
+        assert_eq!(sent, vec![(OWNER.to_string(), host::NEAR), (RECIPIENT.to_string(), 999 * host::NEAR)]);
+        assert_eq!(contract.settlements.total_pending, 1000 * host::NEAR);
+    }
+
+    #[test]
+    fn test_failed_fee_share_is_claimable() {
+        let mut contract = contract();
+        send_direct(&mut contract, "t1", 1000 * host::NEAR);
+        fail_settlement(&mut contract, 0);
+        
+        assert_eq!(contract.get_claimable_settlement(account(OWNER)), U128(host::NEAR));
+        assert_eq!(contract.assert_solvency().claimable_refunds, U128(host::NEAR));
+        
+        host::clear_receipts();
+        host::set_caller(OWNER);
+        let _ = contract.claim_settlement();
+        assert_eq!(host::transfers(), vec![(OWNER.to_string(), host::NEAR)]);
+        assert_eq!(contract.get_claimable_settlement(account(OWNER)), U128(0));
+    }
+
+    #[test]
+    fn test_rejected_fee_deposit_is_claimable_by_owner() {
+        let mut contract = contract();
+        contract.set_fee_treasury(Some(account("treasury.near")));
+        contract.set_fee_distribution(vec![FeeShare {
+            label: "treasury".to_string(),
+            recipient: "treasury.near".to_string(),
+            share_bps: 10000,
+        }]);
+        send_direct(&mut contract, "t1", 1000 * host::NEAR);
+        assert_eq!(host::function_calls()[0], ("treasury.near".to_string(), "deposit_fees".to_string()));
+        
+        host::set_self_caller();
+        host::set_promise_results(vec![None]);
+        assert!(!contract.on_fee_deposit_settled(0));
+        
+        assert_eq!(contract.get_claimable_settlement(account(OWNER)), U128(host::NEAR));
+        assert_eq!(contract.get_claimable_settlement(account("treasury.near")), U128(0));
+    }
+
+    #[test]
+    fn test_delivered_settlement_clears_pending() {
+        let mut contract = contract();
+        send_direct(&mut contract, "t1", 1000 * host::NEAR);
+        host::set_self_caller();
+        host::set_promise_results(vec![Some(vec![])]);
+        assert!(contract.on_settlement(1));
+        
+        assert_eq!(contract.settlements.total_pending, host::NEAR);
+        assert_eq!(contract.get_claimable_settlement(account(RECIPIENT)), U128(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "Nothing to claim")]
+    fn test_claim_without_balance() {
+        let mut contract = contract();
+        host::set_caller(SENDER);
+        let _ = contract.claim_settlement();
+    }
+}
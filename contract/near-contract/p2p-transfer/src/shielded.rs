@@ -0,0 +1,300 @@
+use crate::*;
+
+#[near_bindgen]
+impl P2PTransferContract {
+    // Shielded deposit - create commitment
+    #[payable]
+    pub fn shield_deposit(
+        &mut self,
+        note_id: String,
+        commitment: String,
+    ) -> ShieldedNote {
+        self.assert_not_paused();
+        let amount = env::attached_deposit();
+        
+        ensure!(amount.as_yoctonear() > 0, InsufficientFunds, "Must attach NEAR tokens");
+        self.rate_limiter.record(&env::predecessor_account_id(), 1);
+        
+        let note = self.insert_note(note_id.clone(), commitment.clone(), amount.as_yoctonear());
+        
+        env::log_str(&format!(
+            "Shielded deposit: {} | Commitment: {} | Amount: {}",
+            note_id, commitment, amount
+        ));
+        emit_event("note_deposited", json!({
+            "note_id": note_id,
+            "commitment": commitment,
+            "amount": note.amount,
+        }));
+        
+        note
+    }
+
+    // Batch shielded deposit - one attached deposit funding several notes of any amount
+    #[payable]
+    pub fn shield_deposit_batch(&mut self, notes: Vec<(String, String, U128)>) -> Vec<ShieldedNote> {
+        self.assert_not_paused();
+        let amount = env::attached_deposit();
+        
+        ensure!(amount.as_yoctonear() > 0, InsufficientFunds, "Must attach NEAR tokens");
+        ensure!(!notes.is_empty(), InvalidArgument, "At least one note required");
+        ensure!(notes.len() <= MAX_NOTES_PER_DEPOSIT, LimitExceeded, "Too many notes in one deposit");
+        ensure!(
+            notes.iter().all(|(_, _, note_amount)| note_amount.0 > 0),
+            InvalidArgument,
+            "Note amounts must be positive"
+        );
+        let total_yocto: u128 = notes.iter().map(|(_, _, note_amount)| note_amount.0).sum();
+        ensure!(
+            total_yocto == amount.as_yoctonear(),
+            InvalidArgument,
+            "Note amounts must sum to the attached deposit"
+        );
+        self.rate_limiter.record(&env::predecessor_account_id(), notes.len() as u16);
+        
+        let notes: Vec<ShieldedNote> = notes
+            .into_iter()
+            .map(|(note_id, commitment, note_amount)| self.insert_note(note_id, commitment, note_amount.0))
+            .collect();
+        
+        env::log_str(&format!(
+            "Batch shielded deposit: {} notes | Amount: {}",
+            notes.len(), amount
+        ));
+        emit_event("notes_deposited", json!({
+            "note_ids": notes.iter().map(|note| note.note_id.as_str()).collect::<Vec<_>>(),
+            "amount": U128(amount.as_yoctonear()),
+        }));
+        
+        notes
+    }
+
+    // Shielded transfer - spend commitment, create new one
+    pub fn shield_transfer(
+        &mut self,
+        transfer_id: String,
+        input_note_id: String,
+        nullifier: String,
+        new_commitment: String,
+        recipient_commitment: String,
+        root: String,
+        proof: String,
+        memo: String,
+    ) -> Promise {
+        self.assert_new_transfer_id(&transfer_id);
+        self.assert_known_root(&root);
+        
+        // Get and verify input note
+        let mut input_note = self.shielded_pool.get(&input_note_id)
+            .or_fail(ErrorCode::NotFound, "Input note not found");
+        ensure!(!input_note.spent, InvalidState, "Note already spent");
+        self.assert_spend_proof(
+            &input_note,
+            &root,
+            &nullifier,
+            &("shield_transfer", &new_commitment, &recipient_commitment),
+            &proof,
+        );
+        self.consume_nullifier(&nullifier);
+        
+        // Mark as spent
+        input_note.spent = true;
+        input_note.nullifier = Some(nullifier.clone());
+        self.shielded_pool.insert(&input_note_id, &input_note);
+        // Value stays in the pool under the recipient's new commitment
+        self.record_note_spent(0);
+        
+        let new_leaf_index = self.append_commitment(&new_commitment);
+        let recipient_leaf_index = self.append_commitment(&recipient_commitment);
+        
+        let amount_yocto: u128 = input_note.amount.0;
+        
+        // Create transfer record (sender/recipient hidden)
+        let transfer = Transfer {
+            transfer_id: transfer_id.clone(),
+            sender: "shielded".to_string(),
+            recipient: "shielded".to_string(),
+            amount: U128(amount_yocto),
+            transfer_type: TransferType::Shielded,
+            status: TransferStatus::Completed,
+            commitment: Some(recipient_commitment.clone()),
+            nullifier: Some(nullifier.clone()),
+            memo,
+            encrypted_memo: None,
+            signer: None,
+            reference: None,
+            timestamp: env::block_timestamp(),
+        };
+        
+        self.transfers.insert(&transfer_id, &transfer);
+        self.track_new_transfer(&transfer);
+        self.lock_record_storage(&transfer);
+        
+        env::log_str(&format!(
+            "Shielded transfer: {} | Nullifier: {} | Leaves: {}, {}",
+            transfer_id, nullifier, new_leaf_index, recipient_leaf_index
+        ));
+        emit_event("shielded_transfer", json!({
+            "transfer_id": transfer_id,
+            "nullifier": nullifier,
+            "leaf_indexes": [new_leaf_index, recipient_leaf_index],
+        }));
+        
+        Promise::new(env::current_account_id())
+    }
+
+    // Shielded withdrawal - reveal recipient
+    pub fn shield_withdraw(
+        &mut self,
+        transfer_id: String,
+        note_id: String,
+        nullifier: String,
+        recipient: AccountId,
+        root: String,
+        proof: String,
+    ) -> Promise {
+        self.assert_new_transfer_id(&transfer_id);
+        self.assert_known_root(&root);
+        
+        let mut note = self.shielded_pool.get(&note_id)
+            .or_fail(ErrorCode::NotFound, "Note not found");
+        ensure!(!note.spent, InvalidState, "Note already spent");
+        self.assert_spend_proof(&note, &root, &nullifier, &("shield_withdraw", &recipient), &proof);
+        self.consume_nullifier(&nullifier);
+        
+        // Mark as spent
+        note.spent = true;
+        note.nullifier = Some(nullifier.clone());
+        self.shielded_pool.insert(&note_id, &note);
+        
+        let amount_yocto: u128 = note.amount.0;
+        self.record_note_spent(amount_yocto);
+        self.enforce_spending_limit(&env::predecessor_account_id(), amount_yocto);
+        let fee_yocto = self.calculate_fee(amount_yocto, self.fee_config.shielded_withdraw_bps, &recipient);
+        let payout_yocto = amount_yocto - fee_yocto;
+        
+        let transfer = Transfer {
+            transfer_id: transfer_id.clone(),
+            sender: "shielded".to_string(),
+            recipient: recipient.to_string(),
+            amount: U128(payout_yocto),
+            transfer_type: TransferType::Shielded,
+            status: TransferStatus::Completed,
+            commitment: None,
+            nullifier: Some(nullifier),
+            memo: "Shielded withdrawal".to_string(),
+            encrypted_memo: None,
+            signer: None,
+            reference: None,
+            timestamp: env::block_timestamp(),
+        };
+        
+        self.transfers.insert(&transfer_id, &transfer);
+        self.track_new_transfer(&transfer);
+        self.lock_record_storage(&transfer);
+        self.add_user_transfer(&recipient, &transfer_id);
+        
+        env::log_str(&format!(
+            "Shielded withdrawal: {} | To: {} | Amount: {}",
+            transfer_id, recipient, payout_yocto
+        ));
+        emit_event("shielded_withdrawal", json!({
+            "transfer_id": transfer_id,
+            "recipient": recipient,
+            "amount": U128(payout_yocto),
+        }));
+        
+        // Send fee
+        self.distribute_fee(fee_yocto);
+        
+        self.settle(recipient, payout_yocto)
+    }
+
+    // Partial shielded withdrawal - spend a note, withdraw `amount` and return the
+    // remainder to the pool as a new change note. The balance equation
+    // (note value = withdrawn + change) is enforced on-chain.
+    pub fn shield_withdraw_partial(
+        &mut self,
+        transfer_id: String,
+        note_id: String,
+        nullifier: String,
+        recipient: AccountId,
+        amount: U128,
+        change_note_id: String,
+        change_commitment: String,
+        root: String,
+        proof: String,
+    ) -> Promise {
+        self.assert_new_transfer_id(&transfer_id);
+        self.assert_known_root(&root);
+        
+        let mut note = self.shielded_pool.get(&note_id)
+            .or_fail(ErrorCode::NotFound, "Note not found");
+        ensure!(!note.spent, InvalidState, "Note already spent");
+        self.assert_spend_proof(
+            &note,
+            &root,
+            &nullifier,
+            &("shield_withdraw_partial", &recipient, amount.0, &change_commitment),
+            &proof,
+        );
+        
+        let note_yocto: u128 = note.amount.0;
+        let amount_yocto = amount.0;
+        ensure!(amount_yocto > 0, InvalidArgument, "Withdrawal amount must be positive");
+        ensure!(amount_yocto < note_yocto, InvalidArgument, "Use shield_withdraw to withdraw the full note");
+        let change_yocto = note_yocto - amount_yocto;
+        
+        self.consume_nullifier(&nullifier);
+        
+        // Mark as spent
+        note.spent = true;
+        note.nullifier = Some(nullifier.clone());
+        self.shielded_pool.insert(&note_id, &note);
+        self.record_note_spent(note_yocto);
+        
+        // Return the change to the pool under a fresh commitment
+        self.insert_note(change_note_id.clone(), change_commitment, change_yocto);
+        
+        self.enforce_spending_limit(&env::predecessor_account_id(), amount_yocto);
+        let fee_yocto = self.calculate_fee(amount_yocto, self.fee_config.shielded_withdraw_bps, &recipient);
+        let payout_yocto = amount_yocto - fee_yocto;
+        
+        let transfer = Transfer {
+            transfer_id: transfer_id.clone(),
+            sender: "shielded".to_string(),
+            recipient: recipient.to_string(),
+            amount: U128(payout_yocto),
+            transfer_type: TransferType::Shielded,
+            status: TransferStatus::Completed,
+            commitment: None,
+            nullifier: Some(nullifier),
+            memo: "Partial shielded withdrawal".to_string(),
+            encrypted_memo: None,
+            signer: None,
+            reference: None,
+            timestamp: env::block_timestamp(),
+        };
+        
+        self.transfers.insert(&transfer_id, &transfer);
+        self.track_new_transfer(&transfer);
+        self.lock_record_storage(&transfer);
+        self.add_user_transfer(&recipient, &transfer_id);
+        
+        env::log_str(&format!(
+            "Partial shielded withdrawal: {} | To: {} | Amount: {} | Change note: {}",
+            transfer_id, recipient, payout_yocto, change_note_id
+        ));
+        emit_event("shielded_withdrawal", json!({
+            "transfer_id": transfer_id,
+            "recipient": recipient,
+            "amount": U128(payout_yocto),
+            "change_note_id": change_note_id,
+        }));
+        
+        // Send fee
+        self.distribute_fee(fee_yocto);
+        
+        self.settle(recipient, payout_yocto)
+    }
+}
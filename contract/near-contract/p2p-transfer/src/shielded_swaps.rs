@@ -0,0 +1,342 @@
+use crate::*;
+use crate::merkle::decode_field_element;
+use near_sdk::{ext_contract, PromiseError};
+
+const GAS_FOR_SWAP_CALL: Gas = Gas::from_tgas(20);
+const GAS_FOR_SWAP_CALLBACK: Gas = Gas::from_tgas(30);
+const GAS_FOR_STORAGE_DEPOSIT: Gas = Gas::from_tgas(10);
+
+// Spend proof for the funding note plus the swap terms passed through to the swap contract.
+// refund_note_id/refund_commitment describe the note re-created if the swap is refunded.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ShieldedSwapRequest {
+    pub swap_id: String,
+    pub note_id: String,
+    pub nullifier: String,
+    pub root: String,
+    pub proof: String,
+    pub participant: AccountId,
+    pub hash_lock: String,
+    pub hash_algorithm: HashAlgorithm,
+    pub time_lock_duration: u64,
+    pub target_chain: String,
+    pub target_address: String,
+    pub counterparty_swap_id: Option<String>,
+    pub refund_note_id: String,
+    pub refund_commitment: String,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ShieldedSwap {
+    pub swap_id: String,
+    pub transfer_id: String,
+    #[borsh(serialize_with = "decimal_borsh::serialize", deserialize_with = "decimal_borsh::deserialize")]
+    #[schemars(with = "String")]
+    pub amount: U128,
+    pub refund_note_id: String,
+    pub refund_commitment: String,
+}
+
+// Subset of the swap contract's AtomicSwap record
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SwapSnapshot {
+    pub status: SwapStatus,
+}
+
+// Only the generated ext_swap module is used
+#[allow(dead_code)]
+#[ext_contract(ext_swap)]
+trait AtomicSwapContract {
+    fn initiate_swap(
+        &mut self,
+        swap_id: String,
+        participant: AccountId,
+        hash_lock: String,
+        hash_algorithm: HashAlgorithm,
+        time_lock_duration: u64,
+        target_chain: String,
+        target_address: String,
+        counterparty_swap_id: Option<String>,
+    );
+    fn refund_swap(&mut self, swap_id: String);
+    fn storage_deposit(&mut self, account_id: Option<AccountId>, registration_only: Option<bool>);
+    fn get_swap(&self, swap_id: String) -> Option<SwapSnapshot>;
+}
+
+#[near_bindgen]
+impl P2PTransferContract {
+    // Spend a shielded note to fund an atomic swap - this contract becomes the swap
+    // initiator, so the funds never pass through a visible account. The swap locks this
+    // contract's storage there (see fund_swap_storage), and the swap contract should exempt
+    // this contract from its rate limit.
+    pub fn fund_swap_from_note(&mut self, request: ShieldedSwapRequest) -> Promise {
+        self.assert_not_paused();
+        let swap_contract = self.swap_contract.clone().or_fail(ErrorCode::NotFound, "Swap contract not configured");
+        let transfer_id = format!("swap:{}", request.swap_id);
+        
+        ensure!(self.transfers.get(&transfer_id).is_none(), InvalidState, "Swap already funded");
+        ensure!(
+            self.shielded_pool.get(&request.refund_note_id).is_none(),
+            AlreadyExists,
+            "Refund note ID already exists"
+        );
+        decode_field_element(&request.refund_commitment, "Refund commitment");
+        self.assert_known_root(&request.root);
+        
+        let mut note = self.shielded_pool.get(&request.note_id).or_fail(ErrorCode::NotFound, "Note not found");
+        ensure!(!note.spent, InvalidState, "Note already spent");
+        self.assert_spend_proof(
+            &note,
+            &request.root,
+            &request.nullifier,
+            &(
+                "fund_swap_from_note",
+                &request.swap_id,
+                &request.participant,
+                &request.hash_lock,
+                &request.refund_note_id,
+                &request.refund_commitment,
+            ),
+            &request.proof,
+        );
+        self.consume_nullifier(&request.nullifier);
+        
+        note.spent = true;
+        note.nullifier = Some(request.nullifier.clone());
+        self.shielded_pool.insert(&request.note_id, &note);
+        
+        let amount_yocto: u128 = note.amount.0;
+        self.record_note_spent(amount_yocto);
+        self.enforce_spending_limit(&env::predecessor_account_id(), amount_yocto);
+        let fee_yocto = self.calculate_fee(
+            amount_yocto,
+            self.fee_config.shielded_withdraw_bps,
+            &request.participant,
+        );
+        let swap_yocto = amount_yocto - fee_yocto;
+        
+        let transfer = Transfer {
+            transfer_id: transfer_id.clone(),
+            sender: "shielded".to_string(),
+            recipient: request.participant.to_string(),
+            amount: U128(swap_yocto),
+            transfer_type: TransferType::Swap,
+            status: TransferStatus::Pending,
+            commitment: None,
+            nullifier: Some(request.nullifier),
+            memo: format!("Swap {} to {}", request.swap_id, request.target_chain),
+            encrypted_memo: None,
+            signer: None,
+            reference: None,
+            timestamp: env::block_timestamp(),
+        };
+        
+        let shielded_swap = ShieldedSwap {
+            swap_id: request.swap_id.clone(),
+            transfer_id: transfer_id.clone(),
+            amount: U128(swap_yocto),
+            refund_note_id: request.refund_note_id,
+            refund_commitment: request.refund_commitment,
+        };
+        
+        self.transfers.insert(&transfer_id, &transfer);
+        self.track_new_transfer(&transfer);
+        self.lock_record_storage(&transfer);
+        self.shielded_swaps.insert(&request.swap_id, &shielded_swap);
+        self.add_user_transfer(&request.participant, &transfer_id);
+        
+        env::log_str(&format!(
+            "Shielded swap funded: {} | Participant: {} | Amount: {}",
+            request.swap_id, request.participant, swap_yocto
+        ));
+        
+        // Send fee
+        self.distribute_fee(fee_yocto);
+        
+        ext_swap::ext(swap_contract)
+            .with_attached_deposit(NearToken::from_yoctonear(swap_yocto))
+            .with_static_gas(GAS_FOR_SWAP_CALL)
+            .initiate_swap(
+                request.swap_id.clone(),
+                request.participant,
+                request.hash_lock,
+                request.hash_algorithm,
+                request.time_lock_duration,
+                request.target_chain,
+                request.target_address,
+                request.counterparty_swap_id,
+            )
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_SWAP_CALLBACK)
+                    .on_shielded_swap_funded(request.swap_id),
+            )
+    }
+
+    // Callback for funding - if the swap wasn't created the deposit came back and is re-shielded
+    #[private]
+    pub fn on_shielded_swap_funded(&mut self, swap_id: String) -> bool {
+        if is_promise_success() {
+            return true;
+        }
+        
+        self.reshield_swap(&swap_id, TransferStatus::Failed);
+        
+        env::log_str(&format!("Shielded swap funding failed: {}", swap_id));
+        
+        false
+    }
+
+    // Refund an expired swap back into the pool as the pre-committed refund note - callable by anyone
+    pub fn refund_shielded_swap(&mut self, swap_id: String) -> Promise {
+        let swap_contract = self.swap_contract.clone().or_fail(ErrorCode::NotFound, "Swap contract not configured");
+        let shielded_swap = self.shielded_swaps.get(&swap_id).or_fail(ErrorCode::NotFound, "Shielded swap not found");
+        let transfer = self.transfers.get(&shielded_swap.transfer_id).or_fail(ErrorCode::NotFound, "Transfer not found");
+        
+        ensure!(
+            matches!(transfer.status, TransferStatus::Pending),
+            InvalidState,
+            "Swap is not pending"
+        );
+        
+        ext_swap::ext(swap_contract)
+            .with_static_gas(GAS_FOR_SWAP_CALL)
+            .refund_swap(swap_id.clone())
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_SWAP_CALLBACK)
+                    .on_shielded_swap_refunded(swap_id),
+            )
+    }
+
+    #[private]
+    pub fn on_shielded_swap_refunded(&mut self, swap_id: String) -> bool {
+        if !is_promise_success() {
+            env::log_str(&format!("Shielded swap refund failed: {}", swap_id));
+            return false;
+        }
+        
+        self.reshield_swap(&swap_id, TransferStatus::Cancelled);
+        
+        env::log_str(&format!("Shielded swap refunded: {}", swap_id));
+        
+        true
+    }
+
+    // Mark a swap Completed once the participant has claimed it on the swap contract
+    pub fn sync_shielded_swap(&mut self, swap_id: String) -> Promise {
+        let swap_contract = self.swap_contract.clone().or_fail(ErrorCode::NotFound, "Swap contract not configured");
+        ensure!(self.shielded_swaps.get(&swap_id).is_some(), NotFound, "Shielded swap not found");
+        
+        ext_swap::ext(swap_contract)
+            .with_static_gas(GAS_FOR_SWAP_CALL)
+            .get_swap(swap_id.clone())
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_SWAP_CALLBACK)
+                    .on_shielded_swap_synced(swap_id),
+            )
+    }
+
+    #[private]
+    pub fn on_shielded_swap_synced(
+        &mut self,
+        swap_id: String,
+        #[callback_result] swap: Result<Option<SwapSnapshot>, PromiseError>,
+    ) -> bool {
+        let completed = matches!(swap, Ok(Some(ref swap)) if swap.status == SwapStatus::Completed);
+        if !completed {
+            return false;
+        }
+        
+        let shielded_swap = self.shielded_swaps.get(&swap_id).or_fail(ErrorCode::NotFound, "Shielded swap not found");
+        let mut transfer = self.transfers.get(&shielded_swap.transfer_id).or_fail(ErrorCode::NotFound, "Transfer not found");
+        if !matches!(transfer.status, TransferStatus::Pending) {
+            return false;
+        }
+        
+        transfer.status = TransferStatus::Completed;
+        transfer.timestamp = env::block_timestamp();
+        self.transfers.insert(&shielded_swap.transfer_id, &transfer);
+        self.track_status_change(&transfer, &TransferStatus::Pending);
+        
+        env::log_str(&format!("Shielded swap completed: {}", swap_id));
+        
+        true
+    }
+
+    // Prepays this contract's storage on the swap contract, which every note-funded swap locks
+    // until the swap is cleaned up
+    #[payable]
+    pub fn fund_swap_storage(&mut self) -> Promise {
+        let swap_contract = self.swap_contract.clone().or_fail(ErrorCode::NotFound, "Swap contract not configured");
+        ensure!(env::attached_deposit().as_yoctonear() > 0, InsufficientFunds, "Must attach NEAR tokens");
+        
+        ext_swap::ext(swap_contract)
+            .with_attached_deposit(env::attached_deposit())
+            .with_static_gas(GAS_FOR_STORAGE_DEPOSIT)
+            .storage_deposit(Some(env::current_account_id()), None)
+    }
+
+    pub fn get_shielded_swap(&self, swap_id: String) -> Option<ShieldedSwap> {
+        self.shielded_swaps.get(&swap_id)
+    }
+
+    pub fn set_swap_contract(&mut self, swap_contract: AccountId) {
+        assert_owner(&self.owner);
+        self.swap_contract = Some(swap_contract);
+        self.record_audit(AuditAction::ConfigChanged, vec!["swap_contract".to_string()], None);
+    }
+
+    // Return the swap amount to the pool as the refund note and close the transfer record
+    fn reshield_swap(&mut self, swap_id: &str, status: TransferStatus) {
+        let shielded_swap = self.shielded_swaps
+            .get(&swap_id.to_string())
+            .or_fail(ErrorCode::NotFound, "Shielded swap not found");
+        let mut transfer = self.transfers.get(&shielded_swap.transfer_id).or_fail(ErrorCode::NotFound, "Transfer not found");
+        if !matches!(transfer.status, TransferStatus::Pending) {
+            return;
+        }
+        
+        transfer.status = status;
+        transfer.timestamp = env::block_timestamp();
+        self.transfers.insert(&shielded_swap.transfer_id, &transfer);
+        self.track_status_change(&transfer, &TransferStatus::Pending);
+        
+        let amount_yocto: u128 = shielded_swap.amount.0;
+        self.insert_note(
+            shielded_swap.refund_note_id,
+            shielded_swap.refund_commitment,
+            amount_yocto,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::*;
+    use ciphra_testing as host;
+
+    const SWAP: &str = "swap.near";
+
+    #[test]
+    fn test_swap_storage_is_prepaid_for_this_contract() {
+        let mut contract = contract();
+        contract.set_swap_contract(account(SWAP));
+        host::set_deposit(host::NEAR);
+        let _ = contract.fund_swap_storage();
+        
+        assert_eq!(host::function_calls(), vec![(SWAP.to_string(), "storage_deposit".to_string())]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Must attach NEAR tokens")]
+    fn test_swap_storage_needs_a_deposit() {
+        let mut contract = contract();
+        contract.set_swap_contract(account(SWAP));
+        let _ = contract.fund_swap_storage();
+    }
+}
@@ -0,0 +1,136 @@
+use crate::*;
+
+// Running totals for liabilities kept in LookupMaps, which can't be iterated
+#[derive(BorshDeserialize, BorshSerialize, Default)]
+pub struct LiabilityTotals {
+    pub ledger_balances: u128,
+    pub storage_deposits: u128,
+    pub storage_locked: u128,
+}
+
+// delta = available_balance - total_liabilities, as a signed yoctoNEAR string
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SolvencyReport {
+    #[schemars(with = "String")]
+    pub contract_balance: U128,
+    #[schemars(with = "String")]
+    pub storage_cost: U128,
+    #[schemars(with = "String")]
+    pub available_balance: U128,
+    #[schemars(with = "String")]
+    pub unspent_notes: U128,
+    #[schemars(with = "String")]
+    pub held_transfers: U128,
+    #[schemars(with = "String")]
+    pub subscription_budgets: U128,
+    #[schemars(with = "String")]
+    pub stream_balances: U128,
+    #[schemars(with = "String")]
+    pub ledger_balances: U128,
+    #[schemars(with = "String")]
+    pub claimable_refunds: U128,
+    #[schemars(with = "String")]
+    pub storage_deposits: U128,
+    #[schemars(with = "String")]
+    pub total_liabilities: U128,
+    pub delta: String,
+    pub solvent: bool,
+}
+
+#[near_bindgen]
+impl P2PTransferContract {
+    // Compare everything the contract owes against its spendable NEAR balance so monitors
+    // can catch accounting drift (e.g. from failed Promises). Scans pending transfers,
+    // subscriptions and streams, so it is meant for off-chain view calls.
+    pub fn assert_solvency(&self) -> SolvencyReport {
+        let contract_balance = env::account_balance().as_yoctonear();
+        let storage_cost = env::storage_byte_cost().as_yoctonear() * env::storage_usage() as u128;
+        let available_balance = contract_balance.saturating_sub(storage_cost);
+        
+        let unspent_notes = self.pool_stats.total_value_locked;
+        let held_transfers = self.held_transfer_total();
+        let subscription_budgets: u128 = self.subscriptions
+            .values()
+            .filter(|subscription| subscription.status != SubscriptionStatus::Cancelled)
+            .map(|subscription| subscription.remaining_budget.0)
+            .sum();
+        let stream_balances: u128 = self.streams
+            .values()
+            .filter(|stream| stream.status == StreamStatus::Active)
+            .map(|stream| {
+                let deposit: u128 = stream.deposit.0;
+                let withdrawn: u128 = stream.withdrawn.0;
+                deposit - withdrawn
+            })
+            .sum();
+        let totals = &self.liability_totals;
+        let storage_deposits = totals.storage_deposits - totals.storage_locked;
+        // Failed refunds and payouts wait in the settlement ledger
+        let claimable_refunds = self.settlements.total_claimable;
+        
+        let total_liabilities = unspent_notes
+            + held_transfers
+            + subscription_budgets
+            + stream_balances
+            + totals.ledger_balances
+            + claimable_refunds
+            + storage_deposits;
+        let solvent = available_balance >= total_liabilities;
+        let delta = if solvent {
+            (available_balance - total_liabilities).to_string()
+        } else {
+            format!("-{}", total_liabilities - available_balance)
+        };
+        
+        env::log_str(&format!(
+            "Solvency check: {} | Available: {} | Liabilities: {} | Delta: {}",
+            if solvent { "OK" } else { "DEFICIT" }, available_balance, total_liabilities, delta
+        ));
+        
+        SolvencyReport {
+            contract_balance: U128(contract_balance),
+            storage_cost: U128(storage_cost),
+            available_balance: U128(available_balance),
+            unspent_notes: U128(unspent_notes),
+            held_transfers: U128(held_transfers),
+            subscription_budgets: U128(subscription_budgets),
+            stream_balances: U128(stream_balances),
+            ledger_balances: U128(totals.ledger_balances),
+            claimable_refunds: U128(claimable_refunds),
+            storage_deposits: U128(storage_deposits),
+            total_liabilities: U128(total_liabilities),
+            delta,
+            solvent,
+        }
+    }
+
+    // Pending transfers whose funds still sit in this contract
+    fn held_transfer_total(&self) -> u128 {
+        self.transfers
+            .values()
+            .filter(|transfer| matches!(transfer.status, TransferStatus::Pending))
+            .map(|transfer| {
+                let amount_yocto: u128 = transfer.amount.0;
+                match transfer.transfer_type {
+                    TransferType::Direct | TransferType::Claimable => amount_yocto,
+                    TransferType::Scheduled => {
+                        let bounty_yocto: u128 = self.scheduled_transfers
+                            .get(&transfer.transfer_id)
+                            .map(|scheduled| scheduled.executor_bounty.0)
+                            .unwrap_or(0);
+                        amount_yocto + bounty_yocto
+                    }
+                    // Disputed payments have moved to the escrow contract
+                    TransferType::Protected => self.chargeback_holds
+                        .get(&transfer.transfer_id)
+                        .filter(|hold| hold.escrow_id.is_none())
+                        .map(|_| amount_yocto)
+                        .unwrap_or(0),
+                    // Pending swaps are held by the swap contract
+                    _ => 0,
+                }
+            })
+            .sum()
+    }
+}
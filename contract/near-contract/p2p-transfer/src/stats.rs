@@ -0,0 +1,144 @@
+use crate::*;
+
+// Placeholder parties used for shielded, split and unclaimed transfer records
+pub(crate) const PLACEHOLDER_PARTIES: [&str; 3] = ["shielded", "split", "unclaimed"];
+
+#[derive(BorshDeserialize, BorshSerialize, Default)]
+pub struct AccountStats {
+    pub total_sent: u128,
+    pub total_received: u128,
+    pub pending_count: u64,
+    pub completed_count: u64,
+    pub failed_count: u64,
+    pub cancelled_count: u64,
+    pub first_activity: u64,
+    pub last_activity: u64,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AccountStatsView {
+    #[schemars(with = "String")]
+    pub total_sent: U128,
+    #[schemars(with = "String")]
+    pub total_received: U128,
+    pub pending_count: u64,
+    pub completed_count: u64,
+    pub failed_count: u64,
+    pub cancelled_count: u64,
+    pub first_activity: u64,
+    pub last_activity: u64,
+}
+
+impl AccountStats {
+    fn count_mut(&mut self, status: &TransferStatus) -> &mut u64 {
+        match status {
+            TransferStatus::Pending => &mut self.pending_count,
+            TransferStatus::Completed => &mut self.completed_count,
+            TransferStatus::Failed => &mut self.failed_count,
+            TransferStatus::Cancelled => &mut self.cancelled_count,
+        }
+    }
+
+    fn touch(&mut self, now: u64) {
+        if self.first_activity == 0 {
+            self.first_activity = now;
+        }
+        self.last_activity = now;
+    }
+}
+
+fn party_account(party: &str) -> Option<AccountId> {
+    if PLACEHOLDER_PARTIES.contains(&party) {
+        return None;
+    }
+    party.parse().ok()
+}
+
+#[near_bindgen]
+impl P2PTransferContract {
+    pub fn get_account_stats(&self, account_id: AccountId) -> AccountStatsView {
+        let stats = self.account_stats.get(&account_id).unwrap_or_default();
+        AccountStatsView {
+            total_sent: U128(stats.total_sent),
+            total_received: U128(stats.total_received),
+            pending_count: stats.pending_count,
+            completed_count: stats.completed_count,
+            failed_count: stats.failed_count,
+            cancelled_count: stats.cancelled_count,
+            first_activity: stats.first_activity,
+            last_activity: stats.last_activity,
+        }
+    }
+
+    pub(crate) fn track_new_transfer(&mut self, transfer: &Transfer) {
+        self.record_audit(AuditAction::TransferCreated, vec![transfer.transfer_id.clone()], None);
+        let amount_yocto: u128 = transfer.amount.0;
+        let completed = matches!(transfer.status, TransferStatus::Completed);
+        
+        self.update_account_stats(&transfer.sender, |stats| {
+            *stats.count_mut(&transfer.status) += 1;
+            if completed {
+                stats.total_sent += amount_yocto;
+            }
+        });
+        self.update_account_stats(&transfer.recipient, |stats| {
+            *stats.count_mut(&transfer.status) += 1;
+            if completed {
+                stats.total_received += amount_yocto;
+            }
+        });
+    }
+
+    // Call after updating `transfer.status` away from `previous`
+    pub(crate) fn track_status_change(&mut self, transfer: &Transfer, previous: &TransferStatus) {
+        self.record_audit(
+            AuditAction::TransferStatusChanged,
+            vec![transfer.transfer_id.clone()],
+            Some(format!("{:?} -> {:?}", previous, transfer.status)),
+        );
+        let amount_yocto: u128 = transfer.amount.0;
+        let completed = matches!(transfer.status, TransferStatus::Completed);
+        let reverted = matches!(previous, TransferStatus::Completed)
+            && matches!(transfer.status, TransferStatus::Failed);
+        
+        self.update_account_stats(&transfer.sender, |stats| {
+            let previous_count = stats.count_mut(previous);
+            *previous_count = previous_count.saturating_sub(1);
+            *stats.count_mut(&transfer.status) += 1;
+            if completed {
+                stats.total_sent += amount_yocto;
+            } else if reverted {
+                stats.total_sent = stats.total_sent.saturating_sub(amount_yocto);
+            }
+        });
+        self.update_account_stats(&transfer.recipient, |stats| {
+            let previous_count = stats.count_mut(previous);
+            *previous_count = previous_count.saturating_sub(1);
+            *stats.count_mut(&transfer.status) += 1;
+            if completed {
+                stats.total_received += amount_yocto;
+            } else if reverted {
+                stats.total_received = stats.total_received.saturating_sub(amount_yocto);
+            }
+        });
+    }
+
+    // For payees that are not the record's `recipient` at creation (split shares, claim links)
+    pub(crate) fn track_received(&mut self, recipient: &AccountId, amount_yocto: u128) {
+        self.update_account_stats(recipient.as_str(), |stats| {
+            stats.completed_count += 1;
+            stats.total_received += amount_yocto;
+        });
+    }
+
+    fn update_account_stats(&mut self, party: &str, update: impl FnOnce(&mut AccountStats)) {
+        let Some(account_id) = party_account(party) else {
+            return;
+        };
+        let mut stats = self.account_stats.get(&account_id).unwrap_or_default();
+        update(&mut stats);
+        stats.touch(env::block_timestamp());
+        self.account_stats.insert(&account_id, &stats);
+    }
+}
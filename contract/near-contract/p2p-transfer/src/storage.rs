@@ -0,0 +1,79 @@
+use crate::*;
+use crate::stats::PLACEHOLDER_PARTIES;
+
+// Storage locked per transfer record or shielded note - covers the record, its index entries
+// and stats updates
+const STORAGE_DEPOSIT_PER_RECORD: u128 = 10_000_000_000_000_000_000_000; // 0.01 NEAR
+
+#[near_bindgen]
+impl P2PTransferContract {
+    // NEP-145 - prepay storage for the transfer records and notes an account creates
+    #[payable]
+    pub fn storage_deposit(
+        &mut self,
+        account_id: Option<AccountId>,
+        registration_only: Option<bool>,
+    ) -> StorageBalance {
+        let account_id = account_id.unwrap_or_else(env::predecessor_account_id);
+        let (balance, amount_yocto) =
+            self.storage_accounts.deposit(&account_id, registration_only.unwrap_or(false), STORAGE_DEPOSIT_PER_RECORD);
+        self.liability_totals.storage_deposits += amount_yocto;
+        balance
+    }
+
+    // NEP-145 - withdraw storage balance that isn't locked by existing records
+    #[payable]
+    pub fn storage_withdraw(&mut self, amount: Option<U128>) -> StorageBalance {
+        let (balance, amount_yocto) = self.storage_accounts.withdraw(amount);
+        self.liability_totals.storage_deposits -= amount_yocto;
+        balance
+    }
+
+    // NEP-145 - close an account with no records holding storage and refund its balance
+    #[payable]
+    pub fn storage_unregister(&mut self, force: Option<bool>) -> bool {
+        let Some(refund_yocto) = self.storage_accounts.unregister(force.unwrap_or(false)) else {
+            return false;
+        };
+        self.liability_totals.storage_deposits -= refund_yocto;
+        true
+    }
+
+    pub fn storage_balance_of(&self, account_id: AccountId) -> Option<StorageBalance> {
+        self.storage_accounts.balance_of(&account_id)
+    }
+
+    pub fn storage_balance_bounds(&self) -> StorageBalanceBounds {
+        StorageAccounts::bounds(STORAGE_DEPOSIT_PER_RECORD)
+    }
+
+    // Lock the per-record deposit from the sender, or the caller when the sender is a placeholder
+    pub(crate) fn lock_record_storage(&mut self, transfer: &Transfer) {
+        let payer: AccountId = if PLACEHOLDER_PARTIES.contains(&transfer.sender.as_str()) {
+            env::predecessor_account_id()
+        } else {
+            transfer.sender.parse().or_fail(ErrorCode::Internal, "Invalid sender")
+        };
+        
+        self.lock_storage(payer, &transfer.transfer_id);
+    }
+
+    // Notes stay in the pool after they're spent, so their deposit is never unlocked. The
+    // key is prefixed to keep note ids apart from transfer ids.
+    pub(crate) fn lock_note_storage(&mut self, note_id: &str) {
+        self.lock_storage(env::predecessor_account_id(), &format!("note:{}", note_id));
+    }
+
+    fn lock_storage(&mut self, payer: AccountId, record_key: &str) {
+        self.storage_accounts.lock(&payer, STORAGE_DEPOSIT_PER_RECORD);
+        self.liability_totals.storage_locked += STORAGE_DEPOSIT_PER_RECORD;
+        self.record_storage_payers.insert(&record_key.to_string(), &payer);
+    }
+
+    // Unlock the deposit of a removed record so its payer can withdraw it
+    pub(crate) fn release_record_storage(&mut self, transfer_id: &str) {
+        if let Some(payer) = self.record_storage_payers.remove(&transfer_id.to_string()) {
+            self.liability_totals.storage_locked -= self.storage_accounts.unlock(&payer, STORAGE_DEPOSIT_PER_RECORD);
+        }
+    }
+}
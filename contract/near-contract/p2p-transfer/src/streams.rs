@@ -0,0 +1,205 @@
+use crate::*;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum StreamStatus {
+    Active,
+    Completed,
+    Cancelled,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Stream {
+    pub stream_id: String,
+    pub sender: String,
+    pub recipient: String,
+    #[borsh(serialize_with = "decimal_borsh::serialize", deserialize_with = "decimal_borsh::deserialize")]
+    #[schemars(with = "String")]
+    pub deposit: U128,
+    #[borsh(serialize_with = "decimal_borsh::serialize", deserialize_with = "decimal_borsh::deserialize")]
+    #[schemars(with = "String")]
+    pub withdrawn: U128,
+    pub start_time: u64,
+    pub end_time: u64,
+    pub status: StreamStatus,
+    pub created_at: u64,
+}
+
+impl Stream {
+    // Amount accrued to the recipient so far, at one-second resolution
+    fn accrued_at(&self, now: u64) -> u128 {
+        let deposit_yocto: u128 = self.deposit.0;
+        if now <= self.start_time {
+            return 0;
+        }
+        if now >= self.end_time {
+            return deposit_yocto;
+        }
+        let elapsed_seconds = ((now - self.start_time) / 1_000_000_000) as u128;
+        let duration_seconds = ((self.end_time - self.start_time) / 1_000_000_000) as u128;
+        deposit_yocto * elapsed_seconds / duration_seconds
+    }
+}
+
+#[near_bindgen]
+impl P2PTransferContract {
+    // Streaming payment - the deposit accrues to the recipient per second between start and end
+    #[payable]
+    pub fn create_stream(
+        &mut self,
+        stream_id: String,
+        recipient: AccountId,
+        start_time: u64,
+        end_time: u64,
+    ) -> Stream {
+        self.assert_not_paused();
+        let sender = env::predecessor_account_id();
+        let deposit = env::attached_deposit();
+        
+        ensure!(deposit.as_yoctonear() > 0, InsufficientFunds, "Must attach NEAR tokens");
+        ensure!(self.streams.get(&stream_id).is_none(), AlreadyExists, "Stream ID already exists");
+        ensure!(start_time >= env::block_timestamp(), InvalidArgument, "Start time cannot be in the past");
+        ensure!(
+            end_time >= start_time + 1_000_000_000,
+            InvalidArgument,
+            "Stream must last at least one second"
+        );
+        ensure!(sender != recipient, InvalidArgument, "Cannot stream to yourself");
+        
+        let stream = Stream {
+            stream_id: stream_id.clone(),
+            sender: sender.to_string(),
+            recipient: recipient.to_string(),
+            deposit: U128(deposit.as_yoctonear()),
+            withdrawn: U128(0),
+            start_time,
+            end_time,
+            status: StreamStatus::Active,
+            created_at: env::block_timestamp(),
+        };
+        
+        self.streams.insert(&stream_id, &stream);
+        self.add_user_stream(&sender, &stream_id);
+        self.add_user_stream(&recipient, &stream_id);
+        
+        env::log_str(&format!(
+            "Stream created: {} | From: {} | To: {} | Amount: {} | Start: {} | End: {}",
+            stream_id, sender, recipient, deposit, start_time, end_time
+        ));
+        
+        stream
+    }
+
+    // Recipient withdraws everything accrued so far
+    pub fn withdraw_stream(&mut self, stream_id: String) -> Promise {
+        let mut stream = self.streams.get(&stream_id).or_fail(ErrorCode::NotFound, "Stream not found");
+        
+        let recipient: AccountId = stream.recipient.parse().or_fail(ErrorCode::Internal, "Invalid recipient");
+        ensure!(env::predecessor_account_id() == recipient, Unauthorized, "Only recipient can withdraw");
+        ensure!(stream.status == StreamStatus::Active, InvalidState, "Stream is not active");
+        
+        let deposit_yocto: u128 = stream.deposit.0;
+        let withdrawn_yocto: u128 = stream.withdrawn.0;
+        let accrued_yocto = stream.accrued_at(env::block_timestamp());
+        let available_yocto = accrued_yocto - withdrawn_yocto;
+        ensure!(available_yocto > 0, InvalidState, "Nothing to withdraw");
+        
+        stream.withdrawn = U128(accrued_yocto);
+        if accrued_yocto == deposit_yocto {
+            stream.status = StreamStatus::Completed;
+        }
+        self.streams.insert(&stream_id, &stream);
+        
+        let fee_yocto = self.calculate_fee(
+            available_yocto,
+            self.fee_config.direct_bps,
+            &stream.sender.parse().or_fail(ErrorCode::Internal, "Invalid sender"),
+        );
+        let payout_yocto = available_yocto - fee_yocto;
+        
+        env::log_str(&format!(
+            "Stream withdrawal: {} | To: {} | Amount: {}",
+            stream_id, recipient, payout_yocto
+        ));
+        
+        // Send fee
+        self.distribute_fee(fee_yocto);
+        
+        self.settle(recipient, payout_yocto)
+    }
+
+    // Either party can cancel - accrued funds go to the recipient, the rest back to the sender
+    pub fn cancel_stream(&mut self, stream_id: String) {
+        let mut stream = self.streams.get(&stream_id).or_fail(ErrorCode::NotFound, "Stream not found");
+        
+        let caller = env::predecessor_account_id();
+        let sender: AccountId = stream.sender.parse().or_fail(ErrorCode::Internal, "Invalid sender");
+        let recipient: AccountId = stream.recipient.parse().or_fail(ErrorCode::Internal, "Invalid recipient");
+        ensure!(
+            caller == sender || caller == recipient,
+            Unauthorized,
+            "Only stream parties can cancel"
+        );
+        ensure!(stream.status == StreamStatus::Active, InvalidState, "Stream is not active");
+        
+        let deposit_yocto: u128 = stream.deposit.0;
+        let withdrawn_yocto: u128 = stream.withdrawn.0;
+        let accrued_yocto = stream.accrued_at(env::block_timestamp());
+        let recipient_yocto = accrued_yocto - withdrawn_yocto;
+        let sender_yocto = deposit_yocto - accrued_yocto;
+        
+        stream.withdrawn = U128(accrued_yocto);
+        stream.status = StreamStatus::Cancelled;
+        self.streams.insert(&stream_id, &stream);
+        
+        let fee_yocto = self.calculate_fee(recipient_yocto, self.fee_config.direct_bps, &sender);
+        let payout_yocto = recipient_yocto - fee_yocto;
+        
+        env::log_str(&format!(
+            "Stream cancelled: {} | Recipient: {} | Refund: {}",
+            stream_id, payout_yocto, sender_yocto
+        ));
+        
+        // Send fee
+        self.distribute_fee(fee_yocto);
+        
+        if payout_yocto > 0 {
+            self.settle(recipient, payout_yocto);
+        }
+        
+        if sender_yocto > 0 {
+            self.settle(sender, sender_yocto);
+        }
+    }
+
+    pub fn get_stream(&self, stream_id: String) -> Option<Stream> {
+        self.streams.get(&stream_id)
+    }
+
+    // Amount the recipient could withdraw right now
+    pub fn get_stream_balance(&self, stream_id: String) -> U128 {
+        let stream = self.streams.get(&stream_id).or_fail(ErrorCode::NotFound, "Stream not found");
+        if stream.status != StreamStatus::Active {
+            return U128(0);
+        }
+        let withdrawn_yocto: u128 = stream.withdrawn.0;
+        U128(stream.accrued_at(env::block_timestamp()) - withdrawn_yocto)
+    }
+
+    pub fn get_user_streams(
+        &self,
+        account_id: AccountId,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Paged<Stream> {
+        let stream_ids = self.user_streams.get(&account_id).unwrap_or_default();
+        Paged::from_ids(&stream_ids, from_index, limit, |stream_id| self.streams.get(stream_id))
+    }
+
+    fn add_user_stream(&mut self, user: &AccountId, stream_id: &str) {
+        let mut streams = self.user_streams.get(user).unwrap_or_default();
+        streams.push(stream_id.to_string());
+        self.user_streams.insert(user, &streams);
+    }
+}
@@ -0,0 +1,244 @@
+use crate::*;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum SubscriptionStatus {
+    Active,
+    Exhausted,
+    Cancelled,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Subscription {
+    pub subscription_id: String,
+    pub payer: String,
+    pub recipient: String,
+    #[borsh(serialize_with = "decimal_borsh::serialize", deserialize_with = "decimal_borsh::deserialize")]
+    #[schemars(with = "String")]
+    pub amount_per_period: U128,
+    pub interval: u64,
+    pub next_payment_at: u64,
+    #[borsh(serialize_with = "decimal_borsh::serialize", deserialize_with = "decimal_borsh::deserialize")]
+    #[schemars(with = "String")]
+    pub remaining_budget: U128,
+    pub payments_made: u64,
+    pub status: SubscriptionStatus,
+    pub created_at: u64,
+}
+
+#[near_bindgen]
+impl P2PTransferContract {
+    // Subscription - payer pre-funds a budget that is pulled one installment per interval
+    #[payable]
+    pub fn create_subscription(
+        &mut self,
+        subscription_id: String,
+        recipient: AccountId,
+        amount_per_period: U128,
+        interval_seconds: u64,
+        start_at: Option<u64>,
+    ) -> Subscription {
+        self.assert_not_paused();
+        let payer = env::predecessor_account_id();
+        let budget = env::attached_deposit();
+        
+        ensure!(self.subscriptions.get(&subscription_id).is_none(), AlreadyExists, "Subscription ID already exists");
+        ensure!(amount_per_period.0 > 0, InvalidArgument, "Amount per period must be positive");
+        ensure!(interval_seconds > 0, InvalidArgument, "Interval must be positive");
+        ensure!(
+            budget.as_yoctonear() >= amount_per_period.0,
+            InvalidArgument,
+            "Budget must cover at least one period"
+        );
+        
+        let next_payment_at = start_at.unwrap_or_else(env::block_timestamp);
+        ensure!(
+            next_payment_at >= env::block_timestamp(),
+            InvalidArgument,
+            "Start time cannot be in the past"
+        );
+        
+        let subscription = Subscription {
+            subscription_id: subscription_id.clone(),
+            payer: payer.to_string(),
+            recipient: recipient.to_string(),
+            amount_per_period,
+            interval: interval_seconds * 1_000_000_000,
+            next_payment_at,
+            remaining_budget: U128(budget.as_yoctonear()),
+            payments_made: 0,
+            status: SubscriptionStatus::Active,
+            created_at: env::block_timestamp(),
+        };
+        
+        self.subscriptions.insert(&subscription_id, &subscription);
+        self.add_user_subscription(&payer, &subscription_id);
+        self.add_user_subscription(&recipient, &subscription_id);
+        
+        env::log_str(&format!(
+            "Subscription created: {} | Payer: {} | Recipient: {} | Amount: {} | Budget: {}",
+            subscription_id, payer, recipient, amount_per_period.0, budget
+        ));
+        
+        subscription
+    }
+
+    // Pull the next due installment - callable by the recipient or any keeper
+    pub fn collect_subscription(&mut self, subscription_id: String) -> Promise {
+        let mut subscription = self.subscriptions
+            .get(&subscription_id)
+            .or_fail(ErrorCode::NotFound, "Subscription not found");
+        
+        ensure!(
+            subscription.status == SubscriptionStatus::Active,
+            InvalidState,
+            "Subscription is not active"
+        );
+        ensure!(
+            env::block_timestamp() >= subscription.next_payment_at,
+            TooEarly,
+            "Installment is not due yet"
+        );
+        
+        let amount_yocto: u128 = subscription.amount_per_period.0;
+        let remaining_yocto: u128 = subscription.remaining_budget.0;
+        let remaining_yocto = remaining_yocto - amount_yocto;
+        
+        subscription.remaining_budget = U128(remaining_yocto);
+        subscription.payments_made += 1;
+        subscription.next_payment_at += subscription.interval;
+        if remaining_yocto < amount_yocto {
+            subscription.status = SubscriptionStatus::Exhausted;
+        }
+        self.subscriptions.insert(&subscription_id, &subscription);
+        
+        let payer: AccountId = subscription.payer.parse().or_fail(ErrorCode::Internal, "Invalid payer");
+        let recipient: AccountId = subscription.recipient.parse().or_fail(ErrorCode::Internal, "Invalid recipient");
+        // Installment ids live under the reserved prefix so no caller can take them first
+        let transfer_id = format!("{}{}:{}", RESERVED_TRANSFER_ID_PREFIX, subscription_id, subscription.payments_made);
+        ensure!(self.transfers.get(&transfer_id).is_none(), Internal, "Installment already recorded");
+        
+        let transfer = Transfer {
+            transfer_id: transfer_id.clone(),
+            sender: payer.to_string(),
+            recipient: recipient.to_string(),
+            amount: U128(amount_yocto),
+            transfer_type: TransferType::Subscription,
+            status: TransferStatus::Completed,
+            commitment: None,
+            nullifier: None,
+            memo: format!("Subscription {}", subscription_id),
+            encrypted_memo: None,
+            signer: None,
+            reference: None,
+            timestamp: env::block_timestamp(),
+        };
+        
+        self.transfers.insert(&transfer_id, &transfer);
+        self.track_new_transfer(&transfer);
+        self.lock_record_storage(&transfer);
+        self.add_user_transfer(&payer, &transfer_id);
+        self.add_user_transfer(&recipient, &transfer_id);
+        
+        let fee_yocto = self.calculate_fee(amount_yocto, self.fee_config.direct_bps, &payer);
+        let payout_yocto = amount_yocto - fee_yocto;
+        
+        env::log_str(&format!(
+            "Subscription installment paid: {} | Installment: {} | To: {} | Amount: {}",
+            subscription_id, subscription.payments_made, recipient, payout_yocto
+        ));
+        
+        // Send fee
+        self.distribute_fee(fee_yocto);
+        
+        self.payout_with_refund(&transfer_id, recipient, payer, payout_yocto)
+    }
+
+    // Cancel a subscription and reclaim whatever budget has not been paid out
+    pub fn cancel_subscription(&mut self, subscription_id: String) -> Promise {
+        let mut subscription = self.subscriptions
+            .get(&subscription_id)
+            .or_fail(ErrorCode::NotFound, "Subscription not found");
+        
+        let payer: AccountId = subscription.payer.parse().or_fail(ErrorCode::Internal, "Invalid payer");
+        ensure!(env::predecessor_account_id() == payer, Unauthorized, "Only payer can cancel");
+        ensure!(
+            subscription.status != SubscriptionStatus::Cancelled,
+            InvalidState,
+            "Subscription already cancelled"
+        );
+        
+        let refund_yocto: u128 = subscription.remaining_budget.0;
+        ensure!(refund_yocto > 0, InvalidState, "Nothing to reclaim");
+        
+        subscription.status = SubscriptionStatus::Cancelled;
+        subscription.remaining_budget = U128(0);
+        self.subscriptions.insert(&subscription_id, &subscription);
+        
+        env::log_str(&format!(
+            "Subscription cancelled: {} | Refund: {}",
+            subscription_id, refund_yocto
+        ));
+        
+        self.settle(payer, refund_yocto)
+    }
+
+    pub fn get_subscription(&self, subscription_id: String) -> Option<Subscription> {
+        self.subscriptions.get(&subscription_id)
+    }
+
+    pub fn get_user_subscriptions(
+        &self,
+        account_id: AccountId,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Paged<Subscription> {
+        let subscription_ids = self.user_subscriptions.get(&account_id).unwrap_or_default();
+        Paged::from_ids(&subscription_ids, from_index, limit, |subscription_id| {
+            self.subscriptions.get(subscription_id)
+        })
+    }
+
+    fn add_user_subscription(&mut self, user: &AccountId, subscription_id: &str) {
+        let mut subscriptions = self.user_subscriptions.get(user).unwrap_or_default();
+        subscriptions.push(subscription_id.to_string());
+        self.user_subscriptions.insert(user, &subscriptions);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use ciphra_testing as host;
+
+    fn subscribed_contract() -> P2PTransferContract {
+        let mut contract = contract();
+        host::set_caller(SENDER);
+        host::set_deposit(host::NEAR);
+        contract.storage_deposit(None, None);
+        host::set_deposit(10 * host::NEAR);
+        contract.create_subscription("s1".to_string(), account(RECIPIENT), U128(host::NEAR), 60, None);
+        host::set_deposit(0);
+        contract
+    }
+
+    #[test]
+    fn test_installments_use_reserved_ids() {
+        let mut contract = subscribed_contract();
+        let _ = contract.collect_subscription("s1".to_string());
+        host::advance_seconds(60);
+        let _ = contract.collect_subscription("s1".to_string());
+        
+        assert!(contract.get_transfer("subscription:s1:1".to_string()).is_some());
+        assert!(contract.get_transfer("subscription:s1:2".to_string()).is_some());
+    }
+
+    #[test]
+    #[should_panic(expected = "Transfer ID prefix is reserved")]
+    fn test_caller_cannot_take_installment_id() {
+        let mut contract = subscribed_contract();
+        send_direct(&mut contract, "subscription:s1:1", host::NEAR);
+    }
+}
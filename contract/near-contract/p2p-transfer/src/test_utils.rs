@@ -0,0 +1,25 @@
+// Fixtures shared by the unit tests; contract calls run against the ciphra-testing host
+use crate::*;
+
+pub(crate) const OWNER: &str = "owner.near";
+pub(crate) const SENDER: &str = "alice.near";
+pub(crate) const RECIPIENT: &str = "bob.near";
+
+pub(crate) fn account(name: &str) -> AccountId {
+    name.parse().unwrap()
+}
+
+pub(crate) fn contract() -> P2PTransferContract {
+    ciphra_testing::set_caller(OWNER);
+    P2PTransferContract::new(account(OWNER))
+}
+
+// Instant direct transfer from the sender to the recipient, with the sender's storage prepaid
+pub(crate) fn send_direct(contract: &mut P2PTransferContract, transfer_id: &str, amount: u128) {
+    ciphra_testing::set_caller(SENDER);
+    ciphra_testing::set_deposit(ciphra_testing::NEAR);
+    contract.storage_deposit(None, None);
+    ciphra_testing::set_deposit(amount);
+    let _ = contract.send_direct(transfer_id.to_string(), RECIPIENT.to_string(), String::new(), None, None, None);
+    ciphra_testing::set_deposit(0);
+}
@@ -0,0 +1,363 @@
+use ciphra_types::{assert_owner, ensure, ErrorCode, OrFail, Paged};
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::collections::{LookupMap, UnorderedMap};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{env, near_bindgen, AccountId, BorshStorageKey, PanicOnDefault};
+use schemars::JsonSchema;
+
+// Longest accepted contract name, version and network labels
+const MAX_LABEL_LENGTH: usize = 64;
+
+// Upper bound on maintainers allowed to publish releases
+const MAX_MAINTAINERS: usize = 10;
+
+#[derive(BorshSerialize, BorshStorageKey)]
+pub enum StorageKey {
+    Releases,
+    Canonical,
+    ReleasesByContract,
+}
+
+// One deployed instance of a ciphra contract at a given version on a given network
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ContractRelease {
+    pub contract: String, // e.g. "swap", "escrow", "p2p-transfer"
+    pub version: String,
+    pub network: String, // e.g. "mainnet", "testnet"
+    pub account_id: String,
+    pub code_hash: String, // Base58 sha256 of the deployed wasm, as reported by view_account
+    pub published_by: String,
+    pub published_at: u64,
+    pub deprecated: bool,
+}
+
+#[near_bindgen]
+#[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
+pub struct RegistryContract {
+    pub owner: AccountId,
+    pub maintainers: Vec<AccountId>,
+    pub releases: UnorderedMap<String, ContractRelease>, // network:contract:version -> release
+    pub canonical: LookupMap<String, String>, // network:contract -> version
+    pub releases_by_contract: LookupMap<String, Vec<String>>, // network:contract -> versions
+}
+
+fn contract_key(network: &str, contract: &str) -> String {
+    format!("{}:{}", network, contract)
+}
+
+fn release_key(network: &str, contract: &str, version: &str) -> String {
+    format!("{}:{}:{}", network, contract, version)
+}
+
+fn assert_label(label: &str, name: &str) {
+    ensure!(
+        !label.is_empty()
+            && label.len() <= MAX_LABEL_LENGTH
+            && label.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_' || b == b'.'),
+        InvalidArgument,
+        "{} must be 1-64 letters, digits, '-', '_' or '.'",
+        name
+    );
+}
+
+fn is_valid_code_hash(code_hash: &str) -> bool {
+    const BASE58_ALPHABET: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+    (43..=44).contains(&code_hash.len()) && code_hash.chars().all(|c| BASE58_ALPHABET.contains(c))
+}
+
+#[near_bindgen]
+impl RegistryContract {
+    #[init]
+    pub fn new(owner: AccountId) -> Self {
+        Self {
+            owner: owner.clone(),
+            maintainers: vec![owner],
+            releases: UnorderedMap::new(StorageKey::Releases),
+            canonical: LookupMap::new(StorageKey::Canonical),
+            releases_by_contract: LookupMap::new(StorageKey::ReleasesByContract),
+        }
+    }
+
+    // Maintainer records a deployed release; with `make_canonical` it becomes the instance
+    // clients resolve for that contract on that network
+    pub fn publish_release(
+        &mut self,
+        contract: String,
+        version: String,
+        network: String,
+        account_id: AccountId,
+        code_hash: String,
+        make_canonical: bool,
+    ) -> ContractRelease {
+        let caller = env::predecessor_account_id();
+        ensure!(self.maintainers.contains(&caller), Unauthorized, "Only maintainers can publish releases");
+        assert_label(&contract, "Contract");
+        assert_label(&version, "Version");
+        assert_label(&network, "Network");
+        ensure!(is_valid_code_hash(&code_hash), InvalidArgument, "Code hash must be a base58 sha256 digest");
+        
+        let key = release_key(&network, &contract, &version);
+        ensure!(self.releases.get(&key).is_none(), AlreadyExists, "Release already published");
+        
+        let release = ContractRelease {
+            contract: contract.clone(),
+            version: version.clone(),
+            network: network.clone(),
+            account_id: account_id.to_string(),
+            code_hash,
+            published_by: caller.to_string(),
+            published_at: env::block_timestamp(),
+            deprecated: false,
+        };
+        self.releases.insert(&key, &release);
+        
+        let contract_key = contract_key(&network, &contract);
+        let mut versions = self.releases_by_contract.get(&contract_key).unwrap_or_default();
+        versions.push(version.clone());
+        self.releases_by_contract.insert(&contract_key, &versions);
+        if make_canonical {
+            self.canonical.insert(&contract_key, &version);
+        }
+        
+        env::log_str(&format!(
+            "Release published: {} {} | Network: {} | Account: {} | Canonical: {}",
+            contract, version, network, account_id, make_canonical
+        ));
+        
+        release
+    }
+
+    // Points clients at another published version, e.g. to roll back
+    pub fn set_canonical(&mut self, contract: String, network: String, version: String) {
+        ensure!(
+            self.maintainers.contains(&env::predecessor_account_id()),
+            Unauthorized,
+            "Only maintainers can set canonical releases"
+        );
+        let release = self.releases
+            .get(&release_key(&network, &contract, &version))
+            .or_fail(ErrorCode::NotFound, "Release not found");
+        ensure!(!release.deprecated, InvalidState, "Release is deprecated");
+        
+        self.canonical.insert(&contract_key(&network, &contract), &version);
+        
+        env::log_str(&format!("Canonical release: {} {} | Network: {}", contract, version, network));
+    }
+
+    // Deprecated releases stay queryable for auditing but can't be canonical
+    pub fn deprecate_release(&mut self, contract: String, network: String, version: String) {
+        ensure!(
+            self.maintainers.contains(&env::predecessor_account_id()),
+            Unauthorized,
+            "Only maintainers can deprecate releases"
+        );
+        let key = release_key(&network, &contract, &version);
+        let mut release = self.releases.get(&key).or_fail(ErrorCode::NotFound, "Release not found");
+        let contract_key = contract_key(&network, &contract);
+        ensure!(
+            self.canonical.get(&contract_key).as_ref() != Some(&version),
+            InvalidState,
+            "Cannot deprecate the canonical release"
+        );
+        
+        release.deprecated = true;
+        self.releases.insert(&key, &release);
+        
+        env::log_str(&format!("Release deprecated: {} {} | Network: {}", contract, version, network));
+    }
+
+    pub fn set_maintainers(&mut self, maintainers: Vec<AccountId>) {
+        assert_owner(&self.owner);
+        ensure!(
+            !maintainers.is_empty() && maintainers.len() <= MAX_MAINTAINERS,
+            LimitExceeded,
+            "Registry must have 1-10 maintainers"
+        );
+        let mut maintainers = maintainers;
+        maintainers.sort();
+        maintainers.dedup();
+        self.maintainers = maintainers;
+    }
+
+    pub fn get_maintainers(&self) -> Vec<AccountId> {
+        self.maintainers.clone()
+    }
+
+    // What SDKs call to resolve the instance to talk to
+    pub fn get_canonical(&self, contract: String, network: String) -> Option<ContractRelease> {
+        self.canonical
+            .get(&contract_key(&network, &contract))
+            .and_then(|version| self.releases.get(&release_key(&network, &contract, &version)))
+    }
+
+    pub fn get_release(&self, contract: String, network: String, version: String) -> Option<ContractRelease> {
+        self.releases.get(&release_key(&network, &contract, &version))
+    }
+
+    // Every published version of a contract on a network, oldest first
+    pub fn get_releases_for(
+        &self,
+        contract: String,
+        network: String,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Paged<ContractRelease> {
+        let versions = self.releases_by_contract.get(&contract_key(&network, &contract)).unwrap_or_default();
+        Paged::from_ids(&versions, from_index, limit, |version| {
+            self.releases.get(&release_key(&network, &contract, version))
+        })
+    }
+
+    pub fn get_releases(&self, from_index: Option<u64>, limit: Option<u64>) -> Paged<ContractRelease> {
+        let releases = self.releases.values_as_vector();
+        Paged::from_range(releases.len(), from_index, limit, |index| releases.get(index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ciphra_testing as host;
+
+    const OWNER: &str = "owner.near";
+    const CODE_HASH: &str = "7LqGQuT8xwMKGTDsSRzqMexnWrnvvnhASxtoUfBpNeAR";
+
+    fn account(name: &str) -> AccountId {
+        name.parse().unwrap()
+    }
+
+    fn contract() -> RegistryContract {
+        host::set_caller(OWNER);
+        RegistryContract::new(account(OWNER))
+    }
+
+    fn publish(contract: &mut RegistryContract, version: &str, make_canonical: bool) -> ContractRelease {
+        contract.publish_release(
+            "swap".to_string(),
+            version.to_string(),
+            "testnet".to_string(),
+            account("swap.testnet"),
+            CODE_HASH.to_string(),
+            make_canonical,
+        )
+    }
+
+    #[test]
+    fn test_publish_release_becomes_canonical() {
+        let mut contract = contract();
+        let release = publish(&mut contract, "1.0.0", true);
+
+        assert_eq!(release.published_by, OWNER);
+        assert!(!release.deprecated);
+        let canonical = contract.get_canonical("swap".to_string(), "testnet".to_string()).unwrap();
+        assert_eq!(canonical.version, "1.0.0");
+        assert_eq!(canonical.account_id, "swap.testnet");
+    }
+
+    #[test]
+    fn test_releases_are_listed_oldest_first() {
+        let mut contract = contract();
+        publish(&mut contract, "1.0.0", true);
+        publish(&mut contract, "1.1.0", false);
+
+        let releases = contract.get_releases_for("swap".to_string(), "testnet".to_string(), None, None);
+        assert_eq!(releases.total, 2);
+        assert_eq!(releases.items.iter().map(|r| r.version.as_str()).collect::<Vec<_>>(), vec!["1.0.0", "1.1.0"]);
+        // A non-canonical publish leaves clients on the previous version
+        let canonical = contract.get_canonical("swap".to_string(), "testnet".to_string()).unwrap();
+        assert_eq!(canonical.version, "1.0.0");
+        assert_eq!(contract.get_releases(None, None).total, 2);
+    }
+
+    #[test]
+    fn test_canonical_release_can_be_rolled_back() {
+        let mut contract = contract();
+        publish(&mut contract, "1.0.0", true);
+        publish(&mut contract, "1.1.0", true);
+
+        contract.set_canonical("swap".to_string(), "testnet".to_string(), "1.0.0".to_string());
+        contract.deprecate_release("swap".to_string(), "testnet".to_string(), "1.1.0".to_string());
+
+        let canonical = contract.get_canonical("swap".to_string(), "testnet".to_string()).unwrap();
+        assert_eq!(canonical.version, "1.0.0");
+        let deprecated = contract.get_release("swap".to_string(), "testnet".to_string(), "1.1.0".to_string());
+        assert!(deprecated.unwrap().deprecated);
+    }
+
+    #[test]
+    fn test_maintainers_are_sorted_and_deduplicated() {
+        let mut contract = contract();
+        contract.set_maintainers(vec![account("bob.near"), account("alice.near"), account("bob.near")]);
+        assert_eq!(contract.get_maintainers(), vec![account("alice.near"), account("bob.near")]);
+
+        host::set_caller("bob.near");
+        publish(&mut contract, "1.0.0", false);
+        assert_eq!(
+            contract.get_release("swap".to_string(), "testnet".to_string(), "1.0.0".to_string()).unwrap().published_by,
+            "bob.near"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Only maintainers can publish releases")]
+    fn test_only_maintainers_publish() {
+        let mut contract = contract();
+        host::set_caller("mallory.near");
+        publish(&mut contract, "1.0.0", true);
+    }
+
+    #[test]
+    #[should_panic(expected = r#""code":"AlreadyExists","message":"Release already published""#)]
+    fn test_releases_cannot_be_republished() {
+        let mut contract = contract();
+        publish(&mut contract, "1.0.0", false);
+        publish(&mut contract, "1.0.0", true);
+    }
+
+    #[test]
+    #[should_panic(expected = "Version must be 1-64 letters, digits, '-', '_' or '.'")]
+    fn test_labels_are_validated() {
+        let mut contract = contract();
+        publish(&mut contract, "1.0.0:evil", false);
+    }
+
+    #[test]
+    #[should_panic(expected = "Code hash must be a base58 sha256 digest")]
+    fn test_code_hash_must_be_base58() {
+        let mut contract = contract();
+        contract.publish_release(
+            "swap".to_string(),
+            "1.0.0".to_string(),
+            "testnet".to_string(),
+            account("swap.testnet"),
+            "0OIl".repeat(11),
+            false,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot deprecate the canonical release")]
+    fn test_canonical_release_cannot_be_deprecated() {
+        let mut contract = contract();
+        publish(&mut contract, "1.0.0", true);
+        contract.deprecate_release("swap".to_string(), "testnet".to_string(), "1.0.0".to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = r#""code":"InvalidState","message":"Release is deprecated""#)]
+    fn test_deprecated_release_cannot_become_canonical() {
+        let mut contract = contract();
+        publish(&mut contract, "1.0.0", true);
+        publish(&mut contract, "1.1.0", false);
+        contract.deprecate_release("swap".to_string(), "testnet".to_string(), "1.1.0".to_string());
+        contract.set_canonical("swap".to_string(), "testnet".to_string(), "1.1.0".to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "Registry must have 1-10 maintainers")]
+    fn test_maintainer_list_cannot_be_emptied() {
+        let mut contract = contract();
+        contract.set_maintainers(Vec::new());
+    }
+}
@@ -0,0 +1,34 @@
+#!/bin/bash
+
+# Incident response: pause new deposits on every payment contract at once.
+# Run from a guardian account registered on all three contracts (add_guardian).
+# Settlement, withdrawal and refund paths stay live; only the owner can unpause.
+#
+# Usage: GUARDIAN=guardian.testnet ./scripts/pause-all.sh [account-suffix]
+
+set -e
+
+export NEAR_ENV=${NEAR_ENV:-testnet}
+SUFFIX=${1:-ashiq09.testnet}
+
+if [ -z "$GUARDIAN" ]; then
+  echo "GUARDIAN must be set to the guardian account id"
+  exit 1
+fi
+
+# Fire all three calls concurrently so no contract stays open while another pauses
+PIDS=()
+for CONTRACT in swap escrow p2p; do
+  near call $CONTRACT.$SUFFIX pause '{}' --accountId $GUARDIAN &
+  PIDS+=($!)
+done
+FAILED=0
+for PID in "${PIDS[@]}"; do
+  wait $PID || FAILED=1
+done
+
+for CONTRACT in swap escrow p2p; do
+  echo "$CONTRACT.$SUFFIX paused: $(near view $CONTRACT.$SUFFIX is_paused '{}')"
+done
+
+exit $FAILED
@@ -0,0 +1,51 @@
+use crate::*;
+
+#[near_bindgen]
+impl SwapContract {
+    // Two-step handover of the admin role, e.g. to the governance contract; the proposed
+    // account only takes over once it calls `accept_owner`
+    pub fn propose_owner(&mut self, new_owner: AccountId) {
+        self.access.propose_owner(&self.owner, new_owner.clone());
+        env::log_str(&format!("Owner proposed: {} | Current: {}", new_owner, self.owner));
+        emit_event("owner_proposed", json!({ "owner": self.owner, "proposed_owner": new_owner }));
+    }
+
+    pub fn accept_owner(&mut self) {
+        let new_owner = self.access.accept_owner();
+        env::log_str(&format!("Owner changed: {} -> {}", self.owner, new_owner));
+        emit_event("owner_changed", json!({ "old_owner": self.owner, "new_owner": new_owner }));
+        self.owner = new_owner;
+    }
+
+    pub fn cancel_owner_transfer(&mut self) {
+        self.access.cancel_owner_transfer(&self.owner);
+        env::log_str("Owner transfer cancelled");
+    }
+
+    pub fn grant_role(&mut self, role: Role, account_id: AccountId) {
+        self.access.grant_role(&self.owner, role, account_id.clone());
+        env::log_str(&format!("Role granted: {:?} | Account: {}", role, account_id));
+        emit_event("role_granted", json!({ "role": role, "account_id": account_id }));
+    }
+
+    pub fn revoke_role(&mut self, role: Role, account_id: AccountId) {
+        self.access.revoke_role(&self.owner, role, &account_id);
+        env::log_str(&format!("Role revoked: {:?} | Account: {}", role, account_id));
+        emit_event("role_revoked", json!({ "role": role, "account_id": account_id }));
+    }
+
+    pub fn renounce_role(&mut self, role: Role) {
+        self.access.renounce_role(role);
+        let account_id = env::predecessor_account_id();
+        env::log_str(&format!("Role renounced: {:?} | Account: {}", role, account_id));
+        emit_event("role_revoked", json!({ "role": role, "account_id": account_id }));
+    }
+
+    pub fn has_role(&self, role: Role, account_id: AccountId) -> bool {
+        self.access.has_role(role, &account_id)
+    }
+
+    pub fn get_access_status(&self) -> AccessStatus {
+        self.access.status(&self.owner)
+    }
+}
@@ -0,0 +1,8 @@
+use ciphra_types::EventStandard;
+use near_sdk::serde_json::Value;
+
+pub(crate) const EVENTS: EventStandard = EventStandard::new("swap", "1.0.0");
+
+pub(crate) fn emit_event(event: &str, data: Value) {
+    EVENTS.emit(event, data);
+}
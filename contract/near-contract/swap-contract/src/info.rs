@@ -0,0 +1,26 @@
+use crate::*;
+use crate::events::EVENTS;
+
+#[near_bindgen]
+impl SwapContract {
+    // Configuration and counters in the shape every Ciphra contract shares
+    pub fn get_contract_info(&self) -> ContractInfo {
+        ContractInfo::new(
+            EVENTS.contract,
+            env!("CARGO_PKG_VERSION"),
+            format!("{:?}", self.get_state_version()),
+            &self.owner,
+            self.access.pending_owner.as_ref(),
+            &self.pause_guard,
+        )
+        .fee("fee_percentage", self.fee_percentage)
+        .fee("insurance_fee_bps", self.insurance_fee_bps)
+        .bound("min_time_lock", self.min_time_lock)
+        .bound("max_time_lock", self.max_time_lock)
+        .bound("max_insurance_fee_bps", MAX_INSURANCE_FEE_BPS as u64)
+        .counter("swaps", self.swaps.len())
+        .counter("oracle_verifications", self.oracle_verifications.len())
+        .counter("settlements", self.settlements.next_settlement_id)
+        .rate_limit(self.rate_limiter.config)
+    }
+}
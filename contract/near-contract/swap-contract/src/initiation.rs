@@ -0,0 +1,79 @@
+use crate::*;
+
+#[near_bindgen]
+impl SwapContract {
+    #[payable]
+    pub fn initiate_swap(
+        &mut self,
+        swap_id: String,
+        participant: AccountId,
+        hash_lock: String,
+        hash_algorithm: HashAlgorithm,
+        time_lock_duration: u64,
+        target_chain: String,
+        target_address: String,
+        counterparty_swap_id: Option<String>,
+        linked_escrow: Option<String>,
+    ) -> AtomicSwap {
+        let initiator = env::predecessor_account_id();
+        let amount = env::attached_deposit();
+        
+        self.pause_guard.assert_not_paused();
+        ensure!(amount.as_yoctonear() > 0, InsufficientFunds, "Must attach NEAR tokens");
+        self.rate_limiter.record(&initiator, 1);
+        ensure!(self.swaps.get(&swap_id).is_none(), AlreadyExists, "Swap ID already exists");
+        ensure!(
+            time_lock_duration >= self.min_time_lock && time_lock_duration <= self.max_time_lock,
+            InvalidArgument,
+            "Time lock duration out of bounds"
+        );
+        let hash_lock = normalize_hashlock(&target_chain, &hash_lock, &hash_algorithm)
+            .unwrap_or_else(|err| fail(ErrorCode::InvalidArgument, err));
+        validate_chain_address(&target_chain, &target_address)
+            .unwrap_or_else(|err| fail(ErrorCode::InvalidArgument, err));
+        ensure!(
+            linked_escrow.is_none() || self.escrow_contract.is_some(),
+            NotFound,
+            "No escrow contract configured"
+        );
+        
+        let time_lock = env::block_timestamp() + (time_lock_duration * 1_000_000_000);
+        
+        let swap = AtomicSwap {
+            swap_id: swap_id.clone(),
+            initiator: initiator.to_string(),
+            participant: participant.to_string(),
+            amount: U128(amount.as_yoctonear()),
+            hash_lock,
+            hash_algorithm,
+            time_lock,
+            status: SwapStatus::Initiated,
+            secret: None,
+            target_chain,
+            target_address,
+            counterparty_swap_id,
+            created_at: env::block_timestamp(),
+            linked_escrow,
+        };
+        
+        self.swaps.insert(&swap_id, &swap);
+        self.add_swap_to_initiator(&initiator, &swap_id);
+        self.add_swap_to_participant(&participant, &swap_id);
+        self.lock_swap_storage(&initiator, &swap_id);
+        
+        env::log_str(&format!(
+            "Swap initiated: {} | Algorithm: {:?} | Counterparty: {:?}",
+            swap_id, swap.hash_algorithm, swap.counterparty_swap_id
+        ));
+        emit_event("initiated", json!({
+            "swap_id": swap_id,
+            "initiator": swap.initiator,
+            "participant": swap.participant,
+            "amount": swap.amount,
+            "hash_algorithm": swap.hash_algorithm,
+            "time_lock": swap.time_lock,
+        }));
+        
+        swap
+    }
+}
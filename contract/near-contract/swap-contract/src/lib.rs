@@ -1,33 +1,56 @@
+use ciphra_types::{
+    assert_owner, bps_of, decimal_borsh, ensure, fail, normalize_hashlock, validate_chain_address, ErrorCode, OrFail,
+};
 use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
 use near_sdk::collections::{LookupMap, UnorderedMap};
 use near_sdk::json_types::U128;
-use near_sdk::{env, near_bindgen, AccountId, BorshStorageKey, PanicOnDefault, Promise, NearToken};
+use near_sdk::{
+    env, ext_contract, is_promise_success, near_bindgen, AccountId, BorshStorageKey, Gas, PanicOnDefault, Promise, NearToken,
+};
 use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::serde_json::json;
 use schemars::JsonSchema;
 
+pub use ciphra_types::{
+    AccessControl, AccessStatus, ContractInfo, HashAlgorithm, Paged, PauseGuard, PauseStatus, RateLimitConfig,
+    RateLimiter, Role, SettlementLedger, StorageAccounts, StorageBalance, StorageBalanceBounds, SwapStatus,
+};
+
+mod access;
+mod events;
+mod info;
+mod migration;
+mod rate_limit;
+mod settlement;
+mod storage;
+#[cfg(test)]
+mod test_utils;
+
+// initiate_swap takes every swap term as its own argument; its ext wrapper, generated beside
+// the impl, ignores method attributes, so the module allows the lint
+#[allow(clippy::too_many_arguments)]
+mod initiation;
+
+use events::emit_event;
+
+pub use migration::StateVersion;
+
+const GAS_FOR_ESCROW_ATTESTATION: Gas = Gas::from_tgas(40);
+
+// Upper bound for the insurance share of each swap fee (50%)
+const MAX_INSURANCE_FEE_BPS: u16 = 5000;
+
 #[derive(BorshSerialize, BorshStorageKey)]
 pub enum StorageKey {
     Swaps,
     SwapsByInitiator,
     SwapsByParticipant,
     OracleVerifications,
-}
-
-#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema, PartialEq, Debug)]
-#[serde(crate = "near_sdk::serde")]
-pub enum HashAlgorithm {
-    SHA256,
-    Poseidon,
-}
-
-#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
-#[serde(crate = "near_sdk::serde")]
-pub enum SwapStatus {
-    Initiated,
-    Locked,
-    Completed,
-    Refunded,
-    Cancelled,
+    StorageAccounts,
+    RecordStoragePayers,
+    PendingSettlements,
+    ClaimableSettlements,
+    RateLimits,
 }
 
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, JsonSchema)]
@@ -36,7 +59,9 @@ pub struct AtomicSwap {
     pub swap_id: String,
     pub initiator: String,
     pub participant: String,
-    pub amount: String,
+    #[borsh(serialize_with = "decimal_borsh::serialize", deserialize_with = "decimal_borsh::deserialize")]
+    #[schemars(with = "String")]
+    pub amount: U128,
     pub hash_lock: String,
     pub hash_algorithm: HashAlgorithm,
     pub time_lock: u64,
@@ -46,6 +71,7 @@ pub struct AtomicSwap {
     pub target_address: String,
     pub counterparty_swap_id: Option<String>,
     pub created_at: u64,
+    pub linked_escrow: Option<String>, // Escrow released when this swap completes
 }
 
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
@@ -57,7 +83,34 @@ pub struct PoseidonVerification {
     pub verified_at: Option<u64>,
 }
 
-#[near_bindgen]
+// Implemented by both the insurance pool and the fee treasury
+// Only the generated ext_fee_contract module is used
+#[allow(dead_code)]
+#[ext_contract(ext_fee_contract)]
+trait FeeContract {
+    fn deposit_fees(&mut self);
+}
+
+// Only the generated ext_escrow module is used
+#[allow(dead_code)]
+#[ext_contract(ext_escrow)]
+trait EscrowSettlement {
+    fn attest_swap_settlement(
+        &mut self,
+        escrow_id: String,
+        swap_id: String,
+        initiator: AccountId,
+        participant: AccountId,
+        token: Option<AccountId>,
+        amount: U128,
+    );
+}
+
+// Version and link come from the package; the swap also emits NEP-297 events
+#[near_bindgen(contract_metadata(
+    standard(standard = "nep330", version = "1.1.0"),
+    standard(standard = "nep297", version = "1.0.0"),
+))]
 #[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
 pub struct SwapContract {
     pub swaps: UnorderedMap<String, AtomicSwap>,
@@ -67,9 +120,19 @@ pub struct SwapContract {
     pub owner: AccountId,
     pub oracle_account: AccountId,
     pub fee_recipient: AccountId,
+    pub fee_treasury: Option<AccountId>, // Treasury contract credited with fees instead of fee_recipient
     pub fee_percentage: u16,
+    pub insurance_pool: Option<AccountId>,
+    pub insurance_fee_bps: u16, // Share of each swap fee routed to the insurance pool
+    pub escrow_contract: Option<AccountId>,
     pub min_time_lock: u64,
     pub max_time_lock: u64,
+    pub pause_guard: PauseGuard, // Guardians may pause new swaps but not unpause
+    pub storage_accounts: StorageAccounts, // NEP-145 prepaid storage
+    pub record_storage_payers: LookupMap<String, AccountId>,     // swap_id -> account whose deposit it locks
+    pub access: AccessControl, // Pending owner transfer and delegated roles
+    pub settlements: SettlementLedger, // Payouts and refunds awaiting their transfer callback
+    pub rate_limiter: RateLimiter,     // Bounds swaps initiated per account
 }
 
 #[near_bindgen]
@@ -77,6 +140,7 @@ impl SwapContract {
     #[init]
     pub fn new(owner: AccountId, oracle_account: AccountId) -> Self {
         let fee_recipient = owner.clone();
+        Self::write_state_version();
         Self {
             swaps: UnorderedMap::new(StorageKey::Swaps),
             swaps_by_initiator: LookupMap::new(StorageKey::SwapsByInitiator),
@@ -85,79 +149,38 @@ impl SwapContract {
             owner,
             oracle_account,
             fee_recipient,
+            fee_treasury: None,
             fee_percentage: 30, // 0.3% default
+            insurance_pool: None,
+            insurance_fee_bps: 0,
+            escrow_contract: None,
             min_time_lock: 3600,
             max_time_lock: 86400,
+            pause_guard: PauseGuard::default(),
+            storage_accounts: StorageAccounts::new(StorageKey::StorageAccounts),
+            record_storage_payers: LookupMap::new(StorageKey::RecordStoragePayers),
+            access: AccessControl::default(),
+            settlements: Self::new_settlement_ledger(),
+            rate_limiter: Self::new_rate_limiter(),
         }
     }
 
-    #[payable]
-    pub fn initiate_swap(
-        &mut self,
-        swap_id: String,
-        participant: AccountId,
-        hash_lock: String,
-        hash_algorithm: HashAlgorithm,
-        time_lock_duration: u64,
-        target_chain: String,
-        target_address: String,
-        counterparty_swap_id: Option<String>,
-    ) -> AtomicSwap {
-        let initiator = env::predecessor_account_id();
-        let amount = env::attached_deposit();
-        
-        assert!(amount.as_yoctonear() > 0, "Must attach NEAR tokens");
-        assert!(self.swaps.get(&swap_id).is_none(), "Swap ID already exists");
-        assert!(
-            time_lock_duration >= self.min_time_lock && time_lock_duration <= self.max_time_lock,
-            "Time lock duration out of bounds"
-        );
-        assert!(hash_lock.len() == 64, "Hash lock must be 64 characters (32 bytes hex)");
-        
-        let time_lock = env::block_timestamp() + (time_lock_duration * 1_000_000_000);
-        
-        let swap = AtomicSwap {
-            swap_id: swap_id.clone(),
-            initiator: initiator.to_string(),
-            participant: participant.to_string(),
-            amount: amount.as_yoctonear().to_string(),
-            hash_lock,
-            hash_algorithm,
-            time_lock,
-            status: SwapStatus::Initiated,
-            secret: None,
-            target_chain,
-            target_address,
-            counterparty_swap_id,
-            created_at: env::block_timestamp(),
-        };
-        
-        self.swaps.insert(&swap_id, &swap);
-        self.add_swap_to_initiator(&initiator, &swap_id);
-        self.add_swap_to_participant(&participant, &swap_id);
-        
-        env::log_str(&format!(
-            "Swap initiated: {} | Algorithm: {:?} | Counterparty: {:?}",
-            swap_id, swap.hash_algorithm, swap.counterparty_swap_id
-        ));
-        
-        swap
-    }
-
     pub fn lock_swap(&mut self, swap_id: String) {
-        let mut swap = self.swaps.get(&swap_id).expect("Swap not found");
+        let mut swap = self.swaps.get(&swap_id).or_fail(ErrorCode::NotFound, "Swap not found");
         
-        assert_eq!(
-            env::predecessor_account_id(),
-            swap.participant,
+        ensure!(
+            env::predecessor_account_id() == swap.participant,
+            Unauthorized,
             "Only participant can lock"
         );
-        assert!(
+        ensure!(
             matches!(swap.status, SwapStatus::Initiated),
+            InvalidState,
             "Swap must be in Initiated status"
         );
-        assert!(
+        ensure!(
             env::block_timestamp() < swap.time_lock,
+            Expired,
             "Swap has expired"
         );
         
@@ -165,28 +188,30 @@ impl SwapContract {
         self.swaps.insert(&swap_id, &swap);
         
         env::log_str(&format!("Swap locked: {}", swap_id));
+        emit_event("locked", json!({ "swap_id": swap_id }));
     }
 
     pub fn complete_swap_with_oracle_verification(&mut self, swap_id: String, secret: String) -> Promise {
-        let mut swap = self.swaps.get(&swap_id).expect("Swap not found");
+        let mut swap = self.swaps.get(&swap_id).or_fail(ErrorCode::NotFound, "Swap not found");
         
-        // For Poseidon hashes, require Oracle verification
-        if swap.hash_algorithm == HashAlgorithm::Poseidon {
-            let verification = self.oracle_verifications.get(&swap_id)
-                .expect("Oracle verification required for Poseidon");
-            assert!(verification.verified, "Oracle verification not completed");
-        } else {
-            // For SHA256, verify locally
-            let secret_hash = self.hash_secret(&secret);
-            assert_eq!(secret_hash, swap.hash_lock, "Invalid secret");
+        // Hashes computable on-chain are verified locally; Poseidon requires Oracle verification
+        match swap.hash_algorithm.hash_hex(secret.as_bytes()) {
+            Some(secret_hash) => ensure!(secret_hash == swap.hash_lock, InvalidProof, "Invalid secret"),
+            None => {
+                let verification = self.oracle_verifications.get(&swap_id)
+                    .or_fail(ErrorCode::InvalidState, "Oracle verification required for Poseidon");
+                ensure!(verification.verified, InvalidState, "Oracle verification not completed");
+            }
         }
         
-        assert!(
+        ensure!(
             matches!(swap.status, SwapStatus::Locked),
+            InvalidState,
             "Swap must be locked"
         );
-        assert!(
+        ensure!(
             env::block_timestamp() < swap.time_lock,
+            Expired,
             "Swap has expired"
         );
         
@@ -194,26 +219,58 @@ impl SwapContract {
         swap.status = SwapStatus::Completed;
         self.swaps.insert(&swap_id, &swap);
         
-        let amount_yocto: u128 = swap.amount.parse().expect("Invalid amount");
-        let fee_yocto = (amount_yocto * self.fee_percentage as u128) / 10000;
+        let amount_yocto: u128 = swap.amount.0;
+        let fee_yocto = bps_of(amount_yocto, self.fee_percentage);
         let payout_yocto = amount_yocto - fee_yocto;
         
         env::log_str(&format!(
             "Swap completed: {} | Fee: {} | Payout: {}",
             swap_id, fee_yocto, payout_yocto
         ));
+        emit_event("completed", json!({
+            "swap_id": swap_id,
+            "participant": swap.participant,
+            "amount": U128(payout_yocto),
+            "fee": U128(fee_yocto),
+        }));
         
         // Transfer to participant
-        let participant: AccountId = swap.participant.parse().expect("Invalid participant");
-        let payout = NearToken::from_yoctonear(payout_yocto);
+        let participant: AccountId = swap.participant.parse().or_fail(ErrorCode::Internal, "Invalid participant");
         
-        // Transfer fee to fee recipient
-        if fee_yocto > 0 {
-            let fee = NearToken::from_yoctonear(fee_yocto);
-            Promise::new(self.fee_recipient.clone()).transfer(fee);
+        // A linked escrow settles on this swap's completion; its own checks decide whether it releases
+        if let (Some(escrow_contract), Some(escrow_id)) = (self.escrow_contract.clone(), swap.linked_escrow.clone()) {
+            ext_escrow::ext(escrow_contract)
+                .with_static_gas(GAS_FOR_ESCROW_ATTESTATION)
+                .attest_swap_settlement(
+                    escrow_id,
+                    swap_id.clone(),
+                    swap.initiator.parse().or_fail(ErrorCode::Internal, "Invalid initiator"),
+                    swap.participant.parse().or_fail(ErrorCode::Internal, "Invalid participant"),
+                    None,
+                    U128(payout_yocto),
+                );
         }
         
-        Promise::new(participant).transfer(payout)
+        // Route the insurance slice to the pool, the rest of the fee to the treasury or fee recipient
+        let insurance_yocto = match self.insurance_pool.clone() {
+            Some(pool) => {
+                let insurance_yocto = bps_of(fee_yocto, self.insurance_fee_bps);
+                if insurance_yocto > 0 {
+                    self.settle_fee_deposit(pool, insurance_yocto);
+                }
+                insurance_yocto
+            }
+            None => 0,
+        };
+        if fee_yocto > insurance_yocto {
+            let remaining_fee_yocto = fee_yocto - insurance_yocto;
+            match self.fee_treasury.clone() {
+                Some(treasury) => self.settle_fee_deposit(treasury, remaining_fee_yocto),
+                None => self.settle(self.fee_recipient.clone(), remaining_fee_yocto),
+            };
+        }
+        
+        self.settle(participant, payout_yocto)
     }
 
     // Oracle submits Poseidon hash verification
@@ -223,14 +280,14 @@ impl SwapContract {
         poseidon_hash: String,
         secret_matches: bool,
     ) {
-        assert_eq!(
-            env::predecessor_account_id(),
-            self.oracle_account,
+        ensure!(
+            env::predecessor_account_id() == self.oracle_account,
+            Unauthorized,
             "Only oracle can verify"
         );
         
-        let swap = self.swaps.get(&swap_id).expect("Swap not found");
-        assert_eq!(swap.hash_algorithm, HashAlgorithm::Poseidon, "Not a Poseidon swap");
+        let swap = self.swaps.get(&swap_id).or_fail(ErrorCode::NotFound, "Swap not found");
+        ensure!(swap.hash_algorithm == HashAlgorithm::Poseidon, InvalidState, "Not a Poseidon swap");
         
         let verification = PoseidonVerification {
             swap_id: swap_id.clone(),
@@ -245,23 +302,29 @@ impl SwapContract {
             "Oracle verification submitted: {} | Verified: {}",
             swap_id, secret_matches
         ));
+        emit_event("oracle_verified", json!({
+            "swap_id": swap_id,
+            "verified": secret_matches,
+        }));
     }
 
     pub fn refund_swap(&mut self, swap_id: String) -> Promise {
-        let mut swap = self.swaps.get(&swap_id).expect("Swap not found");
+        let mut swap = self.swaps.get(&swap_id).or_fail(ErrorCode::NotFound, "Swap not found");
         
-        let initiator: AccountId = swap.initiator.parse().expect("Invalid initiator");
-        assert_eq!(
-            env::predecessor_account_id(),
-            initiator,
+        let initiator: AccountId = swap.initiator.parse().or_fail(ErrorCode::Internal, "Invalid initiator");
+        ensure!(
+            env::predecessor_account_id() == initiator,
+            Unauthorized,
             "Only initiator can refund"
         );
-        assert!(
+        ensure!(
             !matches!(swap.status, SwapStatus::Completed | SwapStatus::Refunded),
+            InvalidState,
             "Cannot refund completed or already refunded swap"
         );
-        assert!(
+        ensure!(
             env::block_timestamp() >= swap.time_lock,
+            TooEarly,
             "Time lock has not expired yet"
         );
         
@@ -269,10 +332,14 @@ impl SwapContract {
         self.swaps.insert(&swap_id, &swap);
         
         env::log_str(&format!("Swap refunded: {}", swap_id));
+        emit_event("refunded", json!({
+            "swap_id": swap_id,
+            "initiator": swap.initiator,
+            "amount": swap.amount,
+        }));
         
-        let amount_yocto: u128 = swap.amount.parse().expect("Invalid amount");
-        let refund_amount = NearToken::from_yoctonear(amount_yocto);
-        Promise::new(initiator).transfer(refund_amount)
+        let amount_yocto: u128 = swap.amount.0;
+        self.settle(initiator, amount_yocto)
     }
 
     pub fn get_swap(&self, swap_id: String) -> Option<AtomicSwap> {
@@ -283,45 +350,129 @@ impl SwapContract {
         self.oracle_verifications.get(&swap_id)
     }
     
-    pub fn get_swaps_by_initiator(&self, account_id: AccountId) -> Vec<AtomicSwap> {
-        self.swaps_by_initiator
-            .get(&account_id)
-            .unwrap_or_default()
-            .iter()
-            .filter_map(|swap_id| self.swaps.get(swap_id))
-            .collect()
+    pub fn get_oracle_verifications(&self, from_index: Option<u64>, limit: Option<u64>) -> Paged<PoseidonVerification> {
+        let verifications = self.oracle_verifications.values_as_vector();
+        Paged::from_range(verifications.len(), from_index, limit, |index| verifications.get(index))
+    }
+    
+    pub fn get_swaps(&self, from_index: Option<u64>, limit: Option<u64>) -> Paged<AtomicSwap> {
+        let swaps = self.swaps.values_as_vector();
+        Paged::from_range(swaps.len(), from_index, limit, |index| swaps.get(index))
+    }
+    
+    pub fn get_swaps_by_initiator(
+        &self,
+        account_id: AccountId,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Paged<AtomicSwap> {
+        let swap_ids = self.swaps_by_initiator.get(&account_id).unwrap_or_default();
+        Paged::from_ids(&swap_ids, from_index, limit, |swap_id| self.swaps.get(swap_id))
     }
     
-    pub fn get_swaps_by_participant(&self, account_id: AccountId) -> Vec<AtomicSwap> {
-        self.swaps_by_participant
-            .get(&account_id)
-            .unwrap_or_default()
-            .iter()
-            .filter_map(|swap_id| self.swaps.get(swap_id))
-            .collect()
+    pub fn get_swaps_by_participant(
+        &self,
+        account_id: AccountId,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Paged<AtomicSwap> {
+        let swap_ids = self.swaps_by_participant.get(&account_id).unwrap_or_default();
+        Paged::from_ids(&swap_ids, from_index, limit, |swap_id| self.swaps.get(swap_id))
     }
 
     pub fn set_fee_percentage(&mut self, fee_percentage: u16) {
-        assert_eq!(env::predecessor_account_id(), self.owner, "Only owner");
-        assert!(fee_percentage <= 1000, "Fee cannot exceed 10%");
+        self.access.assert_owner_or_role(&self.owner, Role::FeeManager);
+        ensure!(fee_percentage <= 1000, LimitExceeded, "Fee cannot exceed 10%");
         self.fee_percentage = fee_percentage;
     }
 
     pub fn set_fee_recipient(&mut self, fee_recipient: AccountId) {
-        assert_eq!(env::predecessor_account_id(), self.owner, "Only owner");
+        assert_owner(&self.owner);
         self.fee_recipient = fee_recipient;
     }
 
+    // Pay swap fees into the fee treasury contract; None falls back to the fee recipient
+    pub fn set_fee_treasury(&mut self, fee_treasury: Option<AccountId>) {
+        assert_owner(&self.owner);
+        self.fee_treasury = fee_treasury;
+    }
+
+    pub fn get_fee_treasury(&self) -> Option<AccountId> {
+        self.fee_treasury.clone()
+    }
+
+    // Route `insurance_fee_bps` of every swap fee to the insurance pool contract
+    pub fn set_insurance_pool(&mut self, insurance_pool: Option<AccountId>, insurance_fee_bps: u16) {
+        assert_owner(&self.owner);
+        ensure!(insurance_fee_bps <= MAX_INSURANCE_FEE_BPS, LimitExceeded, "Insurance share cannot exceed 50%");
+        self.insurance_pool = insurance_pool;
+        self.insurance_fee_bps = insurance_fee_bps;
+    }
+
+    pub fn set_escrow_contract(&mut self, escrow_contract: Option<AccountId>) {
+        assert_owner(&self.owner);
+        self.escrow_contract = escrow_contract;
+    }
+
     pub fn set_oracle_account(&mut self, oracle_account: AccountId) {
-        assert_eq!(env::predecessor_account_id(), self.owner, "Only owner");
+        assert_owner(&self.owner);
         self.oracle_account = oracle_account;
     }
 
-    fn hash_secret(&self, secret: &str) -> String {
-        let hash = env::sha256(secret.as_bytes());
-        hex::encode(hash)
+    // Bounds (in seconds) on the time lock an initiator may choose
+    pub fn set_time_lock_bounds(&mut self, min_time_lock: u64, max_time_lock: u64) {
+        self.access.assert_owner_or_role(&self.owner, Role::Operator);
+        ensure!(
+            min_time_lock > 0 && min_time_lock <= max_time_lock,
+            InvalidArgument,
+            "Time lock bounds must satisfy 0 < min <= max"
+        );
+        self.min_time_lock = min_time_lock;
+        self.max_time_lock = max_time_lock;
     }
-    
+
+    pub fn get_time_lock_bounds(&self) -> (u64, u64) {
+        (self.min_time_lock, self.max_time_lock)
+    }
+
+    pub fn get_owner(&self) -> AccountId {
+        self.owner.clone()
+    }
+
+    // Incident response: halts new swaps; locking, completion and refunds keep working
+    pub fn pause(&mut self) {
+        let caller = self.pause_guard.pause(&self.owner);
+        env::log_str(&format!("Contract paused by: {}", caller));
+        emit_event("paused", json!({ "account_id": caller }));
+    }
+
+    // Guardians can only pause; resuming is an owner decision
+    pub fn unpause(&mut self) {
+        self.pause_guard.unpause(&self.owner);
+        env::log_str("Contract unpaused");
+        emit_event("unpaused", json!({ "account_id": self.owner }));
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.pause_guard.paused
+    }
+
+    pub fn get_pause_status(&self) -> PauseStatus {
+        self.pause_guard.status()
+    }
+
+    pub fn add_guardian(&mut self, account_id: AccountId) {
+        self.pause_guard.add_guardian(&self.owner, account_id);
+    }
+
+    pub fn remove_guardian(&mut self, account_id: AccountId) {
+        self.pause_guard.remove_guardian(&self.owner, &account_id);
+    }
+
+    pub fn get_guardians(&self) -> Vec<AccountId> {
+        self.pause_guard.guardians.clone()
+    }
+
     fn add_swap_to_initiator(&mut self, initiator: &AccountId, swap_id: &str) {
         let mut swaps = self.swaps_by_initiator.get(initiator).unwrap_or_default();
         swaps.push(swap_id.to_string());
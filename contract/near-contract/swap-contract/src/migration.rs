@@ -0,0 +1,50 @@
+use crate::*;
+use ciphra_types::{migrate_state, state_read_appending, stored_state_version, write_state_version, VersionedState};
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, PartialEq, Debug, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub enum StateVersion {
+    V1, // Layout at the introduction of versioning
+    V2, // Appended `access`
+    V3, // Appended `settlements`
+    V4, // Appended `rate_limiter`
+}
+
+// Add a new variant and a `migrate_from` arm with every layout change
+impl VersionedState for SwapContract {
+    type Version = StateVersion;
+
+    const CURRENT_VERSION: StateVersion = StateVersion::V4;
+    const UNVERSIONED: StateVersion = StateVersion::V1;
+
+    fn migrate_from(version: &StateVersion) -> Self {
+        match version {
+            StateVersion::V1 => state_read_appending(&(
+                AccessControl::default(),
+                Self::new_settlement_ledger(),
+                Self::new_rate_limiter(),
+            )),
+            StateVersion::V2 => state_read_appending(&(Self::new_settlement_ledger(), Self::new_rate_limiter())),
+            StateVersion::V3 => state_read_appending(&Self::new_rate_limiter()),
+            StateVersion::V4 => fail(ErrorCode::Internal, "Already at the current version"),
+        }
+    }
+}
+
+#[near_bindgen]
+impl SwapContract {
+    // Called with the upgrade deploy; rewrites state from whichever version is stored
+    #[private]
+    #[init(ignore_state)]
+    pub fn migrate() -> Self {
+        migrate_state()
+    }
+
+    pub fn get_state_version(&self) -> StateVersion {
+        stored_state_version::<Self>()
+    }
+
+    pub(crate) fn write_state_version() {
+        write_state_version::<Self>();
+    }
+}
@@ -0,0 +1,105 @@
+use crate::*;
+use ciphra_types::DEFAULT_RATE_LIMIT;
+
+#[near_bindgen]
+impl SwapContract {
+    // None turns limiting off, e.g. for a relayer that initiates on behalf of many users
+    pub fn set_rate_limit(&mut self, config: Option<RateLimitConfig>) {
+        self.access.assert_owner_or_role(&self.owner, Role::Operator);
+        self.rate_limiter.set_config(config);
+        
+        env::log_str(&format!("Rate limit updated: {:?}", config));
+        emit_event("rate_limit_updated", json!({ "config": config }));
+    }
+
+    // For contracts such as the orchestrator or the p2p contract that act for many users and
+    // limit them themselves
+    pub fn set_rate_limit_exemption(&mut self, account_id: AccountId, exempt: bool) {
+        self.access.assert_owner_or_role(&self.owner, Role::Operator);
+        self.rate_limiter.set_exempt(account_id.clone(), exempt);
+        
+        env::log_str(&format!("Rate limit exemption: {} | Exempt: {}", account_id, exempt));
+        emit_event("rate_limit_exemption_updated", json!({ "account_id": account_id, "exempt": exempt }));
+    }
+
+    pub fn get_rate_limit_exemptions(&self) -> Vec<AccountId> {
+        self.rate_limiter.exempt.clone()
+    }
+
+    pub fn get_rate_limit(&self) -> Option<RateLimitConfig> {
+        self.rate_limiter.config
+    }
+
+    // Swaps the account can still initiate in the current window; None when limiting is off or the account is exempt
+    pub fn get_rate_limit_remaining(&self, account_id: AccountId) -> Option<u16> {
+        self.rate_limiter.remaining(&account_id)
+    }
+
+    pub(crate) fn new_rate_limiter() -> RateLimiter {
+        RateLimiter::new(StorageKey::RateLimits, Some(DEFAULT_RATE_LIMIT))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use ciphra_testing as host;
+
+    const P2P: &str = "p2p.near";
+
+    // One swap per hour, with the p2p contract's storage prepaid by its owner
+    fn limited_contract() -> SwapContract {
+        let mut contract = contract();
+        contract.set_rate_limit(Some(RateLimitConfig { max_actions: 1, window_seconds: 3_600 }));
+        host::set_deposit(host::NEAR);
+        contract.storage_deposit(Some(account(P2P)), None);
+        host::set_deposit(0);
+        contract
+    }
+
+    fn initiate_as_p2p(contract: &mut SwapContract, swap_id: &str) -> AtomicSwap {
+        host::set_caller(P2P);
+        host::set_deposit(host::NEAR);
+        contract.initiate_swap(
+            swap_id.to_string(),
+            account(PARTICIPANT),
+            hex::encode(env::sha256(SECRET.as_bytes())),
+            HashAlgorithm::SHA256,
+            3600,
+            "near".to_string(),
+            PARTICIPANT.to_string(),
+            None,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_exempt_contract_initiates_past_the_limit() {
+        let mut contract = limited_contract();
+        contract.set_rate_limit_exemption(account(P2P), true);
+        
+        for swap_id in ["note-swap-1", "note-swap-2", "note-swap-3"] {
+            initiate_as_p2p(&mut contract, swap_id);
+        }
+        
+        let storage = contract.storage_balance_of(account(P2P)).unwrap();
+        assert_eq!(storage.total.0 - storage.available.0, 3 * contract.storage_balance_bounds().min.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Rate limit exceeded")]
+    fn test_limit_applies_without_exemption() {
+        let mut contract = limited_contract();
+        initiate_as_p2p(&mut contract, "note-swap-1");
+        initiate_as_p2p(&mut contract, "note-swap-2");
+    }
+
+    #[test]
+    #[should_panic(expected = "Insufficient storage deposit")]
+    fn test_exempt_contract_still_pays_storage() {
+        let mut contract = contract();
+        contract.set_rate_limit_exemption(account(P2P), true);
+        initiate_as_p2p(&mut contract, "note-swap-1");
+    }
+}
@@ -0,0 +1,178 @@
+use crate::*;
+
+const GAS_FOR_SETTLEMENT_CALLBACK: Gas = Gas::from_tgas(10);
+const GAS_FOR_FEE_DEPOSIT: Gas = Gas::from_tgas(10);
+
+#[near_bindgen]
+impl SwapContract {
+    // Rolls a failed payout or refund back into a balance the receiver claims with `claim_settlement`
+    #[private]
+    pub fn on_settlement(&mut self, settlement_id: u64) -> bool {
+        let Some(settlement) = self.settlements.resolve(settlement_id, is_promise_success()) else {
+            return true;
+        };
+        self.settlements.credit(&settlement.receiver, settlement.amount.0);
+        
+        env::log_str(&format!(
+            "Settlement failed: {} | Claimable by: {} | Amount: {}",
+            settlement_id, settlement.receiver, settlement.amount.0
+        ));
+        emit_event("settlement_failed", json!({
+            "settlement_id": settlement_id,
+            "receiver": settlement.receiver,
+            "amount": settlement.amount,
+        }));
+        
+        false
+    }
+
+    // A fee contract that rejects its deposit can't claim it back, so the amount becomes
+    // claimable by the fee recipient instead
+    #[private]
+    pub fn on_fee_deposit_settled(&mut self, settlement_id: u64) -> bool {
+        let Some(settlement) = self.settlements.resolve(settlement_id, is_promise_success()) else {
+            return true;
+        };
+        let fee_recipient = self.fee_recipient.clone();
+        self.settlements.credit(&fee_recipient, settlement.amount.0);
+        
+        env::log_str(&format!(
+            "Fee deposit failed: {} | Fee contract: {} | Claimable by: {} | Amount: {}",
+            settlement_id, settlement.receiver, fee_recipient, settlement.amount.0
+        ));
+        emit_event("fee_deposit_failed", json!({
+            "settlement_id": settlement_id,
+            "fee_contract": settlement.receiver,
+            "claimable_by": fee_recipient,
+            "amount": settlement.amount,
+        }));
+        
+        false
+    }
+
+    // Retries a failed settlement; a failure again leaves it claimable
+    pub fn claim_settlement(&mut self) -> Promise {
+        let account_id = env::predecessor_account_id();
+        let amount_yocto = self.settlements.claim(&account_id);
+        
+        env::log_str(&format!("Settlement claimed: {} | Amount: {}", account_id, amount_yocto));
+        
+        self.settle(account_id, amount_yocto)
+    }
+
+    pub fn get_claimable_settlement(&self, account_id: AccountId) -> U128 {
+        U128(self.settlements.claimable_of(&account_id))
+    }
+
+    pub(crate) fn new_settlement_ledger() -> SettlementLedger {
+        SettlementLedger::new(StorageKey::PendingSettlements, StorageKey::ClaimableSettlements)
+    }
+
+    // Sends NEAR through the settlement ledger so a failed transfer stays claimable
+    pub(crate) fn settle(&mut self, receiver: AccountId, amount_yocto: u128) -> Promise {
+        self.settlements.send(receiver, amount_yocto, |settlement_id| {
+            Self::ext(env::current_account_id())
+                .with_static_gas(GAS_FOR_SETTLEMENT_CALLBACK)
+                .on_settlement(settlement_id)
+        })
+    }
+
+    // Pays a fee share into the insurance pool or fee treasury through `deposit_fees`
+    pub(crate) fn settle_fee_deposit(&mut self, fee_contract: AccountId, amount_yocto: u128) -> Promise {
+        let deposit = ext_fee_contract::ext(fee_contract.clone())
+            .with_attached_deposit(NearToken::from_yoctonear(amount_yocto))
+            .with_static_gas(GAS_FOR_FEE_DEPOSIT)
+            .deposit_fees();
+        self.settlements.send_call(fee_contract, amount_yocto, deposit, |settlement_id| {
+            Self::ext(env::current_account_id())
+                .with_static_gas(GAS_FOR_SETTLEMENT_CALLBACK)
+                .on_fee_deposit_settled(settlement_id)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use ciphra_testing as host;
+
+    const TREASURY: &str = "treasury.near";
+    const POOL: &str = "insurance.near";
+
+    fn pending_receivers(contract: &SwapContract) -> Vec<(String, u128)> {
+        (0..contract.settlements.next_settlement_id)
+            .filter_map(|id| contract.settlements.pending.get(&id))
+            .map(|settlement| (settlement.receiver.to_string(), settlement.amount.0))
+            .collect()
+    }
+
+    #[test]
+    fn test_fee_and_payout_go_through_ledger() {
+        let mut contract = contract();
+        initiate_swap(&mut contract, "s1", 1000 * host::NEAR);
+        complete_swap(&mut contract, "s1");
+        
+        // 0.3% fee to the fee recipient, the rest to the participant
+        assert_eq!(
+            pending_receivers(&contract),
+            vec![(OWNER.to_string(), 3 * host::NEAR), (PARTICIPANT.to_string(), 997 * host::NEAR)]
+        );
+    }
+
+    #[test]
+    fn test_fee_contracts_are_paid_through_ledger() {
+        let mut contract = contract();
+        contract.set_fee_treasury(Some(account(TREASURY)));
+        contract.set_insurance_pool(Some(account(POOL)), 5000);
+        initiate_swap(&mut contract, "s1", 1000 * host::NEAR);
+        complete_swap(&mut contract, "s1");
+        
+        assert_eq!(
+            pending_receivers(&contract),
+            vec![
+                (POOL.to_string(), 1_500 * host::NEAR / 1000),
+                (TREASURY.to_string(), 1_500 * host::NEAR / 1000),
+                (PARTICIPANT.to_string(), 997 * host::NEAR),
+            ]
+        );
+        let calls = host::function_calls();
+        assert!(calls.contains(&(POOL.to_string(), "deposit_fees".to_string())));
+        assert!(calls.contains(&(TREASURY.to_string(), "deposit_fees".to_string())));
+        assert!(calls.contains(&(host::CONTRACT.to_string(), "on_fee_deposit_settled".to_string())));
+    }
+
+    #[test]
+    fn test_rejected_fee_deposit_is_claimable_by_fee_recipient() {
+        let mut contract = contract();
+        contract.set_fee_treasury(Some(account(TREASURY)));
+        initiate_swap(&mut contract, "s1", 1000 * host::NEAR);
+        complete_swap(&mut contract, "s1");
+        
+        host::set_self_caller();
+        host::set_promise_results(vec![None]);
+        assert!(!contract.on_fee_deposit_settled(0));
+        
+        assert_eq!(contract.get_claimable_settlement(account(OWNER)), U128(3 * host::NEAR));
+        assert_eq!(contract.get_claimable_settlement(account(TREASURY)), U128(0));
+        assert_eq!(contract.settlements.total_pending, 997 * host::NEAR);
+    }
+
+    #[test]
+    fn test_failed_payout_is_claimable() {
+        let mut contract = contract();
+        initiate_swap(&mut contract, "s1", 1000 * host::NEAR);
+        complete_swap(&mut contract, "s1");
+        
+        host::set_self_caller();
+        host::set_promise_results(vec![None]);
+        assert!(!contract.on_settlement(1));
+        host::set_promise_results(vec![]);
+        assert_eq!(contract.get_claimable_settlement(account(PARTICIPANT)), U128(997 * host::NEAR));
+        
+        host::clear_receipts();
+        host::set_caller(PARTICIPANT);
+        let _ = contract.claim_settlement();
+        assert_eq!(host::transfers(), vec![(PARTICIPANT.to_string(), 997 * host::NEAR)]);
+    }
+}
@@ -0,0 +1,101 @@
+use crate::*;
+use near_sdk::json_types::U128;
+
+// Storage locked per swap record - covers the record, its index entries and any oracle verification
+const STORAGE_DEPOSIT_PER_RECORD: u128 = 10_000_000_000_000_000_000_000; // 0.01 NEAR
+
+// Settled swaps stay readable this long past their time lock so the counterparty can pick up
+// the revealed secret before the record is removed
+const SWAP_RETENTION_PERIOD: u64 = 7 * 24 * 60 * 60 * 1_000_000_000; // 7 days
+
+// Upper bound on swaps removed in one call
+const MAX_CLEANUP_BATCH: usize = 50;
+
+#[near_bindgen]
+impl SwapContract {
+    // NEP-145 - prepay storage for the swap records an account initiates
+    #[payable]
+    pub fn storage_deposit(
+        &mut self,
+        account_id: Option<AccountId>,
+        registration_only: Option<bool>,
+    ) -> StorageBalance {
+        let account_id = account_id.unwrap_or_else(env::predecessor_account_id);
+        self.storage_accounts.deposit(&account_id, registration_only.unwrap_or(false), STORAGE_DEPOSIT_PER_RECORD).0
+    }
+
+    // NEP-145 - withdraw storage balance that isn't locked by existing swaps
+    #[payable]
+    pub fn storage_withdraw(&mut self, amount: Option<U128>) -> StorageBalance {
+        self.storage_accounts.withdraw(amount).0
+    }
+
+    // NEP-145 - close an account with no swaps holding storage and refund its balance
+    #[payable]
+    pub fn storage_unregister(&mut self, force: Option<bool>) -> bool {
+        self.storage_accounts.unregister(force.unwrap_or(false)).is_some()
+    }
+
+    pub fn storage_balance_of(&self, account_id: AccountId) -> Option<StorageBalance> {
+        self.storage_accounts.balance_of(&account_id)
+    }
+
+    pub fn storage_balance_bounds(&self) -> StorageBalanceBounds {
+        StorageAccounts::bounds(STORAGE_DEPOSIT_PER_RECORD)
+    }
+
+    // Remove settled swaps past the retention window - callable by anyone. Each removed
+    // record unlocks its initiator's storage deposit; ids that don't qualify are skipped.
+    pub fn cleanup_swaps(&mut self, swap_ids: Vec<String>) -> u32 {
+        ensure!(swap_ids.len() <= MAX_CLEANUP_BATCH, LimitExceeded, "Cannot clean up more than 50 swaps");
+        
+        let now = env::block_timestamp();
+        let mut removed = 0;
+        for swap_id in swap_ids {
+            let Some(swap) = self.swaps.get(&swap_id) else {
+                continue;
+            };
+            let settled = matches!(swap.status, SwapStatus::Completed | SwapStatus::Refunded);
+            if !settled || now < swap.time_lock + SWAP_RETENTION_PERIOD {
+                continue;
+            }
+            
+            self.swaps.remove(&swap_id);
+            self.oracle_verifications.remove(&swap_id);
+            self.remove_swap_from_index(&swap.initiator, &swap_id, true);
+            self.remove_swap_from_index(&swap.participant, &swap_id, false);
+            self.release_swap_storage(&swap_id);
+            removed += 1;
+            
+            env::log_str(&format!("Swap cleaned up: {}", swap_id));
+        }
+        
+        removed
+    }
+
+    pub(crate) fn lock_swap_storage(&mut self, initiator: &AccountId, swap_id: &str) {
+        self.storage_accounts.lock(initiator, STORAGE_DEPOSIT_PER_RECORD);
+        self.record_storage_payers.insert(&swap_id.to_string(), initiator);
+    }
+
+    // Unlock the deposit of a removed swap so its initiator can withdraw it
+    fn release_swap_storage(&mut self, swap_id: &str) {
+        if let Some(payer) = self.record_storage_payers.remove(&swap_id.to_string()) {
+            self.storage_accounts.unlock(&payer, STORAGE_DEPOSIT_PER_RECORD);
+        }
+    }
+
+    fn remove_swap_from_index(&mut self, account: &str, swap_id: &str, initiator: bool) {
+        let Ok(account) = account.parse::<AccountId>() else {
+            return;
+        };
+        let index = if initiator { &mut self.swaps_by_initiator } else { &mut self.swaps_by_participant };
+        let mut swaps = index.get(&account).unwrap_or_default();
+        swaps.retain(|id| id != swap_id);
+        if swaps.is_empty() {
+            index.remove(&account);
+        } else {
+            index.insert(&account, &swaps);
+        }
+    }
+}
@@ -0,0 +1,47 @@
+// Fixtures shared by the unit tests; contract calls run against the ciphra-testing host
+use crate::*;
+use ciphra_testing as host;
+
+pub(crate) const OWNER: &str = "owner.near";
+pub(crate) const ORACLE: &str = "oracle.near";
+pub(crate) const INITIATOR: &str = "alice.near";
+pub(crate) const PARTICIPANT: &str = "bob.near";
+pub(crate) const SECRET: &str = "swap secret";
+
+pub(crate) fn account(name: &str) -> AccountId {
+    name.parse().unwrap()
+}
+
+pub(crate) fn contract() -> SwapContract {
+    host::set_caller(OWNER);
+    SwapContract::new(account(OWNER), account(ORACLE))
+}
+
+// SHA-256 swap from the initiator to the participant locked for an hour, with storage prepaid
+pub(crate) fn initiate_swap(contract: &mut SwapContract, swap_id: &str, amount: u128) -> AtomicSwap {
+    host::set_caller(INITIATOR);
+    host::set_deposit(host::NEAR);
+    contract.storage_deposit(None, None);
+    host::set_deposit(amount);
+    let swap = contract.initiate_swap(
+        swap_id.to_string(),
+        account(PARTICIPANT),
+        hex::encode(env::sha256(SECRET.as_bytes())),
+        HashAlgorithm::SHA256,
+        3600,
+        "near".to_string(),
+        PARTICIPANT.to_string(),
+        None,
+        None,
+    );
+    host::set_deposit(0);
+    host::clear_receipts();
+    swap
+}
+
+// Locks and completes a swap with the secret as the participant
+pub(crate) fn complete_swap(contract: &mut SwapContract, swap_id: &str) {
+    host::set_caller(PARTICIPANT);
+    contract.lock_swap(swap_id.to_string());
+    let _ = contract.complete_swap_with_oracle_verification(swap_id.to_string(), SECRET.to_string());
+}
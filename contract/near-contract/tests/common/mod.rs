@@ -1,2 +1,196 @@
-// Common test utilities
-// Placeholder - utilities will be added later
\ No newline at end of file
+// Common test utilities - sandbox setup shared by the simulation tests
+use ciphra_poseidon::{commitment, hash1, Fr};
+use near_workspaces::network::Sandbox;
+use near_workspaces::types::NearToken;
+use near_workspaces::{Account, Contract, Worker};
+use serde_json::json;
+
+mod spend_fixture;
+pub use spend_fixture::{spend_proof, spend_verifying_key};
+
+// Balance handed to every test user
+pub const USER_BALANCE: NearToken = NearToken::from_near(50);
+
+// NEP-145 storage each user prepays on every contract before creating records
+pub const STORAGE_PREPAID: NearToken = NearToken::from_near(1);
+
+// Chargeback settings of the p2p contract, in nanoseconds
+pub const CHARGEBACK_DISPUTE_WINDOW: u64 = 3_600 * 1_000_000_000;
+pub const CHARGEBACK_ARBITRATION_PERIOD: u64 = 86_400 * 1_000_000_000;
+
+// Gas covering a call plus the promises it schedules
+pub const MAX_GAS: near_workspaces::types::Gas = near_workspaces::types::Gas::from_tgas(300);
+
+pub struct Env {
+    pub worker: Worker<Sandbox>,
+    pub owner: Account,
+    pub alice: Account,
+    pub bob: Account,
+    pub swap: Contract,
+    pub escrow: Contract,
+    pub p2p: Contract,
+    pub orchestrator: Contract,
+    pub oracle: Contract,
+}
+
+// Builds a contract crate from the workspace and deploys it to a fresh dev account
+async fn deploy(worker: &Worker<Sandbox>, crate_dir: &str) -> anyhow::Result<Contract> {
+    let wasm = near_workspaces::compile_project(&format!("../{}", crate_dir)).await?;
+    Ok(worker.dev_deploy(&wasm).await?)
+}
+
+async fn create_user(owner: &Account, name: &str) -> anyhow::Result<Account> {
+    let account = owner
+        .create_subaccount(name)
+        .initial_balance(USER_BALANCE)
+        .transact()
+        .await?
+        .into_result()?;
+    Ok(account)
+}
+
+// Deploys the payment contracts, the orchestrator and the oracle into one sandbox, initialises
+// them with the owner as oracle operator, wires swap <-> escrow and the p2p contract to both, and
+// prepays storage for alice and bob
+pub async fn setup() -> anyhow::Result<Env> {
+    let worker = near_workspaces::sandbox().await?;
+    let root = worker.root_account()?;
+
+    let owner = root
+        .create_subaccount("owner")
+        .initial_balance(NearToken::from_near(200))
+        .transact()
+        .await?
+        .into_result()?;
+    let alice = create_user(&owner, "alice").await?;
+    let bob = create_user(&owner, "bob").await?;
+
+    let swap = deploy(&worker, "swap-contract").await?;
+    let escrow = deploy(&worker, "escrow-contract").await?;
+    let p2p = deploy(&worker, "p2p-transfer").await?;
+    let orchestrator = deploy(&worker, "orchestrator").await?;
+    let oracle = deploy(&worker, "oracle").await?;
+
+    swap.call("new")
+        .args_json(json!({ "owner": owner.id(), "oracle_account": oracle.id() }))
+        .transact()
+        .await?
+        .into_result()?;
+    escrow.call("new")
+        .args_json(json!({ "owner": owner.id() }))
+        .transact()
+        .await?
+        .into_result()?;
+    p2p.call("new")
+        .args_json(json!({ "owner": owner.id() }))
+        .transact()
+        .await?
+        .into_result()?;
+    orchestrator.call("new")
+        .args_json(json!({ "owner": owner.id(), "swap_contract": swap.id(), "escrow_contract": escrow.id() }))
+        .transact()
+        .await?
+        .into_result()?;
+    oracle.call("new")
+        .args_json(json!({ "owner": owner.id(), "swap_contract": swap.id(), "operator": owner.id() }))
+        .transact()
+        .await?
+        .into_result()?;
+
+    owner.call(swap.id(), "set_escrow_contract")
+        .args_json(json!({ "escrow_contract": escrow.id() }))
+        .transact()
+        .await?
+        .into_result()?;
+    owner.call(escrow.id(), "set_swap_contract")
+        .args_json(json!({ "swap_contract": swap.id() }))
+        .transact()
+        .await?
+        .into_result()?;
+
+    // The orchestrator is depositor of every order's escrow and limits buyers itself
+    owner.call(escrow.id(), "set_rate_limit_exemption")
+        .args_json(json!({ "account_id": orchestrator.id(), "exempt": true }))
+        .transact()
+        .await?
+        .into_result()?;
+    owner.call(orchestrator.id(), "fund_escrow_storage")
+        .deposit(STORAGE_PREPAID)
+        .max_gas()
+        .transact()
+        .await?
+        .into_result()?;
+
+    // The p2p contract is depositor of every chargeback escrow and initiator of every
+    // note-funded swap; it opens them for many senders, so it's exempt and prepays storage
+    owner.call(p2p.id(), "set_chargeback_config")
+        .args_json(json!({
+            "escrow_contract": escrow.id(),
+            "arbiter": owner.id(),
+            "dispute_window": CHARGEBACK_DISPUTE_WINDOW,
+            "arbitration_period": CHARGEBACK_ARBITRATION_PERIOD,
+        }))
+        .transact()
+        .await?
+        .into_result()?;
+    owner.call(p2p.id(), "set_swap_contract")
+        .args_json(json!({ "swap_contract": swap.id() }))
+        .transact()
+        .await?
+        .into_result()?;
+    for (contract, fund_method) in [(&escrow, "fund_escrow_storage"), (&swap, "fund_swap_storage")] {
+        owner.call(contract.id(), "set_rate_limit_exemption")
+            .args_json(json!({ "account_id": p2p.id(), "exempt": true }))
+            .transact()
+            .await?
+            .into_result()?;
+        owner.call(p2p.id(), fund_method)
+            .deposit(STORAGE_PREPAID)
+            .max_gas()
+            .transact()
+            .await?
+            .into_result()?;
+    }
+
+    for user in [&alice, &bob] {
+        for contract in [&swap, &escrow, &p2p] {
+            user.call(contract.id(), "storage_deposit")
+                .args_json(json!({ "account_id": null, "registration_only": null }))
+                .deposit(STORAGE_PREPAID)
+                .transact()
+                .await?
+                .into_result()?;
+        }
+    }
+
+    Ok(Env { worker, owner, alice, bob, swap, escrow, p2p, orchestrator, oracle })
+}
+
+pub async fn balance(account: &Account) -> anyhow::Result<u128> {
+    Ok(account.view_account().await?.balance.as_yoctonear())
+}
+
+// Balances move by the expected amount minus at most `slack` of gas spent by the account
+pub fn assert_gained(before: u128, after: u128, expected: u128, slack: u128) {
+    let gained = after as i128 - before as i128;
+    assert!(
+        gained <= expected as i128 && gained >= expected as i128 - slack as i128,
+        "expected a gain of ~{} yocto, got {}",
+        expected,
+        gained
+    );
+}
+
+pub fn bps_of(amount: u128, bps: u128) -> u128 {
+    amount * bps / 10_000
+}
+
+// Hex note commitment as a wallet builds it, a field element the pool's Poseidon tree accepts
+pub fn note_commitment(secret: u64, blinding: u64) -> String {
+    hex::encode(commitment(Fr::from_u64(secret), Fr::from_u64(blinding)).to_be_bytes())
+}
+
+// Hex nullifier for a note secret; nullifiers are public inputs of the spend circuit, so they're field elements
+pub fn note_nullifier(secret: u64) -> String {
+    hex::encode(hash1(Fr::from_u64(secret)).to_be_bytes())
+}
@@ -0,0 +1,52 @@
+// Verifying key and proof for shielded spends in the sandbox; the gas benchmarks include this
+// file too
+use base64::Engine;
+
+// Stand-in for the spend circuit's trusted setup: alpha = IC[0] = C = G1 and
+// beta = gamma = delta = B = G2, with IC[1..] the point at infinity. vk_x is then G1 whatever
+// the public inputs, and A = 3 * G1 satisfies e(-A, B) e(alpha, beta) e(vk_x, gamma) e(C, delta) = 1,
+// so the simulations go through the real pairing check without a circuit.
+const G1_GENERATOR: [&str; 2] = [
+    "0000000000000000000000000000000000000000000000000000000000000001",
+    "0000000000000000000000000000000000000000000000000000000000000002",
+];
+const G1_GENERATOR_TIMES_3: [&str; 2] = [
+    "0769bf9ac56bea3ff40232bcb1b6bd159315d84715b8e679f2d355961915abf0",
+    "2ab799bee0489429554fdb7c8d086475319e63b40b9c5b57cdf1ff3dd9fe2261",
+];
+// x.c0, x.c1, y.c0, y.c1
+const G2_GENERATOR: [&str; 4] = [
+    "1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed",
+    "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2",
+    "12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa",
+    "090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b",
+];
+const SPEND_PUBLIC_INPUTS: usize = 4;
+
+// Points as the alt_bn128 host functions take them: little-endian coordinates
+fn point(coordinates: &[&str]) -> Vec<u8> {
+    coordinates
+        .iter()
+        .flat_map(|coordinate| hex::decode(coordinate).unwrap().into_iter().rev())
+        .collect()
+}
+
+// Base64 verifying key to register for the spend circuit
+pub fn spend_verifying_key() -> String {
+    let g2 = point(&G2_GENERATOR);
+    let key = [
+        point(&G1_GENERATOR),
+        g2.clone(),
+        g2.clone(),
+        g2,
+        point(&G1_GENERATOR),
+        vec![0; 64 * SPEND_PUBLIC_INPUTS],
+    ]
+    .concat();
+    base64::engine::general_purpose::STANDARD.encode(key)
+}
+
+// Hex proof that spend_verifying_key accepts for any public inputs
+pub fn spend_proof() -> String {
+    hex::encode([point(&G1_GENERATOR_TIMES_3), point(&G2_GENERATOR), point(&G1_GENERATOR)].concat())
+}
@@ -1,2 +1,574 @@
-// Workspace-level simulation tests
-// Placeholder - tests will be added later
\ No newline at end of file
+// Workspace-level simulation tests - all contracts deployed into one sandbox.
+// Run with `cargo test` from this directory; needs cargo-near and a sandbox binary.
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+const ESCROW_SETTLEMENT_FEE_BPS: u128 = 10;
+const SWAP_FEE_BPS: u128 = 30;
+const SHIELDED_WITHDRAW_FEE_BPS: u128 = 10;
+
+// Oracle contract's default minimum verification fee
+const ORACLE_MIN_FEE: NearToken = NearToken::from_millinear(10);
+
+// Gas a user may burn across the calls in one scenario
+const GAS_SLACK: u128 = NearToken::from_millinear(100).as_yoctonear();
+
+// Alice escrows funds for Bob; Bob pays Alice through a linked swap, and the swap's
+// completion releases the escrow to Bob
+#[tokio::test]
+async fn swap_completion_settles_linked_escrow() -> anyhow::Result<()> {
+    let env = setup().await?;
+    let escrow_amount = NearToken::from_near(2).as_yoctonear();
+    let swap_amount = NearToken::from_near(3).as_yoctonear();
+    let secret = "integration-secret";
+    let hash_lock = hex::encode(Sha256::digest(secret.as_bytes()));
+
+    env.alice.call(env.escrow.id(), "create_escrow")
+        .args_json(json!({
+            "escrow_id": "escrow-1",
+            "beneficiary": env.bob.id(),
+            "release_time": 0,
+            "arbiter": null,
+            "metadata": { "title": "Swap-settled escrow", "category": "test" },
+            "arbiter_fee": null,
+            "required_proofs": null,
+        }))
+        .deposit(NearToken::from_yoctonear(escrow_amount))
+        .max_gas()
+        .transact()
+        .await?
+        .into_result()?;
+
+    env.bob.call(env.swap.id(), "initiate_swap")
+        .args_json(json!({
+            "swap_id": "swap-1",
+            "participant": env.alice.id(),
+            "hash_lock": hash_lock,
+            "hash_algorithm": "SHA256",
+            "time_lock_duration": 3600,
+            "target_chain": "near",
+            "target_address": env.alice.id(),
+            "counterparty_swap_id": null,
+            "linked_escrow": "escrow-1",
+        }))
+        .deposit(NearToken::from_yoctonear(swap_amount))
+        .max_gas()
+        .transact()
+        .await?
+        .into_result()?;
+
+    env.alice.call(env.escrow.id(), "link_swap")
+        .args_json(json!({ "escrow_id": "escrow-1", "swap_id": "swap-1" }))
+        .transact()
+        .await?
+        .into_result()?;
+    env.alice.call(env.swap.id(), "lock_swap")
+        .args_json(json!({ "swap_id": "swap-1" }))
+        .transact()
+        .await?
+        .into_result()?;
+
+    let alice_before = balance(&env.alice).await?;
+    let bob_before = balance(&env.bob).await?;
+
+    env.alice.call(env.swap.id(), "complete_swap_with_oracle_verification")
+        .args_json(json!({ "swap_id": "swap-1", "secret": secret }))
+        .gas(MAX_GAS)
+        .transact()
+        .await?
+        .into_result()?;
+
+    let escrow: Value = env.escrow.view("get_escrow")
+        .args_json(json!({ "escrow_id": "escrow-1" }))
+        .await?
+        .json()?;
+    assert_eq!(escrow["status"], "Completed");
+    assert!(escrow["swap_link"]["attested_at"].is_u64());
+
+    let swap: Value = env.swap.view("get_swap")
+        .args_json(json!({ "swap_id": "swap-1" }))
+        .await?
+        .json()?;
+    assert_eq!(swap["status"], "Completed");
+
+    assert_gained(
+        alice_before,
+        balance(&env.alice).await?,
+        swap_amount - bps_of(swap_amount, SWAP_FEE_BPS),
+        GAS_SLACK,
+    );
+    // Bob sent no transaction here, so his gain is exact
+    assert_gained(
+        bob_before,
+        balance(&env.bob).await?,
+        escrow_amount - bps_of(escrow_amount, ESCROW_SETTLEMENT_FEE_BPS),
+        0,
+    );
+
+    Ok(())
+}
+
+// Alice shields funds into the pool and later withdraws the note to Bob
+#[tokio::test]
+async fn shielded_deposit_then_withdraw_pays_recipient() -> anyhow::Result<()> {
+    let env = setup().await?;
+    let amount = NearToken::from_near(3).as_yoctonear();
+    let commitment = note_commitment(1, 1);
+    let nullifier = note_nullifier(1);
+
+    // Spends are checked against the verifying key of the note's circuit
+    env.owner.call(env.p2p.id(), "set_verifying_key")
+        .args_json(json!({ "circuit_version": 0, "vk_bytes": spend_verifying_key() }))
+        .transact()
+        .await?
+        .into_result()?;
+
+    let pool_before = env.p2p.view_account().await?.balance.as_yoctonear();
+    env.alice.call(env.p2p.id(), "shield_deposit")
+        .args_json(json!({ "note_id": "note-1", "commitment": commitment }))
+        .deposit(NearToken::from_yoctonear(amount))
+        .max_gas()
+        .transact()
+        .await?
+        .into_result()?;
+    let pool_after = env.p2p.view_account().await?.balance.as_yoctonear();
+    assert!(pool_after >= pool_before + amount - GAS_SLACK, "Deposit did not reach the pool");
+
+    let note: Value = env.p2p.view("get_shielded_note")
+        .args_json(json!({ "note_id": "note-1" }))
+        .await?
+        .json()?;
+    assert_eq!(note["amount"], amount.to_string());
+    assert_eq!(note["spent"], false);
+
+    let root: String = env.p2p.view("get_current_root").await?.json()?;
+    let bob_before = balance(&env.bob).await?;
+
+    env.alice.call(env.p2p.id(), "shield_withdraw")
+        .args_json(json!({
+            "transfer_id": "withdraw-1",
+            "note_id": "note-1",
+            "nullifier": nullifier,
+            "recipient": env.bob.id(),
+            "root": root,
+            "proof": spend_proof(),
+        }))
+        .gas(MAX_GAS)
+        .transact()
+        .await?
+        .into_result()?;
+
+    assert_gained(
+        bob_before,
+        balance(&env.bob).await?,
+        amount - bps_of(amount, SHIELDED_WITHDRAW_FEE_BPS),
+        0,
+    );
+
+    let note: Value = env.p2p.view("get_shielded_note")
+        .args_json(json!({ "note_id": "note-1" }))
+        .await?
+        .json()?;
+    assert_eq!(note["spent"], true);
+
+    // The nullifier cannot be replayed
+    let replay = env.alice.call(env.p2p.id(), "shield_withdraw")
+        .args_json(json!({
+            "transfer_id": "withdraw-2",
+            "note_id": "note-1",
+            "nullifier": nullifier,
+            "recipient": env.alice.id(),
+            "root": root,
+            "proof": spend_proof(),
+        }))
+        .gas(MAX_GAS)
+        .transact()
+        .await?;
+    assert!(replay.is_failure());
+
+    Ok(())
+}
+
+// Alice orders from Bob through the orchestrator; Bob delivers through a swap to Alice, and
+// settling the order after the swap completes releases Alice's escrowed payment to Bob
+#[tokio::test]
+async fn orchestrated_order_settles_on_swap_completion() -> anyhow::Result<()> {
+    let env = setup().await?;
+    let order_amount = NearToken::from_near(5).as_yoctonear();
+    let swap_amount = NearToken::from_near(2).as_yoctonear();
+    let secret = "delivery-secret";
+    let hash_lock = hex::encode(Sha256::digest(secret.as_bytes()));
+
+    env.alice.call(env.orchestrator.id(), "open_order")
+        .args_json(json!({
+            "order_id": "order-1",
+            "seller": env.bob.id(),
+            "swap_id": "delivery-1",
+            "min_swap_amount": swap_amount.to_string(),
+            "duration_seconds": 3600,
+            "metadata": { "title": "Pay on delivery", "category": "test" },
+        }))
+        .deposit(NearToken::from_yoctonear(order_amount))
+        .gas(MAX_GAS)
+        .transact()
+        .await?
+        .into_result()?;
+
+    let order: Value = env.orchestrator.view("get_order")
+        .args_json(json!({ "order_id": "order-1" }))
+        .await?
+        .json()?;
+    assert_eq!(order["status"], "Open");
+
+    // Settling before delivery is refused
+    let early = env.owner.call(env.orchestrator.id(), "settle_order")
+        .args_json(json!({ "order_id": "order-1" }))
+        .gas(MAX_GAS)
+        .transact()
+        .await?;
+    assert!(early.is_failure());
+
+    env.bob.call(env.swap.id(), "initiate_swap")
+        .args_json(json!({
+            "swap_id": "delivery-1",
+            "participant": env.alice.id(),
+            "hash_lock": hash_lock,
+            "hash_algorithm": "SHA256",
+            "time_lock_duration": 3600,
+            "target_chain": "near",
+            "target_address": env.alice.id(),
+            "counterparty_swap_id": null,
+            "linked_escrow": null,
+        }))
+        .deposit(NearToken::from_yoctonear(swap_amount))
+        .max_gas()
+        .transact()
+        .await?
+        .into_result()?;
+    env.alice.call(env.swap.id(), "lock_swap")
+        .args_json(json!({ "swap_id": "delivery-1" }))
+        .transact()
+        .await?
+        .into_result()?;
+    env.alice.call(env.swap.id(), "complete_swap_with_oracle_verification")
+        .args_json(json!({ "swap_id": "delivery-1", "secret": secret }))
+        .gas(MAX_GAS)
+        .transact()
+        .await?
+        .into_result()?;
+
+    let bob_before = balance(&env.bob).await?;
+
+    env.owner.call(env.orchestrator.id(), "settle_order")
+        .args_json(json!({ "order_id": "order-1" }))
+        .gas(MAX_GAS)
+        .transact()
+        .await?
+        .into_result()?;
+
+    let order: Value = env.orchestrator.view("get_order")
+        .args_json(json!({ "order_id": "order-1" }))
+        .await?
+        .json()?;
+    assert_eq!(order["status"], "Settled");
+
+    // Bob sent no transaction here, so his gain is exact
+    assert_gained(
+        bob_before,
+        balance(&env.bob).await?,
+        order_amount - bps_of(order_amount, ESCROW_SETTLEMENT_FEE_BPS),
+        0,
+    );
+
+    Ok(())
+}
+
+// Alice pays the oracle to verify Bob's Poseidon secret; the operator's fulfilment is recorded
+// on the swap contract, the operator earns the fee and Alice can then complete the swap
+#[tokio::test]
+async fn oracle_fulfilment_unlocks_poseidon_swap() -> anyhow::Result<()> {
+    let env = setup().await?;
+    let swap_amount = NearToken::from_near(2).as_yoctonear();
+    let poseidon_hash = "0b".repeat(32);
+
+    env.bob.call(env.swap.id(), "initiate_swap")
+        .args_json(json!({
+            "swap_id": "poseidon-1",
+            "participant": env.alice.id(),
+            "hash_lock": poseidon_hash,
+            "hash_algorithm": "Poseidon",
+            "time_lock_duration": 3600,
+            "target_chain": "near",
+            "target_address": env.alice.id(),
+            "counterparty_swap_id": null,
+            "linked_escrow": null,
+        }))
+        .deposit(NearToken::from_yoctonear(swap_amount))
+        .max_gas()
+        .transact()
+        .await?
+        .into_result()?;
+
+    let request: Value = env.alice.call(env.oracle.id(), "request_verification")
+        .args_json(json!({ "swap_id": "poseidon-1", "fee": ORACLE_MIN_FEE.as_yoctonear().to_string() }))
+        .deposit(ORACLE_MIN_FEE)
+        .transact()
+        .await?
+        .into_result()?
+        .json()?;
+    assert_eq!(request["status"], "Pending");
+    assert_eq!(request["round"], 1);
+
+    // A second request for the same swap is refused while the first is open
+    let duplicate = env.alice.call(env.oracle.id(), "request_verification")
+        .args_json(json!({ "swap_id": "poseidon-1", "fee": ORACLE_MIN_FEE.as_yoctonear().to_string() }))
+        .deposit(ORACLE_MIN_FEE)
+        .transact()
+        .await?;
+    assert!(duplicate.is_failure());
+
+    env.owner.call(env.oracle.id(), "fulfill")
+        .args_json(json!({
+            "request_id": request["request_id"],
+            "result": { "poseidon_hash": poseidon_hash, "secret_matches": true },
+        }))
+        .gas(MAX_GAS)
+        .transact()
+        .await?
+        .into_result()?;
+
+    let request: Value = env.oracle.view("get_request_for_swap")
+        .args_json(json!({ "swap_id": "poseidon-1" }))
+        .await?
+        .json()?;
+    assert_eq!(request["status"], "Fulfilled");
+    assert_eq!(request["fulfilled_by"], env.owner.id().as_str());
+
+    env.alice.call(env.swap.id(), "lock_swap")
+        .args_json(json!({ "swap_id": "poseidon-1" }))
+        .transact()
+        .await?
+        .into_result()?;
+    env.alice.call(env.swap.id(), "complete_swap_with_oracle_verification")
+        .args_json(json!({ "swap_id": "poseidon-1", "secret": "poseidon-secret" }))
+        .gas(MAX_GAS)
+        .transact()
+        .await?
+        .into_result()?;
+
+    let swap: Value = env.swap.view("get_swap")
+        .args_json(json!({ "swap_id": "poseidon-1" }))
+        .await?
+        .json()?;
+    assert_eq!(swap["status"], "Completed");
+
+    Ok(())
+}
+
+// The owner rotates the oracle to a live standby while a request is open; the old key is locked
+// out at once and the standby is reported active and live
+#[tokio::test]
+async fn rotated_oracle_operator_takes_over_open_requests() -> anyhow::Result<()> {
+    let env = setup().await?;
+
+    let request: Value = env.alice.call(env.oracle.id(), "request_verification")
+        .args_json(json!({ "swap_id": "poseidon-2", "fee": ORACLE_MIN_FEE.as_yoctonear().to_string() }))
+        .deposit(ORACLE_MIN_FEE)
+        .transact()
+        .await?
+        .into_result()?
+        .json()?;
+
+    env.owner.call(env.oracle.id(), "register_operator")
+        .args_json(json!({ "account_id": env.bob.id() }))
+        .transact()
+        .await?
+        .into_result()?;
+
+    // A standby that never sent a heartbeat can't take over
+    let silent = env.owner.call(env.oracle.id(), "rotate_operator")
+        .args_json(json!({ "new_operator": env.bob.id() }))
+        .transact()
+        .await?;
+    assert!(silent.is_failure());
+
+    env.bob.call(env.oracle.id(), "heartbeat")
+        .transact()
+        .await?
+        .into_result()?;
+    env.owner.call(env.oracle.id(), "rotate_operator")
+        .args_json(json!({ "new_operator": env.bob.id() }))
+        .transact()
+        .await?
+        .into_result()?;
+
+    // The old key is locked out straight away
+    let stale = env.owner.call(env.oracle.id(), "fulfill")
+        .args_json(json!({
+            "request_id": request["request_id"],
+            "result": { "poseidon_hash": "0c".repeat(32), "secret_matches": false },
+        }))
+        .gas(MAX_GAS)
+        .transact()
+        .await?;
+    assert!(stale.is_failure());
+
+    let status: Value = env.oracle.view("get_operator")
+        .args_json(json!({ "account_id": env.bob.id() }))
+        .await?
+        .json()?;
+    assert_eq!(status["active"], true);
+    assert_eq!(status["live"], true);
+
+    let operators: Value = env.oracle.view("get_operators")
+        .args_json(json!({ "from_index": null, "limit": null }))
+        .await?
+        .json()?;
+    assert_eq!(operators["total"], 2);
+
+    Ok(())
+}
+
+// Swap initiation checks the target address and hashlock against the target chain's formats
+#[tokio::test]
+async fn swap_initiation_validates_target_chain_formats() -> anyhow::Result<()> {
+    let env = setup().await?;
+    let hash_lock = hex::encode(Sha256::digest(b"chain-secret"));
+    let initiate = |swap_id: &'static str, chain: &'static str, address: &'static str, algorithm: &'static str| {
+        env.bob.call(env.swap.id(), "initiate_swap")
+            .args_json(json!({
+                "swap_id": swap_id,
+                "participant": env.alice.id(),
+                "hash_lock": hash_lock,
+                "hash_algorithm": algorithm,
+                "time_lock_duration": 3600,
+                "target_chain": chain,
+                "target_address": address,
+                "counterparty_swap_id": null,
+                "linked_escrow": null,
+            }))
+            .deposit(NearToken::from_near(1))
+            .max_gas()
+            .transact()
+    };
+
+    // Bad EIP-55 checksum, and a Bitcoin script can't check a Keccak preimage
+    let bad_checksum = initiate("evm-1", "ethereum", "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD", "SHA256").await?;
+    assert!(bad_checksum.is_failure());
+    let bad_algorithm = initiate("btc-1", "bitcoin", "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4", "Keccak256").await?;
+    assert!(bad_algorithm.is_failure());
+
+    initiate("evm-2", "ethereum", "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed", "Keccak256")
+        .await?
+        .into_result()?;
+    initiate("btc-2", "bitcoin", "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4", "SHA256")
+        .await?
+        .into_result()?;
+
+    Ok(())
+}
+
+// Alice disputes a protected payment to Bob; the p2p contract opens the chargeback escrow as
+// depositor and puts it straight into dispute
+#[tokio::test]
+async fn protected_payment_dispute_opens_escrow_through_p2p() -> anyhow::Result<()> {
+    let env = setup().await?;
+    let amount = NearToken::from_near(2);
+
+    env.alice.call(env.p2p.id(), "send_protected")
+        .args_json(json!({ "transfer_id": "protected-1", "recipient": env.bob.id(), "memo": "" }))
+        .deposit(amount)
+        .max_gas()
+        .transact()
+        .await?
+        .into_result()?;
+    env.alice.call(env.p2p.id(), "dispute_protected")
+        .args_json(json!({ "transfer_id": "protected-1" }))
+        .gas(MAX_GAS)
+        .transact()
+        .await?
+        .into_result()?;
+
+    let escrow_id = format!("chargeback:{}:protected-1", env.p2p.id());
+    let escrow: Value = env.escrow.view("get_escrow")
+        .args_json(json!({ "escrow_id": escrow_id }))
+        .await?
+        .json()?;
+    assert_eq!(escrow["status"], "Disputed");
+    assert_eq!(escrow["depositor"], env.p2p.id().to_string());
+    assert_eq!(escrow["amount"], amount.as_yoctonear().to_string());
+
+    let hold: Value = env.p2p.view("get_chargeback_hold")
+        .args_json(json!({ "transfer_id": "protected-1" }))
+        .await?
+        .json()?;
+    assert_eq!(hold["escrow_id"], escrow_id);
+
+    Ok(())
+}
+
+// A shielded note funds a swap to Bob; the p2p contract is the swap's initiator
+#[tokio::test]
+async fn shielded_note_funds_swap_through_p2p() -> anyhow::Result<()> {
+    let env = setup().await?;
+    let amount = NearToken::from_near(3).as_yoctonear();
+    let commitment = note_commitment(2, 1);
+
+    env.owner.call(env.p2p.id(), "set_verifying_key")
+        .args_json(json!({ "circuit_version": 0, "vk_bytes": spend_verifying_key() }))
+        .transact()
+        .await?
+        .into_result()?;
+    env.alice.call(env.p2p.id(), "shield_deposit")
+        .args_json(json!({ "note_id": "swap-note", "commitment": commitment }))
+        .deposit(NearToken::from_yoctonear(amount))
+        .max_gas()
+        .transact()
+        .await?
+        .into_result()?;
+
+    let root: String = env.p2p.view("get_current_root").await?.json()?;
+    env.alice.call(env.p2p.id(), "fund_swap_from_note")
+        .args_json(json!({
+            "request": {
+                "swap_id": "note-swap-1",
+                "note_id": "swap-note",
+                "nullifier": note_nullifier(2),
+                "root": root,
+                "proof": spend_proof(),
+                "participant": env.bob.id(),
+                "hash_lock": hex::encode(Sha256::digest(b"note-swap secret")),
+                "hash_algorithm": "SHA256",
+                "time_lock_duration": 3600,
+                "target_chain": "near",
+                "target_address": env.bob.id(),
+                "counterparty_swap_id": null,
+                "refund_note_id": "swap-note-refund",
+                "refund_commitment": note_commitment(2, 2),
+            }
+        }))
+        .gas(MAX_GAS)
+        .transact()
+        .await?
+        .into_result()?;
+
+    let swap: Value = env.swap.view("get_swap")
+        .args_json(json!({ "swap_id": "note-swap-1" }))
+        .await?
+        .json()?;
+    assert_eq!(swap["status"], "Initiated");
+    assert_eq!(swap["initiator"], env.p2p.id().to_string());
+    assert_eq!(swap["amount"], (amount - bps_of(amount, SHIELDED_WITHDRAW_FEE_BPS)).to_string());
+
+    let transfer: Value = env.p2p.view("get_transfer")
+        .args_json(json!({ "transfer_id": "swap:note-swap-1" }))
+        .await?
+        .json()?;
+    assert_eq!(transfer["status"], "Pending");
+
+    Ok(())
+}
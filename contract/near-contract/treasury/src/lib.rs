@@ -0,0 +1,390 @@
+use ciphra_types::{assert_owner, decimal_borsh, ensure, ErrorCode, OrFail, Paged};
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::collections::{UnorderedMap, Vector};
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{env, is_promise_success, near_bindgen, AccountId, BorshStorageKey, Gas, NearToken, PanicOnDefault, Promise};
+use schemars::JsonSchema;
+
+const GAS_FOR_WITHDRAW_CALLBACK: Gas = Gas::from_tgas(10);
+
+// Product label for fees from contracts that were never registered
+const UNREGISTERED_PRODUCT: &str = "unregistered";
+
+// Upper bound on accounts holding the treasurer role
+const MAX_TREASURERS: usize = 10;
+
+// Longest accepted product label and withdrawal memo
+const MAX_PRODUCT_LENGTH: usize = 32;
+const MAX_MEMO_LENGTH: usize = 256;
+
+#[derive(BorshSerialize, BorshStorageKey)]
+pub enum StorageKey {
+    Sources,
+    ProductTotals,
+    Withdrawals,
+}
+
+// A protocol contract paying fees in, tagged with the product it belongs to
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FeeSource {
+    pub account_id: String,
+    pub product: String, // e.g. "swap", "escrow", "p2p"
+    #[borsh(serialize_with = "decimal_borsh::serialize", deserialize_with = "decimal_borsh::deserialize")]
+    #[schemars(with = "String")]
+    pub total_received: U128,
+    pub deposits: u64,
+    pub last_deposit_at: Option<u64>,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Withdrawal {
+    pub index: u64,
+    pub treasurer: String,
+    pub recipient: String,
+    #[borsh(serialize_with = "decimal_borsh::serialize", deserialize_with = "decimal_borsh::deserialize")]
+    #[schemars(with = "String")]
+    pub amount: U128,
+    pub memo: String,
+    pub withdrawn_at: u64,
+    pub failed: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ProductTotal {
+    pub product: String,
+    #[schemars(with = "String")]
+    pub total_received: U128,
+}
+
+#[near_bindgen]
+#[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
+pub struct TreasuryContract {
+    pub owner: AccountId,
+    pub treasurers: Vec<AccountId>, // May withdraw; the owner manages the role but can't withdraw
+    pub sources: UnorderedMap<AccountId, FeeSource>,
+    pub product_totals: UnorderedMap<String, u128>,
+    pub total_received: u128,
+    pub total_withdrawn: u128,
+    pub withdrawals: Vector<Withdrawal>,
+}
+
+#[near_bindgen]
+impl TreasuryContract {
+    #[init]
+    pub fn new(owner: AccountId, treasurers: Vec<AccountId>) -> Self {
+        let mut contract = Self {
+            owner,
+            treasurers: Vec::new(),
+            sources: UnorderedMap::new(StorageKey::Sources),
+            product_totals: UnorderedMap::new(StorageKey::ProductTotals),
+            total_received: 0,
+            total_withdrawn: 0,
+            withdrawals: Vector::new(StorageKey::Withdrawals),
+        };
+        contract.apply_treasurers(treasurers);
+        contract
+    }
+
+    // Protocol contracts pay their fees here; attributed to the calling contract and its product
+    #[payable]
+    pub fn deposit_fees(&mut self) {
+        let source = env::predecessor_account_id();
+        let amount_yocto = env::attached_deposit().as_yoctonear();
+        ensure!(amount_yocto > 0, InsufficientFunds, "Must attach NEAR tokens");
+        
+        let mut fee_source = self.sources.get(&source).unwrap_or_else(|| FeeSource {
+            account_id: source.to_string(),
+            product: UNREGISTERED_PRODUCT.to_string(),
+            total_received: U128(0),
+            deposits: 0,
+            last_deposit_at: None,
+        });
+        let received_yocto: u128 = fee_source.total_received.0;
+        fee_source.total_received = U128(received_yocto + amount_yocto);
+        fee_source.deposits += 1;
+        fee_source.last_deposit_at = Some(env::block_timestamp());
+        self.sources.insert(&source, &fee_source);
+        
+        let product_total = self.product_totals.get(&fee_source.product).unwrap_or(0);
+        self.product_totals.insert(&fee_source.product, &(product_total + amount_yocto));
+        self.total_received += amount_yocto;
+        
+        env::log_str(&format!(
+            "Fees deposited: {} | Product: {} | Amount: {}",
+            source, fee_source.product, amount_yocto
+        ));
+    }
+
+    // Owner tags a contract with its product; fees it already paid move to the new product
+    pub fn register_source(&mut self, source: AccountId, product: String) -> FeeSource {
+        assert_owner(&self.owner);
+        ensure!(
+            !product.is_empty() && product.len() <= MAX_PRODUCT_LENGTH,
+            InvalidArgument,
+            "Product must be 1-32 characters"
+        );
+        
+        let mut fee_source = self.sources.get(&source).unwrap_or_else(|| FeeSource {
+            account_id: source.to_string(),
+            product: product.clone(),
+            total_received: U128(0),
+            deposits: 0,
+            last_deposit_at: None,
+        });
+        let received_yocto: u128 = fee_source.total_received.0;
+        if fee_source.product != product && received_yocto > 0 {
+            let previous_total = self.product_totals.get(&fee_source.product).unwrap_or(0);
+            self.product_totals.insert(&fee_source.product, &(previous_total - received_yocto));
+            let product_total = self.product_totals.get(&product).unwrap_or(0);
+            self.product_totals.insert(&product, &(product_total + received_yocto));
+        }
+        fee_source.product = product;
+        self.sources.insert(&source, &fee_source);
+        
+        env::log_str(&format!("Fee source registered: {} | Product: {}", source, fee_source.product));
+        
+        fee_source
+    }
+
+    // Treasurer pays out of the accumulated fees
+    pub fn withdraw(&mut self, amount: U128, recipient: AccountId, memo: String) -> Promise {
+        let treasurer = env::predecessor_account_id();
+        ensure!(self.treasurers.contains(&treasurer), Unauthorized, "Only treasurer");
+        ensure!(amount.0 > 0, InvalidArgument, "Amount must be positive");
+        ensure!(amount.0 <= self.available_balance(), InsufficientFunds, "Insufficient treasury balance");
+        ensure!(memo.len() <= MAX_MEMO_LENGTH, LimitExceeded, "Memo cannot exceed 256 characters");
+        
+        let index = self.withdrawals.len();
+        self.withdrawals.push(&Withdrawal {
+            index,
+            treasurer: treasurer.to_string(),
+            recipient: recipient.to_string(),
+            amount,
+            memo,
+            withdrawn_at: env::block_timestamp(),
+            failed: false,
+        });
+        self.total_withdrawn += amount.0;
+        
+        env::log_str(&format!(
+            "Treasury withdrawal: {} | By: {} | To: {} | Amount: {}",
+            index, treasurer, recipient, amount.0
+        ));
+        
+        Promise::new(recipient)
+            .transfer(NearToken::from_yoctonear(amount.0))
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_WITHDRAW_CALLBACK)
+                    .on_withdrawn(index),
+            )
+    }
+
+    // Callback - a failed transfer comes back, so the amount is available again
+    #[private]
+    pub fn on_withdrawn(&mut self, index: u64) -> bool {
+        if is_promise_success() {
+            return true;
+        }
+        
+        let mut withdrawal = self.withdrawals.get(index).or_fail(ErrorCode::NotFound, "Withdrawal not found");
+        let amount_yocto: u128 = withdrawal.amount.0;
+        withdrawal.failed = true;
+        self.withdrawals.replace(index, &withdrawal);
+        self.total_withdrawn -= amount_yocto;
+        
+        env::log_str(&format!("Treasury withdrawal failed: {} | Amount: {}", index, amount_yocto));
+        
+        false
+    }
+
+    pub fn set_treasurers(&mut self, treasurers: Vec<AccountId>) {
+        assert_owner(&self.owner);
+        self.apply_treasurers(treasurers);
+    }
+
+    pub fn get_treasurers(&self) -> Vec<AccountId> {
+        self.treasurers.clone()
+    }
+
+    pub fn get_source(&self, source: AccountId) -> Option<FeeSource> {
+        self.sources.get(&source)
+    }
+
+    pub fn get_sources(&self, from_index: Option<u64>, limit: Option<u64>) -> Paged<FeeSource> {
+        let sources = self.sources.values_as_vector();
+        Paged::from_range(sources.len(), from_index, limit, |index| sources.get(index))
+    }
+
+    // Cumulative fees received per product
+    pub fn get_product_totals(&self) -> Vec<ProductTotal> {
+        self.product_totals
+            .iter()
+            .map(|(product, total)| ProductTotal { product, total_received: U128(total) })
+            .collect()
+    }
+
+    pub fn get_total_received(&self) -> U128 {
+        U128(self.total_received)
+    }
+
+    pub fn get_total_withdrawn(&self) -> U128 {
+        U128(self.total_withdrawn)
+    }
+
+    // Received fees not yet withdrawn
+    pub fn get_balance(&self) -> U128 {
+        U128(self.available_balance())
+    }
+
+    pub fn get_withdrawals(&self, from_index: Option<u64>, limit: Option<u64>) -> Paged<Withdrawal> {
+        Paged::from_range(self.withdrawals.len(), from_index, limit, |index| self.withdrawals.get(index))
+    }
+
+    fn available_balance(&self) -> u128 {
+        self.total_received - self.total_withdrawn
+    }
+
+    fn apply_treasurers(&mut self, treasurers: Vec<AccountId>) {
+        let mut treasurers = treasurers;
+        treasurers.sort();
+        treasurers.dedup();
+        ensure!(treasurers.len() <= MAX_TREASURERS, LimitExceeded, "At most 10 treasurers");
+        ensure!(!treasurers.contains(&self.owner), InvalidArgument, "Owner cannot be a treasurer");
+        self.treasurers = treasurers;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ciphra_testing as host;
+
+    const OWNER: &str = "owner.near";
+    const TREASURER: &str = "treasurer.near";
+
+    fn account(name: &str) -> AccountId {
+        name.parse().unwrap()
+    }
+
+    fn contract() -> TreasuryContract {
+        host::set_caller(OWNER);
+        TreasuryContract::new(account(OWNER), vec![account(TREASURER)])
+    }
+
+    fn deposit_fees(contract: &mut TreasuryContract, source: &str, amount: u128) {
+        host::set_caller(source);
+        host::set_deposit(amount);
+        contract.deposit_fees();
+        host::set_deposit(0);
+    }
+
+    fn product_total(contract: &TreasuryContract, product: &str) -> u128 {
+        contract.get_product_totals().into_iter().find(|t| t.product == product).map_or(0, |t| t.total_received.0)
+    }
+
+    #[test]
+    fn test_fees_are_attributed_to_the_source_product() {
+        let mut contract = contract();
+        host::set_caller(OWNER);
+        contract.register_source(account("swap.near"), "swap".to_string());
+
+        deposit_fees(&mut contract, "swap.near", 3 * host::NEAR);
+        deposit_fees(&mut contract, "swap.near", 2 * host::NEAR);
+
+        let source = contract.get_source(account("swap.near")).unwrap();
+        assert_eq!(source.total_received, U128(5 * host::NEAR));
+        assert_eq!(source.deposits, 2);
+        assert_eq!(product_total(&contract, "swap"), 5 * host::NEAR);
+        assert_eq!(contract.get_total_received(), U128(5 * host::NEAR));
+    }
+
+    #[test]
+    fn test_registering_a_source_moves_its_earlier_fees() {
+        let mut contract = contract();
+        deposit_fees(&mut contract, "escrow.near", host::NEAR);
+        assert_eq!(product_total(&contract, UNREGISTERED_PRODUCT), host::NEAR);
+
+        host::set_caller(OWNER);
+        contract.register_source(account("escrow.near"), "escrow".to_string());
+
+        assert_eq!(product_total(&contract, UNREGISTERED_PRODUCT), 0);
+        assert_eq!(product_total(&contract, "escrow"), host::NEAR);
+    }
+
+    #[test]
+    fn test_treasurer_withdraws_available_fees() {
+        let mut contract = contract();
+        deposit_fees(&mut contract, "swap.near", 5 * host::NEAR);
+
+        host::set_caller(TREASURER);
+        let _ = contract.withdraw(U128(2 * host::NEAR), account("ops.near"), "Audit".to_string());
+
+        assert_eq!(host::transfers(), vec![("ops.near".to_string(), 2 * host::NEAR)]);
+        assert_eq!(contract.get_balance(), U128(3 * host::NEAR));
+        let withdrawal = &contract.get_withdrawals(None, None).items[0];
+        assert_eq!(withdrawal.treasurer, TREASURER);
+        assert!(!withdrawal.failed);
+    }
+
+    #[test]
+    fn test_failed_withdrawal_is_made_available_again() {
+        let mut contract = contract();
+        deposit_fees(&mut contract, "swap.near", 5 * host::NEAR);
+        host::set_caller(TREASURER);
+        let _ = contract.withdraw(U128(2 * host::NEAR), account("ops.near"), String::new());
+
+        host::set_self_caller();
+        host::set_promise_results(vec![None]);
+        assert!(!contract.on_withdrawn(0));
+
+        assert_eq!(contract.get_balance(), U128(5 * host::NEAR));
+        assert_eq!(contract.get_total_withdrawn(), U128(0));
+        assert!(contract.get_withdrawals(None, None).items[0].failed);
+    }
+
+    #[test]
+    #[should_panic(expected = r#""code":"InsufficientFunds","message":"Insufficient treasury balance""#)]
+    fn test_withdrawal_cannot_exceed_the_balance() {
+        let mut contract = contract();
+        deposit_fees(&mut contract, "swap.near", host::NEAR);
+        host::set_caller(TREASURER);
+        let _ = contract.withdraw(U128(2 * host::NEAR), account("ops.near"), String::new());
+    }
+
+    #[test]
+    #[should_panic(expected = r#""code":"Unauthorized","message":"Only treasurer""#)]
+    fn test_owner_cannot_withdraw() {
+        let mut contract = contract();
+        deposit_fees(&mut contract, "swap.near", host::NEAR);
+        host::set_caller(OWNER);
+        let _ = contract.withdraw(U128(host::NEAR), account("ops.near"), String::new());
+    }
+
+    #[test]
+    #[should_panic(expected = "Must attach NEAR tokens")]
+    fn test_fee_deposit_requires_an_attachment() {
+        let mut contract = contract();
+        deposit_fees(&mut contract, "swap.near", 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Owner cannot be a treasurer")]
+    fn test_owner_cannot_hold_the_treasurer_role() {
+        let mut contract = contract();
+        host::set_caller(OWNER);
+        contract.set_treasurers(vec![account(OWNER)]);
+    }
+
+    #[test]
+    #[should_panic(expected = r#""code":"Unauthorized""#)]
+    fn test_only_the_owner_registers_sources() {
+        let mut contract = contract();
+        host::set_caller(TREASURER);
+        contract.register_source(account("swap.near"), "swap".to_string());
+    }
+}